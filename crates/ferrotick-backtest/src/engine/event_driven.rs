@@ -1,13 +1,19 @@
 use std::collections::HashMap;
+use std::time::Instant;
 
-use ferrotick_core::{Bar, Symbol, UtcDateTime};
+use ferrotick_core::{
+    Bar, Symbol, UniverseMembership, UtcDateTime, Warehouse, WarehouseError, RISK_FREE_SERIES,
+};
+use ferrotick_telemetry::metrics::BACKTEST_DURATION;
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc;
+use tracing::field::Empty;
+use tracing::Instrument;
 
 use crate::costs::{SlippageModel, TransactionCosts};
 use crate::engine::executor::OrderExecutor;
 use crate::metrics::{EquityPoint, MetricsReport};
-use crate::portfolio::{Fill, Order, Portfolio};
+use crate::portfolio::{Fill, Order, OrderSide, Portfolio};
 use crate::{BacktestError, BacktestResult};
 
 /// Events processed by the backtesting engine.
@@ -77,6 +83,29 @@ impl Default for BacktestConfig {
     }
 }
 
+impl BacktestConfig {
+    /// Sources `risk_free_rate` from T-bill yields in the warehouse.
+    ///
+    /// Uses the average 3-month T-bill yield over `start_date..end_date`
+    /// (see [`Warehouse::risk_free_rate`]). The configured rate is kept when
+    /// the yield series has not been ingested.
+    pub fn with_warehouse_risk_free_rate(
+        mut self,
+        warehouse: &Warehouse,
+    ) -> Result<Self, WarehouseError> {
+        let end = self.end_date.unwrap_or_else(UtcDateTime::now);
+        let start = self.start_date.unwrap_or(end);
+        if let Some(rate) = warehouse.risk_free_rate(
+            RISK_FREE_SERIES,
+            &start.format_rfc3339(),
+            &end.format_rfc3339(),
+        )? {
+            self.risk_free_rate = rate;
+        }
+        Ok(self)
+    }
+}
+
 /// Final report returned by a backtest run.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BacktestReport {
@@ -117,6 +146,7 @@ pub struct BacktestEngine {
     event_bus: EventBus,
     latest_bars: HashMap<Symbol, Bar>,
     pending_orders: Vec<PendingOrder>,
+    universe: Option<UniverseMembership>,
 }
 
 #[derive(Debug, Clone)]
@@ -134,9 +164,19 @@ impl BacktestEngine {
             event_bus: EventBus::new(),
             latest_bars: HashMap::new(),
             pending_orders: Vec::new(),
+            universe: None,
         }
     }
 
+    /// Only buy symbols that were members of `membership` on the day the
+    /// order is placed, so a backtest over a historical universe is free of
+    /// survivorship bias. Sells are never blocked, so positions can still be
+    /// closed after a symbol leaves.
+    pub fn with_universe(mut self, membership: UniverseMembership) -> Self {
+        self.universe = Some(membership);
+        self
+    }
+
     pub fn config(&self) -> &BacktestConfig {
         &self.config
     }
@@ -149,6 +189,20 @@ impl BacktestEngine {
         &mut self,
         strategy: &mut S,
         data: &[BarEvent],
+    ) -> BacktestResult<BacktestReport> {
+        let span = tracing::info_span!("backtest_run", bars = data.len(), outcome = Empty);
+        let started = Instant::now();
+        let result = self.run_bars(strategy, data).instrument(span.clone()).await;
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        span.record("outcome", outcome);
+        BACKTEST_DURATION.observe(&[("outcome", outcome)], started.elapsed());
+        result
+    }
+
+    async fn run_bars<S: Strategy + Send>(
+        &mut self,
+        strategy: &mut S,
+        data: &[BarEvent],
     ) -> BacktestResult<BacktestReport> {
         self.validate_config()?;
 
@@ -265,18 +319,22 @@ impl BacktestEngine {
                     if let Some(order) =
                         strategy.create_order(&signal, &self.portfolio, &self.config)
                     {
+                        if self.admits(&order, signal.ts) {
+                            self.pending_orders.push(PendingOrder {
+                                order,
+                                queued_at: signal.ts,
+                            });
+                        }
+                    }
+                }
+                BacktestEvent::Order(order) => {
+                    if self.admits(&order, order.created_at) {
                         self.pending_orders.push(PendingOrder {
+                            queued_at: order.created_at,
                             order,
-                            queued_at: signal.ts,
                         });
                     }
                 }
-                BacktestEvent::Order(order) => {
-                    self.pending_orders.push(PendingOrder {
-                        queued_at: order.created_at,
-                        order,
-                    });
-                }
                 BacktestEvent::Fill(fill) => {
                     self.portfolio.apply_fill(&fill)?;
                 }
@@ -287,6 +345,16 @@ impl BacktestEngine {
         Ok(())
     }
 
+    /// Whether the universe, if any, allows `order` placed at `ts`.
+    fn admits(&self, order: &Order, ts: UtcDateTime) -> bool {
+        match &self.universe {
+            Some(universe) if order.side == OrderSide::Buy => {
+                universe.contains(order.symbol.as_str(), &ts.format_rfc3339())
+            }
+            _ => true,
+        }
+    }
+
     fn generate_report(&self, equity_curve: Vec<EquityPoint>) -> BacktestResult<BacktestReport> {
         let metrics = MetricsReport::from_equity_curve(
             &equity_curve,
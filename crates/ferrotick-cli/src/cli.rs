@@ -10,8 +10,12 @@
 //! |---------|-------------|
 //! | `quote` | Fetch latest quotes for symbols |
 //! | `bars` | Fetch historical OHLCV bars |
+//! | `ticks` | Fetch historical trades or quotes |
 //! | `fundamentals` | Fetch company fundamentals |
 //! | `search` | Search for instruments |
+//! | `ratios` | Compute financial ratios from statements |
+//! | `economic` | Fetch macro/economic series |
+//! | `news` | Fetch news articles with sentiment |
 //! | `sql` | Query the local DuckDB warehouse |
 //! | `cache` | Manage local cache |
 //! | `schema` | Inspect bundled JSON schemas |
@@ -44,7 +48,10 @@
 //! ferrotick quote AAPL --strict
 //! ```
 
+use std::str::FromStr;
+
 use clap::{Args, Parser, Subcommand, ValueEnum};
+use ferrotick_core::{ProviderId, ValidationError};
 
 /// 🦀 Ferrotick - Provider-neutral financial data CLI
 ///
@@ -88,15 +95,17 @@ pub struct Cli {
     #[arg(long, global = true, default_value_t = false)]
     pub strict: bool,
 
-    /// Source selection strategy for routing requests.
-    #[arg(long, global = true, value_enum, default_value_t = SourceSelector::Auto)]
+    /// Source selection strategy for routing requests: `auto`, a built-in
+    /// provider (yahoo, polygon, alphavantage, alpaca) or a registered
+    /// provider name.
+    #[arg(long, global = true, default_value = "auto")]
     pub source: SourceSelector,
 
     /// Request timeout budget in milliseconds.
     #[arg(long, global = true, default_value_t = 3000)]
     pub timeout_ms: u64,
 
-    /// Enable profiling metadata in output (placeholder).
+    /// Print a per-span timing breakdown as JSON on stderr.
     #[arg(long, global = true, default_value_t = false)]
     pub profile: bool,
 
@@ -133,18 +142,23 @@ pub enum OutputFormat {
 /// Source selection strategy.
 ///
 /// Controls which provider(s) handle requests.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SourceSelector {
     /// Automatic selection with priority scoring and fallback.
     Auto,
-    /// Use Yahoo Finance directly.
-    Yahoo,
-    /// Use Polygon.io directly.
-    Polygon,
-    /// Use Alpha Vantage directly.
-    Alphavantage,
-    /// Use Alpaca directly.
-    Alpaca,
+    /// Use one provider directly, built-in or custom.
+    Provider(ProviderId),
+}
+
+impl FromStr for SourceSelector {
+    type Err = ValidationError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if value.trim().eq_ignore_ascii_case("auto") {
+            return Ok(Self::Auto);
+        }
+        value.parse().map(Self::Provider)
+    }
 }
 
 /// Available CLI commands.
@@ -174,6 +188,18 @@ pub enum Command {
     ///   ferrotick bars GOOGL --interval 1h --limit 48
     Bars(BarsArgs),
 
+    /// 🧾 Fetch historical trade prints or NBBO quotes.
+    ///
+    /// Trades can optionally be aggregated into time, tick, volume or
+    /// dollar bars.
+    ///
+    /// # Examples
+    ///
+    ///   ferrotick ticks AAPL --limit 1000
+    ///   ferrotick ticks AAPL --kind quotes --start 2024-01-02T14:30:00Z
+    ///   ferrotick ticks AAPL --bars volume:10000
+    Ticks(TicksArgs),
+
     /// 📈 Fetch company fundamentals snapshot(s).
     ///
     /// Returns fundamental data including market cap, P/E ratio,
@@ -216,6 +242,39 @@ pub enum Command {
     ///   ferrotick earnings MSFT --limit 8
     Earnings(EarningsArgs),
 
+    /// 🧮 Compute financial ratios and growth metrics.
+    ///
+    /// Fetches income, balance sheet and cash flow statements and derives
+    /// margins, ROE/ROA, current ratio, debt/equity, FCF yield and growth.
+    ///
+    /// # Examples
+    ///
+    ///   ferrotick ratios AAPL
+    ///   ferrotick ratios MSFT --period ttm --limit 8
+    Ratios(RatiosArgs),
+
+    /// 🏦 Fetch a macro or economic data series.
+    ///
+    /// Returns treasury yields, CPI, unemployment and similar series and
+    /// stores observations in the warehouse `macro_series` table.
+    ///
+    /// # Examples
+    ///
+    ///   ferrotick economic CPI --frequency monthly
+    ///   ferrotick economic TREASURY_YIELD_3MONTH --frequency daily --limit 250
+    Economic(EconomicArgs),
+
+    /// 📰 Fetch news articles and sentiment for symbols.
+    ///
+    /// Returns recent headlines with overall and per-ticker sentiment and
+    /// stores them in the warehouse `news` and `news_tickers` tables.
+    ///
+    /// # Examples
+    ///
+    ///   ferrotick news AAPL
+    ///   ferrotick news AAPL MSFT --since 2024-01-01T00:00:00Z --limit 100
+    News(NewsArgs),
+
     /// 🗄️ Run SQL queries against the DuckDB warehouse.
     ///
     /// Execute SQL queries against the local warehouse database.
@@ -234,14 +293,32 @@ pub enum Command {
     ///   ferrotick sql "SELECT COUNT(*) FROM bars_1d"
     Sql(SqlArgs),
 
-    /// 📤 Export data from warehouse to Parquet or CSV.
+    /// 📤 Export data from warehouse to Parquet, Arrow IPC, NDJSON or CSV.
+    ///
+    /// Column types are preserved; partitioned exports write a hive-style
+    /// directory (`symbol=AAPL/data_0.parquet`) at --output.
     ///
     /// # Examples
     ///
-    ///   ferrotick export --output data.csv --format csv --table bars --symbol AAPL
-    ///   ferrotick export --output data.parquet --format parquet --query "SELECT * FROM bars_1d"
+    ///   ferrotick export --output data.csv --export-format csv --table bars --symbol AAPL
+    ///   ferrotick export --output data.parquet --compression zstd --query "SELECT * FROM bars_1d"
+    ///   ferrotick export --output bars/ --table bars_1m --partition-by date
+    ///   ferrotick export --output bars.arrow --export-format arrow --table bars_1d
     Export(ExportArgs),
 
+    /// 📥 Import vendor CSV or Parquet files into the warehouse.
+    ///
+    /// Rows are validated, converted to UTC and tagged with a source; bars
+    /// already stored for a symbol and timestamp are skipped. Invalid rows go
+    /// to an NDJSON reject file with the reason.
+    ///
+    /// # Examples
+    ///
+    ///   ferrotick import bars 'vendor/*.csv' --interval 1d --mapping mapping.toml
+    ///   ferrotick import fundamentals fundamentals.parquet --source-tag acme
+    ///   ferrotick import corporate-actions actions.csv --rejects actions.rejects.ndjson
+    Import(ImportArgs),
+
     /// 🤖 ML feature engineering commands.
     ///
     /// # Examples
@@ -254,6 +331,60 @@ pub enum Command {
     /// 📦 Cache management commands.
     Cache(CacheArgs),
 
+    /// 🗃️ Warehouse snapshots, restore and schema migrations.
+    ///
+    /// Snapshots hold a consistent copy of the database and the parquet
+    /// cache with SHA-256 checksums, as a directory or a .tar/.tar.gz file.
+    ///
+    /// # Examples
+    ///
+    ///   ferrotick warehouse snapshot backups/2024-06-30.tar.gz
+    ///   ferrotick warehouse restore backups/2024-06-30.tar.gz
+    ///   ferrotick warehouse migrate status
+    ///   ferrotick warehouse migrate down --to 0009_unified_bars --dry-run
+    Warehouse(WarehouseArgs),
+
+    /// 🧬 Trace stored rows back to the ingest batches that wrote them.
+    ///
+    /// Shows every version of a data point, with the request, source and
+    /// ferrotick version of the batch that wrote it and the batch that
+    /// later overwrote it.
+    ///
+    /// # Examples
+    ///
+    ///   ferrotick lineage bars AAPL --date 2024-05-01
+    ///   ferrotick lineage bars AAPL --date 2024-05-01 --interval 1m
+    ///   ferrotick lineage batch 0b9f6c1e-2d4a-4c5e-9a8b-7f3e1d2c4b5a
+    Lineage(LineageArgs),
+
+    /// 🌐 Named universes with point-in-time membership.
+    ///
+    /// Symbols join and leave a universe on dates, so features and
+    /// backtests see the universe as it was on each day. Pass
+    /// `--universe <name>` to quote, fundamentals, news, ml features,
+    /// export, cache load or cache sync instead of a symbol list.
+    ///
+    /// # Examples
+    ///
+    ///   ferrotick universe create sp500 --description "S&P 500"
+    ///   ferrotick universe add sp500 AAPL MSFT --date 2000-01-03
+    ///   ferrotick universe remove sp500 MSFT --date 2005-06-01
+    ///   ferrotick universe members sp500 --as-of 2004-12-31
+    ///   ferrotick universe list
+    Universe(UniverseArgs),
+
+    /// ⏰ Scheduled ingestion daemon.
+    ///
+    /// Runs incremental cache loads for the jobs of a YAML spec on their
+    /// schedules and records every run in the warehouse `ingest_log`.
+    ///
+    /// # Examples
+    ///
+    ///   ferrotick daemon run --spec jobs.yaml --health-addr 127.0.0.1:9465
+    ///   ferrotick daemon run --spec jobs.yaml --once
+    ///   ferrotick daemon status
+    Daemon(DaemonArgs),
+
     /// 📋 Inspect bundled JSON schemas.
     Schema(SchemaArgs),
 
@@ -276,8 +407,12 @@ pub enum Command {
 #[derive(Debug, Args)]
 pub struct QuoteArgs {
     /// One or more market symbols (e.g., AAPL, MSFT, GOOGL).
-    #[arg(required = true, num_args = 1..)]
+    #[arg(required_unless_present = "universe", num_args = 1..)]
     pub symbols: Vec<String>,
+
+    /// Also use today's members of this stored universe.
+    #[arg(long)]
+    pub universe: Option<String>,
 }
 
 /// Arguments for the `bars` command.
@@ -302,12 +437,46 @@ pub struct BarsArgs {
     pub limit: usize,
 }
 
+/// Arguments for the `ticks` command.
+#[derive(Debug, Args)]
+pub struct TicksArgs {
+    /// Market symbol to fetch ticks for.
+    pub symbol: String,
+
+    /// Tick kind (trades, quotes).
+    #[arg(long, default_value = "trades")]
+    pub kind: String,
+
+    /// Earliest tick timestamp (RFC3339 UTC).
+    #[arg(long)]
+    pub start: Option<String>,
+
+    /// Latest tick timestamp (RFC3339 UTC).
+    #[arg(long)]
+    pub end: Option<String>,
+
+    /// Maximum number of ticks to return (default: 1000).
+    #[arg(long, default_value_t = 1000)]
+    pub limit: usize,
+
+    /// Aggregate trades into bars.
+    ///
+    /// Accepts an interval (1m, 5m, 15m, 1h, 1d) for time bars, or
+    /// ticks:N, volume:N or dollar:X for activity-driven bars.
+    #[arg(long)]
+    pub bars: Option<String>,
+}
+
 /// Arguments for the `fundamentals` command.
 #[derive(Debug, Args)]
 pub struct FundamentalsArgs {
     /// One or more market symbols.
-    #[arg(required = true, num_args = 1..)]
+    #[arg(required_unless_present = "universe", num_args = 1..)]
     pub symbols: Vec<String>,
+
+    /// Also use today's members of this stored universe.
+    #[arg(long)]
+    pub universe: Option<String>,
 }
 
 /// Arguments for the `search` command.
@@ -356,18 +525,120 @@ pub struct EarningsArgs {
     pub limit: usize,
 }
 
+/// Arguments for the `ratios` command.
+#[derive(Debug, Args)]
+pub struct RatiosArgs {
+    /// Market symbol to compute ratios for.
+    pub symbol: String,
+
+    /// Ratio basis (annual, quarterly, ttm).
+    #[arg(long, default_value = "annual")]
+    pub period: String,
+
+    /// Number of periods to return (default: 4).
+    #[arg(long, default_value_t = 4)]
+    pub limit: usize,
+}
+
+/// Arguments for the `economic` command.
+#[derive(Debug, Args)]
+pub struct EconomicArgs {
+    /// Series identifier (e.g. CPI, UNEMPLOYMENT, TREASURY_YIELD_10YEAR).
+    pub series: String,
+
+    /// Sampling frequency (daily, weekly, monthly, quarterly, semiannual, annual).
+    #[arg(long)]
+    pub frequency: Option<String>,
+
+    /// Number of most recent observations to return (default: 100).
+    #[arg(long, default_value_t = 100)]
+    pub limit: usize,
+}
+
+/// Arguments for the `news` command.
+#[derive(Debug, Args)]
+pub struct NewsArgs {
+    /// One or more market symbols (e.g., AAPL, MSFT).
+    #[arg(required_unless_present = "universe", num_args = 1..)]
+    pub symbols: Vec<String>,
+
+    /// Also use today's members of this stored universe.
+    #[arg(long)]
+    pub universe: Option<String>,
+
+    /// Only return articles published at or after this RFC3339 timestamp.
+    #[arg(long)]
+    pub since: Option<String>,
+
+    /// Maximum number of articles to return (default: 50).
+    #[arg(long, default_value_t = 50)]
+    pub limit: usize,
+}
+
 /// Arguments for the `sql` command.
 #[derive(Debug, Args)]
 pub struct SqlArgs {
-    /// SQL query to execute.
-    pub query: String,
+    /// SQL query to execute; reference parameters as `$name`.
+    #[arg(required_unless_present_any = ["name", "list"], conflicts_with_all = ["name", "list"])]
+    pub query: Option<String>,
+
+    /// Run a query from the named query library (`$FERROTICK_HOME/queries/<name>.sql`).
+    #[arg(long, conflicts_with = "list")]
+    pub name: Option<String>,
+
+    /// Bind a `$name` placeholder; repeatable.
+    ///
+    /// TYPE is one of text (default), int, float, bool or date. Named
+    /// queries take their types from the query's declarations.
+    #[arg(long = "param", value_name = "NAME[:TYPE]=VALUE")]
+    pub params: Vec<String>,
+
+    /// List the named query library with each query's parameters.
+    #[arg(long, default_value_t = false)]
+    pub list: bool,
 
     /// Allow write operations (INSERT, UPDATE, DELETE, CREATE, etc.).
     ///
     /// Without this flag, only SELECT and CTE queries are allowed.
-    #[arg(long, default_value_t = false)]
+    #[arg(long, default_value_t = false, conflicts_with = "sandbox")]
     pub write: bool,
 
+    /// Run the query in the SQL sandbox, for untrusted SQL such as queries
+    /// from agents.
+    ///
+    /// The sandbox has no file, network or extension access and rejects
+    /// table functions other than `range`, `generate_series`, `unnest` and
+    /// the analytics macros. Rejections name the rule that fired, such as
+    /// `sandbox.external_access`.
+    #[arg(long, default_value_t = false)]
+    pub sandbox: bool,
+
+    /// Only allow these tables and views in the sandbox; repeatable.
+    #[arg(
+        long = "allow-table",
+        value_name = "TABLE",
+        value_delimiter = ',',
+        requires = "sandbox"
+    )]
+    pub allow_tables: Vec<String>,
+
+    /// Only allow calls to these functions in the sandbox; repeatable.
+    #[arg(
+        long = "allow-function",
+        value_name = "FUNCTION",
+        value_delimiter = ',',
+        requires = "sandbox"
+    )]
+    pub allow_functions: Vec<String>,
+
+    /// Sandbox memory limit in megabytes (default: 256).
+    #[arg(long, requires = "sandbox")]
+    pub memory_limit_mb: Option<u64>,
+
+    /// Sandbox thread limit (default: 1).
+    #[arg(long, requires = "sandbox")]
+    pub threads: Option<u64>,
+
     /// Maximum number of rows to return (prevents memory exhaustion).
     #[arg(long, default_value_t = 10_000)]
     pub max_rows: usize,
@@ -388,7 +659,12 @@ pub struct CacheArgs {
 #[derive(Debug, Args)]
 pub struct CacheLoadArgs {
     /// Stock symbol to load (e.g., AAPL).
-    pub symbol: String,
+    #[arg(required_unless_present = "universe")]
+    pub symbol: Option<String>,
+
+    /// Also load today's members of this stored universe.
+    #[arg(long)]
+    pub universe: Option<String>,
 
     /// Number of days of historical data to fetch.
     #[arg(long, default_value = "30")]
@@ -399,6 +675,46 @@ pub struct CacheLoadArgs {
     pub interval: String,
 }
 
+/// Arguments for `cache sync` command.
+#[derive(Debug, Args)]
+pub struct CacheSyncArgs {
+    /// Only sync partitions of symbols that were ever members of this
+    /// stored universe.
+    #[arg(long)]
+    pub universe: Option<String>,
+}
+
+/// Arguments for `cache rollup` command.
+#[derive(Debug, Args)]
+pub struct CacheRollupArgs {
+    /// Target bar interval (5m, 15m, 1h, 1d).
+    #[arg(long)]
+    pub interval: String,
+
+    /// Only roll up this symbol (default: every symbol with 1m bars).
+    #[arg(long)]
+    pub symbol: Option<String>,
+}
+
+/// Arguments for `cache compact` command.
+#[derive(Debug, Args)]
+pub struct CacheCompactArgs {
+    /// Report what would be expired, merged and pruned without changing anything.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Retention for a dataset as DATASET=DAYS or DATASET=forever; repeatable.
+    ///
+    /// Defaults keep bars_1m for 730 days, ingest_log and quotes_audit for 90
+    /// days and everything else forever.
+    #[arg(long = "keep", value_name = "DATASET=DAYS")]
+    pub keep: Vec<String>,
+
+    /// Largest monthly file compaction may produce, in MiB.
+    #[arg(long, default_value_t = 128)]
+    pub monthly_target_mb: u64,
+}
+
 /// Arguments for `export` command.
 #[derive(Debug, Args)]
 pub struct ExportArgs {
@@ -408,7 +724,7 @@ pub struct ExportArgs {
     #[arg(long)]
     pub query: Option<String>,
 
-    /// Table to export (bars, quotes, fundamentals).
+    /// Table to export (bars, bars_<interval>, quotes, fundamentals).
     ///
     /// Used when --query is not provided.
     #[arg(long)]
@@ -420,14 +736,52 @@ pub struct ExportArgs {
     #[arg(long)]
     pub symbol: Option<String>,
 
-    /// Output format (parquet, csv).
+    /// Only export rows of symbols that were members of this stored
+    /// universe on the row's date.
+    ///
+    /// Used with --table.
+    #[arg(long, requires = "table")]
+    pub universe: Option<String>,
+
+    /// Bind a `$name` placeholder of --query; repeatable.
+    ///
+    /// TYPE is one of text (default), int, float, bool or date.
+    #[arg(long = "param", value_name = "NAME[:TYPE]=VALUE", requires = "query")]
+    pub params: Vec<String>,
+
+    /// Bar interval to export with `--table bars` (1m, 5m, 15m, 1h, 1d).
+    ///
+    /// Without it, bars of every interval are exported.
+    #[arg(long)]
+    pub interval: Option<String>,
+
+    /// Output format (parquet, arrow, ndjson, csv).
     ///
     /// - parquet: Columnar format optimized for analytics
+    /// - arrow: Arrow IPC file, also read as Feather v2
+    /// - ndjson: One JSON object per line
     /// - csv: Human-readable spreadsheet format
     #[arg(long, default_value = "parquet")]
     pub export_format: String,
 
-    /// Output file path.
+    /// Compression codec (none, snappy, gzip, zstd, lz4).
+    ///
+    /// Parquet takes any codec and defaults to snappy; CSV takes gzip or
+    /// zstd; Arrow and NDJSON are written uncompressed.
+    #[arg(long)]
+    pub compression: Option<String>,
+
+    /// Rows per Parquet row group.
+    #[arg(long)]
+    pub row_group_size: Option<usize>,
+
+    /// Partition the output by `symbol` or `date` (derived from `ts`).
+    ///
+    /// --output then names a directory, which must be empty or missing.
+    #[arg(long)]
+    pub partition_by: Option<String>,
+
+    /// Output file path, or directory with --partition-by.
     #[arg(long)]
     pub output: String,
 
@@ -440,6 +794,59 @@ pub struct ExportArgs {
     pub query_timeout_ms: Option<String>,
 }
 
+#[derive(Debug, Args)]
+pub struct ImportArgs {
+    #[command(subcommand)]
+    pub command: ImportCommand,
+}
+
+/// Datasets that can be imported from vendor files.
+#[derive(Debug, Subcommand)]
+pub enum ImportCommand {
+    /// Import OHLCV bars of one interval.
+    Bars(ImportBarsArgs),
+
+    /// Import fundamentals snapshots (market cap, P/E, dividend yield).
+    Fundamentals(ImportFileArgs),
+
+    /// Import dividends, splits and other corporate actions.
+    CorporateActions(ImportFileArgs),
+}
+
+/// Arguments for `import bars` command.
+#[derive(Debug, Args)]
+pub struct ImportBarsArgs {
+    #[command(flatten)]
+    pub file: ImportFileArgs,
+
+    /// Bar interval of the files (1m, 5m, 15m, 1h, 1d).
+    #[arg(long, default_value = "1d")]
+    pub interval: String,
+}
+
+/// Files and mapping of an import.
+#[derive(Debug, Args)]
+pub struct ImportFileArgs {
+    /// File path or glob (e.g., 'vendor/*.csv').
+    pub path: String,
+
+    /// TOML mapping of vendor columns, timezone and source tag.
+    #[arg(long)]
+    pub mapping: Option<String>,
+
+    /// Source tag for imported rows; overrides the mapping's `source`.
+    #[arg(long)]
+    pub source_tag: Option<String>,
+
+    /// File format (csv, parquet); taken from the extension by default.
+    #[arg(long)]
+    pub file_format: Option<String>,
+
+    /// NDJSON file receiving rejected rows with their reasons.
+    #[arg(long, default_value = "import-rejects.ndjson")]
+    pub rejects: String,
+}
+
 #[derive(Debug, Args)]
 pub struct MlArgs {
     #[command(subcommand)]
@@ -458,7 +865,17 @@ pub enum MlCommand {
 #[derive(Debug, Args)]
 pub struct MlFeaturesArgs {
     /// Market symbol to compute features for.
-    pub symbol: String,
+    #[arg(required_unless_present = "universe", conflicts_with = "universe")]
+    pub symbol: Option<String>,
+
+    /// Compute features for every symbol that was ever a member of this
+    /// universe, keeping only rows of dates it was a member.
+    #[arg(long)]
+    pub universe: Option<String>,
+
+    /// Bar interval to compute features on (1m, 5m, 15m, 1h, 1d).
+    #[arg(long, default_value = "1d")]
+    pub interval: String,
 
     /// Indicator list: all,rsi,macd,bb,atr.
     #[arg(long, default_value = "all")]
@@ -506,14 +923,276 @@ pub enum CacheCommand {
     ///
     /// Fetches OHLCV bars for a given symbol and interval,
     /// storing them in both the DuckDB warehouse and as Parquet files
-    /// for analytics and ML workflows.
+    /// for analytics and ML workflows. Weekdays that already have stored
+    /// bars are skipped; the response lists fetched and present ranges.
     Load(CacheLoadArgs),
 
     /// Sync local Parquet cache partitions into warehouse metadata.
     ///
     /// Scans the cache directory for parquet files and registers them
     /// in the warehouse manifest for query access.
-    Sync,
+    Sync(CacheSyncArgs),
+
+    /// Rebuild warehouse tables from local Parquet cache partitions.
+    ///
+    /// Loads every cached bars partition back into the warehouse, so a
+    /// fresh warehouse can be restored from a copied cache directory.
+    Rebuild,
+
+    /// Materialize coarser bars from stored 1m bars.
+    ///
+    /// Rolled-up bars are marked with source `rollup` and never replace
+    /// bars fetched from a provider.
+    Rollup(CacheRollupArgs),
+
+    /// Apply retention, merge closed months into monthly files and vacuum.
+    ///
+    /// Expired partitions and bars are deleted, old ingest_log rows are
+    /// pruned, and daily partitions of past months are merged into one
+    /// Parquet file per month. Reports the bytes reclaimed.
+    Compact(CacheCompactArgs),
+}
+
+/// Arguments for the `warehouse` command group.
+#[derive(Debug, Args)]
+pub struct WarehouseArgs {
+    #[command(subcommand)]
+    pub command: WarehouseCommand,
+}
+
+/// Warehouse maintenance subcommands.
+#[derive(Debug, Subcommand)]
+pub enum WarehouseCommand {
+    /// Write a consistent snapshot of the database and parquet cache.
+    ///
+    /// Safe while the warehouse is in use. The output is a new directory,
+    /// or a tarball when it ends in .tar, .tar.gz or .tgz.
+    Snapshot(WarehouseSnapshotArgs),
+
+    /// Verify a snapshot and swap it in for the current warehouse.
+    ///
+    /// Checksums and the schema version are checked before anything is
+    /// replaced; the previous database and cache are kept in
+    /// `cache/pre-restore`. Stop daemons using the warehouse first.
+    Restore(WarehouseRestoreArgs),
+
+    /// Show, apply or revert schema migrations.
+    ///
+    /// Reads the database directly, so it also works on one this build
+    /// refuses to open because a newer build migrated it.
+    Migrate(WarehouseMigrateArgs),
+}
+
+/// Arguments for `warehouse snapshot` command.
+#[derive(Debug, Args)]
+pub struct WarehouseSnapshotArgs {
+    /// Snapshot directory or .tar/.tar.gz/.tgz file to create.
+    pub output: String,
+}
+
+/// Arguments for `warehouse restore` command.
+#[derive(Debug, Args)]
+pub struct WarehouseRestoreArgs {
+    /// Snapshot directory or tarball written by `warehouse snapshot`.
+    pub snapshot: String,
+}
+
+/// Arguments for `warehouse migrate` command group.
+#[derive(Debug, Args)]
+pub struct WarehouseMigrateArgs {
+    #[command(subcommand)]
+    pub command: MigrateCommand,
+}
+
+/// Schema migration subcommands.
+#[derive(Debug, Subcommand)]
+pub enum MigrateCommand {
+    /// List every migration and whether the database has it.
+    Status,
+
+    /// Apply pending migrations, up to --to or the latest.
+    Up(MigrateUpArgs),
+
+    /// Revert migrations applied after --to; `--to 0` reverts all of them.
+    ///
+    /// Run with the current build before downgrading ferrotick, so the
+    /// older build finds a schema it knows.
+    Down(MigrateDownArgs),
+}
+
+/// Arguments for `warehouse migrate up` command.
+#[derive(Debug, Args)]
+pub struct MigrateUpArgs {
+    /// Last migration to apply (e.g. 0009_unified_bars).
+    #[arg(long)]
+    pub to: Option<String>,
+
+    /// Print the migrations that would run without running them.
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+/// Arguments for `warehouse migrate down` command.
+#[derive(Debug, Args)]
+pub struct MigrateDownArgs {
+    /// Migration to leave in place; 0 reverts every migration.
+    #[arg(long)]
+    pub to: String,
+
+    /// Print the migrations that would be reverted without reverting them.
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+/// Arguments for the `lineage` command group.
+#[derive(Debug, Args)]
+pub struct LineageArgs {
+    #[command(subcommand)]
+    pub command: LineageCommand,
+}
+
+/// Lineage subcommands.
+#[derive(Debug, Subcommand)]
+pub enum LineageCommand {
+    /// History of one symbol's bars on one date, oldest version first.
+    Bars(LineageBarsArgs),
+
+    /// An ingest batch and the bar values it overwrote.
+    Batch(LineageBatchArgs),
+}
+
+/// Arguments for `lineage bars` command.
+#[derive(Debug, Args)]
+pub struct LineageBarsArgs {
+    /// Market symbol.
+    pub symbol: String,
+
+    /// Trading date (YYYY-MM-DD).
+    #[arg(long)]
+    pub date: String,
+
+    /// Bar interval (1m, 5m, 15m, 1h, 1d).
+    #[arg(long, default_value = "1d")]
+    pub interval: String,
+}
+
+/// Arguments for `lineage batch` command.
+#[derive(Debug, Args)]
+pub struct LineageBatchArgs {
+    /// Batch id, as shown by `lineage bars` or stored in `ingest_batches`.
+    pub batch_id: String,
+}
+
+/// Arguments for the `universe` command group.
+#[derive(Debug, Args)]
+pub struct UniverseArgs {
+    #[command(subcommand)]
+    pub command: UniverseCommand,
+}
+
+/// Universe subcommands.
+#[derive(Debug, Subcommand)]
+pub enum UniverseCommand {
+    /// Create an empty universe.
+    Create(UniverseCreateArgs),
+
+    /// Add symbols to a universe from a date on.
+    Add(UniverseChangeArgs),
+
+    /// End the membership of symbols on a date.
+    Remove(UniverseChangeArgs),
+
+    /// List universes with their current member counts.
+    List,
+
+    /// Members of a universe today, on a past date, or their full history.
+    Members(UniverseMembersArgs),
+}
+
+/// Arguments for `universe create` command.
+#[derive(Debug, Args)]
+pub struct UniverseCreateArgs {
+    /// Universe name (letters, digits, '_', '-', '.').
+    pub name: String,
+
+    /// Free-form description.
+    #[arg(long)]
+    pub description: Option<String>,
+}
+
+/// Arguments for `universe add` and `universe remove` commands.
+#[derive(Debug, Args)]
+pub struct UniverseChangeArgs {
+    /// Universe name.
+    pub name: String,
+
+    /// Symbols to add or remove.
+    #[arg(required = true, num_args = 1..)]
+    pub symbols: Vec<String>,
+
+    /// Date the change takes effect (YYYY-MM-DD, default: today).
+    #[arg(long)]
+    pub date: Option<String>,
+}
+
+/// Arguments for `universe members` command.
+#[derive(Debug, Args)]
+pub struct UniverseMembersArgs {
+    /// Universe name.
+    pub name: String,
+
+    /// List the members on this date (YYYY-MM-DD, default: today).
+    #[arg(long)]
+    pub as_of: Option<String>,
+
+    /// List every membership interval instead.
+    #[arg(long, conflicts_with = "as_of")]
+    pub history: bool,
+}
+
+/// Arguments for the `daemon` command group.
+#[derive(Debug, Args)]
+pub struct DaemonArgs {
+    #[command(subcommand)]
+    pub command: DaemonCommand,
+}
+
+/// Ingestion daemon subcommands.
+#[derive(Debug, Subcommand)]
+pub enum DaemonCommand {
+    /// Run the jobs of a spec until SIGINT or SIGTERM.
+    Run(DaemonRunArgs),
+
+    /// Show the latest daemon runs recorded in the warehouse.
+    Status(DaemonStatusArgs),
+}
+
+/// Arguments for `daemon run` command.
+#[derive(Debug, Args)]
+pub struct DaemonRunArgs {
+    /// Path to the YAML job spec.
+    #[arg(long)]
+    pub spec: String,
+
+    /// Serve job status as JSON on `/health` at this address.
+    #[arg(long)]
+    pub health_addr: Option<String>,
+
+    /// Run every job once immediately, then exit.
+    #[arg(long)]
+    pub once: bool,
+
+    /// Seconds running loads get to finish after a shutdown signal.
+    #[arg(long, default_value_t = 30)]
+    pub grace_secs: u64,
+}
+
+/// Arguments for `daemon status` command.
+#[derive(Debug, Args)]
+pub struct DaemonStatusArgs {
+    /// Maximum number of recent runs to return.
+    #[arg(long, default_value_t = 50)]
+    pub limit: usize,
 }
 
 /// Arguments for the `schema` command group.
@@ -535,6 +1214,12 @@ pub enum SchemaCommand {
     ///
     /// Outputs the full JSON schema for the specified type.
     Get(SchemaGetArgs),
+
+    /// List warehouse analytics views and table macros.
+    ///
+    /// Shows each versioned view or macro with its parameters, defaults,
+    /// output columns and an example query for `ferrotick sql`.
+    Views,
 }
 
 /// Arguments for `schema get` command.
@@ -615,7 +1300,7 @@ mod tests {
         match cli.command {
             Command::Ml(args) => match args.command {
                 MlCommand::Features(feature_args) => {
-                    assert_eq!(feature_args.symbol, "AAPL");
+                    assert_eq!(feature_args.symbol.as_deref(), Some("AAPL"));
                     assert_eq!(feature_args.window, 20);
                     assert_eq!(feature_args.output, "json");
                 }
@@ -625,6 +1310,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn cache_load_takes_a_universe_instead_of_a_symbol() {
+        let cli = Cli::try_parse_from(["ferrotick", "cache", "load", "--universe", "sp500"])
+            .expect("parse");
+
+        match cli.command {
+            Command::Cache(args) => match args.command {
+                CacheCommand::Load(load_args) => {
+                    assert_eq!(load_args.symbol, None);
+                    assert_eq!(load_args.universe.as_deref(), Some("sp500"));
+                }
+                _ => panic!("expected cache load"),
+            },
+            _ => panic!("expected cache command"),
+        }
+        assert!(Cli::try_parse_from(["ferrotick", "cache", "load"]).is_err());
+        assert!(Cli::try_parse_from([
+            "ferrotick",
+            "export",
+            "--universe",
+            "sp500",
+            "--output",
+            "out.parquet"
+        ])
+        .is_err());
+    }
+
     #[test]
     fn parses_ml_export_command() {
         let cli = Cli::try_parse_from([
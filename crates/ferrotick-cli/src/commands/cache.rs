@@ -1,29 +1,83 @@
-use ferrotick_core::{ProviderId, Warehouse};
+use std::str::FromStr;
 
-use crate::cli::{CacheArgs, CacheCommand};
+use ferrotick_core::{Interval, ProviderId, RetentionPolicy, Symbol, Warehouse};
+
+use crate::cli::{CacheArgs, CacheCommand, CacheCompactArgs};
 use crate::error::CliError;
 
 use super::CommandResult;
 
 pub fn run(args: &CacheArgs, source_chain: Vec<ProviderId>) -> Result<CommandResult, CliError> {
-    match args.command {
-        CacheCommand::Sync => {
+    match &args.command {
+        CacheCommand::Sync(sync_args) => {
+            let warehouse =
+                Warehouse::open_default().map_err(|error| CliError::Command(error.to_string()))?;
+            let report = match &sync_args.universe {
+                // Past members too: the cache holds their history.
+                Some(universe) => warehouse
+                    .universe_membership(universe)
+                    .and_then(|membership| warehouse.sync_cache_symbols(&membership.symbols())),
+                None => warehouse.sync_cache(),
+            }
+            .map_err(|error| CliError::Command(error.to_string()))?;
+            let mut result = CommandResult::ok(serde_json::to_value(report)?, source_chain);
+            if result_has_sync_failures(&result.data) {
+                result = result.with_warning(String::from(
+                    "cache sync completed with failures; inspect failed_partitions",
+                ));
+            }
+            Ok(result)
+        }
+        CacheCommand::Rebuild => {
             let warehouse =
                 Warehouse::open_default().map_err(|error| CliError::Command(error.to_string()))?;
             let report = warehouse
-                .sync_cache()
+                .rebuild_from_cache()
                 .map_err(|error| CliError::Command(error.to_string()))?;
             let mut result = CommandResult::ok(serde_json::to_value(report)?, source_chain);
             if result_has_sync_failures(&result.data) {
                 result = result.with_warning(String::from(
-                    "cache sync completed with failures; inspect failed_partitions",
+                    "cache rebuild completed with failures; inspect failures",
                 ));
             }
             Ok(result)
         }
+        CacheCommand::Rollup(rollup_args) => {
+            let interval = Interval::from_str(&rollup_args.interval)?;
+            let symbol = rollup_args
+                .symbol
+                .as_deref()
+                .map(Symbol::parse)
+                .transpose()?;
+            let warehouse =
+                Warehouse::open_default().map_err(|error| CliError::Command(error.to_string()))?;
+            let bars_written = warehouse
+                .rollup_bars(interval.as_str(), symbol.as_ref().map(Symbol::as_str))
+                .map_err(|error| CliError::Command(error.to_string()))?;
+            Ok(CommandResult::ok(
+                serde_json::json!({
+                    "interval": interval,
+                    "symbol": symbol.as_ref().map(Symbol::as_str),
+                    "bars_written": bars_written,
+                }),
+                source_chain,
+            ))
+        }
+        CacheCommand::Compact(compact_args) => {
+            let policy = retention_policy(compact_args)?;
+            let warehouse =
+                Warehouse::open_default().map_err(|error| CliError::Command(error.to_string()))?;
+            let report = warehouse
+                .compact_cache(&policy, compact_args.dry_run)
+                .map_err(|error| CliError::Command(error.to_string()))?;
+            Ok(CommandResult::ok(
+                serde_json::to_value(report)?,
+                source_chain,
+            ))
+        }
         CacheCommand::Load(_) => {
             // This is handled in cache_load.rs module
-            // This run() function is only for CacheCommand::Sync
+            // This run() function handles every other cache subcommand
             Err(CliError::Command(
                 "cache load command should be handled by cache_load module".to_string(),
             ))
@@ -31,6 +85,27 @@ pub fn run(args: &CacheArgs, source_chain: Vec<ProviderId>) -> Result<CommandRes
     }
 }
 
+fn retention_policy(args: &CacheCompactArgs) -> Result<RetentionPolicy, CliError> {
+    let mut policy = RetentionPolicy::default()
+        .with_monthly_target_bytes(args.monthly_target_mb.saturating_mul(1024 * 1024));
+    for keep in &args.keep {
+        let invalid = || {
+            CliError::Command(format!(
+                "--keep expects DATASET=DAYS or DATASET=forever, got '{keep}'"
+            ))
+        };
+        let (dataset, days) = keep.split_once('=').ok_or_else(invalid)?;
+        if dataset.is_empty() {
+            return Err(invalid());
+        }
+        policy = match days {
+            "forever" => policy.with_keep_forever(dataset),
+            days => policy.with_keep_days(dataset, days.parse().map_err(|_| invalid())?),
+        };
+    }
+    Ok(policy)
+}
+
 fn result_has_sync_failures(data: &serde_json::Value) -> bool {
     data.get("failed_partitions")
         .and_then(|value| value.as_u64())
@@ -1,21 +1,35 @@
 //! Load historical data from providers into the warehouse cache.
+//!
+//! Loading is incremental: weekdays of the window that already have stored
+//! bars are skipped, and only bars falling into the missing ranges are
+//! upserted. Missing weekdays the provider covered without returning bars,
+//! such as exchange holidays, are recorded as empty and not fetched again.
+//! The most recent session is always refetched because its bars may still
+//! have been forming when it was last loaded.
+//!
+//! With `--universe`, today's members are loaded one after another and the
+//! response lists one load per symbol. A member that fails to load is
+//! reported in the envelope errors and the remaining members still load.
 
 use ferrotick_core::{
-    BarsRequest, Interval, ProviderId, SourceRouter, SourceStrategy, Symbol, UtcDateTime,
+    BarCoverage, BarsRequest, DateRange, Endpoint, EnvelopeError, Interval, ProviderId,
+    SourceRouter, SourceStrategy, Symbol, UtcDateTime, Warehouse,
 };
 use ferrotick_warehouse::BarRecord;
+use time::format_description::well_known::Iso8601;
+use time::{Date, Duration, Weekday};
 
 use crate::cli::CacheLoadArgs;
 use crate::error::CliError;
 
-use super::CommandResult;
+use super::{universe, CommandResult};
 
 pub async fn run(
     args: &CacheLoadArgs,
     router: &SourceRouter,
     strategy: SourceStrategy,
 ) -> Result<CommandResult, CliError> {
-    let symbol = Symbol::parse(&args.symbol).map_err(CliError::Validation)?;
+    let symbols = universe::resolve_symbols(args.symbol.as_slice(), args.universe.as_deref())?;
 
     let warehouse = ferrotick_warehouse::Warehouse::open_default()
         .map_err(|error| CliError::Command(error.to_string()))?;
@@ -34,95 +48,286 @@ pub async fn run(
         "1d" => Interval::OneDay,
         _ => Interval::OneDay,
     };
+    let started = UtcDateTime::now().into_inner().unix_timestamp();
 
-    let limit = (days * 24) as usize; // Approximate bars per day
-    let now = UtcDateTime::now();
-    let request_id = format!(
-        "cache_load:{}:{}",
-        args.symbol,
-        now.into_inner().unix_timestamp()
-    );
+    let mut responses = Vec::new();
+    let mut errors = Vec::new();
+    let mut source_chain = Vec::new();
+    for symbol in &symbols {
+        let request_id = format!("cache_load:{}:{started}", symbol.as_str());
+        let outcome = match load(
+            &warehouse,
+            router,
+            strategy.clone(),
+            symbol,
+            interval,
+            days,
+            &request_id,
+        )
+        .await
+        {
+            Ok(outcome) => outcome,
+            // A single symbol has nothing else to report.
+            Err(error) if symbols.len() == 1 => return Err(error),
+            Err(error) => {
+                errors.push(
+                    EnvelopeError::new(
+                        "cache_load.failed",
+                        format!("{}: {error}", symbol.as_str()),
+                    )
+                    .expect("code/message are non-empty"),
+                );
+                continue;
+            }
+        };
+        if outcome.response.bars_loaded > 0 {
+            eprintln!(
+                "✓ Cached {} {} bars to warehouse",
+                outcome.response.bars_loaded,
+                symbol.as_str()
+            );
+        }
+        for source in &outcome.response.source_chain {
+            if !source_chain.contains(source) {
+                source_chain.push(*source);
+            }
+        }
+        errors.extend(outcome.errors);
+        responses.push(outcome.response);
+    }
 
-    // Fetch bars
-    let bars_request = BarsRequest::new(symbol.clone(), interval, limit)?;
-    let route_result = router.route_bars(&bars_request, strategy).await;
+    // Nothing is fetched when the window is already covered.
+    if source_chain.is_empty() {
+        source_chain = router
+            .source_chain_for_strategy(Endpoint::Bars, &strategy)
+            .await;
+    }
+    let data = match (&args.universe, responses.as_slice()) {
+        (None, [response]) => serde_json::to_value(response)?,
+        _ => serde_json::json!({ "universe": args.universe, "loads": responses }),
+    };
+    Ok(CommandResult::ok(data, source_chain).with_errors(errors))
+}
+
+/// Result of one incremental load, with the provider errors of a failed
+/// route kept apart from the response body.
+pub(crate) struct LoadOutcome {
+    pub response: CacheLoadResponse,
+    pub errors: Vec<EnvelopeError>,
+}
+
+/// Fetches and upserts the bars of `symbol` missing from the last `days`
+/// days, tagging the ingest under `request_id`.
+pub(crate) async fn load(
+    warehouse: &Warehouse,
+    router: &SourceRouter,
+    strategy: SourceStrategy,
+    symbol: &Symbol,
+    interval: Interval,
+    days: u32,
+    request_id: &str,
+) -> Result<LoadOutcome, CliError> {
+    let today = UtcDateTime::now().into_inner().date();
+    let window = DateRange {
+        start: (today - Duration::days(i64::from(days))).to_string(),
+        end: today.to_string(),
+    };
+    let mut coverage = warehouse
+        .bar_coverage(
+            interval.bars_dataset(),
+            symbol.as_str(),
+            &window.start,
+            &window.end,
+        )
+        .map_err(|error| CliError::Command(error.to_string()))?;
+    refetch_last_session(&mut coverage, today);
+
+    let mut response = CacheLoadResponse {
+        symbol: symbol.as_str().to_string(),
+        days,
+        interval: interval.as_str().to_string(),
+        window,
+        present_ranges: coverage.present,
+        empty_ranges: coverage.empty,
+        missing_ranges: coverage.missing,
+        fetched_ranges: Vec::new(),
+        bars_loaded: 0,
+        source_chain: Vec::new(),
+        cached_at: UtcDateTime::now().format_rfc3339(),
+    };
+    let Some(first_gap) = response.missing_ranges.first() else {
+        return Ok(LoadOutcome {
+            response,
+            errors: Vec::new(),
+        });
+    };
 
-    match route_result {
+    // Providers return the most recent `limit` bars, so reach back to the
+    // oldest gap and keep only the bars that fall inside a gap.
+    let oldest_gap = Date::parse(&first_gap.start, &Iso8601::DATE)
+        .map_err(|error| CliError::Command(error.to_string()))?;
+    let limit = weekdays_between(oldest_gap, today) * interval.bars_per_session();
+
+    let bars_request = BarsRequest::new(symbol.clone(), interval, limit)?;
+    match router.route_bars(&bars_request, strategy).await {
         Ok(result) => {
             let bars = result.data;
-            let source_chain = result.source_chain.clone();
-
-            // Ingest into warehouse
-            if !bars.bars.is_empty() {
-                let bar_records: Vec<BarRecord> = bars
-                    .bars
-                    .iter()
-                    .map(|bar| BarRecord {
-                        symbol: bars.symbol.as_str().to_string(),
-                        ts: bar.ts.format_rfc3339(),
-                        open: bar.open,
-                        high: bar.high,
-                        low: bar.low,
-                        close: bar.close,
-                        volume: bar.volume,
-                    })
-                    .collect();
+            let bar_records: Vec<BarRecord> = bars
+                .bars
+                .iter()
+                .map(|bar| BarRecord {
+                    symbol: bars.symbol.as_str().to_string(),
+                    ts: bar.ts.format_rfc3339(),
+                    open: bar.open,
+                    high: bar.high,
+                    low: bar.low,
+                    close: bar.close,
+                    volume: bar.volume,
+                })
+                .filter(|record| in_ranges(&record.ts, &response.missing_ranges))
+                .collect();
 
+            if !bar_records.is_empty() {
                 warehouse
                     .ingest_bars(
-                        source_chain.first().unwrap_or(&ProviderId::Yahoo).as_str(),
-                        &format!("bars_{}", interval_str(interval)),
-                        &request_id,
+                        result
+                            .source_chain
+                            .first()
+                            .unwrap_or(&ProviderId::Yahoo)
+                            .as_str(),
+                        interval.bars_dataset(),
+                        request_id,
                         &bar_records,
                         result.latency_ms,
                     )
                     .map_err(|error| CliError::Command(error.to_string()))?;
+            }
 
-                eprintln!("✓ Cached {} bars to warehouse", bars.bars.len());
+            // Gap days inside the span the provider returned but without any
+            // bar are holidays or halts. The latest session is left out: it
+            // may simply not have traded yet.
+            let returned_days = bars.bars.iter().map(|bar| bar.ts.into_inner().date());
+            if let (Some(first), Some(last)) = (returned_days.clone().min(), returned_days.max()) {
+                let last = last.min(last_weekday(last_weekday(today) - Duration::days(1)));
+                for range in covered(
+                    &response.missing_ranges,
+                    &first.to_string(),
+                    &last.to_string(),
+                ) {
+                    warehouse
+                        .record_empty_sessions(
+                            interval.bars_dataset(),
+                            symbol.as_str(),
+                            &range.start,
+                            &range.end,
+                        )
+                        .map_err(|error| CliError::Command(error.to_string()))?;
+                }
             }
 
-            let response_value = serde_json::to_value(CacheLoadResponse {
-                symbol: args.symbol.clone(),
-                days,
-                interval: args.interval.clone(),
-                bars_loaded: bars.bars.len(),
-                source_chain: source_chain.clone(),
-                cached_at: UtcDateTime::now().format_rfc3339(),
-            })?;
+            response.fetched_ranges = response.missing_ranges.clone();
+            response.bars_loaded = bar_records.len();
+            response.source_chain = result.source_chain;
+            response.cached_at = UtcDateTime::now().format_rfc3339();
+            Ok(LoadOutcome {
+                response,
+                errors: Vec::new(),
+            })
+        }
+        Err(failure) => {
+            response.source_chain = failure.source_chain;
+            Ok(LoadOutcome {
+                response,
+                errors: failure.errors,
+            })
+        }
+    }
+}
+
+/// Moves the most recent weekday on or before `today` from the present
+/// ranges into the missing ones.
+fn refetch_last_session(coverage: &mut BarCoverage, today: Date) {
+    let last_session = last_weekday(today).to_string();
+    let previous_session = last_weekday(last_weekday(today) - Duration::days(1)).to_string();
 
-            Ok(CommandResult::ok(response_value, source_chain))
+    if let Some(range) = coverage.present.last_mut() {
+        if range.end != last_session {
+            return;
         }
-        Err(failure) => Ok(CommandResult::ok(
-            serde_json::to_value(CacheLoadResponse {
-                symbol: args.symbol.clone(),
-                days,
-                interval: args.interval.clone(),
-                bars_loaded: 0,
-                source_chain: failure.source_chain.clone(),
-                cached_at: UtcDateTime::now().format_rfc3339(),
-            })?,
-            failure.source_chain,
-        )
-        .with_errors(failure.errors)),
+        if range.start == last_session {
+            coverage.present.pop();
+        } else {
+            range.end = previous_session.clone();
+        }
+    } else {
+        return;
+    }
+
+    match coverage.missing.last_mut() {
+        Some(range) if range.end == previous_session => range.end = last_session,
+        _ => coverage.missing.push(DateRange {
+            start: last_session.clone(),
+            end: last_session,
+        }),
     }
 }
 
-#[derive(Debug, serde::Serialize)]
-struct CacheLoadResponse {
-    symbol: String,
-    days: u32,
-    interval: String,
-    bars_loaded: usize,
-    source_chain: Vec<ProviderId>,
-    cached_at: String,
+fn last_weekday(mut date: Date) -> Date {
+    while matches!(date.weekday(), Weekday::Saturday | Weekday::Sunday) {
+        date -= Duration::days(1);
+    }
+    date
 }
 
-fn interval_str(interval: Interval) -> &'static str {
-    match interval {
-        Interval::OneMinute => "1m",
-        Interval::FiveMinutes => "5m",
-        Interval::FifteenMinutes => "15m",
-        Interval::OneHour => "1h",
-        Interval::OneDay => "1d",
+fn weekdays_between(start: Date, end: Date) -> usize {
+    let mut count = 0;
+    let mut date = start;
+    while date <= end {
+        if !matches!(date.weekday(), Weekday::Saturday | Weekday::Sunday) {
+            count += 1;
+        }
+        date += Duration::days(1);
     }
+    count.max(1)
+}
+
+/// The parts of `ranges` between `first` and `last` (inclusive dates).
+fn covered(ranges: &[DateRange], first: &str, last: &str) -> Vec<DateRange> {
+    ranges
+        .iter()
+        .filter_map(|range| {
+            let start = range.start.as_str().max(first);
+            let end = range.end.as_str().min(last);
+            (start <= end).then(|| DateRange {
+                start: start.to_string(),
+                end: end.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Whether the RFC 3339 timestamp `ts` falls on a date inside one of `ranges`.
+fn in_ranges(ts: &str, ranges: &[DateRange]) -> bool {
+    let date = ts.get(..10).unwrap_or(ts);
+    ranges
+        .iter()
+        .any(|range| range.start.as_str() <= date && date <= range.end.as_str())
+}
+
+#[derive(Debug, serde::Serialize)]
+pub(crate) struct CacheLoadResponse {
+    pub symbol: String,
+    pub days: u32,
+    pub interval: String,
+    pub window: DateRange,
+    pub present_ranges: Vec<DateRange>,
+    /// Weekdays fetched before without getting bars, such as holidays.
+    pub empty_ranges: Vec<DateRange>,
+    /// Weekdays without stored bars when the load started.
+    pub missing_ranges: Vec<DateRange>,
+    /// The missing ranges, once a provider was actually asked for them.
+    pub fetched_ranges: Vec<DateRange>,
+    pub bars_loaded: usize,
+    pub source_chain: Vec<ProviderId>,
+    pub cached_at: String,
 }
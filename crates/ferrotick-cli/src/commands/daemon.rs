@@ -0,0 +1,428 @@
+//! Scheduled ingestion daemon.
+//!
+//! Runs the incremental `cache load` for every job of a YAML spec on its
+//! schedule, with at most `concurrency` loads in flight and retries with
+//! backoff. Every run is recorded in `ingest_log` under a `daemon:<job>:`
+//! request id, which `daemon status` reads back. SIGINT or SIGTERM stops
+//! scheduling and gives running loads `--grace-secs` to finish before their
+//! provider calls are cancelled through the request context.
+
+mod schedule;
+mod spec;
+
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::routing::get;
+use axum::{Json, Router};
+use ferrotick_core::{
+    Backoff, CancellationToken, IngestRun, Interval, ProviderId, RequestContext, SourceRouter,
+    SourceRouterBuilder, SourceStrategy, Symbol, UtcDateTime, Warehouse,
+};
+use serde::Serialize;
+use serde_json::Value;
+use time::OffsetDateTime;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use uuid::Uuid;
+
+use self::spec::{DaemonConfig, Job};
+use super::{cache_load, CommandResult};
+use crate::cli::{DaemonCommand, DaemonRunArgs, DaemonStatusArgs};
+use crate::error::CliError;
+
+const REQUEST_PREFIX: &str = "daemon:";
+
+pub async fn run(
+    command: &DaemonCommand,
+    timeout_ms: u64,
+    source_chain: Vec<ProviderId>,
+) -> Result<CommandResult, CliError> {
+    match command {
+        DaemonCommand::Run(args) => serve(args, timeout_ms, source_chain).await,
+        DaemonCommand::Status(args) => status(args, source_chain),
+    }
+}
+
+/// Per-job counters exposed on `/health` and in the final summary.
+#[derive(Debug, Clone, Serialize)]
+struct JobStatus {
+    schedule: String,
+    next_run: Option<String>,
+    last_run: Option<String>,
+    last_status: Option<&'static str>,
+    runs: u64,
+    failures: u64,
+    in_flight: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct DaemonStatus {
+    status: &'static str,
+    started_at: String,
+    jobs: BTreeMap<String, JobStatus>,
+}
+
+type SharedStatus = Arc<Mutex<DaemonStatus>>;
+
+/// Everything a spawned load needs, shared across tasks.
+struct LoadContext {
+    warehouse: Warehouse,
+    router: SourceRouter,
+    status: SharedStatus,
+    /// Set on SIGINT/SIGTERM: no new runs or retries start.
+    stop: CancellationToken,
+    /// Set once the grace period is over: in-flight provider calls abort.
+    cancel: CancellationToken,
+    permits: Semaphore,
+    retries: u32,
+    timeout_ms: u64,
+}
+
+async fn serve(
+    args: &DaemonRunArgs,
+    timeout_ms: u64,
+    source_chain: Vec<ProviderId>,
+) -> Result<CommandResult, CliError> {
+    let config = DaemonConfig::load(Path::new(&args.spec))?;
+    let warehouse =
+        Warehouse::open_default().map_err(|error| CliError::Command(error.to_string()))?;
+
+    let now = OffsetDateTime::now_utc();
+    let status: SharedStatus = Arc::new(Mutex::new(DaemonStatus {
+        status: "running",
+        started_at: UtcDateTime::now().format_rfc3339(),
+        jobs: config
+            .jobs
+            .iter()
+            .map(|job| {
+                (
+                    job.name.clone(),
+                    JobStatus {
+                        schedule: job.schedule_expression.clone(),
+                        next_run: job.schedule.next_after(now).map(format_time),
+                        last_run: None,
+                        last_status: None,
+                        runs: 0,
+                        failures: 0,
+                        in_flight: 0,
+                    },
+                )
+            })
+            .collect(),
+    }));
+
+    let stop = CancellationToken::new();
+    let cancel = CancellationToken::new();
+    tokio::spawn(forward_signals(stop.clone()));
+    if let Some(addr) = args.health_addr.as_deref() {
+        let addr = addr
+            .parse::<SocketAddr>()
+            .map_err(|error| CliError::Command(format!("invalid --health-addr: {error}")))?;
+        serve_health(addr, Arc::clone(&status), cancel.clone()).await?;
+    }
+
+    let context = Arc::new(LoadContext {
+        warehouse,
+        router: SourceRouterBuilder::new().with_real_clients().build(),
+        status: Arc::clone(&status),
+        stop: stop.clone(),
+        cancel: cancel.clone(),
+        permits: Semaphore::new(config.concurrency),
+        retries: config.retries,
+        timeout_ms,
+    });
+    let mut loads = JoinSet::new();
+
+    if args.once {
+        for job in &config.jobs {
+            spawn_job(&mut loads, &context, job);
+        }
+        tokio::select! {
+            _ = async { while loads.join_next().await.is_some() {} } => {}
+            _ = stop.cancelled() => {}
+        }
+    } else {
+        let mut next_runs = config
+            .jobs
+            .iter()
+            .map(|job| job.schedule.next_after(now))
+            .collect::<Vec<_>>();
+        while !stop.is_cancelled() {
+            let Some(due) = next_runs.iter().flatten().min().copied() else {
+                tracing::warn!("no daemon job has an upcoming run");
+                break;
+            };
+            let wait = (due - OffsetDateTime::now_utc())
+                .try_into()
+                .unwrap_or(Duration::ZERO);
+            tokio::select! {
+                _ = tokio::time::sleep(wait) => {}
+                _ = stop.cancelled() => break,
+            }
+            while loads.try_join_next().is_some() {}
+
+            for (job, next_run) in config.jobs.iter().zip(next_runs.iter_mut()) {
+                if *next_run != Some(due) {
+                    continue;
+                }
+                *next_run = job.schedule.next_after(due);
+                let busy = {
+                    let mut status = lock(&status);
+                    let entry = status.jobs.get_mut(&job.name).expect("job status");
+                    entry.next_run = next_run.map(format_time);
+                    entry.in_flight > 0
+                };
+                if busy {
+                    tracing::warn!(job = %job.name, "previous run still in flight; skipping");
+                    continue;
+                }
+                spawn_job(&mut loads, &context, job);
+            }
+        }
+    }
+
+    // Graceful shutdown: running loads get the grace period, then their
+    // provider calls are cancelled so they still record a failed run.
+    let grace = Duration::from_secs(args.grace_secs);
+    let drained = tokio::time::timeout(grace, async { while loads.join_next().await.is_some() {} })
+        .await
+        .is_ok();
+    cancel.cancel();
+    if !drained {
+        let _ = tokio::time::timeout(Duration::from_secs(5), async {
+            while loads.join_next().await.is_some() {}
+        })
+        .await;
+        loads.abort_all();
+    }
+
+    let mut status = lock(&status);
+    status.status = "stopped";
+    let summary = serde_json::to_value(&*status)?;
+    drop(status);
+    let mut result = CommandResult::ok(summary, source_chain);
+    if !drained {
+        result = result.with_warning(format!(
+            "loads still running after {}s were cancelled",
+            args.grace_secs
+        ));
+    }
+    Ok(result)
+}
+
+fn spawn_job(loads: &mut JoinSet<()>, context: &Arc<LoadContext>, job: &Job) {
+    {
+        let mut status = lock(&context.status);
+        let entry = status.jobs.get_mut(&job.name).expect("job status");
+        entry.in_flight += job.symbols.len() * job.intervals.len();
+        entry.last_run = Some(UtcDateTime::now().format_rfc3339());
+    }
+    for symbol in &job.symbols {
+        for interval in &job.intervals {
+            let context = Arc::clone(context);
+            let job_name = job.name.clone();
+            let symbol = symbol.clone();
+            let interval = *interval;
+            let days = job.days;
+            let strategy = job.strategy.clone();
+            loads.spawn(async move {
+                let succeeded =
+                    run_load(&context, &job_name, &symbol, interval, days, strategy).await;
+                let mut status = lock(&context.status);
+                let entry = status.jobs.get_mut(&job_name).expect("job status");
+                entry.in_flight -= 1;
+                entry.runs += 1;
+                if succeeded {
+                    entry.last_status = Some("succeeded");
+                } else {
+                    entry.failures += 1;
+                    entry.last_status = Some("failed");
+                }
+            });
+        }
+    }
+}
+
+/// Loads one symbol and interval with retries, recording the run in
+/// `ingest_log`. Returns whether the load succeeded.
+async fn run_load(
+    context: &LoadContext,
+    job_name: &str,
+    symbol: &Symbol,
+    interval: Interval,
+    days: u32,
+    strategy: SourceStrategy,
+) -> bool {
+    let Ok(_permit) = context.permits.acquire().await else {
+        return false;
+    };
+    let request_id = format!("{REQUEST_PREFIX}{job_name}:{}", Uuid::new_v4());
+    let started = Instant::now();
+    let backoff = Backoff::default();
+    let mut attempt = 0;
+
+    let (source, status, detail) = loop {
+        let request = RequestContext::with_timeout(Duration::from_millis(context.timeout_ms))
+            .with_cancellation(context.cancel.clone());
+        let outcome = request
+            .scope(cache_load::load(
+                &context.warehouse,
+                &context.router,
+                strategy.clone(),
+                symbol,
+                interval,
+                days,
+                &request_id,
+            ))
+            .await;
+
+        let error = match outcome {
+            Ok(outcome) if outcome.errors.is_empty() => {
+                let source = outcome
+                    .response
+                    .source_chain
+                    .first()
+                    .map_or("auto", |provider| provider.as_str())
+                    .to_string();
+                let detail = format!(
+                    "loaded {} bars into {} gap(s)",
+                    outcome.response.bars_loaded,
+                    outcome.response.fetched_ranges.len()
+                );
+                break (source, "succeeded", detail);
+            }
+            Ok(outcome) => outcome
+                .errors
+                .iter()
+                .map(|error| error.message.as_str())
+                .collect::<Vec<_>>()
+                .join("; "),
+            Err(error) => error.to_string(),
+        };
+
+        if attempt >= context.retries || context.stop.is_cancelled() {
+            break (String::from("auto"), "failed", error);
+        }
+        tracing::warn!(job = job_name, symbol = symbol.as_str(), attempt, %error, "load failed; retrying");
+        tokio::select! {
+            _ = tokio::time::sleep(backoff.delay(attempt)) => {}
+            _ = context.stop.cancelled() => {}
+        }
+        attempt += 1;
+    };
+
+    let latency_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+    if let Err(error) = context.warehouse.record_ingest_run(
+        &request_id,
+        Some(symbol.as_str()),
+        &source,
+        interval.bars_dataset(),
+        status,
+        latency_ms,
+        &detail,
+    ) {
+        tracing::error!(job = job_name, %error, "failed to record ingest run");
+    }
+    status == "succeeded"
+}
+
+async fn serve_health(
+    addr: SocketAddr,
+    status: SharedStatus,
+    shutdown: CancellationToken,
+) -> Result<(), CliError> {
+    let app = Router::new().route(
+        "/health",
+        get(move || {
+            let status = Arc::clone(&status);
+            async move { Json(serde_json::to_value(&*lock(&status)).unwrap_or(Value::Null)) }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    tokio::spawn(async move {
+        let server = axum::serve(listener, app)
+            .with_graceful_shutdown(async move { shutdown.cancelled().await });
+        if let Err(error) = server.await {
+            tracing::error!(%error, "daemon health endpoint failed");
+        }
+    });
+    Ok(())
+}
+
+async fn forward_signals(stop: CancellationToken) {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        if let Ok(mut terminate) = signal(SignalKind::terminate()) {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {}
+                _ = terminate.recv() => {}
+            }
+            stop.cancel();
+            return;
+        }
+    }
+    if tokio::signal::ctrl_c().await.is_ok() {
+        stop.cancel();
+    }
+}
+
+/// Latest run of every job, derived from the daemon entries in `ingest_log`.
+fn status(
+    args: &DaemonStatusArgs,
+    source_chain: Vec<ProviderId>,
+) -> Result<CommandResult, CliError> {
+    let warehouse =
+        Warehouse::open_default().map_err(|error| CliError::Command(error.to_string()))?;
+    let runs = warehouse
+        .ingest_runs(REQUEST_PREFIX, args.limit)
+        .map_err(|error| CliError::Command(error.to_string()))?;
+
+    let mut jobs = BTreeMap::<String, Value>::new();
+    for run in &runs {
+        let Some(job) = job_name(run) else {
+            continue;
+        };
+        let entry = jobs.entry(job.to_string()).or_insert_with(|| {
+            serde_json::json!({
+                "last_run": run.timestamp,
+                "last_status": run.status,
+                "runs": 0,
+                "failures": 0,
+            })
+        });
+        entry["runs"] = Value::from(entry["runs"].as_u64().unwrap_or(0) + 1);
+        if run.status == "failed" {
+            entry["failures"] = Value::from(entry["failures"].as_u64().unwrap_or(0) + 1);
+        }
+    }
+
+    Ok(CommandResult::ok(
+        serde_json::json!({ "jobs": jobs, "runs": runs }),
+        source_chain,
+    ))
+}
+
+fn job_name(run: &IngestRun) -> Option<&str> {
+    run.request_id
+        .strip_prefix(REQUEST_PREFIX)?
+        .rsplit_once(':')
+        .map(|(job, _)| job)
+}
+
+fn format_time(value: OffsetDateTime) -> String {
+    UtcDateTime::from_offset_datetime(value)
+        .map(UtcDateTime::format_rfc3339)
+        .unwrap_or_default()
+}
+
+fn lock(status: &SharedStatus) -> std::sync::MutexGuard<'_, DaemonStatus> {
+    status
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
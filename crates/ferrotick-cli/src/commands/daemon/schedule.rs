@@ -0,0 +1,212 @@
+//! Cron-style schedule expressions for ingestion jobs.
+//!
+//! Supports the classic five fields (minute, hour, day of month, month, day
+//! of week, all in UTC) with `*`, `*/n`, `a-b`, `a-b/n` and comma lists, plus
+//! the `@hourly` and `@daily` shorthands. Day of week runs 0-6 from Sunday;
+//! 7 is accepted as Sunday too. As in cron, when both day of month and day
+//! of week are restricted a day matches if either field does.
+
+use std::str::FromStr;
+
+use time::{Duration, OffsetDateTime};
+
+use crate::error::CliError;
+
+/// A parsed schedule expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    minutes: Vec<u8>,
+    hours: Vec<u8>,
+    days_of_month: Vec<u8>,
+    months: Vec<u8>,
+    days_of_week: Vec<u8>,
+    /// Both day fields were restricted, so either one may match.
+    either_day: bool,
+}
+
+impl Schedule {
+    /// First minute strictly after `after` that matches the schedule.
+    ///
+    /// Returns `None` when nothing matches within four years, which only
+    /// happens for impossible dates such as `0 0 31 2 *`.
+    pub fn next_after(&self, after: OffsetDateTime) -> Option<OffsetDateTime> {
+        let mut candidate =
+            after.replace_second(0).ok()?.replace_nanosecond(0).ok()? + Duration::minutes(1);
+        let limit = after + Duration::days(4 * 366);
+
+        while candidate <= limit {
+            if !self.months.contains(&u8::from(candidate.month())) || !self.day_matches(candidate) {
+                candidate = candidate.replace_time(time::Time::MIDNIGHT) + Duration::days(1);
+                continue;
+            }
+            if !self.hours.contains(&candidate.hour()) {
+                candidate = candidate.replace_minute(0).ok()? + Duration::hours(1);
+                continue;
+            }
+            if !self.minutes.contains(&candidate.minute()) {
+                candidate += Duration::minutes(1);
+                continue;
+            }
+            return Some(candidate);
+        }
+        None
+    }
+
+    fn day_matches(&self, candidate: OffsetDateTime) -> bool {
+        let day_of_month = self.days_of_month.contains(&candidate.day());
+        let day_of_week = self
+            .days_of_week
+            .contains(&candidate.weekday().number_days_from_sunday());
+        if self.either_day {
+            day_of_month || day_of_week
+        } else {
+            day_of_month && day_of_week
+        }
+    }
+}
+
+impl FromStr for Schedule {
+    type Err = CliError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let expression = match value.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" => "0 0 * * *",
+            other => other,
+        };
+        let fields = expression.split_whitespace().collect::<Vec<_>>();
+        let [minute, hour, day_of_month, month, day_of_week] = fields.as_slice() else {
+            return Err(CliError::Command(format!(
+                "schedule '{value}' must have five fields (minute hour day month weekday)"
+            )));
+        };
+
+        let mut days_of_week = parse_field(day_of_week, 0, 7, value)?;
+        if days_of_week.contains(&7) {
+            days_of_week.retain(|day| *day != 7);
+            if !days_of_week.contains(&0) {
+                days_of_week.insert(0, 0);
+            }
+        }
+
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59, value)?,
+            hours: parse_field(hour, 0, 23, value)?,
+            days_of_month: parse_field(day_of_month, 1, 31, value)?,
+            months: parse_field(month, 1, 12, value)?,
+            days_of_week,
+            either_day: !day_of_month.starts_with('*') && !day_of_week.starts_with('*'),
+        })
+    }
+}
+
+fn parse_field(field: &str, min: u8, max: u8, expression: &str) -> Result<Vec<u8>, CliError> {
+    let invalid = || {
+        CliError::Command(format!(
+            "invalid schedule field '{field}' in '{expression}'"
+        ))
+    };
+    let number = |text: &str| {
+        text.parse::<u8>()
+            .ok()
+            .filter(|value| (min..=max).contains(value))
+            .ok_or_else(invalid)
+    };
+
+    let mut values = Vec::new();
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, number(step).ok().filter(|step| *step > 0)),
+            None => (part, Some(1)),
+        };
+        let step = step.ok_or_else(invalid)?;
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (number(start)?, number(end)?),
+                None if part.contains('/') => (number(range)?, max),
+                None => {
+                    let value = number(range)?;
+                    (value, value)
+                }
+            },
+        };
+        if start > end {
+            return Err(invalid());
+        }
+        values.extend((start..=end).step_by(usize::from(step)));
+    }
+
+    values.sort_unstable();
+    values.dedup();
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use time::format_description::well_known::Rfc3339;
+
+    use super::*;
+
+    fn at(value: &str) -> OffsetDateTime {
+        OffsetDateTime::parse(value, &Rfc3339).expect("timestamp")
+    }
+
+    #[test]
+    fn weekday_evening_schedule_skips_to_monday() {
+        let schedule = Schedule::from_str("30 21 * * 1-5").expect("schedule");
+        // Friday 2026-02-20 after the run, so the next one is Monday.
+        let next = schedule
+            .next_after(at("2026-02-20T21:30:00Z"))
+            .expect("next run");
+        assert_eq!(next, at("2026-02-23T21:30:00Z"));
+    }
+
+    #[test]
+    fn steps_lists_and_shorthands_are_supported() {
+        let schedule = Schedule::from_str("*/15 9,13 * * *").expect("schedule");
+        assert_eq!(
+            schedule.next_after(at("2026-02-20T09:50:12Z")),
+            Some(at("2026-02-20T13:00:00Z"))
+        );
+        assert_eq!(
+            Schedule::from_str("@hourly")
+                .expect("hourly")
+                .next_after(at("2026-02-20T09:00:00Z")),
+            Some(at("2026-02-20T10:00:00Z"))
+        );
+        assert_eq!(
+            Schedule::from_str("0 0 * * 7").expect("sunday"),
+            Schedule::from_str("0 0 * * 0").expect("sunday")
+        );
+    }
+
+    #[test]
+    fn restricted_day_of_month_and_weekday_match_either() {
+        let schedule = Schedule::from_str("0 6 1 * 1").expect("schedule");
+        // Sunday 2026-02-01 is the 1st; Monday 2026-02-02 is not.
+        assert_eq!(
+            schedule.next_after(at("2026-01-31T12:00:00Z")),
+            Some(at("2026-02-01T06:00:00Z"))
+        );
+        assert_eq!(
+            schedule.next_after(at("2026-02-01T06:00:00Z")),
+            Some(at("2026-02-02T06:00:00Z"))
+        );
+        let any_weekday = Schedule::from_str("0 6 1 * *").expect("schedule");
+        assert_eq!(
+            any_weekday.next_after(at("2026-02-01T06:00:00Z")),
+            Some(at("2026-03-01T06:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn malformed_and_impossible_schedules_are_rejected() {
+        assert!(Schedule::from_str("* * * *").is_err());
+        assert!(Schedule::from_str("60 * * * *").is_err());
+        assert!(Schedule::from_str("*/0 * * * *").is_err());
+        assert!(Schedule::from_str("5-1 * * * *").is_err());
+        let february_31 = Schedule::from_str("0 0 31 2 *").expect("parses");
+        assert_eq!(february_31.next_after(at("2026-01-01T00:00:00Z")), None);
+    }
+}
@@ -0,0 +1,233 @@
+//! YAML job specs for the ingestion daemon.
+//!
+//! ```yaml
+//! concurrency: 4
+//! retries: 2
+//! universes:
+//!   megacaps: [AAPL, MSFT, NVDA]
+//! jobs:
+//!   - name: daily-bars
+//!     universe: megacaps
+//!     intervals: [1d]
+//!     schedule: "30 21 * * 1-5"
+//!     days: 5
+//!     providers: [yahoo, polygon]
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::str::FromStr;
+
+use ferrotick_core::{Interval, ProviderId, SourceStrategy, Symbol};
+use serde::Deserialize;
+
+use super::schedule::Schedule;
+use crate::error::CliError;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DaemonSpec {
+    #[serde(default = "default_concurrency")]
+    concurrency: usize,
+    #[serde(default = "default_retries")]
+    retries: u32,
+    #[serde(default)]
+    universes: BTreeMap<String, Vec<String>>,
+    jobs: Vec<JobSpec>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JobSpec {
+    name: String,
+    #[serde(default)]
+    universe: Option<String>,
+    #[serde(default)]
+    symbols: Vec<String>,
+    #[serde(default = "default_intervals")]
+    intervals: Vec<Interval>,
+    schedule: String,
+    #[serde(default = "default_days")]
+    days: u32,
+    #[serde(default)]
+    providers: Vec<ProviderId>,
+}
+
+fn default_concurrency() -> usize {
+    4
+}
+
+fn default_retries() -> u32 {
+    2
+}
+
+fn default_intervals() -> Vec<Interval> {
+    vec![Interval::OneDay]
+}
+
+fn default_days() -> u32 {
+    30
+}
+
+/// A validated daemon configuration.
+#[derive(Debug)]
+pub struct DaemonConfig {
+    /// Maximum number of loads running at once across all jobs.
+    pub concurrency: usize,
+    /// Extra attempts after a failed load.
+    pub retries: u32,
+    pub jobs: Vec<Job>,
+}
+
+/// One scheduled load of a set of symbols and intervals.
+#[derive(Debug)]
+pub struct Job {
+    pub name: String,
+    pub symbols: Vec<Symbol>,
+    pub intervals: Vec<Interval>,
+    pub schedule: Schedule,
+    pub schedule_expression: String,
+    pub days: u32,
+    pub strategy: SourceStrategy,
+}
+
+impl DaemonConfig {
+    pub fn load(path: &Path) -> Result<Self, CliError> {
+        let contents = std::fs::read_to_string(path)?;
+        Self::from_yaml(&contents)
+    }
+
+    pub fn from_yaml(contents: &str) -> Result<Self, CliError> {
+        let spec: DaemonSpec = serde_yaml::from_str(contents)
+            .map_err(|error| CliError::Command(format!("invalid daemon spec: {error}")))?;
+        if spec.concurrency == 0 {
+            return Err(CliError::Command(String::from(
+                "daemon spec concurrency must be greater than zero",
+            )));
+        }
+        if spec.jobs.is_empty() {
+            return Err(CliError::Command(String::from(
+                "daemon spec must define at least one job",
+            )));
+        }
+
+        let mut names = BTreeSet::new();
+        let mut jobs = Vec::with_capacity(spec.jobs.len());
+        for job in spec.jobs {
+            if !names.insert(job.name.clone()) {
+                return Err(CliError::Command(format!(
+                    "duplicate daemon job '{}'",
+                    job.name
+                )));
+            }
+
+            let mut symbols = Vec::new();
+            if let Some(universe) = &job.universe {
+                let members = spec.universes.get(universe).ok_or_else(|| {
+                    CliError::Command(format!(
+                        "job '{}' references unknown universe '{universe}'",
+                        job.name
+                    ))
+                })?;
+                symbols.extend(members.iter().cloned());
+            }
+            symbols.extend(job.symbols.iter().cloned());
+            let mut symbols = symbols
+                .iter()
+                .map(|symbol| Symbol::parse(symbol))
+                .collect::<Result<Vec<_>, _>>()?;
+            symbols.sort_by(|left, right| left.as_str().cmp(right.as_str()));
+            symbols.dedup();
+            if symbols.is_empty() || job.intervals.is_empty() {
+                return Err(CliError::Command(format!(
+                    "job '{}' needs at least one symbol and one interval",
+                    job.name
+                )));
+            }
+
+            let strategy = match job.providers.as_slice() {
+                [] => SourceStrategy::Auto,
+                [provider] => SourceStrategy::Strict(*provider),
+                providers => SourceStrategy::Priority(providers.to_vec()),
+            };
+            jobs.push(Job {
+                schedule: Schedule::from_str(&job.schedule)?,
+                schedule_expression: job.schedule,
+                name: job.name,
+                symbols,
+                intervals: job.intervals,
+                days: job.days,
+                strategy,
+            });
+        }
+
+        Ok(Self {
+            concurrency: spec.concurrency,
+            retries: spec.retries,
+            jobs,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn universes_and_symbols_merge_into_sorted_unique_jobs() {
+        let config = DaemonConfig::from_yaml(
+            r#"
+concurrency: 2
+universes:
+  megacaps: [MSFT, AAPL]
+jobs:
+  - name: daily
+    universe: megacaps
+    symbols: [AAPL, NVDA]
+    schedule: "30 21 * * 1-5"
+    providers: [yahoo, polygon]
+  - name: minutes
+    symbols: [SPY]
+    intervals: [1m, 5m]
+    schedule: "@hourly"
+    days: 2
+    providers: [polygon]
+"#,
+        )
+        .expect("valid spec");
+
+        assert_eq!(config.concurrency, 2);
+        assert_eq!(config.retries, 2);
+        let daily = &config.jobs[0];
+        assert_eq!(
+            daily.symbols.iter().map(Symbol::as_str).collect::<Vec<_>>(),
+            vec!["AAPL", "MSFT", "NVDA"]
+        );
+        assert_eq!(daily.intervals, vec![Interval::OneDay]);
+        assert_eq!(daily.days, 30);
+        assert!(
+            matches!(daily.strategy, SourceStrategy::Priority(ref providers) if providers.len() == 2)
+        );
+        let minutes = &config.jobs[1];
+        assert_eq!(
+            minutes.intervals,
+            vec![Interval::OneMinute, Interval::FiveMinutes]
+        );
+        assert!(matches!(minutes.strategy, SourceStrategy::Strict(_)));
+    }
+
+    #[test]
+    fn invalid_specs_are_rejected() {
+        for spec in [
+            "jobs: []",
+            "jobs:\n  - {name: a, symbols: [AAPL], schedule: '* * *'}",
+            "jobs:\n  - {name: a, universe: missing, schedule: '@daily'}",
+            "jobs:\n  - {name: a, schedule: '@daily'}",
+            "jobs:\n  - {name: a, symbols: [AAPL], schedule: '@daily'}\n  - {name: a, symbols: [MSFT], schedule: '@daily'}",
+            "concurrency: 0\njobs:\n  - {name: a, symbols: [AAPL], schedule: '@daily'}",
+            "jobs:\n  - {name: a, symbols: [AAPL], schedule: '@daily', cron: x}",
+        ] {
+            assert!(DaemonConfig::from_yaml(spec).is_err(), "{spec}");
+        }
+    }
+}
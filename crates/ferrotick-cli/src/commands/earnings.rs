@@ -5,6 +5,7 @@ use ferrotick_core::{EarningsReport, SourceRouter, SourceStrategy, Symbol};
 use crate::cli::EarningsArgs;
 use crate::error::CliError;
 
+use super::warehouse_sync;
 use super::CommandResult;
 
 #[derive(Debug, Serialize)]
@@ -24,13 +25,22 @@ pub async fn run(
 
     match router.route_earnings(&request, strategy.clone()).await {
         Ok(route) => {
+            let mut warnings = route.warnings;
+            if let Err(error) = warehouse_sync::sync_earnings(
+                route.selected_source,
+                &route.data.earnings,
+                route.latency_ms,
+            ) {
+                warnings.push(format!("warehouse sync (earnings) failed: {error}"));
+            }
+
             let data = serde_json::to_value(EarningsResponseData {
                 earnings: route.data.earnings,
             })?;
 
             Ok(CommandResult::ok(data, route.source_chain)
                 .with_errors(route.errors)
-                .with_warnings(route.warnings)
+                .with_warnings(warnings)
                 .with_latency(route.latency_ms)
                 .with_cache_hit(false))
         }
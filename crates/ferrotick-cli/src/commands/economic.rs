@@ -0,0 +1,70 @@
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use ferrotick_core::{
+    EconomicRequest, EconomicSeries, SeriesFrequency, SourceRouter, SourceStrategy,
+};
+
+use crate::cli::EconomicArgs;
+use crate::error::CliError;
+
+use super::warehouse_sync;
+use super::CommandResult;
+
+#[derive(Debug, Serialize)]
+struct EconomicResponseData {
+    series: EconomicSeries,
+}
+
+pub async fn run(
+    args: &EconomicArgs,
+    router: &SourceRouter,
+    strategy: &SourceStrategy,
+) -> Result<CommandResult, CliError> {
+    let frequency = args
+        .frequency
+        .as_deref()
+        .map(SeriesFrequency::from_str)
+        .transpose()?;
+    let mut request = EconomicRequest::new(&args.series, args.limit)?;
+    if let Some(frequency) = frequency {
+        request = request.with_frequency(frequency);
+    }
+
+    match router.route_economic(&request, strategy.clone()).await {
+        Ok(route) => {
+            let mut warnings = route.warnings;
+            if let Err(error) =
+                warehouse_sync::sync_economic(route.selected_source, &route.data, route.latency_ms)
+            {
+                warnings.push(format!("warehouse sync (economic) failed: {error}"));
+            }
+
+            let data = serde_json::to_value(EconomicResponseData { series: route.data })?;
+
+            Ok(CommandResult::ok(data, route.source_chain)
+                .with_errors(route.errors)
+                .with_warnings(warnings)
+                .with_latency(route.latency_ms)
+                .with_cache_hit(false))
+        }
+        Err(failure) => {
+            let empty_series = EconomicSeries::new(
+                request.series_id,
+                None,
+                String::new(),
+                frequency.unwrap_or(SeriesFrequency::Monthly),
+                Vec::new(),
+            );
+            let data = serde_json::to_value(EconomicResponseData {
+                series: empty_series,
+            })?;
+            Ok(CommandResult::ok(data, failure.source_chain)
+                .with_errors(failure.errors)
+                .with_warnings(failure.warnings)
+                .with_latency(failure.latency_ms)
+                .with_cache_hit(false))
+        }
+    }
+}
@@ -1,162 +1,129 @@
-//! Export data from warehouse to CSV format.
+//! Export data from the warehouse to Parquet, Arrow IPC, NDJSON or CSV.
 
-use ferrotick_core::{ProviderId, QueryGuardrails, Warehouse};
+use std::path::Path;
+use std::str::FromStr;
+
+use ferrotick_core::{Interval, ProviderId, Warehouse, WarehouseError};
+use ferrotick_warehouse::{ExportOptions, QueryParam, QueryParams};
 
 use crate::cli::ExportArgs;
 
 use crate::error::CliError;
 
+use super::sql::parse_params;
 use super::CommandResult;
 
-pub fn run(args: &ExportArgs) -> Result<CommandResult, CliError> {
+pub fn run(args: &ExportArgs, source_chain: Vec<ProviderId>) -> Result<CommandResult, CliError> {
     let warehouse =
         Warehouse::open_default().map_err(|error| CliError::Command(error.to_string()))?;
 
-    // Default queries if table is specified
-    let query = if let Some(table) = &args.table {
-        match table.as_str() {
-            "bars" | "bars_1d" | "bars_1m" => {
-                if let Some(symbol) = &args.symbol {
-                    format!(
-                        "SELECT * FROM {} WHERE symbol = '{}' ORDER BY ts",
-                        table, symbol
-                    )
-                } else {
-                    format!("SELECT * FROM {} ORDER BY ts", table)
-                }
-            }
-            "quotes" => {
-                if let Some(symbol) = &args.symbol {
-                    format!("SELECT * FROM quotes WHERE symbol = '{}'", symbol)
-                } else {
-                    "SELECT * FROM quotes".to_string()
-                }
-            }
-            "fundamentals" => {
-                if let Some(symbol) = &args.symbol {
-                    format!(
-                        "SELECT * FROM fundamentals WHERE symbol = '{}' ORDER BY date",
-                        symbol
-                    )
-                } else {
-                    "SELECT * FROM fundamentals ORDER BY date".to_string()
-                }
+    // Default queries if table is specified. Table names come from a fixed
+    // list; the symbol and universe are always bound as parameters.
+    let (query, params) = if let Some(table) = &args.table {
+        let (table, date_column, order_by) = match table.as_str() {
+            name if name == "bars"
+                || Interval::ALL
+                    .iter()
+                    .any(|interval| interval.bars_dataset() == name) =>
+            {
+                // `bars` plus an interval reads that interval's view.
+                let table = match &args.interval {
+                    Some(interval) if name == "bars" => {
+                        Interval::from_str(interval)?.bars_dataset()
+                    }
+                    _ => name,
+                };
+                (table, "ts", Some("ts"))
             }
+            "quotes" => ("quotes", "as_of", None),
+            "fundamentals" => ("fundamentals", "date", Some("date")),
             _ => {
                 return Err(CliError::Command(format!(
-                    "unknown table '{}'. Valid tables: bars, quotes, fundamentals",
+                    "unknown table '{}'. Valid tables: bars, bars_<interval>, quotes, fundamentals",
                     table
                 )));
             }
+        };
+        let mut filters = Vec::new();
+        let mut params = QueryParams::new();
+        if let Some(symbol) = &args.symbol {
+            filters.push(String::from("symbol = $symbol"));
+            params.insert(String::from("symbol"), QueryParam::from(symbol.as_str()));
+        }
+        if let Some(universe) = &args.universe {
+            // Fails early for an unknown universe instead of exporting nothing.
+            warehouse
+                .universe_membership(universe)
+                .map_err(|error| CliError::Command(error.to_string()))?;
+            filters.push(format!(
+                "EXISTS (SELECT 1 FROM universe_members m \
+                 WHERE m.universe = $universe AND m.symbol = {table}.symbol \
+                   AND m.added <= CAST({table}.{date_column} AS DATE) \
+                   AND (m.removed IS NULL OR m.removed > CAST({table}.{date_column} AS DATE)))"
+            ));
+            params.insert(
+                String::from("universe"),
+                QueryParam::from(universe.as_str()),
+            );
+        }
+        let mut query = format!("SELECT * FROM {table}");
+        if !filters.is_empty() {
+            query.push_str(" WHERE ");
+            query.push_str(&filters.join(" AND "));
+        }
+        if let Some(column) = order_by {
+            query.push_str(" ORDER BY ");
+            query.push_str(column);
         }
+        (query, params)
     } else {
-        args.query
-            .clone()
-            .unwrap_or_else(|| "SELECT 1 LIMIT 0".to_string())
+        (
+            args.query
+                .clone()
+                .unwrap_or_else(|| "SELECT 1 LIMIT 0".to_string()),
+            parse_params(&args.params)?,
+        )
     };
 
-    let guardrails = QueryGuardrails {
-        max_rows: args
-            .max_rows
-            .as_deref()
-            .unwrap_or("100000")
-            .parse::<usize>()
-            .unwrap_or(100000),
-        query_timeout_ms: args
-            .query_timeout_ms
-            .as_deref()
-            .unwrap_or("30000")
-            .parse::<u64>()
-            .unwrap_or(30000),
-    };
+    let mut options = ExportOptions::new(parse(&args.export_format)?);
+    if let Some(compression) = &args.compression {
+        options = options.with_compression(parse(compression)?);
+    }
+    if let Some(rows) = args.row_group_size {
+        options = options.with_row_group_size(rows);
+    }
+    if let Some(partition) = &args.partition_by {
+        options = options.with_partition_by(parse(partition)?);
+    }
+    if let Some(rows) = &args.max_rows {
+        options = options.with_max_rows(
+            rows.parse::<usize>()
+                .map_err(|_| CliError::Command(format!("invalid --max-rows '{rows}'")))?,
+        );
+    }
+    if let Some(timeout_ms) = &args.query_timeout_ms {
+        options = options.with_query_timeout_ms(timeout_ms.parse::<u64>().map_err(|_| {
+            CliError::Command(format!("invalid --query-timeout-ms '{timeout_ms}'"))
+        })?);
+    }
 
-    let result = warehouse
-        .execute_query(&query, guardrails, false)
+    let report = warehouse
+        .export_query(&query, &params, Path::new(&args.output), &options)
         .map_err(|error| CliError::Command(error.to_string()))?;
 
-    if result.rows.is_empty() {
+    if report.rows == 0 {
         eprintln!("⚠ No data found for query");
-        return Ok(CommandResult::ok(
-            serde_json::json!({
-                "query": query,
-                "rows": 0,
-                "exported": false
-            }),
-            vec![],
-        ));
-    }
-
-    // Export based on format
-    match args.export_format.as_str() {
-        "csv" => {
-            export_csv(&args.output, &result.columns, &result.rows)?;
-        }
-        "parquet" => {
-            eprintln!(
-                "ℹ Parquet export requires additional dependencies. Using CSV format instead."
-            );
-            export_csv(&args.output, &result.columns, &result.rows)?;
-        }
-        _ => {
-            return Err(CliError::Command(format!(
-                "unsupported format '{}'. Valid formats: csv, parquet (exports as csv for now)",
-                args.export_format
-            )));
-        }
+    } else {
+        eprintln!("✓ Exported {} rows to {}", report.rows, report.output);
     }
 
-    Ok(CommandResult::ok(
-        serde_json::json!({
-            "query": query,
-            "format": "csv",
-            "output": args.output,
-            "rows_exported": result.row_count,
-            "exported": true
-        }),
-        vec![ProviderId::Yahoo], // Export uses warehouse which may contain data from various sources
-    ))
+    let mut data = serde_json::to_value(&report)?;
+    data["query"] = serde_json::Value::from(query);
+    Ok(CommandResult::ok(data, source_chain))
 }
 
-fn export_csv(
-    output_path: &str,
-    columns: &[ferrotick_core::SqlColumn],
-    rows: &[Vec<serde_json::Value>],
-) -> Result<(), CliError> {
-    use std::fs::File;
-    use std::io::BufWriter;
-    use std::io::Write;
-
-    let file = File::create(output_path)?;
-    let mut writer = BufWriter::new(file);
-
-    // Write header
-    let header: Vec<String> = columns.iter().map(|c| c.name.clone()).collect();
-    writeln!(writer, "{}", header.join(","))?;
-
-    // Write rows
-    for row in rows {
-        let values: Vec<String> = columns
-            .iter()
-            .enumerate()
-            .map(|(i, _)| {
-                row.get(i)
-                    .map(|v| match v {
-                        serde_json::Value::Null => String::new(),
-                        serde_json::Value::Bool(b) => b.to_string(),
-                        serde_json::Value::Number(n) => n.to_string(),
-                        serde_json::Value::String(s) => {
-                            // Escape quotes and commas
-                            format!("\"{}\"", s.replace("\"", "\"\""))
-                        }
-                        _ => String::new(),
-                    })
-                    .unwrap_or_default()
-            })
-            .collect();
-        writeln!(writer, "{}", values.join(","))?;
-    }
-
-    writer.flush()?;
-    eprintln!("✓ Exported {} rows to {}", rows.len(), output_path);
-    Ok(())
+fn parse<T: FromStr<Err = WarehouseError>>(value: &str) -> Result<T, CliError> {
+    value
+        .parse()
+        .map_err(|error: WarehouseError| CliError::Command(error.to_string()))
 }
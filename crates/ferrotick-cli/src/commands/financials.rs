@@ -8,6 +8,7 @@ use ferrotick_core::{
 use crate::cli::FinancialsArgs;
 use crate::error::CliError;
 
+use super::warehouse_sync;
 use super::CommandResult;
 
 #[derive(Debug, Serialize)]
@@ -50,15 +51,26 @@ pub async fn run(
 
     match router.route_financials(&request, strategy.clone()).await {
         Ok(route) => {
+            let warehouse_warning = warehouse_sync::sync_financials(
+                route.selected_source,
+                route.data.financials.as_slice(),
+                route.latency_ms,
+            )
+            .err()
+            .map(|error| format!("warehouse sync (financials) failed: {error}"));
             let data = serde_json::to_value(FinancialsResponseData {
                 financials: route.data,
             })?;
 
-            Ok(CommandResult::ok(data, route.source_chain)
+            let mut result = CommandResult::ok(data, route.source_chain)
                 .with_errors(route.errors)
                 .with_warnings(route.warnings)
                 .with_latency(route.latency_ms)
-                .with_cache_hit(false))
+                .with_cache_hit(false);
+            if let Some(warning) = warehouse_warning {
+                result = result.with_warning(warning);
+            }
+            Ok(result)
         }
         Err(failure) => {
             let data = serde_json::to_value(FinancialsResponseData {
@@ -0,0 +1,611 @@
+//! Bulk import of vendor files into the warehouse.
+//!
+//! `import bars|fundamentals|corporate-actions <path-or-glob>` reads CSV or
+//! Parquet files through the warehouse, renames their columns with a TOML
+//! [`Mapping`], converts timestamps to UTC and validates every row through
+//! the domain constructors (`Bar::new`, `Fundamental::new`,
+//! `CorporateAction::new`). Files are read in chunks of
+//! [`IMPORT_CHUNK_ROWS`] rows, each ingested before the next is read, under
+//! one `import:` request id and tagged with the mapping's source. Bars
+//! already stored for a `(symbol, ts)` and rows repeated within the files are
+//! skipped as duplicates. Invalid rows are written to an NDJSON reject file
+//! with their reason as they are found, and a summary run is recorded in
+//! `ingest_log`.
+
+mod mapping;
+
+use std::collections::{BTreeSet, HashMap};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Instant;
+
+use ferrotick_core::{
+    Bar, CorporateAction, CorporateActionRecord, CorporateActionType, Fundamental, Interval,
+    ProviderId, Symbol, UtcDateTime, Warehouse,
+};
+use ferrotick_warehouse::{FileScan, ImportFileFormat, ScannedRow};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use self::mapping::Mapping;
+use super::warehouse_sync::{bar_record, fundamental_records};
+use super::CommandResult;
+use crate::cli::{ImportArgs, ImportCommand, ImportFileArgs};
+use crate::error::CliError;
+
+/// Rows validated and ingested at a time.
+const IMPORT_CHUNK_ROWS: usize = 10_000;
+
+const BAR_FIELDS: [&str; 8] = [
+    "symbol", "ts", "open", "high", "low", "close", "volume", "vwap",
+];
+const FUNDAMENTAL_FIELDS: [&str; 7] = [
+    "symbol",
+    "date",
+    "market_cap",
+    "pe_ratio",
+    "dividend_yield",
+    "sector",
+    "industry",
+];
+const CORPORATE_ACTION_FIELDS: [&str; 6] = [
+    "symbol",
+    "action_type",
+    "ex_date",
+    "pay_date",
+    "value",
+    "currency",
+];
+
+#[derive(Debug, Serialize)]
+struct ImportResponse {
+    request_id: String,
+    dataset: String,
+    source: String,
+    files: Vec<String>,
+    rows_read: usize,
+    imported: usize,
+    duplicates: usize,
+    rejected: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rejects_path: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct Reject {
+    file: String,
+    row: usize,
+    reason: String,
+    values: Map<String, Value>,
+}
+
+pub fn run(args: &ImportArgs, source_chain: Vec<ProviderId>) -> Result<CommandResult, CliError> {
+    let warehouse =
+        Warehouse::open_default().map_err(|error| CliError::Command(error.to_string()))?;
+    let response = match &args.command {
+        ImportCommand::Bars(bars) => {
+            let interval = Interval::from_str(&bars.interval)?;
+            Import::open(&warehouse, &bars.file, interval.bars_dataset(), &BAR_FIELDS)?
+                .bars(interval)?
+        }
+        ImportCommand::Fundamentals(file) => {
+            Import::open(&warehouse, file, "fundamentals", &FUNDAMENTAL_FIELDS)?.fundamentals()?
+        }
+        ImportCommand::CorporateActions(file) => Import::open(
+            &warehouse,
+            file,
+            "corporate_actions",
+            &CORPORATE_ACTION_FIELDS,
+        )?
+        .corporate_actions()?,
+    };
+
+    if response.rejected > 0 {
+        eprintln!(
+            "⚠ Rejected {} rows, see {}",
+            response.rejected,
+            response.rejects_path.as_deref().unwrap_or_default()
+        );
+    }
+    eprintln!(
+        "✓ Imported {} of {} rows into {}",
+        response.imported, response.rows_read, response.dataset
+    );
+    Ok(CommandResult::ok(
+        serde_json::to_value(response)?,
+        source_chain,
+    ))
+}
+
+/// One import run: the resolved columns, the reject file and the counts so
+/// far.
+struct Import<'a> {
+    warehouse: &'a Warehouse,
+    args: &'a ImportFileArgs,
+    dataset: &'static str,
+    mapping: Mapping,
+    format: ImportFileFormat,
+    /// Columns of the files; files and row count once they are read.
+    scan: FileScan,
+    /// Scan column of each field present in the files.
+    columns: HashMap<&'static str, usize>,
+    request_id: String,
+    started: Instant,
+    rejects: Rejects,
+    imported: usize,
+    duplicates: usize,
+}
+
+impl<'a> Import<'a> {
+    fn open(
+        warehouse: &'a Warehouse,
+        args: &'a ImportFileArgs,
+        dataset: &'static str,
+        fields: &[&'static str],
+    ) -> Result<Self, CliError> {
+        let mut mapping = match &args.mapping {
+            Some(path) => Mapping::load(Path::new(path))?,
+            None => Mapping::default(),
+        };
+        if let Some(source) = &args.source_tag {
+            mapping.source = source.clone();
+        }
+        if let Some(field) = mapping
+            .columns
+            .keys()
+            .find(|field| !fields.contains(&field.as_str()))
+        {
+            return Err(CliError::Command(format!(
+                "unknown field '{field}' in mapping [columns], expected one of {}",
+                fields.join(", ")
+            )));
+        }
+
+        let format = match &args.file_format {
+            Some(format) => ImportFileFormat::from_str(format)?,
+            None => ImportFileFormat::from_path(&args.path),
+        };
+        let started = Instant::now();
+        let scan = warehouse.scan_columns(&args.path, format)?;
+
+        let mut columns = HashMap::new();
+        for field in fields {
+            let column = mapping.column(field);
+            match scan.column_index(column) {
+                Some(index) => {
+                    columns.insert(*field, index);
+                }
+                None if mapping.columns.contains_key(*field) => {
+                    return Err(CliError::Command(format!(
+                        "column '{column}' mapped to {field} is not in {}",
+                        args.path
+                    )));
+                }
+                None => {}
+            }
+        }
+
+        Ok(Self {
+            warehouse,
+            args,
+            dataset,
+            mapping,
+            format,
+            scan,
+            columns,
+            request_id: format!("import:{}", Uuid::new_v4()),
+            started,
+            rejects: Rejects::new(&args.rejects),
+            imported: 0,
+            duplicates: 0,
+        })
+    }
+
+    fn bars(mut self, interval: Interval) -> Result<ImportResponse, CliError> {
+        self.require(&["ts", "open", "high", "low", "close"])?;
+        let dataset = interval.bars_dataset();
+        let mut keys: HashMap<String, BTreeSet<i64>> = HashMap::new();
+        let result = self.read_chunks(|import, rows| {
+            let mut records = Vec::new();
+            for row in &rows {
+                let parsed = import.symbol(row).and_then(|symbol| {
+                    let bar = Bar::new(
+                        import.timestamp(row, "ts")?,
+                        import.number(row, "open")?.ok_or("missing open")?,
+                        import.number(row, "high")?.ok_or("missing high")?,
+                        import.number(row, "low")?.ok_or("missing low")?,
+                        import.number(row, "close")?.ok_or("missing close")?,
+                        import.volume(row)?,
+                        import.number(row, "vwap")?,
+                    )
+                    .map_err(|error| error.to_string())?;
+                    Ok((symbol, bar))
+                });
+                let (symbol, bar) = match parsed {
+                    Ok(parsed) => parsed,
+                    Err(reason) => {
+                        import.reject(row, reason)?;
+                        continue;
+                    }
+                };
+
+                if !keys.contains_key(symbol.as_str()) {
+                    let stored = import.warehouse.bar_keys(dataset, symbol.as_str())?;
+                    keys.insert(symbol.as_str().to_string(), stored);
+                }
+                let key = bar.ts.into_inner().unix_timestamp_nanos() / 1_000;
+                let known = keys.get_mut(symbol.as_str()).expect("keys loaded above");
+                if !known.insert(i64::try_from(key).unwrap_or(i64::MAX)) {
+                    import.duplicates += 1;
+                    continue;
+                }
+                records.push(bar_record(symbol.as_str(), &bar));
+            }
+
+            import.warehouse.ingest_bars(
+                &import.mapping.source,
+                dataset,
+                &import.request_id,
+                &records,
+                import.latency_ms(),
+            )?;
+            import.imported += records.len();
+            Ok(())
+        });
+        self.finish(result)
+    }
+
+    fn fundamentals(mut self) -> Result<ImportResponse, CliError> {
+        self.require(&["date"])?;
+        let mut seen = BTreeSet::new();
+        let result = self.read_chunks(|import, rows| {
+            let mut snapshots = Vec::new();
+            for row in &rows {
+                let parsed = import.symbol(row).and_then(|symbol| {
+                    Fundamental::new(
+                        symbol,
+                        import.timestamp(row, "date")?,
+                        import.number(row, "market_cap")?,
+                        import.number(row, "pe_ratio")?,
+                        import.number(row, "dividend_yield")?,
+                    )
+                    .map(|snapshot| {
+                        snapshot.with_classification(
+                            import.text(row, "sector").map(str::to_string),
+                            import.text(row, "industry").map(str::to_string),
+                        )
+                    })
+                    .map_err(|error| error.to_string())
+                });
+                match parsed {
+                    Ok(snapshot) => {
+                        if seen.insert((snapshot.symbol.as_str().to_string(), snapshot.as_of)) {
+                            snapshots.push(snapshot);
+                        } else {
+                            import.duplicates += 1;
+                        }
+                    }
+                    Err(reason) => import.reject(row, reason)?,
+                }
+            }
+
+            let (rows, instruments) = fundamental_records(&snapshots);
+            let latency_ms = import.latency_ms();
+            import.warehouse.ingest_fundamentals(
+                &import.mapping.source,
+                &import.request_id,
+                &rows,
+                latency_ms,
+            )?;
+            import.warehouse.ingest_instruments(
+                &import.mapping.source,
+                &import.request_id,
+                &instruments,
+                latency_ms,
+            )?;
+            import.imported += snapshots.len();
+            Ok(())
+        });
+        self.finish(result)
+    }
+
+    fn corporate_actions(mut self) -> Result<ImportResponse, CliError> {
+        self.require(&["action_type", "ex_date"])?;
+        let mut seen = BTreeSet::new();
+        let result = self.read_chunks(|import, rows| {
+            let mut records = Vec::new();
+            for row in &rows {
+                let parsed = import.symbol(row).and_then(|symbol| {
+                    let action_type = import
+                        .text(row, "action_type")
+                        .ok_or("missing action_type")?;
+                    let action_type = action_type_from_str(action_type)?;
+                    let pay_date = import
+                        .text(row, "pay_date")
+                        .map(|raw| import.mapping.parse_timestamp(raw))
+                        .transpose()?;
+                    let value = match import.text(row, "value") {
+                        Some(raw) if action_type == CorporateActionType::Split => {
+                            Some(split_ratio(raw)?)
+                        }
+                        _ => import.number(row, "value")?,
+                    };
+                    CorporateAction::new(
+                        symbol,
+                        action_type,
+                        import.timestamp(row, "ex_date")?,
+                        pay_date,
+                        value,
+                        import.text(row, "currency"),
+                    )
+                    .map_err(|error| error.to_string())
+                });
+                let action = match parsed {
+                    Ok(action) => action,
+                    Err(reason) => {
+                        import.reject(row, reason)?;
+                        continue;
+                    }
+                };
+
+                let action_type = serde_json::to_value(action.action_type)?
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string();
+                if !seen.insert((
+                    action.symbol.as_str().to_string(),
+                    action_type.clone(),
+                    action.ex_date,
+                )) {
+                    import.duplicates += 1;
+                    continue;
+                }
+                records.push(CorporateActionRecord {
+                    symbol: action.symbol.as_str().to_string(),
+                    action_type,
+                    ex_date: action.ex_date.format_rfc3339(),
+                    pay_date: action.pay_date.map(UtcDateTime::format_rfc3339),
+                    value: action.value,
+                    currency: action.currency,
+                });
+            }
+
+            import.warehouse.ingest_corporate_actions(
+                &import.mapping.source,
+                &import.request_id,
+                &records,
+                import.latency_ms(),
+            )?;
+            import.imported += records.len();
+            Ok(())
+        });
+        self.finish(result)
+    }
+
+    /// Fail before reading rows when a required field has no column.
+    fn require(&self, fields: &[&str]) -> Result<(), CliError> {
+        let missing = fields
+            .iter()
+            .chain(self.mapping.symbol.is_none().then_some(&"symbol"))
+            .filter(|field| !self.columns.contains_key(**field))
+            .map(|field| format!("'{}' ({field})", self.mapping.column(field)))
+            .collect::<Vec<_>>();
+        if missing.is_empty() {
+            return Ok(());
+        }
+        Err(CliError::Command(format!(
+            "{} has no column {}; map vendor columns under [columns] in --mapping",
+            self.args.path,
+            missing.join(", ")
+        )))
+    }
+
+    /// Stream the files through `chunk` [`IMPORT_CHUNK_ROWS`] rows at a
+    /// time, so each chunk is validated and ingested before the next is read.
+    fn read_chunks(
+        &mut self,
+        mut chunk: impl FnMut(&mut Self, Vec<ScannedRow>) -> Result<(), CliError>,
+    ) -> Result<(), CliError> {
+        let (warehouse, args, format) = (self.warehouse, self.args, self.format);
+        self.scan = warehouse.scan_files(&args.path, format, IMPORT_CHUNK_ROWS, |rows| {
+            chunk(self, rows)
+        })?;
+        Ok(())
+    }
+
+    /// Record `row` in the reject file.
+    fn reject(&mut self, row: &ScannedRow, reason: impl Into<String>) -> Result<(), CliError> {
+        let reject = reject(&self.scan, row, reason);
+        self.rejects.write(&reject)
+    }
+
+    /// Flush rejects, record the run in `ingest_log` and summarize it.
+    ///
+    /// Rejects are written as they are found, so the file is complete even
+    /// when an ingest fails.
+    fn finish(mut self, result: Result<(), CliError>) -> Result<ImportResponse, CliError> {
+        let rejects_path = self.rejects.finish()?;
+        let latency_ms = self.latency_ms();
+        let (status, detail) = match &result {
+            Ok(()) => (
+                "succeeded",
+                format!(
+                    "imported {} of {} rows from {} file(s), {} duplicates, {} rejected",
+                    self.imported,
+                    self.scan.rows,
+                    self.scan.files.len(),
+                    self.duplicates,
+                    self.rejects.count
+                ),
+            ),
+            Err(error) => (
+                "failed",
+                format!(
+                    "{error} after importing {} rows, {} rejected",
+                    self.imported, self.rejects.count
+                ),
+            ),
+        };
+        self.warehouse.record_ingest_run(
+            &self.request_id,
+            None,
+            &self.mapping.source,
+            self.dataset,
+            status,
+            latency_ms,
+            &detail,
+        )?;
+        result?;
+
+        Ok(ImportResponse {
+            request_id: self.request_id,
+            dataset: self.dataset.to_string(),
+            source: self.mapping.source,
+            files: self.scan.files,
+            rows_read: self.scan.rows,
+            imported: self.imported,
+            duplicates: self.duplicates,
+            rejected: self.rejects.count,
+            rejects_path,
+        })
+    }
+
+    fn latency_ms(&self) -> u64 {
+        u64::try_from(self.started.elapsed().as_millis()).unwrap_or(u64::MAX)
+    }
+
+    fn text<'r>(&self, row: &'r ScannedRow, field: &str) -> Option<&'r str> {
+        let index = *self.columns.get(field)?;
+        row.values.get(index)?.as_deref().map(str::trim)
+    }
+
+    fn symbol(&self, row: &ScannedRow) -> Result<Symbol, String> {
+        let raw = self
+            .text(row, "symbol")
+            .or(self.mapping.symbol.as_deref())
+            .ok_or("missing symbol")?;
+        Symbol::parse(raw).map_err(|error| error.to_string())
+    }
+
+    fn timestamp(&self, row: &ScannedRow, field: &str) -> Result<UtcDateTime, String> {
+        let raw = self
+            .text(row, field)
+            .ok_or_else(|| format!("missing {field}"))?;
+        self.mapping.parse_timestamp(raw)
+    }
+
+    fn number(&self, row: &ScannedRow, field: &str) -> Result<Option<f64>, String> {
+        self.text(row, field)
+            .map(|raw| {
+                raw.parse::<f64>()
+                    .ok()
+                    .filter(|value| value.is_finite())
+                    .ok_or_else(|| format!("invalid {field} '{raw}'"))
+            })
+            .transpose()
+    }
+
+    fn volume(&self, row: &ScannedRow) -> Result<Option<u64>, String> {
+        // Vendors often write volumes as floats ("1200.0").
+        self.number(row, "volume")?
+            .map(|value| {
+                if value >= 0.0 && value.fract() == 0.0 && value <= u64::MAX as f64 {
+                    Ok(value as u64)
+                } else {
+                    Err(format!("invalid volume '{value}'"))
+                }
+            })
+            .transpose()
+    }
+}
+
+/// Reject file, created at the first rejected row.
+struct Rejects {
+    path: String,
+    writer: Option<BufWriter<File>>,
+    count: usize,
+}
+
+impl Rejects {
+    fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            writer: None,
+            count: 0,
+        }
+    }
+
+    fn write(&mut self, reject: &Reject) -> Result<(), CliError> {
+        let writer = match &mut self.writer {
+            Some(writer) => writer,
+            None => self
+                .writer
+                .insert(BufWriter::new(File::create(&self.path)?)),
+        };
+        serde_json::to_writer(&mut *writer, reject)?;
+        writer.write_all(b"\n")?;
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Flush the file, returning its path when any row was rejected.
+    fn finish(&mut self) -> Result<Option<String>, CliError> {
+        match &mut self.writer {
+            Some(writer) => {
+                writer.flush()?;
+                Ok(Some(self.path.clone()))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+fn reject(scan: &FileScan, row: &ScannedRow, reason: impl Into<String>) -> Reject {
+    let values = scan
+        .columns
+        .iter()
+        .zip(&row.values)
+        .map(|(column, value)| {
+            (
+                column.clone(),
+                value.clone().map_or(Value::Null, Value::String),
+            )
+        })
+        .collect();
+    Reject {
+        file: row.file.clone(),
+        row: row.row,
+        reason: reason.into(),
+        values,
+    }
+}
+
+fn action_type_from_str(raw: &str) -> Result<CorporateActionType, String> {
+    let normalized = raw.trim().to_ascii_lowercase().replace([' ', '-'], "_");
+    serde_json::from_value(Value::String(normalized)).map_err(|_| {
+        format!(
+            "unknown action_type '{raw}', expected dividend, split, spinoff, merger, rights_issue or other"
+        )
+    })
+}
+
+/// Split ratio from `2`, `2:1` or `2/1` (new shares per old share).
+fn split_ratio(raw: &str) -> Result<f64, String> {
+    let invalid = || format!("invalid split ratio '{raw}'");
+    let ratio = match raw.split_once([':', '/']) {
+        Some((new, old)) => {
+            let new: f64 = new.trim().parse().map_err(|_| invalid())?;
+            let old: f64 = old.trim().parse().map_err(|_| invalid())?;
+            new / old
+        }
+        None => raw.parse().map_err(|_| invalid())?,
+    };
+    if ratio.is_finite() && ratio > 0.0 {
+        Ok(ratio)
+    } else {
+        Err(invalid())
+    }
+}
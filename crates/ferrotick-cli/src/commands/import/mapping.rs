@@ -0,0 +1,236 @@
+//! TOML column mappings for vendor file imports.
+//!
+//! ```toml
+//! source = "acme"                      # `source` tag, "import" by default
+//! timezone = "America/New_York"        # zone of timestamps without an offset, UTC by default
+//! timestamp_format = "%m/%d/%Y %H:%M"  # chrono format, see below
+//! symbol = "AAPL"                      # symbol of files without a symbol column
+//!
+//! [columns]                            # field = vendor column
+//! ts = "Date"
+//! close = "Adj Close"
+//! ```
+//!
+//! Fields without a `[columns]` entry are read from the column of the same
+//! name, ignoring case. Without `timestamp_format`, RFC 3339 and the ISO
+//! forms `YYYY-MM-DD[ HH:MM[:SS[.fff]]][+HH[:MM]]` are accepted. Dates
+//! without a time are taken as midnight UTC rather than shifted by
+//! `timezone`, so daily bars keep their trading day.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use chrono::{DateTime, FixedOffset, LocalResult, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use ferrotick_core::UtcDateTime;
+use serde::Deserialize;
+use time::OffsetDateTime;
+
+use crate::error::CliError;
+
+/// Timestamps with an explicit offset, tried after RFC 3339.
+const OFFSET_FORMATS: [&str; 2] = ["%Y-%m-%d %H:%M:%S%.f%#z", "%Y-%m-%dT%H:%M:%S%.f%#z"];
+
+/// Timestamps in the mapping's timezone.
+const LOCAL_FORMATS: [&str; 4] = [
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M",
+];
+
+const DATE_FORMATS: [&str; 2] = ["%Y-%m-%d", "%Y%m%d"];
+
+/// How the columns of a vendor file map onto import fields.
+#[derive(Debug, Clone)]
+pub struct Mapping {
+    pub source: String,
+    pub timezone: Tz,
+    pub timestamp_format: Option<String>,
+    /// Symbol used when the file has no symbol column.
+    pub symbol: Option<String>,
+    /// Vendor column of each field that is not read under its own name.
+    pub columns: BTreeMap<String, String>,
+}
+
+impl Default for Mapping {
+    fn default() -> Self {
+        Self {
+            source: default_source(),
+            timezone: Tz::UTC,
+            timestamp_format: None,
+            symbol: None,
+            columns: BTreeMap::new(),
+        }
+    }
+}
+
+/// A mapping file as written, before its timezone is resolved.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MappingFile {
+    #[serde(default = "default_source")]
+    source: String,
+    timezone: Option<String>,
+    timestamp_format: Option<String>,
+    symbol: Option<String>,
+    #[serde(default)]
+    columns: BTreeMap<String, String>,
+}
+
+fn default_source() -> String {
+    String::from("import")
+}
+
+impl Mapping {
+    pub fn load(path: &Path) -> Result<Self, CliError> {
+        let contents = std::fs::read_to_string(path)?;
+        Self::from_toml(&contents)
+    }
+
+    pub fn from_toml(contents: &str) -> Result<Self, CliError> {
+        let invalid = |message: String| CliError::Command(format!("invalid mapping: {message}"));
+        let file: MappingFile =
+            toml_edit::de::from_str(contents).map_err(|error| invalid(error.to_string()))?;
+        let timezone = match &file.timezone {
+            Some(value) => value
+                .parse()
+                .map_err(|_| invalid(format!("unknown timezone '{value}'")))?,
+            None => Tz::UTC,
+        };
+        Ok(Self {
+            source: file.source,
+            timezone,
+            timestamp_format: file.timestamp_format,
+            symbol: file.symbol,
+            columns: file.columns,
+        })
+    }
+
+    /// Vendor column holding `field`.
+    pub fn column<'a>(&'a self, field: &'a str) -> &'a str {
+        self.columns.get(field).map_or(field, String::as_str)
+    }
+
+    /// Parse a vendor timestamp, converting it to UTC.
+    pub fn parse_timestamp(&self, raw: &str) -> Result<UtcDateTime, String> {
+        let raw = raw.trim();
+        let parsed = match &self.timestamp_format {
+            Some(format) => self.parse_with(raw, &[format.as_str()], &[format], &[format]),
+            None => DateTime::parse_from_rfc3339(raw)
+                .map(|value| Some(value.with_timezone(&Utc)))
+                .or_else(|_| self.parse_with(raw, &OFFSET_FORMATS, &LOCAL_FORMATS, &DATE_FORMATS)),
+        };
+        match parsed {
+            Ok(Some(value)) => to_utc_datetime(value),
+            Ok(None) => Err(format!(
+                "local time '{raw}' does not exist in {}",
+                self.timezone
+            )),
+            Err(()) => Err(match &self.timestamp_format {
+                Some(format) => format!("timestamp '{raw}' does not match '{format}'"),
+                None => format!("unrecognized timestamp '{raw}'"),
+            }),
+        }
+    }
+
+    /// Try offset, local and date-only formats in turn. `Ok(None)` is a
+    /// local time skipped by a daylight saving transition.
+    fn parse_with(
+        &self,
+        raw: &str,
+        offset_formats: &[&str],
+        local_formats: &[&str],
+        date_formats: &[&str],
+    ) -> Result<Option<DateTime<Utc>>, ()> {
+        for format in offset_formats {
+            if let Ok(value) = DateTime::<FixedOffset>::parse_from_str(raw, format) {
+                return Ok(Some(value.with_timezone(&Utc)));
+            }
+        }
+        for format in local_formats {
+            if let Ok(value) = NaiveDateTime::parse_from_str(raw, format) {
+                // An hour repeated when clocks go back resolves to its first
+                // occurrence.
+                return Ok(match self.timezone.from_local_datetime(&value) {
+                    LocalResult::Single(value) | LocalResult::Ambiguous(value, _) => {
+                        Some(value.with_timezone(&Utc))
+                    }
+                    LocalResult::None => None,
+                });
+            }
+        }
+        for format in date_formats {
+            if let Ok(value) = NaiveDate::parse_from_str(raw, format) {
+                return Ok(value.and_hms_opt(0, 0, 0).map(|value| value.and_utc()));
+            }
+        }
+        Err(())
+    }
+}
+
+fn to_utc_datetime(value: DateTime<Utc>) -> Result<UtcDateTime, String> {
+    let nanos = value
+        .timestamp_nanos_opt()
+        .ok_or_else(|| format!("timestamp {value} is out of range"))?;
+    let value = OffsetDateTime::from_unix_timestamp_nanos(i128::from(nanos))
+        .map_err(|error| error.to_string())?;
+    UtcDateTime::from_offset_datetime(value).map_err(|error| error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn columns_and_timezones_are_read_from_toml() {
+        let mapping = Mapping::from_toml(
+            r#"
+source = "acme"
+timezone = "America/New_York"
+
+[columns]
+ts = "Date"
+close = "Adj Close"
+"#,
+        )
+        .expect("valid mapping");
+        assert_eq!(mapping.source, "acme");
+        assert_eq!(mapping.column("close"), "Adj Close");
+        assert_eq!(mapping.column("open"), "open");
+
+        let utc = |raw: &str| {
+            mapping
+                .parse_timestamp(raw)
+                .map(UtcDateTime::format_rfc3339)
+        };
+        // EST in January, EDT in July.
+        assert_eq!(
+            utc("2024-01-02 09:30"),
+            Ok(String::from("2024-01-02T14:30:00Z"))
+        );
+        assert_eq!(
+            utc("2024-07-01T09:30:00"),
+            Ok(String::from("2024-07-01T13:30:00Z"))
+        );
+        assert_eq!(
+            utc("2024-07-01 09:30:00+00"),
+            Ok(String::from("2024-07-01T09:30:00Z"))
+        );
+        assert_eq!(utc("2024-07-01"), Ok(String::from("2024-07-01T00:00:00Z")));
+        assert!(utc("2024-03-10 02:30").is_err());
+        assert!(utc("07/01/2024").is_err());
+
+        let custom = Mapping::from_toml("timestamp_format = \"%m/%d/%Y\"").expect("valid");
+        assert_eq!(
+            custom
+                .parse_timestamp("07/01/2024")
+                .map(UtcDateTime::format_rfc3339),
+            Ok(String::from("2024-07-01T00:00:00Z"))
+        );
+
+        assert!(Mapping::from_toml("timezone = \"Mars/Olympus\"").is_err());
+        assert!(Mapping::from_toml("delimiter = \";\"").is_err());
+        assert!(Mapping::from_toml("[columns]\nclose = 4").is_err());
+    }
+}
@@ -0,0 +1,59 @@
+use ferrotick_core::{Interval, ProviderId, Symbol, Warehouse};
+use serde_json::json;
+use time::format_description::well_known::Iso8601;
+use time::Date;
+
+use crate::cli::{LineageArgs, LineageCommand};
+use crate::error::CliError;
+
+use super::CommandResult;
+
+pub fn run(args: &LineageArgs, source_chain: Vec<ProviderId>) -> Result<CommandResult, CliError> {
+    let warehouse =
+        Warehouse::open_default().map_err(|error| CliError::Command(error.to_string()))?;
+    match &args.command {
+        LineageCommand::Bars(bars_args) => {
+            let symbol = Symbol::parse(&bars_args.symbol)?;
+            let interval: Interval = bars_args.interval.parse()?;
+            Date::parse(&bars_args.date, &Iso8601::DATE).map_err(|_| {
+                CliError::Command(format!(
+                    "invalid --date '{}': expected YYYY-MM-DD",
+                    bars_args.date
+                ))
+            })?;
+
+            let versions = warehouse
+                .bar_lineage(interval.bars_dataset(), symbol.as_str(), &bars_args.date)
+                .map_err(|error| CliError::Command(error.to_string()))?;
+            let empty = versions.is_empty();
+            let data = json!({
+                "symbol": symbol.as_str(),
+                "dataset": interval.bars_dataset(),
+                "date": bars_args.date,
+                "versions": versions,
+            });
+            let mut result = CommandResult::ok(data, source_chain);
+            if empty {
+                result = result.with_warning(format!(
+                    "no {} rows for {} on {}",
+                    interval.bars_dataset(),
+                    symbol.as_str(),
+                    bars_args.date
+                ));
+            }
+            Ok(result)
+        }
+        LineageCommand::Batch(batch_args) => {
+            let changes = warehouse
+                .batch_changes(&batch_args.batch_id)
+                .map_err(|error| CliError::Command(error.to_string()))?
+                .ok_or_else(|| {
+                    CliError::Command(format!("no ingest batch '{}'", batch_args.batch_id))
+                })?;
+            Ok(CommandResult::ok(
+                serde_json::to_value(&changes)?,
+                source_chain,
+            ))
+        }
+    }
+}
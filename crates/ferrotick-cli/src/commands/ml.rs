@@ -1,6 +1,7 @@
 use std::path::PathBuf;
+use std::str::FromStr;
 
-use ferrotick_core::{ProviderId, Symbol, UtcDateTime};
+use ferrotick_core::{Interval, ProviderId, Symbol, UtcDateTime};
 use ferrotick_ml::{FeatureConfig, FeatureEngineer, FeatureStore, IndicatorSelection};
 
 use crate::cli::{MlArgs, MlCommand, MlExportArgs, MlFeaturesArgs};
@@ -31,7 +32,7 @@ async fn run_features(
         )));
     }
 
-    let symbol = Symbol::parse(&args.symbol)?;
+    let interval = Interval::from_str(&args.interval)?;
     let start = parse_optional_cli_date(args.start.as_deref(), false)?;
     let end = parse_optional_cli_date(args.end.as_deref(), true)?;
     validate_range(start, end)?;
@@ -40,8 +41,28 @@ async fn run_features(
         .map_err(|err| CliError::Command(err.to_string()))?;
 
     let store = FeatureStore::open_default().map_err(|err| CliError::Command(err.to_string()))?;
+    let config = FeatureConfig {
+        window: args.window,
+        bb_period: args.window,
+        ..FeatureConfig::default()
+    };
+    let engineer = FeatureEngineer::new(config, indicators)
+        .map_err(|err| CliError::Command(err.to_string()))?;
+
+    if let Some(universe) = &args.universe {
+        return run_universe_features(
+            &store,
+            &engineer,
+            universe,
+            interval,
+            (start, end),
+            source_chain,
+        );
+    }
+
+    let symbol = Symbol::parse(args.symbol.as_deref().unwrap_or_default())?;
     let bars = store
-        .load_daily_bars(&symbol, start, end)
+        .load_bars(&symbol, interval, start, end)
         .map_err(|err| CliError::Command(err.to_string()))?;
 
     if bars.is_empty() {
@@ -57,15 +78,6 @@ async fn run_features(
         .with_warning("no bars found in warehouse; run `ferrotick cache load <symbol>` first"));
     }
 
-    let config = FeatureConfig {
-        window: args.window,
-        bb_period: args.window,
-        ..FeatureConfig::default()
-    };
-
-    let engineer = FeatureEngineer::new(config, indicators)
-        .map_err(|err| CliError::Command(err.to_string()))?;
-
     let rows = engineer
         .compute_for_symbol(&symbol, &bars)
         .map_err(|err| CliError::Command(err.to_string()))?;
@@ -87,6 +99,67 @@ async fn run_features(
     ))
 }
 
+/// Compute features for every symbol that was ever a member of `universe`,
+/// keeping the rows of dates it was a member. Indicators still warm up on
+/// the bars from before a symbol joined.
+fn run_universe_features(
+    store: &FeatureStore,
+    engineer: &FeatureEngineer,
+    universe: &str,
+    interval: Interval,
+    (start, end): (Option<UtcDateTime>, Option<UtcDateTime>),
+    source_chain: Vec<ProviderId>,
+) -> Result<CommandResult, CliError> {
+    let membership = store
+        .load_universe(universe)
+        .map_err(|err| CliError::Command(err.to_string()))?;
+
+    let mut symbols = Vec::new();
+    let mut without_bars = Vec::new();
+    for member in membership.symbols() {
+        let symbol = Symbol::parse(member)?;
+        let bars = store
+            .load_bars(&symbol, interval, start, end)
+            .map_err(|err| CliError::Command(err.to_string()))?;
+        if bars.is_empty() {
+            without_bars.push(member);
+            continue;
+        }
+
+        let mut rows = engineer
+            .compute_for_symbol(&symbol, &bars)
+            .map_err(|err| CliError::Command(err.to_string()))?;
+        let computed = rows.len();
+        rows.retain(|row| membership.contains(&row.symbol, &row.timestamp));
+        let stored_rows = store
+            .upsert_features(&rows)
+            .map_err(|err| CliError::Command(err.to_string()))?;
+        symbols.push(serde_json::json!({
+            "symbol": member,
+            "rows_computed": computed,
+            "member_rows": rows.len(),
+            "stored_rows": stored_rows,
+        }));
+    }
+
+    let mut result = CommandResult::ok(
+        serde_json::json!({
+            "universe": universe,
+            "start": start.map(UtcDateTime::format_rfc3339),
+            "end": end.map(UtcDateTime::format_rfc3339),
+            "symbols": symbols,
+        }),
+        source_chain,
+    );
+    if !without_bars.is_empty() {
+        result = result.with_warning(format!(
+            "no bars found in warehouse for {}; run `ferrotick cache load <symbol>` first",
+            without_bars.join(", ")
+        ));
+    }
+    Ok(result)
+}
+
 async fn run_export(
     args: &MlExportArgs,
     source_chain: Vec<ProviderId>,
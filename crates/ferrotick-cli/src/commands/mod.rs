@@ -1,17 +1,26 @@
 mod bars;
 mod cache;
 mod cache_load;
+mod daemon;
 mod earnings;
+mod economic;
 mod export;
 mod financials;
 mod fundamentals;
+mod import;
+mod lineage;
 mod ml;
+mod news;
 mod quote;
+mod ratios;
 mod schema;
 mod search;
 mod sources;
 mod sql;
 mod strategy;
+mod ticks;
+mod universe;
+mod warehouse;
 mod warehouse_sync;
 
 use ferrotick_core::{
@@ -77,16 +86,25 @@ pub async fn run(cli: &Cli) -> Result<Envelope<Value>, CliError> {
     let command_result = match &cli.command {
         Command::Quote(args) => quote::run(args, &router, &strategy).await?,
         Command::Bars(args) => bars::run(args, &router, &strategy).await?,
+        Command::Ticks(args) => ticks::run(args, &router, &strategy).await?,
         Command::Fundamentals(args) => fundamentals::run(args, &router, &strategy).await?,
         Command::Search(args) => search::run(args, &router, &strategy).await?,
         Command::Financials(args) => financials::run(args, &router, &strategy).await?,
         Command::Earnings(args) => earnings::run(args, &router, &strategy).await?,
+        Command::Ratios(args) => ratios::run(args, &router, &strategy).await?,
+        Command::Economic(args) => economic::run(args, &router, &strategy).await?,
+        Command::News(args) => news::run(args, &router, &strategy).await?,
         Command::Sql(args) => sql::run(
             args,
             cli.explain,
             non_provider_source_chain(&router, &strategy).await,
         )?,
-        Command::Export(args) => export::run(args)?,
+        Command::Export(args) => {
+            export::run(args, non_provider_source_chain(&router, &strategy).await)?
+        }
+        Command::Import(args) => {
+            import::run(args, non_provider_source_chain(&router, &strategy).await)?
+        }
         Command::Ml(args) => {
             ml::run(args, non_provider_source_chain(&router, &strategy).await).await?
         }
@@ -94,10 +112,30 @@ pub async fn run(cli: &Cli) -> Result<Envelope<Value>, CliError> {
             CacheCommand::Load(load_args) => {
                 cache_load::run(load_args, &router, strategy.clone()).await?
             }
-            CacheCommand::Sync => {
+            CacheCommand::Sync(_)
+            | CacheCommand::Rebuild
+            | CacheCommand::Rollup(_)
+            | CacheCommand::Compact(_) => {
                 cache::run(args, non_provider_source_chain(&router, &strategy).await)?
             }
         },
+        Command::Warehouse(args) => {
+            warehouse::run(args, non_provider_source_chain(&router, &strategy).await)?
+        }
+        Command::Lineage(args) => {
+            lineage::run(args, non_provider_source_chain(&router, &strategy).await)?
+        }
+        Command::Universe(args) => {
+            universe::run(args, non_provider_source_chain(&router, &strategy).await)?
+        }
+        Command::Daemon(args) => {
+            daemon::run(
+                &args.command,
+                cli.timeout_ms,
+                non_provider_source_chain(&router, &strategy).await,
+            )
+            .await?
+        }
         Command::Schema(args) => {
             schema::run(args, non_provider_source_chain(&router, &strategy).await)?
         }
@@ -136,11 +174,6 @@ pub async fn run(cli: &Cli) -> Result<Envelope<Value>, CliError> {
 
     let mut metadata = Metadata::new(source_chain, latency_ms, cache_hit)?;
 
-    if cli.profile {
-        metadata
-            .push_warning("--profile is accepted but profiling is not implemented in this phase");
-    }
-
     for warning in warnings {
         metadata.push_warning(warning);
     }
@@ -154,10 +187,7 @@ pub async fn run(cli: &Cli) -> Result<Envelope<Value>, CliError> {
 fn to_source_strategy(source: SourceSelector) -> SourceStrategy {
     match source {
         SourceSelector::Auto => SourceStrategy::Auto,
-        SourceSelector::Yahoo => SourceStrategy::Strict(ProviderId::Yahoo),
-        SourceSelector::Polygon => SourceStrategy::Strict(ProviderId::Polygon),
-        SourceSelector::Alphavantage => SourceStrategy::Strict(ProviderId::Alphavantage),
-        SourceSelector::Alpaca => SourceStrategy::Strict(ProviderId::Alpaca),
+        SourceSelector::Provider(provider) => SourceStrategy::Strict(provider),
     }
 }
 
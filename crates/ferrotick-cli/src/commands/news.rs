@@ -0,0 +1,60 @@
+use serde::Serialize;
+
+use ferrotick_core::{NewsItem, NewsRequest, SourceRouter, SourceStrategy, UtcDateTime};
+
+use crate::cli::NewsArgs;
+use crate::error::CliError;
+
+use super::universe;
+use super::warehouse_sync;
+use super::CommandResult;
+
+#[derive(Debug, Serialize)]
+struct NewsResponseData {
+    items: Vec<NewsItem>,
+}
+
+pub async fn run(
+    args: &NewsArgs,
+    router: &SourceRouter,
+    strategy: &SourceStrategy,
+) -> Result<CommandResult, CliError> {
+    let symbols = universe::resolve_symbols(&args.symbols, args.universe.as_deref())?;
+    let since = args.since.as_deref().map(UtcDateTime::parse).transpose()?;
+
+    let mut request = NewsRequest::new(symbols, args.limit)?;
+    if let Some(since) = since {
+        request = request.with_since(since);
+    }
+
+    match router.route_news(&request, strategy.clone()).await {
+        Ok(route) => {
+            let mut warnings = route.warnings;
+            if let Err(error) = warehouse_sync::sync_news(
+                route.selected_source,
+                route.data.items.as_slice(),
+                route.latency_ms,
+            ) {
+                warnings.push(format!("warehouse sync (news) failed: {error}"));
+            }
+
+            let data = serde_json::to_value(NewsResponseData {
+                items: route.data.items,
+            })?;
+
+            Ok(CommandResult::ok(data, route.source_chain)
+                .with_errors(route.errors)
+                .with_warnings(warnings)
+                .with_latency(route.latency_ms)
+                .with_cache_hit(false))
+        }
+        Err(failure) => {
+            let data = serde_json::to_value(NewsResponseData { items: Vec::new() })?;
+            Ok(CommandResult::ok(data, failure.source_chain)
+                .with_errors(failure.errors)
+                .with_warnings(failure.warnings)
+                .with_latency(failure.latency_ms)
+                .with_cache_hit(false))
+        }
+    }
+}
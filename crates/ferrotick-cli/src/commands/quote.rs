@@ -1,10 +1,11 @@
 use serde::Serialize;
 
-use ferrotick_core::{Quote, QuoteRequest, SourceRouter, SourceStrategy, Symbol};
+use ferrotick_core::{Quote, QuoteRequest, SourceRouter, SourceStrategy};
 
 use crate::cli::QuoteArgs;
 use crate::error::CliError;
 
+use super::universe;
 use super::warehouse_sync;
 use super::CommandResult;
 
@@ -18,11 +19,7 @@ pub async fn run(
     router: &SourceRouter,
     strategy: &SourceStrategy,
 ) -> Result<CommandResult, CliError> {
-    let symbols = args
-        .symbols
-        .iter()
-        .map(|raw| Symbol::parse(raw))
-        .collect::<Result<Vec<_>, _>>()?;
+    let symbols = universe::resolve_symbols(&args.symbols, args.universe.as_deref())?;
 
     let request =
         QuoteRequest::new(symbols).map_err(|error| CliError::Command(error.to_string()))?;
@@ -0,0 +1,134 @@
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use ferrotick_core::{
+    compute_ratios, FinancialRatios, FinancialsRequest, FundamentalsRequest, ProviderId,
+    RatioBasis, SourceRouter, SourceStrategy, StatementType, Symbol,
+};
+
+use crate::cli::RatiosArgs;
+use crate::error::CliError;
+
+use super::warehouse_sync;
+use super::CommandResult;
+
+const STATEMENT_TYPES: [StatementType; 3] = [
+    StatementType::Income,
+    StatementType::Balance,
+    StatementType::CashFlow,
+];
+
+#[derive(Debug, Serialize)]
+struct RatiosResponseData {
+    symbol: Symbol,
+    basis: RatioBasis,
+    ratios: Vec<FinancialRatios>,
+}
+
+pub async fn run(
+    args: &RatiosArgs,
+    router: &SourceRouter,
+    strategy: &SourceStrategy,
+) -> Result<CommandResult, CliError> {
+    let symbol = Symbol::parse(&args.symbol)?;
+    let basis = RatioBasis::from_str(&args.period)?;
+    if args.limit == 0 {
+        return Err(CliError::Command(String::from(
+            "ratios limit must be greater than zero",
+        )));
+    }
+
+    // Growth needs the prior-year period, and TTM needs three extra quarters.
+    let fetch_limit = match basis {
+        RatioBasis::Annual => args.limit + 1,
+        RatioBasis::Quarterly => args.limit + 4,
+        RatioBasis::Ttm => args.limit + 7,
+    };
+
+    let mut statements = Vec::new();
+    let mut source_chain: Vec<ProviderId> = Vec::new();
+    let mut warnings = Vec::new();
+    let mut errors = Vec::new();
+    let mut latency_ms = 0;
+
+    for statement_type in STATEMENT_TYPES {
+        let request = FinancialsRequest::new(
+            symbol.clone(),
+            statement_type,
+            basis.statement_period(),
+            fetch_limit,
+        )
+        .map_err(|error| CliError::Command(error.to_string()))?;
+
+        match router.route_financials(&request, strategy.clone()).await {
+            Ok(route) => {
+                if let Err(error) = warehouse_sync::sync_financials(
+                    route.selected_source,
+                    route.data.financials.as_slice(),
+                    route.latency_ms,
+                ) {
+                    warnings.push(format!("warehouse sync (financials) failed: {error}"));
+                }
+                merge_chain(&mut source_chain, route.source_chain);
+                warnings.extend(route.warnings);
+                errors.extend(route.errors);
+                latency_ms += route.latency_ms;
+                statements.extend(route.data.financials);
+            }
+            Err(failure) => {
+                merge_chain(&mut source_chain, failure.source_chain);
+                warnings.extend(failure.warnings);
+                errors.extend(failure.errors);
+                latency_ms += failure.latency_ms;
+            }
+        }
+    }
+
+    let fundamentals_request = FundamentalsRequest::new(vec![symbol.clone()])
+        .map_err(|error| CliError::Command(error.to_string()))?;
+    let market_cap = match router
+        .route_fundamentals(&fundamentals_request, strategy.clone())
+        .await
+    {
+        Ok(route) => {
+            latency_ms += route.latency_ms;
+            route
+                .data
+                .fundamentals
+                .iter()
+                .find(|fundamental| fundamental.symbol == symbol)
+                .and_then(|fundamental| fundamental.market_cap)
+        }
+        Err(failure) => {
+            latency_ms += failure.latency_ms;
+            None
+        }
+    };
+    if market_cap.is_none() {
+        warnings.push(String::from("market cap unavailable; fcf_yield is omitted"));
+    }
+
+    let mut ratios = compute_ratios(&symbol, &statements, basis, market_cap);
+    ratios.truncate(args.limit);
+
+    let data = serde_json::to_value(RatiosResponseData {
+        symbol,
+        basis,
+        ratios,
+    })?;
+
+    Ok(CommandResult::ok(data, source_chain)
+        .with_errors(errors)
+        .with_warnings(warnings)
+        .with_latency(latency_ms)
+        .with_cache_hit(false))
+}
+
+fn merge_chain(chain: &mut Vec<ProviderId>, providers: Vec<ProviderId>) {
+    for provider in providers {
+        if !chain.contains(&provider) {
+            chain.push(provider);
+        }
+    }
+}
@@ -2,6 +2,7 @@ use std::fs;
 use std::path::{Path, PathBuf};
 
 use ferrotick_core::ProviderId;
+use ferrotick_warehouse::{AnalyticsObject, ANALYTICS_CATALOG};
 use serde::Serialize;
 
 use crate::cli::{SchemaArgs, SchemaCommand};
@@ -16,6 +17,11 @@ struct SchemaListResponseData {
     schemas: Vec<String>,
 }
 
+#[derive(Debug, Serialize)]
+struct SchemaViewsResponseData {
+    views: &'static [AnalyticsObject],
+}
+
 #[derive(Debug, Serialize)]
 struct SchemaGetResponseData {
     name: String,
@@ -47,6 +53,12 @@ pub fn run(args: &SchemaArgs, source_chain: Vec<ProviderId>) -> Result<CommandRe
 
             Ok(CommandResult::ok(serde_json::to_value(data)?, source_chain))
         }
+        SchemaCommand::Views => {
+            let data = SchemaViewsResponseData {
+                views: ANALYTICS_CATALOG,
+            };
+            Ok(CommandResult::ok(serde_json::to_value(data)?, source_chain))
+        }
     }
 }
 
@@ -5,6 +5,7 @@ use ferrotick_core::{Instrument, SearchRequest, SourceRouter, SourceStrategy};
 use crate::cli::SearchArgs;
 use crate::error::CliError;
 
+use super::warehouse_sync;
 use super::CommandResult;
 
 #[derive(Debug, Serialize)]
@@ -34,13 +35,22 @@ pub async fn run(
 
     match router.route_search(&request, strategy.clone()).await {
         Ok(route) => {
+            let mut warnings = route.warnings;
+            if let Err(error) = warehouse_sync::sync_instruments(
+                route.selected_source,
+                &route.data.results,
+                route.latency_ms,
+            ) {
+                warnings.push(format!("warehouse sync (instruments) failed: {error}"));
+            }
+
             let data = serde_json::to_value(SearchResponseData {
                 query: route.data.query,
                 results: route.data.results,
             })?;
             Ok(CommandResult::ok(data, route.source_chain)
                 .with_errors(route.errors)
-                .with_warnings(route.warnings)
+                .with_warnings(warnings)
                 .with_latency(route.latency_ms)
                 .with_cache_hit(false))
         }
@@ -1,6 +1,6 @@
 use serde::Serialize;
 
-use ferrotick_core::{ProviderId, SourceRouter};
+use ferrotick_core::{ProviderId, QuotaUsage, SourceRouter};
 
 use crate::cli::SourcesArgs;
 use crate::error::CliError;
@@ -20,6 +20,8 @@ struct SourceStatus {
     available: bool,
     status: &'static str,
     capabilities: Vec<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    quota: Option<QuotaUsage>,
 }
 
 #[derive(Debug, Serialize)]
@@ -32,8 +34,18 @@ pub async fn run(
     router: &SourceRouter,
     source_chain: Vec<ProviderId>,
 ) -> Result<CommandResult, CliError> {
-    let mut sources = Vec::with_capacity(PROVIDER_OUTPUT_ORDER.len());
-    for id in PROVIDER_OUTPUT_ORDER {
+    // Built-in providers are always listed; registered plugins follow by name.
+    let custom_providers = router
+        .providers()
+        .into_iter()
+        .filter(|provider| !provider.is_builtin());
+    let providers = PROVIDER_OUTPUT_ORDER
+        .into_iter()
+        .chain(custom_providers)
+        .collect::<Vec<_>>();
+
+    let mut sources = Vec::with_capacity(providers.len());
+    for id in providers {
         let source_status = match router.snapshot(id).await {
             Some(snapshot) => {
                 let capabilities = if args.verbose {
@@ -54,6 +66,7 @@ pub async fn run(
                     available: snapshot.available(),
                     status: snapshot.status_label(),
                     capabilities,
+                    quota: snapshot.quota,
                 }
             }
             None => SourceStatus {
@@ -61,6 +74,7 @@ pub async fn run(
                 available: false,
                 status: "not_configured",
                 capabilities: Vec::new(),
+                quota: None,
             },
         };
         sources.push(source_status);
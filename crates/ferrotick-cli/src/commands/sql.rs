@@ -1,8 +1,13 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
 use serde::Serialize;
 use serde_json::Value;
 
 use ferrotick_core::ProviderId;
-use ferrotick_warehouse::{QueryGuardrails, Warehouse};
+use ferrotick_warehouse::{
+    NamedQuery, QueryGuardrails, QueryParam, QueryParamType, QueryParams, SqlSandbox, Warehouse,
+};
 
 use crate::cli::SqlArgs;
 use crate::error::CliError;
@@ -18,38 +23,81 @@ struct SqlColumn {
 
 #[derive(Debug, Serialize)]
 struct SqlResponseData {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(skip_serializing_if = "QueryParams::is_empty")]
+    params: QueryParams,
     columns: Vec<SqlColumn>,
     rows: Vec<Vec<Value>>,
     row_count: usize,
     truncated: bool,
 }
 
+#[derive(Debug, Serialize)]
+struct SqlListResponseData {
+    queries_dir: String,
+    queries: Vec<NamedQuery>,
+}
+
 pub fn run(
     args: &SqlArgs,
     explain: bool,
     source_chain: Vec<ProviderId>,
 ) -> Result<CommandResult, CliError> {
-    let query = args.query.trim();
-    if query.is_empty() {
-        return Err(CliError::Command(String::from("query must not be empty")));
-    }
-
     // Open warehouse
     let warehouse = Warehouse::open_default()
         .map_err(|e| CliError::Command(format!("failed to open warehouse: {}", e)))?;
 
+    if args.list {
+        let queries = warehouse
+            .named_queries()
+            .map_err(|error| CliError::Command(error.to_string()))?;
+        let data = SqlListResponseData {
+            queries_dir: warehouse.queries_dir().display().to_string(),
+            queries,
+        };
+        return Ok(CommandResult::ok(serde_json::to_value(data)?, source_chain));
+    }
+
+    let (query, params) = match &args.name {
+        Some(name) => {
+            let named = warehouse
+                .named_query(name)
+                .map_err(|error| CliError::Command(error.to_string()))?;
+            let params = named
+                .bind(&named_param_values(&args.params)?)
+                .map_err(|error| CliError::Command(error.to_string()))?;
+            (named.sql, params)
+        }
+        None => (
+            args.query.clone().unwrap_or_default(),
+            parse_params(&args.params)?,
+        ),
+    };
+    let query = query.trim();
+    if query.is_empty() {
+        return Err(CliError::Command(String::from("query must not be empty")));
+    }
+
     // Execute query with guardrails
     let guardrails = QueryGuardrails {
         max_rows: args.max_rows,
         query_timeout_ms: args.query_timeout_ms,
     };
 
-    let result = warehouse
-        .execute_query(query, guardrails, args.write)
+    let sandbox = args.sandbox.then(|| sandbox(args));
+    let execute = |sql: &str, guardrails: QueryGuardrails| match &sandbox {
+        Some(sandbox) => warehouse.execute_sandboxed(sql, &params, guardrails, sandbox),
+        None => warehouse.execute_query_with_params(sql, &params, guardrails, args.write),
+    };
+
+    let result = execute(query, guardrails)
         .map_err(|e| CliError::Command(format!("query execution failed: {}", e)))?;
 
     // Transform result into response format
     let data = SqlResponseData {
+        name: args.name.clone(),
+        params: params.clone(),
         columns: result
             .columns
             .into_iter()
@@ -80,7 +128,7 @@ pub fn run(
             query_timeout_ms: args.query_timeout_ms,
         };
 
-        match warehouse.execute_query(explain_sql.as_str(), explain_guardrails, false) {
+        match execute(explain_sql.as_str(), explain_guardrails) {
             Ok(explain_result) => {
                 let plan_lines = explain_result
                     .rows
@@ -118,6 +166,100 @@ pub fn run(
     Ok(command_result)
 }
 
+/// Sandbox from `--allow-table`, `--allow-function` and the limit flags.
+fn sandbox(args: &SqlArgs) -> SqlSandbox {
+    let mut sandbox = SqlSandbox::new();
+    if !args.allow_tables.is_empty() {
+        sandbox = sandbox.with_allowed_tables(&args.allow_tables);
+    }
+    if !args.allow_functions.is_empty() {
+        sandbox = sandbox.with_allowed_functions(&args.allow_functions);
+    }
+    if let Some(memory_limit_mb) = args.memory_limit_mb {
+        sandbox = sandbox.with_memory_limit_mb(memory_limit_mb);
+    }
+    if let Some(threads) = args.threads {
+        sandbox = sandbox.with_threads(threads);
+    }
+    sandbox
+}
+
+/// Parse `--param NAME[:TYPE]=VALUE` arguments; the type defaults to text.
+pub(crate) fn parse_params(raw: &[String]) -> Result<QueryParams, CliError> {
+    let mut params = QueryParams::new();
+    for RawParam {
+        name,
+        param_type,
+        value,
+    } in split_params(raw)?
+    {
+        let param_type = match param_type {
+            Some(param_type) => QueryParamType::from_str(param_type)
+                .map_err(|error| CliError::Command(error.to_string()))?,
+            None => QueryParamType::Text,
+        };
+        let value: QueryParam = param_type
+            .parse_value(value)
+            .map_err(|error| CliError::Command(error.to_string()))?;
+        params.insert(name.to_string(), value);
+    }
+    Ok(params)
+}
+
+/// Raw `--param` values for a named query, which declares the types itself.
+fn named_param_values(raw: &[String]) -> Result<BTreeMap<String, String>, CliError> {
+    let mut values = BTreeMap::new();
+    for RawParam {
+        name,
+        param_type,
+        value,
+    } in split_params(raw)?
+    {
+        if param_type.is_some() {
+            return Err(CliError::Command(format!(
+                "--param {name}: named queries declare parameter types, pass NAME=VALUE"
+            )));
+        }
+        values.insert(name.to_string(), value.to_string());
+    }
+    Ok(values)
+}
+
+/// One `--param NAME[:TYPE]=VALUE` argument.
+struct RawParam<'a> {
+    name: &'a str,
+    param_type: Option<&'a str>,
+    value: &'a str,
+}
+
+fn split_params(raw: &[String]) -> Result<Vec<RawParam<'_>>, CliError> {
+    let mut params: Vec<RawParam<'_>> = Vec::with_capacity(raw.len());
+    for param in raw {
+        let (key, value) = param.split_once('=').ok_or_else(|| {
+            CliError::Command(format!("--param expects NAME[:TYPE]=VALUE, got '{param}'"))
+        })?;
+        let (name, param_type) = match key.split_once(':') {
+            Some((name, param_type)) => (name.trim(), Some(param_type)),
+            None => (key.trim(), None),
+        };
+        let name = name.trim_start_matches('$');
+        if name.is_empty() {
+            return Err(CliError::Command(format!(
+                "--param expects NAME[:TYPE]=VALUE, got '{param}'"
+            )));
+        }
+        if params.iter().any(|existing| existing.name == name) {
+            return Err(CliError::Command(format!("--param {name} given twice")));
+        }
+        params.push(RawParam {
+            name,
+            param_type,
+            value,
+        });
+    }
+    Ok(params)
+}
+
 fn format_sql_value(value: &Value) -> String {
     match value {
         Value::Null => String::from("null"),
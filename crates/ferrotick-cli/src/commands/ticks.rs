@@ -0,0 +1,99 @@
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use ferrotick_core::{
+    aggregate_trades, QuoteTick, SourceRouter, SourceStrategy, Symbol, TickBarSeries, TickBarSpec,
+    TickKind, TicksRequest, TradeTick, UtcDateTime,
+};
+
+use crate::cli::TicksArgs;
+use crate::error::CliError;
+
+use super::warehouse_sync;
+use super::CommandResult;
+
+#[derive(Debug, Serialize)]
+struct TicksResponseData {
+    symbol: Symbol,
+    kind: TickKind,
+    trades: Vec<TradeTick>,
+    quotes: Vec<QuoteTick>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bars: Option<TickBarSeries>,
+}
+
+pub async fn run(
+    args: &TicksArgs,
+    router: &SourceRouter,
+    strategy: &SourceStrategy,
+) -> Result<CommandResult, CliError> {
+    let symbol = Symbol::parse(&args.symbol)?;
+    let kind = match args.kind.trim().to_ascii_lowercase().as_str() {
+        "trades" => TickKind::Trades,
+        "quotes" => TickKind::Quotes,
+        other => {
+            return Err(CliError::Command(format!(
+                "invalid tick kind '{other}', expected one of trades, quotes"
+            )))
+        }
+    };
+    let bar_spec = args
+        .bars
+        .as_deref()
+        .map(TickBarSpec::from_str)
+        .transpose()?;
+    if bar_spec.is_some() && kind == TickKind::Quotes {
+        return Err(CliError::Command(String::from(
+            "--bars can only be used with --kind trades",
+        )));
+    }
+
+    let start = args.start.as_deref().map(UtcDateTime::parse).transpose()?;
+    let end = args.end.as_deref().map(UtcDateTime::parse).transpose()?;
+    let request = TicksRequest::new(symbol.clone(), kind, args.limit)
+        .and_then(|request| request.with_range(start, end))?;
+
+    match router.route_ticks(&request, strategy.clone()).await {
+        Ok(route) => {
+            let mut warnings = route.warnings;
+            if let Err(error) =
+                warehouse_sync::sync_ticks(route.selected_source, &route.data, route.latency_ms)
+            {
+                warnings.push(format!("warehouse sync (ticks) failed: {error}"));
+            }
+
+            let batch = route.data;
+            let bars = bar_spec
+                .map(|spec| aggregate_trades(&batch.symbol, &batch.trades, spec))
+                .transpose()?;
+            let data = serde_json::to_value(TicksResponseData {
+                symbol: batch.symbol,
+                kind,
+                trades: batch.trades,
+                quotes: batch.quotes,
+                bars,
+            })?;
+
+            Ok(CommandResult::ok(data, route.source_chain)
+                .with_errors(route.errors)
+                .with_warnings(warnings)
+                .with_latency(route.latency_ms)
+                .with_cache_hit(false))
+        }
+        Err(failure) => {
+            let data = serde_json::to_value(TicksResponseData {
+                symbol,
+                kind,
+                trades: Vec::new(),
+                quotes: Vec::new(),
+                bars: None,
+            })?;
+            Ok(CommandResult::ok(data, failure.source_chain)
+                .with_errors(failure.errors)
+                .with_warnings(failure.warnings)
+                .with_latency(failure.latency_ms)
+                .with_cache_hit(false))
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use ferrotick_core::{ProviderId, Symbol, UtcDateTime, Warehouse};
+use serde_json::json;
+
+use crate::cli::{UniverseArgs, UniverseCommand};
+use crate::error::CliError;
+
+use super::CommandResult;
+
+pub fn run(args: &UniverseArgs, source_chain: Vec<ProviderId>) -> Result<CommandResult, CliError> {
+    let warehouse =
+        Warehouse::open_default().map_err(|error| CliError::Command(error.to_string()))?;
+    let data = match &args.command {
+        UniverseCommand::Create(create_args) => {
+            warehouse
+                .create_universe(&create_args.name, create_args.description.as_deref())
+                .map_err(|error| CliError::Command(error.to_string()))?;
+            json!({
+                "name": create_args.name,
+                "description": create_args.description,
+                "created": true,
+            })
+        }
+        UniverseCommand::Add(change_args) => {
+            let symbols = parse_symbols(&change_args.symbols)?;
+            let date = change_args.date.clone().unwrap_or_else(today);
+            let joined = warehouse
+                .add_universe_members(&change_args.name, &symbols, &date)
+                .map_err(|error| CliError::Command(error.to_string()))?;
+            json!({
+                "universe": change_args.name,
+                "date": date,
+                "symbols": symbols,
+                "joined": joined,
+            })
+        }
+        UniverseCommand::Remove(change_args) => {
+            let symbols = parse_symbols(&change_args.symbols)?;
+            let date = change_args.date.clone().unwrap_or_else(today);
+            let removed = warehouse
+                .remove_universe_members(&change_args.name, &symbols, &date)
+                .map_err(|error| CliError::Command(error.to_string()))?;
+            json!({
+                "universe": change_args.name,
+                "date": date,
+                "symbols": symbols,
+                "removed": removed,
+            })
+        }
+        UniverseCommand::List => {
+            let universes = warehouse
+                .universes()
+                .map_err(|error| CliError::Command(error.to_string()))?;
+            json!({ "universes": universes })
+        }
+        UniverseCommand::Members(members_args) if members_args.history => {
+            let membership = warehouse
+                .universe_membership(&members_args.name)
+                .map_err(|error| CliError::Command(error.to_string()))?;
+            serde_json::to_value(membership)?
+        }
+        UniverseCommand::Members(members_args) => {
+            let as_of = members_args.as_of.clone().unwrap_or_else(today);
+            let members = warehouse
+                .universe_members(&members_args.name, &as_of)
+                .map_err(|error| CliError::Command(error.to_string()))?;
+            json!({
+                "universe": members_args.name,
+                "as_of": as_of,
+                "members": members,
+            })
+        }
+    };
+    Ok(CommandResult::ok(data, source_chain))
+}
+
+/// `symbols` followed by today's members of `universe`, without duplicates.
+pub(super) fn resolve_symbols(
+    symbols: &[String],
+    universe: Option<&str>,
+) -> Result<Vec<Symbol>, CliError> {
+    let mut resolved = symbols
+        .iter()
+        .map(|raw| Symbol::parse(raw))
+        .collect::<Result<Vec<_>, _>>()?;
+    if let Some(universe) = universe {
+        let warehouse =
+            Warehouse::open_default().map_err(|error| CliError::Command(error.to_string()))?;
+        let date = today();
+        let members = warehouse
+            .universe_members(universe, &date)
+            .map_err(|error| CliError::Command(error.to_string()))?;
+        if members.is_empty() {
+            return Err(CliError::Command(format!(
+                "universe '{universe}' has no members on {date}"
+            )));
+        }
+        for member in members {
+            let symbol = Symbol::parse(&member)?;
+            if !resolved.contains(&symbol) {
+                resolved.push(symbol);
+            }
+        }
+    }
+    Ok(resolved)
+}
+
+fn parse_symbols(raw: &[String]) -> Result<Vec<String>, CliError> {
+    raw.iter()
+        .map(|symbol| Ok(Symbol::parse(symbol)?.as_str().to_string()))
+        .collect()
+}
+
+fn today() -> String {
+    UtcDateTime::now().into_inner().date().to_string()
+}
@@ -0,0 +1,84 @@
+use std::path::Path;
+
+use ferrotick_core::{MigrationDirection, ProviderId, Warehouse, WarehouseConfig};
+use serde_json::json;
+
+use crate::cli::{MigrateCommand, WarehouseArgs, WarehouseCommand};
+use crate::error::CliError;
+
+use super::CommandResult;
+
+pub fn run(args: &WarehouseArgs, source_chain: Vec<ProviderId>) -> Result<CommandResult, CliError> {
+    match &args.command {
+        WarehouseCommand::Snapshot(snapshot_args) => {
+            let warehouse =
+                Warehouse::open_default().map_err(|error| CliError::Command(error.to_string()))?;
+            let report = warehouse
+                .snapshot(Path::new(&snapshot_args.output))
+                .map_err(|error| CliError::Command(error.to_string()))?;
+            let mut result = CommandResult::ok(serde_json::to_value(&report)?, source_chain);
+            if report.missing_partitions > 0 {
+                result = result.with_warning(format!(
+                    "{} cache_manifest partitions have no file and were left out; run `ferrotick cache sync`",
+                    report.missing_partitions
+                ));
+            }
+            Ok(result)
+        }
+        WarehouseCommand::Migrate(migrate_args) => {
+            let config = WarehouseConfig::default();
+            let data = match &migrate_args.command {
+                MigrateCommand::Status => {
+                    let migrations = Warehouse::migration_status(&config)
+                        .map_err(|error| CliError::Command(error.to_string()))?;
+                    let schema_version = migrations
+                        .iter()
+                        .filter(|migration| migration.applied)
+                        .map(|migration| migration.version.as_str())
+                        .max();
+                    let pending = migrations
+                        .iter()
+                        .filter(|migration| !migration.applied)
+                        .count();
+                    json!({
+                        "db_path": config.db_path,
+                        "schema_version": schema_version,
+                        "pending": pending,
+                        "migrations": migrations,
+                    })
+                }
+                MigrateCommand::Up(up_args) => serde_json::to_value(
+                    Warehouse::migrate(
+                        &config,
+                        MigrationDirection::Up,
+                        up_args.to.as_deref(),
+                        up_args.dry_run,
+                    )
+                    .map_err(|error| CliError::Command(error.to_string()))?,
+                )?,
+                MigrateCommand::Down(down_args) => serde_json::to_value(
+                    Warehouse::migrate(
+                        &config,
+                        MigrationDirection::Down,
+                        Some(&down_args.to),
+                        down_args.dry_run,
+                    )
+                    .map_err(|error| CliError::Command(error.to_string()))?,
+                )?,
+            };
+            Ok(CommandResult::ok(data, source_chain))
+        }
+        WarehouseCommand::Restore(restore_args) => {
+            // The warehouse must stay closed while its files are swapped.
+            let report = Warehouse::restore_snapshot(
+                &WarehouseConfig::default(),
+                Path::new(&restore_args.snapshot),
+            )
+            .map_err(|error| CliError::Command(error.to_string()))?;
+            Ok(CommandResult::ok(
+                serde_json::to_value(report)?,
+                source_chain,
+            ))
+        }
+    }
+}
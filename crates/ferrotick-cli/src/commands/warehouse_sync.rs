@@ -1,8 +1,11 @@
 use uuid::Uuid;
 
 use ferrotick_core::{
-    Bar, BarRecord, Fundamental, FundamentalRecord, Interval, ProviderId, Quote, QuoteRecord,
-    Warehouse, WarehouseError,
+    AssetClass, Bar, BarRecord, EarningsRecord, EarningsReport, EconomicSeries, FinancialMetric,
+    FinancialPeriod, FinancialRecord, FinancialStatement, Fundamental, FundamentalRecord,
+    Instrument, InstrumentRecord, Interval, MacroObservationRecord, NewsItem, NewsRecord,
+    NewsTickerRecord, ProviderId, Quote, QuoteRecord, QuoteTickRecord, StatementType, TickBatch,
+    TradeTickRecord, Warehouse, WarehouseError,
 };
 
 pub fn sync_quotes(
@@ -49,29 +52,13 @@ pub fn sync_bars(
 
     let warehouse = Warehouse::open_default()?;
     let request_id = format!("bars:{}", Uuid::new_v4());
-    let dataset = match interval {
-        Interval::OneDay => "bars_1d",
-        Interval::OneMinute
-        | Interval::FiveMinutes
-        | Interval::FifteenMinutes
-        | Interval::OneHour => "bars_1m",
-    };
-
     let rows = bars
         .iter()
-        .map(|bar| BarRecord {
-            symbol: symbol.to_string(),
-            ts: bar.ts.format_rfc3339(),
-            open: bar.open,
-            high: bar.high,
-            low: bar.low,
-            close: bar.close,
-            volume: bar.volume,
-        })
+        .map(|bar| bar_record(symbol, bar))
         .collect::<Vec<_>>();
     warehouse.ingest_bars(
         source.as_str(),
-        dataset,
+        interval.bars_dataset(),
         request_id.as_str(),
         rows.as_slice(),
         latency_ms,
@@ -89,8 +76,39 @@ pub fn sync_fundamentals(
 
     let warehouse = Warehouse::open_default()?;
     let request_id = format!("fundamentals:{}", Uuid::new_v4());
-    let mut rows = Vec::new();
+    let (rows, instruments) = fundamental_records(fundamentals);
+    warehouse.ingest_fundamentals(
+        source.as_str(),
+        request_id.as_str(),
+        rows.as_slice(),
+        latency_ms,
+    )?;
+    warehouse.ingest_instruments(
+        source.as_str(),
+        request_id.as_str(),
+        instruments.as_slice(),
+        latency_ms,
+    )
+}
 
+pub(crate) fn bar_record(symbol: &str, bar: &Bar) -> BarRecord {
+    BarRecord {
+        symbol: symbol.to_string(),
+        ts: bar.ts.format_rfc3339(),
+        open: bar.open,
+        high: bar.high,
+        low: bar.low,
+        close: bar.close,
+        volume: bar.volume,
+    }
+}
+
+/// One record per reported metric, plus the sector and industry of each
+/// classified snapshot for the instrument master.
+pub(crate) fn fundamental_records(
+    fundamentals: &[Fundamental],
+) -> (Vec<FundamentalRecord>, Vec<InstrumentRecord>) {
+    let mut rows = Vec::new();
     for row in fundamentals {
         let date = row.as_of.format_rfc3339();
         if let Some(value) = row.market_cap {
@@ -119,10 +137,294 @@ pub fn sync_fundamentals(
         }
     }
 
-    warehouse.ingest_fundamentals(
+    let instruments = fundamentals
+        .iter()
+        .filter(|row| row.sector.is_some() || row.industry.is_some())
+        .map(|row| InstrumentRecord {
+            symbol: row.symbol.as_str().to_string(),
+            sector: row.sector.clone(),
+            industry: row.industry.clone(),
+            ..InstrumentRecord::default()
+        })
+        .collect::<Vec<_>>();
+    (rows, instruments)
+}
+
+pub fn sync_instruments(
+    source: ProviderId,
+    instruments: &[Instrument],
+    latency_ms: u64,
+) -> Result<(), WarehouseError> {
+    if instruments.is_empty() {
+        return Ok(());
+    }
+
+    let warehouse = Warehouse::open_default()?;
+    let request_id = format!("search:{}", Uuid::new_v4());
+    let rows = instruments
+        .iter()
+        .map(|instrument| InstrumentRecord {
+            symbol: instrument.symbol.as_str().to_string(),
+            name: Some(instrument.name.clone()),
+            exchange: instrument.exchange.clone(),
+            currency: Some(instrument.currency.clone()),
+            asset_class: Some(asset_class_str(instrument.asset_class).to_string()),
+            is_active: Some(instrument.is_active),
+            isin: instrument.identifiers.isin.clone(),
+            cusip: instrument.identifiers.cusip.clone(),
+            figi: instrument.identifiers.figi.clone(),
+            cik: instrument.identifiers.cik.clone(),
+            sector: instrument.sector.clone(),
+            industry: instrument.industry.clone(),
+            listed_at: instrument.listed_at.map(|ts| ts.format_rfc3339()),
+            delisted_at: instrument.delisted_at.map(|ts| ts.format_rfc3339()),
+        })
+        .collect::<Vec<_>>();
+    warehouse.ingest_instruments(
+        source.as_str(),
+        request_id.as_str(),
+        rows.as_slice(),
+        latency_ms,
+    )
+}
+
+pub fn sync_financials(
+    source: ProviderId,
+    statements: &[FinancialStatement],
+    latency_ms: u64,
+) -> Result<(), WarehouseError> {
+    let rows = statements
+        .iter()
+        .flat_map(|statement| {
+            statement.line_items.iter().filter_map(move |item| {
+                Some(FinancialRecord {
+                    symbol: statement.symbol.as_str().to_string(),
+                    statement_type: statement_type_str(statement.statement_type).to_string(),
+                    period: period_str(statement.period).to_string(),
+                    label: item.label.clone(),
+                    metric: FinancialMetric::from_label(&item.label)
+                        .map(|metric| metric.as_str().to_string()),
+                    value: item.value?,
+                    fiscal_year: item.fiscal_year,
+                    fiscal_quarter: item.fiscal_quarter,
+                    end_date: item.end_date.format_rfc3339(),
+                    available_at: None,
+                    currency: statement.currency.clone(),
+                })
+            })
+        })
+        .collect::<Vec<_>>();
+    if rows.is_empty() {
+        return Ok(());
+    }
+
+    let warehouse = Warehouse::open_default()?;
+    let request_id = format!("financials:{}", Uuid::new_v4());
+    warehouse.ingest_financials(
+        source.as_str(),
+        request_id.as_str(),
+        rows.as_slice(),
+        latency_ms,
+    )
+}
+
+pub fn sync_earnings(
+    source: ProviderId,
+    report: &EarningsReport,
+    latency_ms: u64,
+) -> Result<(), WarehouseError> {
+    if report.entries.is_empty() {
+        return Ok(());
+    }
+
+    let warehouse = Warehouse::open_default()?;
+    let request_id = format!("earnings:{}", Uuid::new_v4());
+    let rows = report
+        .entries
+        .iter()
+        .map(|entry| EarningsRecord {
+            symbol: report.symbol.as_str().to_string(),
+            fiscal_year: entry.fiscal_year,
+            fiscal_quarter: entry.fiscal_quarter,
+            period_end: entry.end_date.format_rfc3339(),
+            report_date: entry.report_date.map(|date| date.format_rfc3339()),
+            eps_actual: entry.eps_actual,
+            eps_estimate: entry.eps_estimate,
+            revenue_actual: entry.revenue_actual,
+            revenue_estimate: entry.revenue_estimate,
+            surprise_percent: entry.surprise_percent,
+        })
+        .collect::<Vec<_>>();
+    warehouse.ingest_earnings(
+        source.as_str(),
+        request_id.as_str(),
+        rows.as_slice(),
+        latency_ms,
+    )
+}
+
+pub fn sync_economic(
+    source: ProviderId,
+    series: &EconomicSeries,
+    latency_ms: u64,
+) -> Result<(), WarehouseError> {
+    if series.observations.is_empty() {
+        return Ok(());
+    }
+
+    let warehouse = Warehouse::open_default()?;
+    let request_id = format!("economic:{}", Uuid::new_v4());
+    let rows = series
+        .observations
+        .iter()
+        .map(|observation| MacroObservationRecord {
+            series_id: series.series_id.clone(),
+            date: observation.date.format_rfc3339(),
+            value: observation.value,
+            unit: Some(series.unit.clone()).filter(|unit| !unit.is_empty()),
+            frequency: Some(series.frequency.as_str().to_string()),
+        })
+        .collect::<Vec<_>>();
+    warehouse.ingest_macro_series(
         source.as_str(),
         request_id.as_str(),
         rows.as_slice(),
         latency_ms,
     )
 }
+
+pub fn sync_news(
+    source: ProviderId,
+    items: &[NewsItem],
+    latency_ms: u64,
+) -> Result<(), WarehouseError> {
+    if items.is_empty() {
+        return Ok(());
+    }
+
+    let warehouse = Warehouse::open_default()?;
+    let request_id = format!("news:{}", Uuid::new_v4());
+    let rows = items
+        .iter()
+        .map(|item| {
+            // Tickers without a sentiment entry are still stored for joins.
+            let mut tickers = item
+                .ticker_sentiment
+                .iter()
+                .map(|entry| NewsTickerRecord {
+                    symbol: entry.symbol.as_str().to_string(),
+                    sentiment: entry.score,
+                    relevance: entry.relevance,
+                })
+                .collect::<Vec<_>>();
+            for symbol in &item.tickers {
+                if !tickers
+                    .iter()
+                    .any(|ticker| ticker.symbol == symbol.as_str())
+                {
+                    tickers.push(NewsTickerRecord {
+                        symbol: symbol.as_str().to_string(),
+                        sentiment: None,
+                        relevance: None,
+                    });
+                }
+            }
+
+            NewsRecord {
+                url: item.url.clone(),
+                published_at: item.published_at.format_rfc3339(),
+                publisher: item.source.clone(),
+                title: item.title.clone(),
+                summary: item.summary.clone(),
+                sentiment: item.sentiment,
+                tickers,
+            }
+        })
+        .collect::<Vec<_>>();
+    warehouse.ingest_news(
+        source.as_str(),
+        request_id.as_str(),
+        rows.as_slice(),
+        latency_ms,
+    )
+}
+
+pub fn sync_ticks(
+    source: ProviderId,
+    batch: &TickBatch,
+    latency_ms: u64,
+) -> Result<(), WarehouseError> {
+    if batch.trades.is_empty() && batch.quotes.is_empty() {
+        return Ok(());
+    }
+
+    let warehouse = Warehouse::open_default()?;
+    let request_id = format!("ticks:{}", Uuid::new_v4());
+    let symbol = batch.symbol.as_str();
+    let trades = batch
+        .trades
+        .iter()
+        .map(|trade| TradeTickRecord {
+            symbol: symbol.to_string(),
+            ts: trade.ts.format_rfc3339(),
+            price: trade.price,
+            size: trade.size,
+            exchange: trade.exchange.clone(),
+            conditions: trade.conditions.clone(),
+        })
+        .collect::<Vec<_>>();
+    warehouse.ingest_trade_ticks(
+        source.as_str(),
+        request_id.as_str(),
+        trades.as_slice(),
+        latency_ms,
+    )?;
+
+    let quotes = batch
+        .quotes
+        .iter()
+        .map(|quote| QuoteTickRecord {
+            symbol: symbol.to_string(),
+            ts: quote.ts.format_rfc3339(),
+            bid: quote.bid,
+            bid_size: quote.bid_size,
+            ask: quote.ask,
+            ask_size: quote.ask_size,
+            bid_exchange: quote.bid_exchange.clone(),
+            ask_exchange: quote.ask_exchange.clone(),
+        })
+        .collect::<Vec<_>>();
+    warehouse.ingest_quote_ticks(
+        source.as_str(),
+        request_id.as_str(),
+        quotes.as_slice(),
+        latency_ms,
+    )
+}
+
+fn asset_class_str(asset_class: AssetClass) -> &'static str {
+    match asset_class {
+        AssetClass::Equity => "equity",
+        AssetClass::Etf => "etf",
+        AssetClass::Index => "index",
+        AssetClass::Crypto => "crypto",
+        AssetClass::Forex => "forex",
+        AssetClass::Fund => "fund",
+        AssetClass::Other => "other",
+    }
+}
+
+fn statement_type_str(statement_type: StatementType) -> &'static str {
+    match statement_type {
+        StatementType::Income => "income",
+        StatementType::Balance => "balance",
+        StatementType::CashFlow => "cash_flow",
+    }
+}
+
+fn period_str(period: FinancialPeriod) -> &'static str {
+    match period {
+        FinancialPeriod::Annual => "annual",
+        FinancialPeriod::Quarterly => "quarterly",
+    }
+}
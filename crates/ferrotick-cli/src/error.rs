@@ -47,3 +47,9 @@ impl From<ferrotick_core::SourceError> for CliError {
         CliError::Command(error.message().to_string())
     }
 }
+
+impl From<ferrotick_core::WarehouseError> for CliError {
+    fn from(error: ferrotick_core::WarehouseError) -> Self {
+        CliError::Command(error.to_string())
+    }
+}
@@ -5,7 +5,11 @@ mod metadata;
 mod output;
 
 use clap::Parser;
+use ferrotick_core::RequestContext;
+use ferrotick_telemetry::{Telemetry, TelemetryConfig, TelemetryError};
 use std::process::ExitCode;
+use std::time::Duration;
+use tracing::Instrument;
 
 use crate::cli::Cli;
 use crate::error::CliError;
@@ -23,13 +27,30 @@ async fn main() -> ExitCode {
 
 async fn run() -> Result<ExitCode, CliError> {
     let cli = Cli::parse();
+    ferrotick_warehouse::set_binary_version(env!("CARGO_PKG_VERSION"));
 
-    let envelope = commands::run(&cli).await?;
-    if cli.stream {
-        output::render_stream(&envelope, cli.explain)?;
-    } else {
-        output::render(&envelope, cli.format, cli.pretty)?;
+    // `--timeout-ms` bounds routing, adapter retries and HTTP calls alike.
+    let context = RequestContext::with_timeout(Duration::from_millis(cli.timeout_ms));
+    let telemetry = Telemetry::install(
+        TelemetryConfig::new("ferrotick")
+            .with_env_exporter()
+            .with_profile(cli.profile),
+    );
+    let command = commands::run(&cli).instrument(tracing::info_span!("command"));
+    let rendered = async {
+        let envelope = context.scope(command).await?;
+        if cli.stream {
+            output::render_stream(&envelope, cli.explain)?;
+        } else {
+            output::render(&envelope, cli.format, cli.pretty)?;
+        }
+        Ok::<_, CliError>(envelope)
     }
+    .await;
+
+    // Failed commands are the ones most worth profiling, so report either way.
+    finish_telemetry(telemetry).await;
+    let envelope = rendered?;
 
     if cli.strict && (!envelope.meta.warnings.is_empty() || !envelope.errors.is_empty()) {
         return Err(CliError::StrictModeViolation {
@@ -44,3 +65,20 @@ async fn run() -> Result<ExitCode, CliError> {
 
     Ok(ExitCode::SUCCESS)
 }
+
+/// Print the profile and export collected spans.
+///
+/// Timing breakdown and exported spans never mix with the envelope on stdout.
+async fn finish_telemetry(telemetry: Result<Telemetry, TelemetryError>) {
+    match telemetry {
+        Ok(telemetry) => {
+            if let Some(profile) = telemetry.profile() {
+                eprintln!("{}", serde_json::json!({ "profile": profile }));
+            }
+            if let Err(error) = telemetry.flush().await {
+                eprintln!("warning: {error}");
+            }
+        }
+        Err(error) => eprintln!("warning: {error}"),
+    }
+}
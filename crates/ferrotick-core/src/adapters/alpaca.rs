@@ -9,9 +9,12 @@ use crate::circuit_breaker::{CircuitBreaker, CircuitState};
 use crate::data_source::{
     BarsRequest, CapabilitySet, DataSource, Endpoint, FundamentalsBatch, FundamentalsRequest,
     HealthState, HealthStatus, QuoteBatch, QuoteRequest, SearchBatch, SearchRequest, SourceError,
+    TickBatch, TickKind, TicksRequest,
 };
 use crate::http_client::{HttpClient, HttpRequest};
-use crate::{Bar, BarSeries, Interval, ProviderId, Quote, Symbol, UtcDateTime};
+use crate::{
+    Bar, BarSeries, Interval, ProviderId, Quote, QuoteTick, Symbol, TradeTick, UtcDateTime,
+};
 
 /// Alpaca adapter for real API calls.
 #[derive(Clone)]
@@ -194,6 +197,107 @@ impl AlpacaAdapter {
         Ok(series)
     }
 
+    async fn fetch_real_ticks(&self, req: &TicksRequest) -> Result<TickBatch, SourceError> {
+        if !self.circuit_breaker.allow_request() {
+            return Err(SourceError::unavailable("alpaca circuit breaker is open"));
+        }
+
+        let mut endpoint = format!(
+            "https://data.alpaca.markets/v2/stocks/{}/{}?limit={}",
+            req.symbol.as_str(),
+            req.kind.as_str(),
+            req.limit.min(10_000)
+        );
+        if let Some(start) = req.start {
+            endpoint.push_str(&format!(
+                "&start={}",
+                urlencoding::encode(&start.format_rfc3339())
+            ));
+        }
+        if let Some(end) = req.end {
+            endpoint.push_str(&format!(
+                "&end={}",
+                urlencoding::encode(&end.format_rfc3339())
+            ));
+        }
+
+        let request = HttpRequest::get(&endpoint)
+            .with_header("APCA-API-KEY-ID", &self.api_key)
+            .with_header("APCA-API-SECRET-KEY", &self.secret_key)
+            .with_timeout_ms(10_000);
+
+        let response = self.http_client.execute(request).await.map_err(|e| {
+            self.circuit_breaker.record_failure();
+            SourceError::unavailable(format!("alpaca transport error: {}", e.message()))
+        })?;
+
+        if !response.is_success() {
+            self.circuit_breaker.record_failure();
+            return Err(SourceError::unavailable(format!(
+                "alpaca returned status {}",
+                response.status
+            )));
+        }
+
+        self.circuit_breaker.record_success();
+        self.parse_ticks_response(req, &response.body)
+    }
+
+    fn parse_ticks_response(
+        &self,
+        req: &TicksRequest,
+        body: &str,
+    ) -> Result<TickBatch, SourceError> {
+        let ticks_response: AlpacaTicksResponse = serde_json::from_str(body)
+            .map_err(|e| SourceError::internal(format!("failed to parse alpaca ticks: {}", e)))?;
+
+        let mut batch = TickBatch {
+            symbol: req.symbol.clone(),
+            trades: Vec::new(),
+            quotes: Vec::new(),
+        };
+        match req.kind {
+            TickKind::Trades => {
+                batch.trades = ticks_response
+                    .trades
+                    .into_iter()
+                    .take(req.limit)
+                    .filter_map(|trade| {
+                        TradeTick::new(
+                            UtcDateTime::parse(&trade.t).ok()?,
+                            trade.p,
+                            trade.s,
+                            trade.x,
+                            trade.c,
+                        )
+                        .ok()
+                    })
+                    .collect();
+            }
+            TickKind::Quotes => {
+                batch.quotes = ticks_response
+                    .quotes
+                    .into_iter()
+                    .take(req.limit)
+                    .filter_map(|quote| {
+                        QuoteTick::new(
+                            UtcDateTime::parse(&quote.t).ok()?,
+                            quote.bp,
+                            quote.bs,
+                            quote.ap,
+                            quote.r#as,
+                            quote.bx,
+                            quote.ax,
+                        )
+                        .ok()
+                    })
+                    .collect();
+            }
+        }
+
+        Ok(batch)
+    }
+
     fn parse_quote_response(&self, body: &str) -> Result<QuoteBatch, SourceError> {
         let alpaca_response: AlpacaQuotesResponse = serde_json::from_str(body).map_err(|e| {
             SourceError::internal(format!("failed to parse alpaca response: {}", e))
@@ -262,7 +366,7 @@ impl DataSource for AlpacaAdapter {
     }
 
     fn capabilities(&self) -> CapabilitySet {
-        CapabilitySet::new(true, true, false, false, false, false)
+        CapabilitySet::new(true, true, false, false, false, false, true, false, false)
     }
 
     fn quote<'a>(
@@ -347,6 +451,36 @@ impl DataSource for AlpacaAdapter {
         })
     }
 
+    fn ticks<'a>(
+        &'a self,
+        req: TicksRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<TickBatch, SourceError>> + Send + 'a>> {
+        Box::pin(async move { self.fetch_real_ticks(&req).await })
+    }
+
+    fn economic<'a>(
+        &'a self,
+        _req: crate::data_source::EconomicRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<crate::EconomicSeries, SourceError>> + Send + 'a>> {
+        Box::pin(async move {
+            Err(SourceError::unsupported_endpoint(
+                crate::data_source::Endpoint::Economic,
+            ))
+        })
+    }
+
+    fn news<'a>(
+        &'a self,
+        _req: crate::data_source::NewsRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<crate::data_source::NewsBatch, SourceError>> + Send + 'a>>
+    {
+        Box::pin(async move {
+            Err(SourceError::unsupported_endpoint(
+                crate::data_source::Endpoint::News,
+            ))
+        })
+    }
+
     fn health<'a>(&'a self) -> Pin<Box<dyn Future<Output = HealthStatus> + Send + 'a>> {
         Box::pin(async move {
             let circuit_state = self.circuit_breaker.state();
@@ -413,6 +547,39 @@ struct AlpacaBarData {
     vw: Option<f64>, // vwap
 }
 
+#[derive(Debug, Clone, Deserialize)]
+struct AlpacaTicksResponse {
+    #[serde(default)]
+    trades: Vec<AlpacaTradeData>,
+    #[serde(default)]
+    quotes: Vec<AlpacaQuoteTickData>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct AlpacaTradeData {
+    t: String, // timestamp
+    p: f64,    // price
+    s: u64,    // size
+    #[serde(default)]
+    x: Option<String>, // exchange
+    #[serde(default)]
+    c: Vec<String>, // conditions
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct AlpacaQuoteTickData {
+    t: String, // timestamp
+    bp: f64,   // bid price
+    bs: u64,   // bid size
+    ap: f64,   // ask price
+    #[serde(rename = "as")]
+    r#as: u64, // ask size
+    #[serde(default)]
+    bx: Option<String>, // bid exchange
+    #[serde(default)]
+    ax: Option<String>, // ask exchange
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -471,6 +638,42 @@ mod tests {
         assert!(!health.rate_available);
     }
 
+    #[test]
+    fn quote_ticks_are_parsed() {
+        let client = Arc::new(RecordingHttpClient::with_response(Ok(
+            HttpResponse::ok_json(
+                r#"{
+                "symbol": "AAPL",
+                "quotes": [
+                    {"t": "2024-01-02T14:30:00.000123Z", "bx": "Q", "bp": 185.49, "bs": 3, "ax": "P", "ap": 185.51, "as": 2, "c": ["R"], "z": "C"}
+                ],
+                "next_page_token": null
+            }"#,
+            ),
+        )));
+        let adapter =
+            AlpacaAdapter::with_http_client(client.clone(), "demo-key", "demo-secret", None);
+        let request = TicksRequest::new(
+            Symbol::parse("AAPL").expect("valid symbol"),
+            TickKind::Quotes,
+            10,
+        )
+        .expect("valid request");
+
+        let batch = block_on(adapter.ticks(request)).expect("quotes should parse");
+
+        assert_eq!(batch.quotes.len(), 1);
+        let quote = &batch.quotes[0];
+        assert_eq!(quote.ask_size, 2);
+        assert_eq!(quote.bid_exchange.as_deref(), Some("Q"));
+        assert!((quote.mid() - 185.5).abs() < 1e-9);
+        let requests = client
+            .requests
+            .lock()
+            .expect("request store should not be poisoned");
+        assert!(requests[0].url.contains("/v2/stocks/AAPL/quotes"));
+    }
+
     fn block_on<F>(future: F) -> F::Output
     where
         F: Future,
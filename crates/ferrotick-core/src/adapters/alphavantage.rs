@@ -7,17 +7,36 @@ use serde::Deserialize;
 use crate::cache::CacheStore;
 use crate::circuit_breaker::{CircuitBreaker, CircuitState};
 use crate::data_source::{
-    BarsRequest, CapabilitySet, DataSource, FundamentalsBatch, FundamentalsRequest, HealthState,
-    HealthStatus, QuoteBatch, QuoteRequest, SearchBatch, SearchRequest, SourceError,
+    BarsRequest, CapabilitySet, DataSource, EarningsBatch, EarningsRequest, EconomicRequest,
+    FundamentalsBatch, FundamentalsRequest, HealthState, HealthStatus, NewsBatch, NewsRequest,
+    QuoteBatch, QuoteRequest, SearchBatch, SearchRequest, SourceError,
 };
 use crate::http_client::{HttpClient, HttpRequest};
 use crate::provider_policy::ProviderPolicy;
 use crate::throttling::ThrottlingQueue;
 use crate::{
-    AssetClass, Bar, BarSeries, Fundamental, Instrument, Interval, ProviderId, Quote, Symbol,
-    UtcDateTime,
+    AssetClass, Bar, BarSeries, EarningsEntry, EarningsReport, EconomicObservation, EconomicSeries,
+    Fundamental, Instrument, Interval, NewsItem, ProviderId, Quote, SeriesFrequency, Symbol,
+    TickerSentiment, UtcDateTime,
 };
 
+/// Economic indicator functions exposed by Alpha Vantage.
+const ECONOMIC_FUNCTIONS: [&str; 10] = [
+    "REAL_GDP",
+    "REAL_GDP_PER_CAPITA",
+    "TREASURY_YIELD",
+    "FEDERAL_FUNDS_RATE",
+    "CPI",
+    "INFLATION",
+    "RETAIL_SALES",
+    "DURABLES",
+    "UNEMPLOYMENT",
+    "NONFARM_PAYROLL",
+];
+
+/// Treasury maturities accepted as `TREASURY_YIELD_<MATURITY>` suffixes.
+const TREASURY_MATURITIES: [&str; 6] = ["3MONTH", "2YEAR", "5YEAR", "7YEAR", "10YEAR", "30YEAR"];
+
 /// Alpha Vantage adapter for real API calls.
 #[derive(Clone)]
 pub struct AlphaVantageAdapter {
@@ -85,6 +104,29 @@ impl AlphaVantageAdapter {
     fn quote_cache_key(symbol: &Symbol) -> String {
         format!("quote:{}", symbol.as_str())
     }
+
+    fn earnings_cache_key(symbol: &Symbol) -> String {
+        format!("earnings:{}", symbol.as_str())
+    }
+
+    fn news_cache_key(tickers: &str, req: &NewsRequest) -> String {
+        format!(
+            "news:{}:{}:{}",
+            tickers,
+            req.since
+                .map(UtcDateTime::format_rfc3339)
+                .unwrap_or_default(),
+            req.limit
+        )
+    }
+
+    fn economic_cache_key(req: &EconomicRequest) -> String {
+        format!(
+            "economic:{}:{}",
+            req.series_id,
+            req.frequency.map_or("default", SeriesFrequency::as_str)
+        )
+    }
 }
 
 // Real API implementation methods
@@ -224,6 +266,174 @@ impl AlphaVantageAdapter {
         Ok(FundamentalsBatch { fundamentals })
     }
 
+    async fn fetch_real_earnings(
+        &self,
+        req: &EarningsRequest,
+    ) -> Result<EarningsBatch, SourceError> {
+        let cache_key = Self::earnings_cache_key(&req.symbol);
+        if let Some(cached_body) = self.cache.get(&cache_key).await {
+            return self.parse_earnings_response(req, &cached_body);
+        }
+
+        if !self.circuit_breaker.allow_request() {
+            return Err(SourceError::unavailable(
+                "alphavantage circuit breaker is open",
+            ));
+        }
+
+        let retry_delay = self.throttling.acquire().err();
+        if let Some(delay) = retry_delay {
+            return Err(SourceError::rate_limited(format!(
+                "alphavantage free-tier limit exceeded; retry in {:.2}s",
+                delay.as_secs_f64()
+            )));
+        }
+
+        let endpoint = format!(
+            "https://www.alphavantage.co/query?function=EARNINGS&symbol={}&apikey={}",
+            req.symbol.as_str(),
+            self.api_key
+        );
+
+        let request = HttpRequest::get(&endpoint).with_timeout_ms(5_000);
+
+        let response = self.http_client.execute(request).await.map_err(|e| {
+            self.circuit_breaker.record_failure();
+            SourceError::unavailable(format!("alphavantage transport error: {}", e.message()))
+        })?;
+
+        if !response.is_success() {
+            self.circuit_breaker.record_failure();
+            return Err(SourceError::unavailable(format!(
+                "alphavantage returned status {}",
+                response.status
+            )));
+        }
+
+        self.throttling.complete_one();
+        self.circuit_breaker.record_success();
+        let batch = self.parse_earnings_response(req, &response.body)?;
+        self.cache.put(cache_key, response.body, None).await;
+        Ok(batch)
+    }
+
+    async fn fetch_real_economic(
+        &self,
+        req: &EconomicRequest,
+    ) -> Result<EconomicSeries, SourceError> {
+        let (function, maturity) = economic_function(&req.series_id)?;
+
+        let cache_key = Self::economic_cache_key(req);
+        if let Some(cached_body) = self.cache.get(&cache_key).await {
+            return self.parse_economic_response(req, &cached_body);
+        }
+
+        if !self.circuit_breaker.allow_request() {
+            return Err(SourceError::unavailable(
+                "alphavantage circuit breaker is open",
+            ));
+        }
+
+        let retry_delay = self.throttling.acquire().err();
+        if let Some(delay) = retry_delay {
+            return Err(SourceError::rate_limited(format!(
+                "alphavantage free-tier limit exceeded; retry in {:.2}s",
+                delay.as_secs_f64()
+            )));
+        }
+
+        let mut endpoint = format!(
+            "https://www.alphavantage.co/query?function={}&apikey={}",
+            function, self.api_key
+        );
+        if let Some(frequency) = req.frequency {
+            endpoint.push_str(&format!("&interval={}", frequency.as_str()));
+        }
+        if let Some(maturity) = maturity {
+            endpoint.push_str(&format!("&maturity={}", maturity.to_ascii_lowercase()));
+        }
+
+        let request = HttpRequest::get(&endpoint).with_timeout_ms(5_000);
+
+        let response = self.http_client.execute(request).await.map_err(|e| {
+            self.circuit_breaker.record_failure();
+            SourceError::unavailable(format!("alphavantage transport error: {}", e.message()))
+        })?;
+
+        if !response.is_success() {
+            self.circuit_breaker.record_failure();
+            return Err(SourceError::unavailable(format!(
+                "alphavantage returned status {}",
+                response.status
+            )));
+        }
+
+        self.throttling.complete_one();
+        self.circuit_breaker.record_success();
+        let series = self.parse_economic_response(req, &response.body)?;
+        self.cache.put(cache_key, response.body, None).await;
+        Ok(series)
+    }
+
+    async fn fetch_real_news(&self, req: &NewsRequest) -> Result<NewsBatch, SourceError> {
+        let tickers = req
+            .symbols
+            .iter()
+            .map(Symbol::as_str)
+            .collect::<Vec<_>>()
+            .join(",");
+
+        let cache_key = Self::news_cache_key(&tickers, req);
+        if let Some(cached_body) = self.cache.get(&cache_key).await {
+            return self.parse_news_response(req, &cached_body);
+        }
+
+        if !self.circuit_breaker.allow_request() {
+            return Err(SourceError::unavailable(
+                "alphavantage circuit breaker is open",
+            ));
+        }
+
+        let retry_delay = self.throttling.acquire().err();
+        if let Some(delay) = retry_delay {
+            return Err(SourceError::rate_limited(format!(
+                "alphavantage free-tier limit exceeded; retry in {:.2}s",
+                delay.as_secs_f64()
+            )));
+        }
+
+        let mut endpoint = format!(
+            "https://www.alphavantage.co/query?function=NEWS_SENTIMENT&tickers={}&sort=LATEST&limit={}&apikey={}",
+            urlencoding::encode(&tickers),
+            req.limit.min(1_000),
+            self.api_key
+        );
+        if let Some(since) = req.since {
+            endpoint.push_str(&format!("&time_from={}", format_av_time(since)));
+        }
+
+        let request = HttpRequest::get(&endpoint).with_timeout_ms(5_000);
+
+        let response = self.http_client.execute(request).await.map_err(|e| {
+            self.circuit_breaker.record_failure();
+            SourceError::unavailable(format!("alphavantage transport error: {}", e.message()))
+        })?;
+
+        if !response.is_success() {
+            self.circuit_breaker.record_failure();
+            return Err(SourceError::unavailable(format!(
+                "alphavantage returned status {}",
+                response.status
+            )));
+        }
+
+        self.throttling.complete_one();
+        self.circuit_breaker.record_success();
+        let batch = self.parse_news_response(req, &response.body)?;
+        self.cache.put(cache_key, response.body, None).await;
+        Ok(batch)
+    }
+
     async fn execute_real_search(&self, req: &SearchRequest) -> Result<SearchBatch, SourceError> {
         if !self.circuit_breaker.allow_request() {
             return Err(SourceError::unavailable(
@@ -387,6 +597,143 @@ impl AlphaVantageAdapter {
         bars.reverse(); // Alpha Vantage returns newest first, we want oldest first
         Ok(BarSeries::new(req.symbol.clone(), req.interval, bars))
     }
+
+    fn parse_earnings_response(
+        &self,
+        req: &EarningsRequest,
+        body: &str,
+    ) -> Result<EarningsBatch, SourceError> {
+        let av_response: AlphaVantageEarningsResponse =
+            serde_json::from_str(body).map_err(|e| {
+                SourceError::internal(format!("failed to parse alphavantage earnings: {}", e))
+            })?;
+
+        let mut entries = Vec::new();
+        for quarter in av_response.quarterly_earnings.iter().take(req.limit) {
+            let Some(end_date) = parse_av_date(&quarter.fiscal_date_ending) else {
+                continue;
+            };
+
+            // Only pre-market reports are tradable on the day they are
+            // published. Post-market reports, and reports of unknown timing,
+            // become available on the following day.
+            let report_date = quarter
+                .reported_date
+                .as_deref()
+                .and_then(parse_av_date)
+                .and_then(|reported| {
+                    if quarter.report_time.as_deref() == Some("pre-market") {
+                        Some(reported)
+                    } else {
+                        let next_day = reported.into_inner() + time::Duration::days(1);
+                        UtcDateTime::from_offset_datetime(next_day).ok()
+                    }
+                });
+
+            if let Ok(entry) = EarningsEntry::new(
+                end_date.year(),
+                None, // Alpha Vantage does not report the fiscal quarter number
+                end_date,
+                parse_av_number(quarter.reported_eps.as_deref()),
+                parse_av_number(quarter.estimated_eps.as_deref()),
+                None,
+                None,
+                parse_av_number(quarter.surprise_percentage.as_deref()),
+            ) {
+                entries.push(match report_date {
+                    Some(report_date) => entry.with_report_date(report_date),
+                    None => entry,
+                });
+            }
+        }
+
+        let report = EarningsReport::new(req.symbol.clone(), "USD", UtcDateTime::now(), entries)
+            .map_err(|e| SourceError::internal(e.to_string()))?;
+
+        Ok(EarningsBatch { earnings: report })
+    }
+
+    fn parse_economic_response(
+        &self,
+        req: &EconomicRequest,
+        body: &str,
+    ) -> Result<EconomicSeries, SourceError> {
+        let av_response: AlphaVantageEconomicResponse =
+            serde_json::from_str(body).map_err(|e| {
+                SourceError::internal(format!(
+                    "failed to parse alphavantage economic series: {}",
+                    e
+                ))
+            })?;
+
+        let frequency = av_response
+            .interval
+            .as_deref()
+            .and_then(|interval| interval.parse::<SeriesFrequency>().ok())
+            .or(req.frequency)
+            .unwrap_or(SeriesFrequency::Monthly);
+
+        // Missing observations are published as ".", which parse_av_number skips.
+        let mut observations = av_response
+            .data
+            .iter()
+            .filter_map(|point| {
+                let date = parse_av_date(&point.date)?;
+                let value = parse_av_number(Some(point.value.as_str()))?;
+                EconomicObservation::new(date, value).ok()
+            })
+            .take(req.limit)
+            .collect::<Vec<_>>();
+        observations.reverse(); // Alpha Vantage returns newest first, we want oldest first
+
+        Ok(EconomicSeries::new(
+            req.series_id.clone(),
+            av_response.name,
+            av_response.unit.unwrap_or_default(),
+            frequency,
+            observations,
+        ))
+    }
+
+    fn parse_news_response(&self, req: &NewsRequest, body: &str) -> Result<NewsBatch, SourceError> {
+        let av_response: AlphaVantageNewsResponse = serde_json::from_str(body).map_err(|e| {
+            SourceError::internal(format!("failed to parse alphavantage news: {}", e))
+        })?;
+
+        let items = av_response
+            .feed
+            .into_iter()
+            .filter_map(|article| {
+                let published_at = parse_av_time(&article.time_published)?;
+                // Non-equity topics such as `CRYPTO:BTC` are not valid symbols.
+                let ticker_sentiment = article
+                    .ticker_sentiment
+                    .iter()
+                    .filter_map(|entry| {
+                        TickerSentiment::new(
+                            Symbol::parse(&entry.ticker).ok()?,
+                            parse_av_number(entry.ticker_sentiment_score.as_deref()),
+                            parse_av_number(entry.relevance_score.as_deref()),
+                        )
+                        .ok()
+                    })
+                    .collect::<Vec<_>>();
+                let tickers = ticker_sentiment
+                    .iter()
+                    .map(|entry| entry.symbol.clone())
+                    .collect();
+
+                NewsItem::new(published_at, article.source, article.title, article.url)
+                    .with_summary(article.summary.filter(|summary| !summary.is_empty()))
+                    .with_tickers(tickers)
+                    .with_sentiment(article.overall_sentiment_score, ticker_sentiment)
+                    .ok()
+            })
+            .take(req.limit)
+            .collect();
+
+        Ok(NewsBatch { items })
+    }
 }
 
 impl DataSource for AlphaVantageAdapter {
@@ -395,7 +742,7 @@ impl DataSource for AlphaVantageAdapter {
     }
 
     fn capabilities(&self) -> CapabilitySet {
-        CapabilitySet::full()
+        CapabilitySet::new(true, true, true, true, true, true, false, true, true)
     }
 
     fn quote<'a>(
@@ -483,19 +830,37 @@ impl DataSource for AlphaVantageAdapter {
 
     fn earnings<'a>(
         &'a self,
-        _req: crate::data_source::EarningsRequest,
-    ) -> Pin<
-        Box<
-            dyn Future<Output = Result<crate::data_source::EarningsBatch, SourceError>> + Send + 'a,
-        >,
-    > {
+        req: EarningsRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<EarningsBatch, SourceError>> + Send + 'a>> {
+        Box::pin(async move { self.fetch_real_earnings(&req).await })
+    }
+
+    fn ticks<'a>(
+        &'a self,
+        _req: crate::data_source::TicksRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<crate::data_source::TickBatch, SourceError>> + Send + 'a>>
+    {
         Box::pin(async move {
             Err(SourceError::unsupported_endpoint(
-                crate::data_source::Endpoint::Earnings,
+                crate::data_source::Endpoint::Ticks,
             ))
         })
     }
 
+    fn economic<'a>(
+        &'a self,
+        req: EconomicRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<EconomicSeries, SourceError>> + Send + 'a>> {
+        Box::pin(async move { self.fetch_real_economic(&req).await })
+    }
+
+    fn news<'a>(
+        &'a self,
+        req: NewsRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<NewsBatch, SourceError>> + Send + 'a>> {
+        Box::pin(async move { self.fetch_real_news(&req).await })
+    }
+
     fn health<'a>(&'a self) -> Pin<Box<dyn Future<Output = HealthStatus> + Send + 'a>> {
         Box::pin(async move {
             let circuit_state = self.circuit_breaker.state();
@@ -520,6 +885,61 @@ impl DataSource for AlphaVantageAdapter {
     }
 }
 
+/// Split a canonical series id into an Alpha Vantage function and maturity.
+///
+/// `TREASURY_YIELD_3MONTH` maps to `TREASURY_YIELD` with `maturity=3month`;
+/// other ids must name an economic indicator function directly.
+fn economic_function(series_id: &str) -> Result<(&str, Option<&str>), SourceError> {
+    if let Some(maturity) = series_id.strip_prefix("TREASURY_YIELD_") {
+        if TREASURY_MATURITIES.contains(&maturity) {
+            return Ok(("TREASURY_YIELD", Some(maturity)));
+        }
+    } else if ECONOMIC_FUNCTIONS.contains(&series_id) {
+        return Ok((series_id, None));
+    }
+
+    Err(SourceError::invalid_request(format!(
+        "alphavantage does not publish economic series '{series_id}'"
+    )))
+}
+
+/// Parse an Alpha Vantage `YYYY-MM-DD` date as midnight UTC.
+fn parse_av_date(value: &str) -> Option<UtcDateTime> {
+    let format = time::format_description::parse("[year]-[month]-[day]").ok()?;
+    let date = time::Date::parse(value, &format).ok()?;
+    UtcDateTime::from_offset_datetime(date.midnight().assume_utc()).ok()
+}
+
+/// Parse an Alpha Vantage `YYYYMMDDTHHMMSS` news timestamp as UTC.
+fn parse_av_time(value: &str) -> Option<UtcDateTime> {
+    let format =
+        time::format_description::parse("[year][month][day]T[hour][minute][second]").ok()?;
+    let datetime = time::PrimitiveDateTime::parse(value, &format).ok()?;
+    UtcDateTime::from_offset_datetime(datetime.assume_utc()).ok()
+}
+
+/// Format a timestamp as the `YYYYMMDDTHHMM` form accepted by `time_from`.
+fn format_av_time(value: UtcDateTime) -> String {
+    let datetime = value.into_inner();
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}",
+        datetime.year(),
+        u8::from(datetime.month()),
+        datetime.day(),
+        datetime.hour(),
+        datetime.minute()
+    )
+}
+
+/// Alpha Vantage encodes numbers as strings and missing values as `"None"`.
+fn parse_av_number(value: Option<&str>) -> Option<f64> {
+    value?
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|number| number.is_finite())
+}
+
 // Alpha Vantage API response structures
 #[derive(Debug, Clone, Deserialize)]
 struct AlphaVantageQuoteResponse {
@@ -596,6 +1016,73 @@ struct AlphaVantageSearchMatch {
     currency: Option<String>,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+struct AlphaVantageEarningsResponse {
+    #[serde(rename = "quarterlyEarnings", default)]
+    quarterly_earnings: Vec<AlphaVantageQuarterlyEarnings>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct AlphaVantageQuarterlyEarnings {
+    #[serde(rename = "fiscalDateEnding")]
+    fiscal_date_ending: String,
+    #[serde(rename = "reportedDate", default)]
+    reported_date: Option<String>,
+    #[serde(rename = "reportedEPS", default)]
+    reported_eps: Option<String>,
+    #[serde(rename = "estimatedEPS", default)]
+    estimated_eps: Option<String>,
+    #[serde(rename = "surprisePercentage", default)]
+    surprise_percentage: Option<String>,
+    #[serde(rename = "reportTime", default)]
+    report_time: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct AlphaVantageEconomicResponse {
+    name: Option<String>,
+    interval: Option<String>,
+    unit: Option<String>,
+    #[serde(default)]
+    data: Vec<AlphaVantageEconomicPoint>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct AlphaVantageEconomicPoint {
+    date: String,
+    value: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct AlphaVantageNewsResponse {
+    #[serde(default)]
+    feed: Vec<AlphaVantageNewsArticle>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct AlphaVantageNewsArticle {
+    title: String,
+    url: String,
+    time_published: String,
+    #[serde(default)]
+    summary: Option<String>,
+    #[serde(default)]
+    source: String,
+    #[serde(default)]
+    overall_sentiment_score: Option<f64>,
+    #[serde(default)]
+    ticker_sentiment: Vec<AlphaVantageTickerSentiment>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct AlphaVantageTickerSentiment {
+    ticker: String,
+    #[serde(default)]
+    relevance_score: Option<String>,
+    #[serde(default)]
+    ticker_sentiment_score: Option<String>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -654,6 +1141,146 @@ mod tests {
         assert!(!health.rate_available);
     }
 
+    #[test]
+    fn earnings_carry_report_dates_for_point_in_time_joins() {
+        let client = Arc::new(RecordingHttpClient::with_response(Ok(
+            HttpResponse::ok_json(
+                r#"{
+                "symbol": "AAPL",
+                "quarterlyEarnings": [
+                    {"fiscalDateEnding": "2024-12-31", "reportedDate": "2025-01-30", "reportedEPS": "2.4", "estimatedEPS": "2.35", "surprisePercentage": "2.1277", "reportTime": "post-market"},
+                    {"fiscalDateEnding": "2024-09-30", "reportedDate": "2024-10-31", "reportedEPS": "1.64", "estimatedEPS": "None", "surprisePercentage": "None", "reportTime": "pre-market"},
+                    {"fiscalDateEnding": "2024-06-30", "reportedDate": "2024-08-01", "reportedEPS": "1.4", "estimatedEPS": "1.35", "surprisePercentage": "3.7037"}
+                ]
+            }"#,
+            ),
+        )));
+        let adapter = AlphaVantageAdapter::with_http_client(client.clone(), "demo-key", None);
+        let request = EarningsRequest::new(Symbol::parse("AAPL").expect("valid symbol"), 4)
+            .expect("valid request");
+
+        let batch = block_on(adapter.earnings(request)).expect("earnings should parse");
+        let entries = batch.earnings.entries;
+
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].end_date.format_rfc3339(), "2024-12-31T00:00:00Z");
+        assert_eq!(
+            entries[0].report_date.map(UtcDateTime::format_rfc3339),
+            Some(String::from("2025-01-31T00:00:00Z"))
+        );
+        assert_eq!(
+            entries[1].report_date.map(UtcDateTime::format_rfc3339),
+            Some(String::from("2024-10-31T00:00:00Z"))
+        );
+        assert_eq!(
+            entries[2].report_date.map(UtcDateTime::format_rfc3339),
+            Some(String::from("2024-08-02T00:00:00Z"))
+        );
+        assert_eq!(entries[1].eps_estimate, None);
+        let requests = client
+            .requests
+            .lock()
+            .expect("request store should not be poisoned");
+        assert!(requests[0].url.contains("function=EARNINGS"));
+    }
+
+    #[test]
+    fn treasury_yields_map_maturity_and_skip_missing_values() {
+        let client = Arc::new(RecordingHttpClient::with_response(Ok(
+            HttpResponse::ok_json(
+                r#"{
+                "name": "3-Month Treasury Constant Maturity Rate",
+                "interval": "daily",
+                "unit": "percent",
+                "data": [
+                    {"date": "2024-01-03", "value": "5.41"},
+                    {"date": "2024-01-02", "value": "."},
+                    {"date": "2024-01-01", "value": "5.40"}
+                ]
+            }"#,
+            ),
+        )));
+        let adapter = AlphaVantageAdapter::with_http_client(client.clone(), "demo-key", None);
+        let request = EconomicRequest::new("treasury_yield_3month", 10)
+            .expect("valid request")
+            .with_frequency(SeriesFrequency::Daily);
+
+        let series = block_on(adapter.economic(request)).expect("series should parse");
+
+        assert_eq!(series.series_id, "TREASURY_YIELD_3MONTH");
+        assert_eq!(series.unit, "percent");
+        assert_eq!(series.frequency, SeriesFrequency::Daily);
+        assert_eq!(series.observations.len(), 2);
+        assert_eq!(
+            series.observations[0].date.format_rfc3339(),
+            "2024-01-01T00:00:00Z"
+        );
+        assert_eq!(series.observations[1].value, 5.41);
+        let requests = client
+            .requests
+            .lock()
+            .expect("request store should not be poisoned");
+        assert!(requests[0].url.contains("function=TREASURY_YIELD&"));
+        assert!(requests[0].url.contains("maturity=3month"));
+        assert!(requests[0].url.contains("interval=daily"));
+    }
+
+    #[test]
+    fn unknown_economic_series_is_rejected() {
+        let client = Arc::new(RecordingHttpClient::failure());
+        let adapter = AlphaVantageAdapter::with_http_client(client, "demo-key", None);
+        let request = EconomicRequest::new("TREASURY_YIELD_4YEAR", 10).expect("valid request");
+
+        let error = block_on(adapter.economic(request)).expect_err("series should be rejected");
+
+        assert_eq!(error.kind(), SourceErrorKind::InvalidRequest);
+    }
+
+    #[test]
+    fn news_feed_maps_ticker_sentiment_and_since_filter() {
+        let client = Arc::new(RecordingHttpClient::with_response(Ok(
+            HttpResponse::ok_json(
+                r#"{
+                "items": "1",
+                "feed": [{
+                    "title": "Apple beats estimates",
+                    "url": "https://example.com/apple-beats",
+                    "time_published": "20240201T213000",
+                    "summary": "Quarterly results topped forecasts.",
+                    "source": "Reuters",
+                    "overall_sentiment_score": 0.31,
+                    "overall_sentiment_label": "Somewhat-Bullish",
+                    "ticker_sentiment": [
+                        {"ticker": "AAPL", "relevance_score": "0.92", "ticker_sentiment_score": "0.45", "ticker_sentiment_label": "Bullish"},
+                        {"ticker": "CRYPTO:BTC", "relevance_score": "0.1", "ticker_sentiment_score": "0.0", "ticker_sentiment_label": "Neutral"}
+                    ]
+                }]
+            }"#,
+            ),
+        )));
+        let adapter = AlphaVantageAdapter::with_http_client(client.clone(), "demo-key", None);
+        let request = NewsRequest::new(vec![Symbol::parse("AAPL").expect("valid symbol")], 10)
+            .expect("valid request")
+            .with_since(UtcDateTime::parse("2024-02-01T09:05:00Z").expect("valid timestamp"));
+
+        let batch = block_on(adapter.news(request)).expect("news should parse");
+
+        assert_eq!(batch.items.len(), 1);
+        let item = &batch.items[0];
+        assert_eq!(item.published_at.format_rfc3339(), "2024-02-01T21:30:00Z");
+        assert_eq!(item.source, "Reuters");
+        assert_eq!(item.sentiment, Some(0.31));
+        assert_eq!(item.tickers.len(), 1);
+        assert_eq!(item.ticker_sentiment[0].score, Some(0.45));
+        assert_eq!(item.ticker_sentiment[0].relevance, Some(0.92));
+        let requests = client
+            .requests
+            .lock()
+            .expect("request store should not be poisoned");
+        assert!(requests[0].url.contains("function=NEWS_SENTIMENT"));
+        assert!(requests[0].url.contains("time_from=20240201T0905"));
+    }
+
     fn block_on<F>(future: F) -> F::Output
     where
         F: Future,
@@ -8,12 +8,13 @@ use crate::cache::CacheStore;
 use crate::circuit_breaker::{CircuitBreaker, CircuitState};
 use crate::data_source::{
     BarsRequest, CapabilitySet, DataSource, FundamentalsBatch, FundamentalsRequest, HealthState,
-    HealthStatus, QuoteBatch, QuoteRequest, SearchBatch, SearchRequest, SourceError,
+    HealthStatus, NewsBatch, NewsRequest, QuoteBatch, QuoteRequest, SearchBatch, SearchRequest,
+    SourceError, TickBatch, TickKind, TicksRequest,
 };
 use crate::http_client::{HttpAuth, HttpClient, HttpRequest};
 use crate::{
-    AssetClass, Bar, BarSeries, Fundamental, Instrument, Interval, ProviderId, Quote, Symbol,
-    UtcDateTime,
+    AssetClass, Bar, BarSeries, Fundamental, Instrument, InstrumentIdentifiers, Interval, NewsItem,
+    ProviderId, Quote, QuoteTick, Symbol, TickerSentiment, TradeTick, UtcDateTime,
 };
 
 /// Polygon adapter for real API calls.
@@ -278,6 +279,16 @@ impl PolygonAdapter {
                     _ => AssetClass::Other,
                 };
 
+                let identifiers = InstrumentIdentifiers {
+                    figi: ticker.composite_figi,
+                    cik: ticker.cik,
+                    ..InstrumentIdentifiers::default()
+                };
+                let delisted_at = ticker
+                    .delisted_utc
+                    .as_deref()
+                    .and_then(|value| UtcDateTime::parse(value).ok());
+
                 Instrument::new(
                     symbol,
                     ticker.name,
@@ -287,6 +298,11 @@ impl PolygonAdapter {
                     ticker.active.unwrap_or(true),
                 )
                 .ok()
+                .map(|instrument| {
+                    instrument
+                        .with_identifiers(identifiers)
+                        .with_listing(None, delisted_at)
+                })
             })
             .take(req.limit)
             .collect();
@@ -297,6 +313,214 @@ impl PolygonAdapter {
         })
     }
 
+    async fn fetch_real_ticks(&self, req: &TicksRequest) -> Result<TickBatch, SourceError> {
+        if !self.circuit_breaker.allow_request() {
+            return Err(SourceError::unavailable("polygon circuit breaker is open"));
+        }
+
+        let mut endpoint = format!(
+            "https://api.polygon.io/v3/{}/{}?order=asc&sort=timestamp&limit={}",
+            req.kind.as_str(),
+            urlencoding::encode(req.symbol.as_str()),
+            req.limit.min(50_000)
+        );
+        if let Some(start) = req.start {
+            endpoint.push_str(&format!(
+                "&timestamp.gte={}",
+                urlencoding::encode(&start.format_rfc3339())
+            ));
+        }
+        if let Some(end) = req.end {
+            endpoint.push_str(&format!(
+                "&timestamp.lte={}",
+                urlencoding::encode(&end.format_rfc3339())
+            ));
+        }
+
+        let request = HttpRequest::get(&endpoint)
+            .with_auth(&self.auth)
+            .with_timeout_ms(10_000);
+
+        let response = self.http_client.execute(request).await.map_err(|e| {
+            self.circuit_breaker.record_failure();
+            SourceError::unavailable(format!("polygon transport error: {}", e.message()))
+        })?;
+
+        if !response.is_success() {
+            self.circuit_breaker.record_failure();
+            return Err(SourceError::unavailable(format!(
+                "polygon returned status {}",
+                response.status
+            )));
+        }
+
+        self.circuit_breaker.record_success();
+        self.parse_ticks_response(req, &response.body)
+    }
+
+    async fn fetch_real_news(&self, req: &NewsRequest) -> Result<NewsBatch, SourceError> {
+        // The news endpoint filters on a single ticker, so fan out per symbol.
+        let mut items: Vec<NewsItem> = Vec::new();
+        for symbol in &req.symbols {
+            if !self.circuit_breaker.allow_request() {
+                return Err(SourceError::unavailable("polygon circuit breaker is open"));
+            }
+
+            let mut endpoint = format!(
+                "https://api.polygon.io/v2/reference/news?ticker={}&order=desc&sort=published_utc&limit={}",
+                urlencoding::encode(symbol.as_str()),
+                req.limit.min(1_000)
+            );
+            if let Some(since) = req.since {
+                endpoint.push_str(&format!(
+                    "&published_utc.gte={}",
+                    urlencoding::encode(&since.format_rfc3339())
+                ));
+            }
+
+            let request = HttpRequest::get(&endpoint)
+                .with_auth(&self.auth)
+                .with_timeout_ms(5_000);
+
+            let response = self.http_client.execute(request).await.map_err(|e| {
+                self.circuit_breaker.record_failure();
+                SourceError::unavailable(format!("polygon transport error: {}", e.message()))
+            })?;
+
+            if !response.is_success() {
+                self.circuit_breaker.record_failure();
+                return Err(SourceError::unavailable(format!(
+                    "polygon returned status {}",
+                    response.status
+                )));
+            }
+
+            self.circuit_breaker.record_success();
+            for item in self.parse_news_response(&response.body)? {
+                if !items.iter().any(|existing| existing.url == item.url) {
+                    items.push(item);
+                }
+            }
+        }
+
+        items.sort_by_key(|item| std::cmp::Reverse(item.published_at));
+        items.truncate(req.limit);
+        Ok(NewsBatch { items })
+    }
+
+    fn parse_news_response(&self, body: &str) -> Result<Vec<NewsItem>, SourceError> {
+        let news_response: PolygonNewsResponse = serde_json::from_str(body)
+            .map_err(|e| SourceError::internal(format!("failed to parse polygon news: {}", e)))?;
+
+        Ok(news_response
+            .results
+            .into_iter()
+            .filter_map(|article| {
+                let published_at = UtcDateTime::parse(&article.published_utc).ok()?;
+                let tickers = article
+                    .tickers
+                    .iter()
+                    .filter_map(|ticker| Symbol::parse(ticker).ok())
+                    .collect();
+                // Insights carry a sentiment label per ticker but no score.
+                let ticker_sentiment = article
+                    .insights
+                    .iter()
+                    .filter_map(|insight| {
+                        let score = match insight.sentiment.as_deref()? {
+                            "positive" => 1.0,
+                            "neutral" => 0.0,
+                            "negative" => -1.0,
+                            _ => return None,
+                        };
+                        TickerSentiment::new(
+                            Symbol::parse(&insight.ticker).ok()?,
+                            Some(score),
+                            None,
+                        )
+                        .ok()
+                    })
+                    .collect();
+
+                NewsItem::new(
+                    published_at,
+                    article.publisher.name,
+                    article.title,
+                    article.article_url,
+                )
+                .with_summary(article.description)
+                .with_tickers(tickers)
+                .with_sentiment(None, ticker_sentiment)
+                .ok()
+            })
+            .collect())
+    }
+
+    fn parse_ticks_response(
+        &self,
+        req: &TicksRequest,
+        body: &str,
+    ) -> Result<TickBatch, SourceError> {
+        let mut batch = TickBatch {
+            symbol: req.symbol.clone(),
+            trades: Vec::new(),
+            quotes: Vec::new(),
+        };
+
+        match req.kind {
+            TickKind::Trades => {
+                let response: PolygonTicksResponse<PolygonTradeResult> = serde_json::from_str(body)
+                    .map_err(|e| {
+                        SourceError::internal(format!("failed to parse polygon trades: {}", e))
+                    })?;
+                batch.trades = response
+                    .results
+                    .into_iter()
+                    .take(req.limit)
+                    .filter_map(|trade| {
+                        TradeTick::new(
+                            nanos_to_utc(trade.sip_timestamp)?,
+                            trade.price,
+                            trade.size.round() as u64,
+                            trade.exchange.map(|code| code.to_string()),
+                            trade
+                                .conditions
+                                .iter()
+                                .map(|code| code.to_string())
+                                .collect(),
+                        )
+                        .ok()
+                    })
+                    .collect();
+            }
+            TickKind::Quotes => {
+                let response: PolygonTicksResponse<PolygonQuoteTickResult> =
+                    serde_json::from_str(body).map_err(|e| {
+                        SourceError::internal(format!("failed to parse polygon quotes: {}", e))
+                    })?;
+                batch.quotes = response
+                    .results
+                    .into_iter()
+                    .take(req.limit)
+                    .filter_map(|quote| {
+                        QuoteTick::new(
+                            nanos_to_utc(quote.sip_timestamp)?,
+                            quote.bid_price,
+                            quote.bid_size.round() as u64,
+                            quote.ask_price,
+                            quote.ask_size.round() as u64,
+                            quote.bid_exchange.map(|code| code.to_string()),
+                            quote.ask_exchange.map(|code| code.to_string()),
+                        )
+                        .ok()
+                    })
+                    .collect();
+            }
+        }
+
+        Ok(batch)
+    }
+
     fn parse_quote_response(&self, body: &str) -> Result<QuoteBatch, SourceError> {
         let polygon_response: PolygonPrevCloseResponse =
             serde_json::from_str(body).map_err(|e| {
@@ -368,7 +592,7 @@ impl DataSource for PolygonAdapter {
     }
 
     fn capabilities(&self) -> CapabilitySet {
-        CapabilitySet::full()
+        CapabilitySet::new(true, true, true, true, true, true, true, false, true)
     }
 
     fn quote<'a>(
@@ -484,6 +708,31 @@ impl DataSource for PolygonAdapter {
         })
     }
 
+    fn ticks<'a>(
+        &'a self,
+        req: TicksRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<TickBatch, SourceError>> + Send + 'a>> {
+        Box::pin(async move { self.fetch_real_ticks(&req).await })
+    }
+
+    fn economic<'a>(
+        &'a self,
+        _req: crate::data_source::EconomicRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<crate::EconomicSeries, SourceError>> + Send + 'a>> {
+        Box::pin(async move {
+            Err(SourceError::unsupported_endpoint(
+                crate::data_source::Endpoint::Economic,
+            ))
+        })
+    }
+
+    fn news<'a>(
+        &'a self,
+        req: NewsRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<NewsBatch, SourceError>> + Send + 'a>> {
+        Box::pin(async move { self.fetch_real_news(&req).await })
+    }
+
     fn health<'a>(&'a self) -> Pin<Box<dyn Future<Output = HealthStatus> + Send + 'a>> {
         Box::pin(async move {
             let circuit_state = self.circuit_breaker.state();
@@ -567,6 +816,83 @@ struct PolygonTickerResult {
     active: Option<bool>,
     primary_exchange: Option<String>,
     currency_name: Option<String>,
+    #[serde(default)]
+    cik: Option<String>,
+    #[serde(default)]
+    composite_figi: Option<String>,
+    #[serde(default)]
+    delisted_utc: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct PolygonTicksResponse<T> {
+    #[serde(default = "Vec::new")]
+    results: Vec<T>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct PolygonTradeResult {
+    sip_timestamp: i64, // nanoseconds since epoch
+    price: f64,
+    size: f64,
+    #[serde(default)]
+    exchange: Option<i32>,
+    #[serde(default)]
+    conditions: Vec<i32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct PolygonQuoteTickResult {
+    sip_timestamp: i64, // nanoseconds since epoch
+    #[serde(default)]
+    bid_price: f64,
+    #[serde(default)]
+    bid_size: f64,
+    #[serde(default)]
+    ask_price: f64,
+    #[serde(default)]
+    ask_size: f64,
+    #[serde(default)]
+    bid_exchange: Option<i32>,
+    #[serde(default)]
+    ask_exchange: Option<i32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct PolygonNewsResponse {
+    #[serde(default)]
+    results: Vec<PolygonNewsArticle>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct PolygonNewsArticle {
+    title: String,
+    article_url: String,
+    published_utc: String,
+    publisher: PolygonNewsPublisher,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    tickers: Vec<String>,
+    #[serde(default)]
+    insights: Vec<PolygonNewsInsight>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct PolygonNewsPublisher {
+    name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct PolygonNewsInsight {
+    ticker: String,
+    #[serde(default)]
+    sentiment: Option<String>,
+}
+
+fn nanos_to_utc(nanos: i64) -> Option<UtcDateTime> {
+    let ts = time::OffsetDateTime::from_unix_timestamp_nanos(i128::from(nanos)).ok()?;
+    UtcDateTime::from_offset_datetime(ts).ok()
 }
 
 #[cfg(test)]
@@ -634,6 +960,128 @@ mod tests {
         assert!(!health.rate_available);
     }
 
+    #[test]
+    fn trades_are_parsed_from_nanosecond_timestamps() {
+        let client = Arc::new(RecordingHttpClient::with_response(Ok(
+            HttpResponse::ok_json(
+                r#"{
+                "status": "OK",
+                "results": [
+                    {"sip_timestamp": 1704205800000000000, "price": 185.5, "size": 100, "exchange": 4, "conditions": [12, 37]},
+                    {"sip_timestamp": 1704205800500000000, "price": 185.52, "size": 50}
+                ]
+            }"#,
+            ),
+        )));
+        let adapter = PolygonAdapter::with_http_client(
+            client.clone(),
+            HttpAuth::Header {
+                name: String::from("x-api-key"),
+                value: String::from("demo"),
+            },
+            None,
+        );
+        let request = TicksRequest::new(
+            Symbol::parse("AAPL").expect("valid symbol"),
+            TickKind::Trades,
+            10,
+        )
+        .expect("valid request");
+
+        let batch = block_on(adapter.ticks(request)).expect("trades should parse");
+
+        assert_eq!(batch.trades.len(), 2);
+        assert!(batch.quotes.is_empty());
+        assert_eq!(batch.trades[0].ts.format_rfc3339(), "2024-01-02T14:30:00Z");
+        assert_eq!(batch.trades[0].exchange.as_deref(), Some("4"));
+        assert_eq!(batch.trades[0].conditions, vec!["12", "37"]);
+        assert_eq!(batch.trades[1].size, 50);
+        let requests = client
+            .requests
+            .lock()
+            .expect("request store should not be poisoned");
+        assert!(requests[0].url.contains("/v3/trades/AAPL"));
+    }
+
+    #[test]
+    fn search_results_carry_cross_provider_identifiers() {
+        let client = Arc::new(RecordingHttpClient::with_response(Ok(
+            HttpResponse::ok_json(
+                r#"{
+                "results": [
+                    {"ticker": "META", "name": "Meta Platforms, Inc.", "market": "stocks", "active": true, "primary_exchange": "XNAS", "currency_name": "usd", "cik": "0001326801", "composite_figi": "BBG000MM2P62"}
+                ]
+            }"#,
+            ),
+        )));
+        let adapter = PolygonAdapter::with_http_client(
+            client,
+            HttpAuth::Header {
+                name: String::from("x-api-key"),
+                value: String::from("demo"),
+            },
+            None,
+        );
+        let request = SearchRequest::new("meta", 5).expect("valid request");
+
+        let batch = block_on(adapter.search(request)).expect("search should parse");
+
+        assert_eq!(batch.results.len(), 1);
+        let identifiers = &batch.results[0].identifiers;
+        assert_eq!(identifiers.figi.as_deref(), Some("BBG000MM2P62"));
+        assert_eq!(identifiers.cik.as_deref(), Some("0001326801"));
+        assert_eq!(identifiers.isin, None);
+    }
+
+    #[test]
+    fn news_insights_become_ticker_sentiment() {
+        let client = Arc::new(RecordingHttpClient::with_response(Ok(
+            HttpResponse::ok_json(
+                r#"{
+                "status": "OK",
+                "results": [{
+                    "id": "abc",
+                    "publisher": {"name": "Benzinga"},
+                    "title": "Apple unveils new product",
+                    "published_utc": "2024-02-01T14:00:00Z",
+                    "article_url": "https://example.com/apple-product",
+                    "tickers": ["AAPL", "MSFT"],
+                    "description": "Apple announced a new device.",
+                    "insights": [
+                        {"ticker": "AAPL", "sentiment": "positive", "sentiment_reasoning": "New revenue line"},
+                        {"ticker": "MSFT", "sentiment": "neutral", "sentiment_reasoning": "Mentioned in passing"}
+                    ]
+                }]
+            }"#,
+            ),
+        )));
+        let adapter = PolygonAdapter::with_http_client(
+            client.clone(),
+            HttpAuth::Header {
+                name: String::from("Authorization"),
+                value: String::from("Bearer demo"),
+            },
+            None,
+        );
+        let request = NewsRequest::new(vec![Symbol::parse("AAPL").expect("valid symbol")], 5)
+            .expect("valid request");
+
+        let batch = block_on(adapter.news(request)).expect("news should parse");
+
+        assert_eq!(batch.items.len(), 1);
+        let item = &batch.items[0];
+        assert_eq!(item.source, "Benzinga");
+        assert_eq!(item.tickers.len(), 2);
+        assert_eq!(item.sentiment, None);
+        assert_eq!(item.ticker_sentiment[0].score, Some(1.0));
+        assert_eq!(item.ticker_sentiment[1].score, Some(0.0));
+        let requests = client
+            .requests
+            .lock()
+            .expect("request store should not be poisoned");
+        assert!(requests[0].url.contains("/v2/reference/news?ticker=AAPL"));
+    }
+
     fn block_on<F>(future: F) -> F::Output
     where
         F: Future,
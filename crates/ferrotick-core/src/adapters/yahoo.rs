@@ -4,10 +4,12 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
 
+use ferrotick_telemetry::metrics;
 use serde::Deserialize;
 
 use crate::cache::CacheStore;
 use crate::circuit_breaker::{CircuitBreaker, CircuitState};
+use crate::context::RequestContext;
 use crate::data_source::{
     BarsRequest, CapabilitySet, DataSource, FundamentalsBatch, FundamentalsRequest, HealthState,
     HealthStatus, QuoteBatch, QuoteRequest, SearchBatch, SearchRequest, SourceError,
@@ -134,6 +136,7 @@ impl YahooAuthManager {
         ];
 
         for endpoint in &crumb_endpoints {
+            RequestContext::current().check("yahoo crumb refresh")?;
             let crumb_request = HttpRequest::get(endpoint.to_string())
                 .with_header("referer", "https://finance.yahoo.com/")
                 .with_timeout_ms(10_000);
@@ -266,6 +269,8 @@ impl YahooAdapter {
 
     /// Handle authentication errors by invalidating cached auth
     fn handle_auth_error(&self) {
+        tracing::debug!(provider = "yahoo", "refreshing auth before retrying");
+        metrics::RETRIES.increment(&[("provider", "yahoo"), ("reason", "auth_refresh")]);
         self.auth_manager.invalidate();
     }
 
@@ -293,7 +298,7 @@ impl DataSource for YahooAdapter {
     }
 
     fn capabilities(&self) -> CapabilitySet {
-        CapabilitySet::full()
+        CapabilitySet::new(true, true, true, true, true, true, false, false, false)
     }
 
     fn quote<'a>(
@@ -402,6 +407,41 @@ impl DataSource for YahooAdapter {
         })
     }
 
+    fn ticks<'a>(
+        &'a self,
+        _req: crate::data_source::TicksRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<crate::data_source::TickBatch, SourceError>> + Send + 'a>>
+    {
+        Box::pin(async move {
+            Err(SourceError::unsupported_endpoint(
+                crate::data_source::Endpoint::Ticks,
+            ))
+        })
+    }
+
+    fn economic<'a>(
+        &'a self,
+        _req: crate::data_source::EconomicRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<crate::EconomicSeries, SourceError>> + Send + 'a>> {
+        Box::pin(async move {
+            Err(SourceError::unsupported_endpoint(
+                crate::data_source::Endpoint::Economic,
+            ))
+        })
+    }
+
+    fn news<'a>(
+        &'a self,
+        _req: crate::data_source::NewsRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<crate::data_source::NewsBatch, SourceError>> + Send + 'a>>
+    {
+        Box::pin(async move {
+            Err(SourceError::unsupported_endpoint(
+                crate::data_source::Endpoint::News,
+            ))
+        })
+    }
+
     fn health<'a>(&'a self) -> Pin<Box<dyn Future<Output = HealthStatus> + Send + 'a>> {
         Box::pin(async move {
             let circuit_state = self.circuit_breaker.state();
@@ -478,6 +518,8 @@ impl YahooAdapter {
 
         // Handle 401/429 by refreshing auth and retrying once
         if response.status == 401 || response.status == 429 {
+            // Only refresh and retry if the request budget allows it.
+            RequestContext::current().check("yahoo auth refresh retry")?;
             self.handle_auth_error();
 
             // Get fresh crumb
@@ -634,6 +676,8 @@ impl YahooAdapter {
 
         // Handle 401/429 by refreshing auth and retrying once
         let response_body = if response.status == 401 || response.status == 429 {
+            // Only refresh and retry if the request budget allows it.
+            RequestContext::current().check("yahoo auth refresh retry")?;
             self.handle_auth_error();
 
             // Get fresh crumb and rebuild endpoint
@@ -759,7 +803,7 @@ impl YahooAdapter {
 
         for symbol in &req.symbols {
             let endpoint = format!(
-                "https://query2.finance.yahoo.com/v10/finance/quoteSummary/{}?modules=price,summaryDetail,defaultKeyStatistics&crumb={}",
+                "https://query2.finance.yahoo.com/v10/finance/quoteSummary/{}?modules=price,summaryDetail,defaultKeyStatistics,assetProfile&crumb={}",
                 urlencoding::encode(symbol.as_str()),
                 urlencoding::encode(&crumb)
             );
@@ -829,10 +873,14 @@ impl YahooAdapter {
                         .and_then(|s| s.dividend_yield.as_ref().and_then(|v| v.to_option()))
                 });
 
+            let profile = result.asset_profile.as_ref();
             if let Ok(fundamental) =
                 Fundamental::new(symbol.clone(), as_of, market_cap, pe_ratio, dividend_yield)
             {
-                fundamentals.push(fundamental);
+                fundamentals.push(fundamental.with_classification(
+                    profile.and_then(|p| p.sector.clone()),
+                    profile.and_then(|p| p.industry.clone()),
+                ));
             }
         }
 
@@ -858,6 +906,8 @@ impl YahooAdapter {
 
         // Handle 401/429 by refreshing auth
         let response = if response.status == 401 || response.status == 429 {
+            // Only refresh and retry if the request budget allows it.
+            RequestContext::current().check("yahoo auth refresh retry")?;
             self.handle_auth_error();
 
             let _ = self.fetch_crumb().await;
@@ -935,6 +985,8 @@ impl YahooAdapter {
 
         // Handle 401/429 by refreshing auth
         let response_body = if response.status == 401 || response.status == 429 {
+            // Only refresh and retry if the request budget allows it.
+            RequestContext::current().check("yahoo auth refresh retry")?;
             self.handle_auth_error();
 
             let _ = self.fetch_crumb().await;
@@ -1018,6 +1070,7 @@ impl YahooAdapter {
                     true,
                 )
                 .ok()
+                .map(|instrument| instrument.with_classification(quote.sector, quote.industry))
             })
             .take(limit)
             .collect();
@@ -1307,6 +1360,8 @@ impl YahooAdapter {
         })?;
 
         let response = if response.status == 401 || response.status == 429 {
+            // Only refresh and retry if the request budget allows it.
+            RequestContext::current().check("yahoo auth refresh retry")?;
             self.handle_auth_error();
             let _ = self.fetch_crumb().await;
             let crumb = self.fetch_crumb().await?;
@@ -1501,6 +1556,10 @@ struct YahooSearchQuote {
     #[serde(rename = "quoteType")]
     quote_type: String,
     currency: Option<String>,
+    #[serde(default)]
+    sector: Option<String>,
+    #[serde(default)]
+    industry: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -1526,6 +1585,8 @@ struct YahooQuoteSummaryResult {
     summary_detail: Option<YahooSummaryDetailData>,
     #[serde(rename = "defaultKeyStatistics", default)]
     default_key_statistics: Option<YahooDefaultKeyStatisticsData>,
+    #[serde(rename = "assetProfile", default)]
+    asset_profile: Option<YahooAssetProfileData>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -1534,6 +1595,14 @@ struct YahooMeta {
     _symbol: String,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+struct YahooAssetProfileData {
+    #[serde(default)]
+    sector: Option<String>,
+    #[serde(default)]
+    industry: Option<String>,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 struct YahooPriceData {
     #[serde(rename = "marketCap", default)]
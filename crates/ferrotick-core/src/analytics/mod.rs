@@ -0,0 +1,12 @@
+//! Derived analytics computed from canonical domain models.
+//!
+//! | Module | Description |
+//! |--------|-------------|
+//! | [`ratios`] | Financial ratios and growth metrics from statements |
+//! | [`tick_bars`] | Time, tick, volume and dollar bars built from trade ticks |
+
+pub mod ratios;
+pub mod tick_bars;
+
+pub use ratios::{compute_ratios, FinancialMetric, FinancialRatios, RatioBasis};
+pub use tick_bars::{aggregate_trades, TickBarSeries, TickBarSpec};
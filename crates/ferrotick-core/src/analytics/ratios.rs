@@ -0,0 +1,574 @@
+//! Financial ratios and growth metrics derived from financial statements.
+//!
+//! Providers return statements as flat line items keyed by label and period
+//! end date. This module maps provider labels onto canonical
+//! [`FinancialMetric`]s, merges income, balance and cash-flow statements that
+//! share a period end, and derives margins, returns, leverage and growth.
+//!
+//! Trailing-twelve-month (TTM) figures are built from quarterly statements:
+//! flow metrics (revenue, income, cash flow) are summed over four consecutive
+//! quarters, while balance-sheet metrics are taken from the latest quarter.
+//!
+//! ```rust
+//! use ferrotick_core::analytics::{compute_ratios, RatioBasis};
+//! use ferrotick_core::{
+//!     FinancialLineItem, FinancialPeriod, FinancialStatement, StatementType, Symbol, UtcDateTime,
+//! };
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let symbol = Symbol::parse("AAPL")?;
+//! let end = UtcDateTime::parse("2024-09-28T00:00:00Z")?;
+//! let income = FinancialStatement::new(
+//!     symbol.clone(),
+//!     StatementType::Income,
+//!     FinancialPeriod::Annual,
+//!     "USD",
+//!     end,
+//!     vec![
+//!         FinancialLineItem::new("Total Revenue", Some(400.0), None, None, end)?,
+//!         FinancialLineItem::new("Net Income", Some(100.0), None, None, end)?,
+//!     ],
+//! )?;
+//!
+//! let ratios = compute_ratios(&symbol, &[income], RatioBasis::Annual, None);
+//! assert_eq!(ratios[0].net_margin, Some(0.25));
+//! # Ok(())
+//! # }
+//! ```
+
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{FinancialPeriod, FinancialStatement, Symbol, UtcDateTime, ValidationError};
+
+/// Day window used to match the same period one year earlier.
+const YEAR_LAG_DAYS: (i64, i64) = (330, 400);
+/// Day window used to match the immediately preceding quarter.
+const QUARTER_LAG_DAYS: (i64, i64) = (75, 105);
+/// Day span between the first and last quarter of a TTM window.
+const TTM_SPAN_DAYS: (i64, i64) = (250, 300);
+
+/// Aggregation basis for ratio computation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RatioBasis {
+    /// Fiscal-year statements.
+    Annual,
+    /// Individual fiscal quarters.
+    Quarterly,
+    /// Trailing twelve months built from quarterly statements.
+    Ttm,
+}
+
+impl RatioBasis {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Annual => "annual",
+            Self::Quarterly => "quarterly",
+            Self::Ttm => "ttm",
+        }
+    }
+
+    /// Statement period required as input for this basis.
+    pub const fn statement_period(self) -> FinancialPeriod {
+        match self {
+            Self::Annual => FinancialPeriod::Annual,
+            Self::Quarterly | Self::Ttm => FinancialPeriod::Quarterly,
+        }
+    }
+}
+
+impl Display for RatioBasis {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for RatioBasis {
+    type Err = ValidationError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "annual" => Ok(Self::Annual),
+            "quarterly" | "quarter" => Ok(Self::Quarterly),
+            "ttm" => Ok(Self::Ttm),
+            other => Err(ValidationError::InvalidRatioBasis {
+                value: other.to_owned(),
+            }),
+        }
+    }
+}
+
+/// Canonical statement metric recognised by the ratios engine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FinancialMetric {
+    Revenue,
+    GrossProfit,
+    OperatingIncome,
+    NetIncome,
+    Eps,
+    TotalAssets,
+    TotalLiabilities,
+    TotalEquity,
+    CurrentAssets,
+    CurrentLiabilities,
+    TotalDebt,
+    Cash,
+    OperatingCashFlow,
+    CapitalExpenditures,
+}
+
+impl FinancialMetric {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Revenue => "revenue",
+            Self::GrossProfit => "gross_profit",
+            Self::OperatingIncome => "operating_income",
+            Self::NetIncome => "net_income",
+            Self::Eps => "eps",
+            Self::TotalAssets => "total_assets",
+            Self::TotalLiabilities => "total_liabilities",
+            Self::TotalEquity => "total_equity",
+            Self::CurrentAssets => "current_assets",
+            Self::CurrentLiabilities => "current_liabilities",
+            Self::TotalDebt => "total_debt",
+            Self::Cash => "cash",
+            Self::OperatingCashFlow => "operating_cash_flow",
+            Self::CapitalExpenditures => "capital_expenditures",
+        }
+    }
+
+    /// Map a provider line-item label onto a canonical metric.
+    ///
+    /// Matching ignores case, whitespace and punctuation, so `"Total Revenue"`,
+    /// `"totalRevenue"` and `"total_revenue"` all resolve to [`Self::Revenue`].
+    pub fn from_label(label: &str) -> Option<Self> {
+        let normalized = label
+            .chars()
+            .filter(char::is_ascii_alphanumeric)
+            .collect::<String>()
+            .to_ascii_lowercase();
+
+        let metric = match normalized.as_str() {
+            "totalrevenue" | "revenue" | "revenues" | "netsales" => Self::Revenue,
+            "grossprofit" => Self::GrossProfit,
+            "operatingincome" | "ebit" => Self::OperatingIncome,
+            "netincome" | "netincomecommonstockholders" => Self::NetIncome,
+            "basiceps" | "dilutedeps" | "eps" => Self::Eps,
+            "totalassets" => Self::TotalAssets,
+            "totalliabilities" | "totalliabilitiesnetminorityinterest" => Self::TotalLiabilities,
+            "totalstockholderequity" | "totalequity" | "stockholdersequity" => Self::TotalEquity,
+            "totalcurrentassets" | "currentassets" => Self::CurrentAssets,
+            "totalcurrentliabilities" | "currentliabilities" => Self::CurrentLiabilities,
+            "totaldebt" => Self::TotalDebt,
+            "cash" | "cashandcashequivalents" => Self::Cash,
+            "operatingcashflow" | "totalcashfromoperatingactivities" => Self::OperatingCashFlow,
+            "capitalexpenditures" | "capitalexpenditure" => Self::CapitalExpenditures,
+            _ => return None,
+        };
+        Some(metric)
+    }
+
+    /// Whether the metric accumulates over a period (income/cash flow) rather
+    /// than being a point-in-time balance.
+    pub const fn is_flow(self) -> bool {
+        matches!(
+            self,
+            Self::Revenue
+                | Self::GrossProfit
+                | Self::OperatingIncome
+                | Self::NetIncome
+                | Self::Eps
+                | Self::OperatingCashFlow
+                | Self::CapitalExpenditures
+        )
+    }
+}
+
+/// Ratios and growth metrics for a single period.
+///
+/// Ratios are `None` when an input metric is missing or a denominator is zero.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FinancialRatios {
+    pub symbol: Symbol,
+    pub basis: RatioBasis,
+    pub period_end: UtcDateTime,
+    pub fiscal_year: Option<i32>,
+    pub fiscal_quarter: Option<i32>,
+    pub revenue: Option<f64>,
+    pub net_income: Option<f64>,
+    pub free_cash_flow: Option<f64>,
+    pub gross_margin: Option<f64>,
+    pub operating_margin: Option<f64>,
+    pub net_margin: Option<f64>,
+    pub return_on_equity: Option<f64>,
+    pub return_on_assets: Option<f64>,
+    pub current_ratio: Option<f64>,
+    pub debt_to_equity: Option<f64>,
+    pub fcf_yield: Option<f64>,
+    pub revenue_growth_yoy: Option<f64>,
+    pub revenue_growth_qoq: Option<f64>,
+    pub net_income_growth_yoy: Option<f64>,
+    pub net_income_growth_qoq: Option<f64>,
+    pub eps_growth_yoy: Option<f64>,
+    pub eps_growth_qoq: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct PeriodValues {
+    fiscal_year: Option<i32>,
+    fiscal_quarter: Option<i32>,
+    values: HashMap<FinancialMetric, f64>,
+}
+
+impl PeriodValues {
+    fn get(&self, metric: FinancialMetric) -> Option<f64> {
+        self.values.get(&metric).copied()
+    }
+}
+
+/// Compute ratios for `symbol` from a set of financial statements.
+///
+/// Statements for other symbols or for a period that does not match
+/// `basis` are ignored. Line items from income, balance and cash-flow
+/// statements are merged by period end date. `market_cap`, when known, is
+/// used for free-cash-flow yield.
+///
+/// Debt-to-equity uses `Total Debt` when reported and falls back to total
+/// liabilities otherwise. Results are ordered newest period first.
+pub fn compute_ratios(
+    symbol: &Symbol,
+    statements: &[FinancialStatement],
+    basis: RatioBasis,
+    market_cap: Option<f64>,
+) -> Vec<FinancialRatios> {
+    let periods = merge_periods(symbol, statements, basis.statement_period());
+    let periods = match basis {
+        RatioBasis::Annual | RatioBasis::Quarterly => periods,
+        RatioBasis::Ttm => trailing_twelve_months(&periods),
+    };
+
+    let mut ratios = periods
+        .iter()
+        .map(|(period_end, values)| {
+            let year_ago = find_prior(&periods, *period_end, YEAR_LAG_DAYS);
+            let quarter_ago = if basis == RatioBasis::Annual {
+                None
+            } else {
+                find_prior(&periods, *period_end, QUARTER_LAG_DAYS)
+            };
+            build_ratios(
+                symbol,
+                basis,
+                *period_end,
+                values,
+                year_ago,
+                quarter_ago,
+                market_cap,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    ratios.sort_by_key(|ratio| Reverse(ratio.period_end));
+    ratios
+}
+
+fn merge_periods(
+    symbol: &Symbol,
+    statements: &[FinancialStatement],
+    period: FinancialPeriod,
+) -> BTreeMap<UtcDateTime, PeriodValues> {
+    let mut periods: BTreeMap<UtcDateTime, PeriodValues> = BTreeMap::new();
+
+    for statement in statements
+        .iter()
+        .filter(|statement| &statement.symbol == symbol && statement.period == period)
+    {
+        for item in &statement.line_items {
+            let (Some(metric), Some(value)) =
+                (FinancialMetric::from_label(&item.label), item.value)
+            else {
+                continue;
+            };
+
+            let entry = periods.entry(item.end_date).or_default();
+            entry.fiscal_year = entry.fiscal_year.or(item.fiscal_year);
+            entry.fiscal_quarter = entry.fiscal_quarter.or(item.fiscal_quarter);
+            entry.values.entry(metric).or_insert(value);
+        }
+    }
+
+    periods
+}
+
+fn trailing_twelve_months(
+    quarters: &BTreeMap<UtcDateTime, PeriodValues>,
+) -> BTreeMap<UtcDateTime, PeriodValues> {
+    let ordered = quarters.iter().collect::<Vec<_>>();
+    let mut output = BTreeMap::new();
+
+    for window in ordered.windows(4) {
+        let (first_end, _) = window[0];
+        let (last_end, latest) = window[3];
+        if !within_days(*first_end, *last_end, TTM_SPAN_DAYS) {
+            continue;
+        }
+
+        let mut values = HashMap::new();
+        for (metric, latest_value) in &latest.values {
+            if metric.is_flow() {
+                let summed = window
+                    .iter()
+                    .map(|(_, quarter)| quarter.get(*metric))
+                    .sum::<Option<f64>>();
+                if let Some(total) = summed {
+                    values.insert(*metric, total);
+                }
+            } else {
+                values.insert(*metric, *latest_value);
+            }
+        }
+
+        output.insert(
+            *last_end,
+            PeriodValues {
+                fiscal_year: latest.fiscal_year,
+                fiscal_quarter: latest.fiscal_quarter,
+                values,
+            },
+        );
+    }
+
+    output
+}
+
+fn find_prior(
+    periods: &BTreeMap<UtcDateTime, PeriodValues>,
+    period_end: UtcDateTime,
+    lag_days: (i64, i64),
+) -> Option<&PeriodValues> {
+    periods
+        .range(..period_end)
+        .rev()
+        .find(|(candidate, _)| within_days(**candidate, period_end, lag_days))
+        .map(|(_, values)| values)
+}
+
+fn within_days(earlier: UtcDateTime, later: UtcDateTime, bounds: (i64, i64)) -> bool {
+    let days = (later.into_inner() - earlier.into_inner()).whole_days();
+    days >= bounds.0 && days <= bounds.1
+}
+
+fn build_ratios(
+    symbol: &Symbol,
+    basis: RatioBasis,
+    period_end: UtcDateTime,
+    values: &PeriodValues,
+    year_ago: Option<&PeriodValues>,
+    quarter_ago: Option<&PeriodValues>,
+    market_cap: Option<f64>,
+) -> FinancialRatios {
+    let revenue = values.get(FinancialMetric::Revenue);
+    let net_income = values.get(FinancialMetric::NetIncome);
+    let equity = values.get(FinancialMetric::TotalEquity);
+    let debt = values
+        .get(FinancialMetric::TotalDebt)
+        .or_else(|| values.get(FinancialMetric::TotalLiabilities));
+    let free_cash_flow = free_cash_flow(values);
+
+    FinancialRatios {
+        symbol: symbol.clone(),
+        basis,
+        period_end,
+        fiscal_year: values.fiscal_year,
+        fiscal_quarter: values.fiscal_quarter,
+        revenue,
+        net_income,
+        free_cash_flow,
+        gross_margin: ratio(values.get(FinancialMetric::GrossProfit), revenue),
+        operating_margin: ratio(values.get(FinancialMetric::OperatingIncome), revenue),
+        net_margin: ratio(net_income, revenue),
+        return_on_equity: ratio(net_income, equity),
+        return_on_assets: ratio(net_income, values.get(FinancialMetric::TotalAssets)),
+        current_ratio: ratio(
+            values.get(FinancialMetric::CurrentAssets),
+            values.get(FinancialMetric::CurrentLiabilities),
+        ),
+        debt_to_equity: ratio(debt, equity),
+        fcf_yield: ratio(free_cash_flow, market_cap),
+        revenue_growth_yoy: growth(values, year_ago, FinancialMetric::Revenue),
+        revenue_growth_qoq: growth(values, quarter_ago, FinancialMetric::Revenue),
+        net_income_growth_yoy: growth(values, year_ago, FinancialMetric::NetIncome),
+        net_income_growth_qoq: growth(values, quarter_ago, FinancialMetric::NetIncome),
+        eps_growth_yoy: growth(values, year_ago, FinancialMetric::Eps),
+        eps_growth_qoq: growth(values, quarter_ago, FinancialMetric::Eps),
+    }
+}
+
+/// Operating cash flow less capital expenditures.
+///
+/// Providers disagree on the sign of capex, so its magnitude is subtracted.
+fn free_cash_flow(values: &PeriodValues) -> Option<f64> {
+    let operating = values.get(FinancialMetric::OperatingCashFlow)?;
+    let capex = values.get(FinancialMetric::CapitalExpenditures)?;
+    Some(operating - capex.abs())
+}
+
+fn ratio(numerator: Option<f64>, denominator: Option<f64>) -> Option<f64> {
+    let (numerator, denominator) = (numerator?, denominator?);
+    if denominator == 0.0 {
+        return None;
+    }
+    let value = numerator / denominator;
+    value.is_finite().then_some(value)
+}
+
+fn growth(
+    current: &PeriodValues,
+    prior: Option<&PeriodValues>,
+    metric: FinancialMetric,
+) -> Option<f64> {
+    let current = current.get(metric)?;
+    let prior = prior?.get(metric)?;
+    ratio(Some(current - prior), Some(prior.abs()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FinancialLineItem, StatementType};
+
+    fn ts(value: &str) -> UtcDateTime {
+        UtcDateTime::parse(value).expect("timestamp")
+    }
+
+    fn statement(
+        statement_type: StatementType,
+        period: FinancialPeriod,
+        items: &[(&str, f64, &str)],
+    ) -> FinancialStatement {
+        let line_items = items
+            .iter()
+            .map(|(label, value, end)| {
+                FinancialLineItem::new(*label, Some(*value), None, None, ts(end)).expect("item")
+            })
+            .collect();
+        FinancialStatement::new(
+            Symbol::parse("AAPL").expect("symbol"),
+            statement_type,
+            period,
+            "USD",
+            ts("2025-01-01T00:00:00Z"),
+            line_items,
+        )
+        .expect("statement")
+    }
+
+    #[test]
+    fn maps_provider_labels_to_metrics() {
+        assert_eq!(
+            FinancialMetric::from_label("Total Revenue"),
+            Some(FinancialMetric::Revenue)
+        );
+        assert_eq!(
+            FinancialMetric::from_label("total_stockholder_equity"),
+            Some(FinancialMetric::TotalEquity)
+        );
+        assert_eq!(FinancialMetric::from_label("Goodwill"), None);
+    }
+
+    #[test]
+    fn merges_statements_and_computes_annual_growth() {
+        let symbol = Symbol::parse("AAPL").expect("symbol");
+        let income = statement(
+            StatementType::Income,
+            FinancialPeriod::Annual,
+            &[
+                ("Total Revenue", 200.0, "2024-09-30T00:00:00Z"),
+                ("Net Income", 50.0, "2024-09-30T00:00:00Z"),
+                ("Total Revenue", 160.0, "2023-09-30T00:00:00Z"),
+                ("Net Income", 40.0, "2023-09-30T00:00:00Z"),
+            ],
+        );
+        let balance = statement(
+            StatementType::Balance,
+            FinancialPeriod::Annual,
+            &[
+                ("Total Assets", 500.0, "2024-09-30T00:00:00Z"),
+                ("Total Liabilities", 300.0, "2024-09-30T00:00:00Z"),
+                ("Total Stockholder Equity", 200.0, "2024-09-30T00:00:00Z"),
+            ],
+        );
+        let cash_flow = statement(
+            StatementType::CashFlow,
+            FinancialPeriod::Annual,
+            &[
+                ("Operating Cash Flow", 80.0, "2024-09-30T00:00:00Z"),
+                ("Capital Expenditures", -20.0, "2024-09-30T00:00:00Z"),
+            ],
+        );
+
+        let ratios = compute_ratios(
+            &symbol,
+            &[income, balance, cash_flow],
+            RatioBasis::Annual,
+            Some(1_200.0),
+        );
+
+        assert_eq!(ratios.len(), 2);
+        let latest = &ratios[0];
+        assert_eq!(latest.period_end, ts("2024-09-30T00:00:00Z"));
+        assert_eq!(latest.net_margin, Some(0.25));
+        assert_eq!(latest.return_on_equity, Some(0.25));
+        assert_eq!(latest.return_on_assets, Some(0.1));
+        assert_eq!(latest.debt_to_equity, Some(1.5));
+        assert_eq!(latest.free_cash_flow, Some(60.0));
+        assert_eq!(latest.fcf_yield, Some(0.05));
+        assert_eq!(latest.revenue_growth_yoy, Some(0.25));
+        assert_eq!(latest.revenue_growth_qoq, None);
+        assert_eq!(ratios[1].revenue_growth_yoy, None);
+    }
+
+    #[test]
+    fn ttm_sums_flows_and_keeps_latest_balances() {
+        let symbol = Symbol::parse("AAPL").expect("symbol");
+        let quarter_ends = [
+            "2023-03-31T00:00:00Z",
+            "2023-06-30T00:00:00Z",
+            "2023-09-30T00:00:00Z",
+            "2023-12-31T00:00:00Z",
+            "2024-03-31T00:00:00Z",
+        ];
+        let mut items = Vec::new();
+        for (index, end) in quarter_ends.iter().enumerate() {
+            items.push(("Total Revenue", 100.0 + 10.0 * index as f64, *end));
+            items.push(("Total Stockholder Equity", 1_000.0 + index as f64, *end));
+        }
+        let quarterly = statement(StatementType::Income, FinancialPeriod::Quarterly, &items);
+
+        let ratios = compute_ratios(&symbol, &[quarterly], RatioBasis::Ttm, None);
+
+        assert_eq!(ratios.len(), 2);
+        assert_eq!(ratios[0].period_end, ts("2024-03-31T00:00:00Z"));
+        assert_eq!(ratios[0].revenue, Some(110.0 + 120.0 + 130.0 + 140.0));
+        assert_eq!(ratios[1].revenue, Some(100.0 + 110.0 + 120.0 + 130.0));
+        assert_eq!(ratios[0].revenue_growth_qoq, Some(40.0 / 460.0));
+    }
+
+    #[test]
+    fn ignores_annual_statements_for_quarterly_basis() {
+        let symbol = Symbol::parse("AAPL").expect("symbol");
+        let annual = statement(
+            StatementType::Income,
+            FinancialPeriod::Annual,
+            &[("Total Revenue", 200.0, "2024-09-30T00:00:00Z")],
+        );
+
+        assert!(compute_ratios(&symbol, &[annual], RatioBasis::Quarterly, None).is_empty());
+    }
+}
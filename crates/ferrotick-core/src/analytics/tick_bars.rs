@@ -0,0 +1,374 @@
+//! Bar aggregation from trade ticks.
+//!
+//! Trade prints are grouped into OHLCV [`Bar`]s using one of four sampling
+//! rules described by [`TickBarSpec`]:
+//!
+//! - **Time** bars close on fixed wall-clock boundaries aligned to the Unix
+//!   epoch (UTC). Buckets without trades are skipped.
+//! - **Tick** bars close after a fixed number of trades.
+//! - **Volume** bars close once cumulative share volume reaches a threshold.
+//! - **Dollar** bars close once cumulative traded notional (`price * size`)
+//!   reaches a threshold.
+//!
+//! Activity-driven bars (tick, volume, dollar) are stamped with the time of
+//! their first trade and have no fixed spacing, so the resulting
+//! [`TickBarSeries`] is labelled with its spec rather than an [`Interval`]. Trades are never split across bars, so a bar may
+//! overshoot its threshold by the size of its last trade, and a trailing
+//! partial bar is emitted for any trades left after the last full bar.
+//!
+//! ```rust
+//! use ferrotick_core::analytics::{aggregate_trades, TickBarSpec};
+//! use ferrotick_core::{Symbol, TradeTick, UtcDateTime};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let symbol = Symbol::parse("AAPL")?;
+//! let trades = vec![
+//!     TradeTick::new(UtcDateTime::parse("2024-01-02T14:30:00Z")?, 100.0, 10, None, vec![])?,
+//!     TradeTick::new(UtcDateTime::parse("2024-01-02T14:30:01Z")?, 101.0, 10, None, vec![])?,
+//! ];
+//!
+//! let series = aggregate_trades(&symbol, &trades, TickBarSpec::Ticks(2))?;
+//! assert_eq!(series.bars.len(), 1);
+//! assert_eq!(series.bars[0].volume, Some(20));
+//! # Ok(())
+//! # }
+//! ```
+
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Bar, BarSeries, Interval, Symbol, TradeTick, UtcDateTime, ValidationError};
+
+/// Sampling rule used to group trades into bars.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind", content = "threshold")]
+pub enum TickBarSpec {
+    /// Fixed wall-clock bars of the given interval.
+    Time(Interval),
+    /// Bars of a fixed number of trades.
+    Ticks(u64),
+    /// Bars of at least the given share volume.
+    Volume(u64),
+    /// Bars of at least the given traded notional.
+    Dollar(f64),
+}
+
+impl TickBarSpec {
+    fn validate(self) -> Result<(), ValidationError> {
+        let valid = match self {
+            Self::Time(_) => true,
+            Self::Ticks(count) => count > 0,
+            Self::Volume(volume) => volume > 0,
+            Self::Dollar(notional) => notional.is_finite() && notional > 0.0,
+        };
+
+        if valid {
+            Ok(())
+        } else {
+            Err(ValidationError::InvalidTickBarThreshold)
+        }
+    }
+}
+
+/// Parses `1m`-style intervals for time bars, or `ticks:N`, `volume:N` and
+/// `dollar:X` for activity-driven bars.
+impl FromStr for TickBarSpec {
+    type Err = ValidationError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || ValidationError::InvalidTickBarSpec {
+            value: value.to_owned(),
+        };
+        let normalized = value.trim().to_ascii_lowercase();
+
+        let spec = match normalized.split_once(':') {
+            None => Self::Time(Interval::from_str(&normalized).map_err(|_| invalid())?),
+            Some(("ticks", threshold)) => Self::Ticks(threshold.parse().map_err(|_| invalid())?),
+            Some(("volume", threshold)) => Self::Volume(threshold.parse().map_err(|_| invalid())?),
+            Some(("dollar", threshold)) => Self::Dollar(threshold.parse().map_err(|_| invalid())?),
+            Some(_) => return Err(invalid()),
+        };
+
+        spec.validate()?;
+        Ok(spec)
+    }
+}
+
+/// Bars aggregated from trade ticks under `spec`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TickBarSeries {
+    pub symbol: Symbol,
+    pub spec: TickBarSpec,
+    pub bars: Vec<Bar>,
+}
+
+impl TickBarSeries {
+    /// The bars as an interval-labelled [`BarSeries`], for time bars only.
+    pub fn into_bar_series(self) -> Option<BarSeries> {
+        match self.spec {
+            TickBarSpec::Time(interval) => Some(BarSeries::new(self.symbol, interval, self.bars)),
+            TickBarSpec::Ticks(_) | TickBarSpec::Volume(_) | TickBarSpec::Dollar(_) => None,
+        }
+    }
+}
+
+/// Aggregates trades into a [`TickBarSeries`] according to `spec`.
+///
+/// Trades are ordered by timestamp before aggregation; the input slice does
+/// not need to be sorted.
+///
+/// # Errors
+///
+/// Returns [`ValidationError::InvalidTickBarThreshold`] when a tick, volume or
+/// dollar threshold is zero, negative or non-finite.
+pub fn aggregate_trades(
+    symbol: &Symbol,
+    trades: &[TradeTick],
+    spec: TickBarSpec,
+) -> Result<TickBarSeries, ValidationError> {
+    spec.validate()?;
+
+    let mut ordered: Vec<&TradeTick> = trades.iter().collect();
+    ordered.sort_by_key(|trade| trade.ts);
+
+    let mut bars = Vec::new();
+    let mut current: Option<BarBuilder> = None;
+
+    for trade in ordered {
+        if let TickBarSpec::Time(interval) = spec {
+            let bucket = bucket_start(trade.ts, interval)?;
+            if current.as_ref().is_some_and(|bar| bar.ts != bucket) {
+                if let Some(bar) = current.take() {
+                    bars.push(bar.finish()?);
+                }
+            }
+            current
+                .get_or_insert_with(|| BarBuilder::new(bucket, trade))
+                .push(trade);
+            continue;
+        }
+
+        let bar = current.get_or_insert_with(|| BarBuilder::new(trade.ts, trade));
+        bar.push(trade);
+
+        let full = match spec {
+            TickBarSpec::Ticks(count) => bar.trades >= count,
+            TickBarSpec::Volume(volume) => bar.volume >= volume,
+            TickBarSpec::Dollar(notional) => bar.notional >= notional,
+            TickBarSpec::Time(_) => false,
+        };
+        if full {
+            if let Some(bar) = current.take() {
+                bars.push(bar.finish()?);
+            }
+        }
+    }
+
+    if let Some(bar) = current {
+        bars.push(bar.finish()?);
+    }
+
+    Ok(TickBarSeries {
+        symbol: symbol.clone(),
+        spec,
+        bars,
+    })
+}
+
+/// Running OHLCV state for the bar being built.
+struct BarBuilder {
+    ts: UtcDateTime,
+    open: f64,
+    high: f64,
+    low: f64,
+    close: f64,
+    volume: u64,
+    notional: f64,
+    trades: u64,
+}
+
+impl BarBuilder {
+    fn new(ts: UtcDateTime, first: &TradeTick) -> Self {
+        Self {
+            ts,
+            open: first.price,
+            high: first.price,
+            low: first.price,
+            close: first.price,
+            volume: 0,
+            notional: 0.0,
+            trades: 0,
+        }
+    }
+
+    fn push(&mut self, trade: &TradeTick) {
+        self.high = self.high.max(trade.price);
+        self.low = self.low.min(trade.price);
+        self.close = trade.price;
+        self.volume = self.volume.saturating_add(trade.size);
+        self.notional += trade.price * trade.size as f64;
+        self.trades += 1;
+    }
+
+    fn finish(self) -> Result<Bar, ValidationError> {
+        let vwap = (self.volume > 0).then(|| self.notional / self.volume as f64);
+        Bar::new(
+            self.ts,
+            self.open,
+            self.high,
+            self.low,
+            self.close,
+            Some(self.volume),
+            vwap,
+        )
+    }
+}
+
+fn bucket_start(ts: UtcDateTime, interval: Interval) -> Result<UtcDateTime, ValidationError> {
+    let width = interval_seconds(interval);
+    let seconds = ts.into_inner().unix_timestamp();
+    UtcDateTime::from_unix_timestamp(seconds - seconds.rem_euclid(width))
+}
+
+fn interval_seconds(interval: Interval) -> i64 {
+    match interval {
+        Interval::OneMinute => 60,
+        Interval::FiveMinutes => 5 * 60,
+        Interval::FifteenMinutes => 15 * 60,
+        Interval::OneHour => 60 * 60,
+        Interval::OneDay => 24 * 60 * 60,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(ts: &str, price: f64, size: u64) -> TradeTick {
+        TradeTick::new(
+            UtcDateTime::parse(ts).expect("valid timestamp"),
+            price,
+            size,
+            None,
+            Vec::new(),
+        )
+        .expect("valid trade")
+    }
+
+    fn sample_trades() -> Vec<TradeTick> {
+        vec![
+            trade("2024-01-02T14:30:05Z", 100.0, 100),
+            trade("2024-01-02T14:30:40Z", 102.0, 200),
+            trade("2024-01-02T14:31:10Z", 99.0, 100),
+            trade("2024-01-02T14:33:00Z", 101.0, 300),
+        ]
+    }
+
+    fn symbol() -> Symbol {
+        Symbol::parse("AAPL").expect("valid symbol")
+    }
+
+    #[test]
+    fn time_bars_align_to_interval_boundaries_and_skip_empty_buckets() {
+        let series = aggregate_trades(
+            &symbol(),
+            &sample_trades(),
+            TickBarSpec::Time(Interval::OneMinute),
+        )
+        .expect("aggregation should succeed");
+
+        assert_eq!(series.bars.len(), 3);
+
+        let first = &series.bars[0];
+        assert_eq!(first.ts.format_rfc3339(), "2024-01-02T14:30:00Z");
+        assert_eq!(first.open, 100.0);
+        assert_eq!(first.high, 102.0);
+        assert_eq!(first.close, 102.0);
+        assert_eq!(first.volume, Some(300));
+        let vwap = first.vwap.expect("vwap");
+        assert!((vwap - (100.0 * 100.0 + 102.0 * 200.0) / 300.0).abs() < 1e-9);
+
+        assert_eq!(series.bars[2].ts.format_rfc3339(), "2024-01-02T14:33:00Z");
+
+        let series = series
+            .into_bar_series()
+            .expect("time bars have an interval");
+        assert_eq!(series.interval, Interval::OneMinute);
+    }
+
+    #[test]
+    fn tick_bars_emit_trailing_partial_bar() {
+        let series = aggregate_trades(&symbol(), &sample_trades(), TickBarSpec::Ticks(3))
+            .expect("aggregation should succeed");
+
+        assert_eq!(series.bars.len(), 2);
+        assert_eq!(series.bars[0].ts.format_rfc3339(), "2024-01-02T14:30:05Z");
+        assert_eq!(series.bars[0].low, 99.0);
+        assert_eq!(series.bars[0].volume, Some(400));
+        assert_eq!(series.bars[1].volume, Some(300));
+        assert_eq!(series.spec, TickBarSpec::Ticks(3));
+        assert_eq!(series.into_bar_series(), None);
+    }
+
+    #[test]
+    fn volume_and_dollar_bars_close_on_threshold() {
+        let volume = aggregate_trades(&symbol(), &sample_trades(), TickBarSpec::Volume(300))
+            .expect("aggregation should succeed");
+        let volumes: Vec<_> = volume.bars.iter().map(|bar| bar.volume).collect();
+        assert_eq!(volumes, vec![Some(300), Some(400)]);
+
+        let dollar = aggregate_trades(&symbol(), &sample_trades(), TickBarSpec::Dollar(20_000.0))
+            .expect("aggregation should succeed");
+        assert_eq!(dollar.bars.len(), 2);
+        assert_eq!(dollar.bars[0].close, 102.0);
+        assert_eq!(dollar.bars[1].open, 99.0);
+    }
+
+    #[test]
+    fn unsorted_input_is_ordered_before_aggregation() {
+        let mut trades = sample_trades();
+        trades.reverse();
+
+        let series = aggregate_trades(&symbol(), &trades, TickBarSpec::Ticks(10))
+            .expect("aggregation should succeed");
+
+        assert_eq!(series.bars.len(), 1);
+        assert_eq!(series.bars[0].open, 100.0);
+        assert_eq!(series.bars[0].close, 101.0);
+    }
+
+    #[test]
+    fn parses_bar_specs() {
+        assert_eq!(
+            "5m".parse::<TickBarSpec>(),
+            Ok(TickBarSpec::Time(Interval::FiveMinutes))
+        );
+        assert_eq!("ticks:500".parse(), Ok(TickBarSpec::Ticks(500)));
+        assert_eq!("Volume:10000".parse(), Ok(TickBarSpec::Volume(10_000)));
+        assert_eq!("dollar:1e6".parse(), Ok(TickBarSpec::Dollar(1_000_000.0)));
+        assert!(matches!(
+            "range:5".parse::<TickBarSpec>(),
+            Err(ValidationError::InvalidTickBarSpec { .. })
+        ));
+        assert_eq!(
+            "ticks:0".parse::<TickBarSpec>(),
+            Err(ValidationError::InvalidTickBarThreshold)
+        );
+    }
+
+    #[test]
+    fn rejects_non_positive_thresholds() {
+        for spec in [
+            TickBarSpec::Ticks(0),
+            TickBarSpec::Volume(0),
+            TickBarSpec::Dollar(0.0),
+            TickBarSpec::Dollar(f64::NAN),
+        ] {
+            assert_eq!(
+                aggregate_trades(&symbol(), &sample_trades(), spec),
+                Err(ValidationError::InvalidTickBarThreshold)
+            );
+        }
+    }
+}
@@ -1,9 +1,28 @@
 //! In-memory caching for API responses.
 
+use std::cell::Cell;
 use std::collections::HashMap;
+use std::future::Future;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+use ferrotick_telemetry::metrics;
+
+tokio::task_local! {
+    /// Set by a cache hit inside [`served_from_cache`].
+    static SERVED_FROM_CACHE: Cell<bool>;
+}
+
+/// Run `future`, reporting whether any [`CacheStore::get`] it made was a hit.
+pub(crate) async fn served_from_cache<F: Future>(future: F) -> (F::Output, bool) {
+    SERVED_FROM_CACHE
+        .scope(Cell::new(false), async move {
+            let output = future.await;
+            (output, SERVED_FROM_CACHE.with(Cell::get))
+        })
+        .await
+}
+
 /// Defines the behavior of the in-memory cache for an API call.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum CacheMode {
@@ -104,7 +123,15 @@ impl CacheStore {
     /// - The cache is disabled (TTL is ZERO)
     pub async fn get(&self, key: &str) -> Option<String> {
         let store = self.inner.read().await;
-        store.get(key)
+        let cached = store.get(key);
+        let result = if cached.is_some() {
+            let _ = SERVED_FROM_CACHE.try_with(|hit| hit.set(true));
+            "hit"
+        } else {
+            "miss"
+        };
+        metrics::CACHE_LOOKUPS.increment(&[("result", result)]);
+        cached
     }
 
     /// Put a value into the cache with the given key.
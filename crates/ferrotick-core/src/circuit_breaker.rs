@@ -1,6 +1,8 @@
 use std::sync::Mutex;
 use std::time::{Duration, Instant};
 
+use ferrotick_telemetry::metrics;
+
 /// Runtime circuit state for source adapter upstream calls.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CircuitState {
@@ -9,6 +11,16 @@ pub enum CircuitState {
     HalfOpen,
 }
 
+impl CircuitState {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Closed => "closed",
+            Self::Open => "open",
+            Self::HalfOpen => "half_open",
+        }
+    }
+}
+
 /// Circuit breaker thresholds and timers.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct CircuitBreakerConfig {
@@ -77,6 +89,7 @@ impl CircuitBreaker {
                     .unwrap_or(false);
 
                 if can_probe {
+                    record_transition(inner.state, CircuitState::HalfOpen);
                     inner.state = CircuitState::HalfOpen;
                     inner.opened_at = None;
                     true
@@ -92,6 +105,7 @@ impl CircuitBreaker {
             .inner
             .lock()
             .expect("circuit breaker lock is not poisoned");
+        record_transition(inner.state, CircuitState::Closed);
         inner.state = CircuitState::Closed;
         inner.consecutive_failures = 0;
         inner.opened_at = None;
@@ -107,6 +121,7 @@ impl CircuitBreaker {
         if inner.state == CircuitState::HalfOpen
             || inner.consecutive_failures >= self.config.failure_threshold
         {
+            record_transition(inner.state, CircuitState::Open);
             inner.state = CircuitState::Open;
             inner.opened_at = Some(Instant::now());
         }
@@ -129,6 +144,18 @@ impl CircuitBreaker {
     }
 }
 
+fn record_transition(from: CircuitState, to: CircuitState) {
+    if from == to {
+        return;
+    }
+    tracing::info!(
+        from = from.as_str(),
+        to = to.as_str(),
+        "circuit breaker transition"
+    );
+    metrics::CIRCUIT_TRANSITIONS.increment(&[("to", to.as_str())]);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
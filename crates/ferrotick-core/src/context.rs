@@ -0,0 +1,232 @@
+//! Request context carrying a deadline and cancellation token.
+//!
+//! A [`RequestContext`] is installed for the duration of a call with
+//! [`RequestContext::scope`] and picked up with [`RequestContext::current`] by
+//! the router, adapters and HTTP clients, so the `DataSource` and `HttpClient`
+//! signatures stay unchanged. Router fallbacks, adapter retries and HTTP
+//! timeouts are all trimmed to the remaining budget.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::sync::Notify;
+
+use crate::data_source::SourceError;
+
+tokio::task_local! {
+    static CURRENT: RequestContext;
+}
+
+/// Cooperative cancellation flag shared between a caller and its call tree.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    inner: Arc<CancellationState>,
+}
+
+#[derive(Debug, Default)]
+struct CancellationState {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        self.inner.notify.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Resolves once [`Self::cancel`] has been called.
+    pub async fn cancelled(&self) {
+        loop {
+            let notified = self.inner.notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+/// Why a guarded call was interrupted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interrupted {
+    DeadlineExceeded,
+    Cancelled,
+}
+
+impl Interrupted {
+    /// Source error naming the `step` that was interrupted.
+    pub fn into_source_error(self, step: &str) -> SourceError {
+        match self {
+            Self::DeadlineExceeded => SourceError::deadline_exceeded(step),
+            Self::Cancelled => SourceError::cancelled(step),
+        }
+    }
+}
+
+/// Deadline and cancellation token for one logical request.
+#[derive(Debug, Clone, Default)]
+pub struct RequestContext {
+    deadline: Option<Instant>,
+    cancellation: CancellationToken,
+}
+
+impl RequestContext {
+    /// Context without a deadline.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Context whose deadline is `timeout` from now.
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self::new().with_deadline(Instant::now() + timeout)
+    }
+
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    pub fn with_cancellation(mut self, cancellation: CancellationToken) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    /// Context installed by the innermost [`Self::scope`], or an unbounded one.
+    pub fn current() -> Self {
+        CURRENT
+            .try_with(Clone::clone)
+            .unwrap_or_else(|_| Self::new())
+    }
+
+    /// Run `future` with this context as [`Self::current`].
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CURRENT.scope(self, future).await
+    }
+
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    pub fn cancellation(&self) -> &CancellationToken {
+        &self.cancellation
+    }
+
+    /// Budget left before the deadline; `None` when unbounded.
+    pub fn remaining(&self) -> Option<Duration> {
+        self.deadline
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+    }
+
+    /// Reason the request may not proceed, if any.
+    pub fn interrupted(&self) -> Option<Interrupted> {
+        if self.cancellation.is_cancelled() {
+            return Some(Interrupted::Cancelled);
+        }
+        if self
+            .remaining()
+            .is_some_and(|remaining| remaining.is_zero())
+        {
+            return Some(Interrupted::DeadlineExceeded);
+        }
+        None
+    }
+
+    /// Fail with an error naming `step` when the request may not proceed.
+    pub fn check(&self, step: &str) -> Result<(), SourceError> {
+        match self.interrupted() {
+            Some(interrupted) => Err(interrupted.into_source_error(step)),
+            None => Ok(()),
+        }
+    }
+
+    /// Cap a per-request timeout to the remaining budget.
+    pub fn clamp_timeout_ms(&self, timeout_ms: u64) -> u64 {
+        match self.remaining() {
+            Some(remaining) => {
+                let remaining_ms = remaining.as_millis().min(u128::from(u64::MAX)) as u64;
+                timeout_ms.min(remaining_ms)
+            }
+            None => timeout_ms,
+        }
+    }
+
+    /// Drive `future` until it completes, the deadline passes or the token
+    /// is cancelled, whichever comes first.
+    pub async fn guard<F: Future>(&self, future: F) -> Result<F::Output, Interrupted> {
+        if let Some(interrupted) = self.interrupted() {
+            return Err(interrupted);
+        }
+
+        let deadline = async {
+            match self.deadline {
+                Some(deadline) => tokio::time::sleep_until(deadline.into()).await,
+                None => std::future::pending::<()>().await,
+            }
+        };
+
+        // Poll the future first so that a nested guard sharing this deadline
+        // reports its own, more specific step.
+        tokio::select! {
+            biased;
+            output = future => Ok(output),
+            () = deadline => Err(Interrupted::DeadlineExceeded),
+            () = self.cancellation.cancelled() => Err(Interrupted::Cancelled),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .expect("runtime")
+    }
+
+    #[test]
+    fn scoped_context_is_visible_to_nested_calls() {
+        let context = RequestContext::with_timeout(Duration::from_secs(5));
+
+        let remaining =
+            runtime().block_on(context.scope(async { RequestContext::current().remaining() }));
+
+        assert!(remaining.is_some_and(|remaining| remaining <= Duration::from_secs(5)));
+        assert_eq!(RequestContext::current().remaining(), None);
+    }
+
+    #[test]
+    fn guard_stops_at_deadline_and_on_cancellation() {
+        let runtime = runtime();
+        let context = RequestContext::with_timeout(Duration::from_millis(20));
+        let slow = async { tokio::time::sleep(Duration::from_secs(5)).await };
+        assert_eq!(
+            runtime.block_on(context.guard(slow)),
+            Err(Interrupted::DeadlineExceeded)
+        );
+        assert_eq!(context.clamp_timeout_ms(10_000), 0);
+
+        let token = CancellationToken::new();
+        let context = RequestContext::new().with_cancellation(token.clone());
+        token.cancel();
+        let error = context.check("quote").expect_err("cancelled");
+        assert_eq!(error.code(), "source.cancelled");
+        assert_eq!(
+            runtime.block_on(context.guard(async { 1 })),
+            Err(Interrupted::Cancelled)
+        );
+    }
+}
@@ -12,6 +12,9 @@
 //! | Bars | [`BarsRequest`] | [`BarSeries`] | Historical OHLCV data |
 //! | Fundamentals | [`FundamentalsRequest`] | [`FundamentalsBatch`] | Company fundamentals |
 //! | Search | [`SearchRequest`] | [`SearchBatch`] | Instrument search |
+//! | Ticks | [`TicksRequest`] | [`TickBatch`] | Historical trades and NBBO quotes |
+//! | Economic | [`EconomicRequest`] | [`EconomicSeries`] | Macro/economic series |
+//! | News | [`NewsRequest`] | [`NewsBatch`] | Headlines with sentiment |
 //!
 //! # Example
 //!
@@ -31,7 +34,10 @@ use std::pin::Pin;
 
 use serde::{Deserialize, Serialize};
 
-use crate::{BarSeries, Fundamental, Instrument, Interval, ProviderId, Quote, Symbol};
+use crate::{
+    BarSeries, EconomicSeries, Fundamental, Instrument, Interval, NewsItem, ProviderId, Quote,
+    QuoteTick, SeriesFrequency, Symbol, TradeTick, UtcDateTime,
+};
 
 /// Data endpoint type used for routing and capability checks.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -43,6 +49,9 @@ pub enum Endpoint {
     Search,
     Financials,
     Earnings,
+    Ticks,
+    Economic,
+    News,
 }
 
 impl Endpoint {
@@ -54,6 +63,9 @@ impl Endpoint {
             Self::Search => "search",
             Self::Financials => "financials",
             Self::Earnings => "earnings",
+            Self::Ticks => "ticks",
+            Self::Economic => "economic",
+            Self::News => "news",
         }
     }
 }
@@ -73,9 +85,13 @@ pub struct CapabilitySet {
     pub search: bool,
     pub financials: bool,
     pub earnings: bool,
+    pub ticks: bool,
+    pub economic: bool,
+    pub news: bool,
 }
 
 impl CapabilitySet {
+    #[allow(clippy::too_many_arguments)]
     pub const fn new(
         quote: bool,
         bars: bool,
@@ -83,6 +99,9 @@ impl CapabilitySet {
         search: bool,
         financials: bool,
         earnings: bool,
+        ticks: bool,
+        economic: bool,
+        news: bool,
     ) -> Self {
         Self {
             quote,
@@ -91,11 +110,14 @@ impl CapabilitySet {
             search,
             financials,
             earnings,
+            ticks,
+            economic,
+            news,
         }
     }
 
     pub const fn full() -> Self {
-        Self::new(true, true, true, true, true, true)
+        Self::new(true, true, true, true, true, true, true, true, true)
     }
 
     pub const fn supports(self, endpoint: Endpoint) -> bool {
@@ -106,11 +128,14 @@ impl CapabilitySet {
             Endpoint::Search => self.search,
             Endpoint::Financials => self.financials,
             Endpoint::Earnings => self.earnings,
+            Endpoint::Ticks => self.ticks,
+            Endpoint::Economic => self.economic,
+            Endpoint::News => self.news,
         }
     }
 
     pub fn supported_endpoints(self) -> Vec<&'static str> {
-        let mut values = Vec::with_capacity(6);
+        let mut values = Vec::with_capacity(9);
         if self.quote {
             values.push("quote");
         }
@@ -129,6 +154,15 @@ impl CapabilitySet {
         if self.earnings {
             values.push("earnings");
         }
+        if self.ticks {
+            values.push("ticks");
+        }
+        if self.economic {
+            values.push("economic");
+        }
+        if self.news {
+            values.push("news");
+        }
         values
     }
 }
@@ -178,6 +212,8 @@ pub enum SourceErrorKind {
     InvalidRequest,
     AdapterNotRegistered,
     Internal,
+    DeadlineExceeded,
+    Cancelled,
 }
 
 /// Structured source error used by router fallback.
@@ -237,6 +273,24 @@ impl SourceError {
         }
     }
 
+    /// The request deadline passed while `step` was running.
+    pub fn deadline_exceeded(step: &str) -> Self {
+        Self {
+            kind: SourceErrorKind::DeadlineExceeded,
+            message: format!("deadline exceeded during {step}"),
+            retryable: false,
+        }
+    }
+
+    /// The request was cancelled while `step` was running.
+    pub fn cancelled(step: &str) -> Self {
+        Self {
+            kind: SourceErrorKind::Cancelled,
+            message: format!("request cancelled during {step}"),
+            retryable: false,
+        }
+    }
+
     pub const fn kind(&self) -> SourceErrorKind {
         self.kind
     }
@@ -257,6 +311,8 @@ impl SourceError {
             SourceErrorKind::InvalidRequest => "source.invalid_request",
             SourceErrorKind::AdapterNotRegistered => "source.adapter_not_registered",
             SourceErrorKind::Internal => "source.internal",
+            SourceErrorKind::DeadlineExceeded => "source.deadline_exceeded",
+            SourceErrorKind::Cancelled => "source.cancelled",
         }
     }
 }
@@ -417,6 +473,155 @@ impl EarningsRequest {
     }
 }
 
+/// Kind of tick data requested from tick endpoints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TickKind {
+    Trades,
+    Quotes,
+}
+
+impl TickKind {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Trades => "trades",
+            Self::Quotes => "quotes",
+        }
+    }
+}
+
+/// Request payload for tick endpoints.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TicksRequest {
+    pub symbol: Symbol,
+    pub kind: TickKind,
+    pub start: Option<UtcDateTime>,
+    pub end: Option<UtcDateTime>,
+    pub limit: usize,
+}
+
+impl TicksRequest {
+    pub fn new(symbol: Symbol, kind: TickKind, limit: usize) -> Result<Self, SourceError> {
+        if limit == 0 {
+            return Err(SourceError::invalid_request(
+                "ticks request limit must be greater than zero",
+            ));
+        }
+        Ok(Self {
+            symbol,
+            kind,
+            start: None,
+            end: None,
+            limit,
+        })
+    }
+
+    pub fn with_range(
+        mut self,
+        start: Option<UtcDateTime>,
+        end: Option<UtcDateTime>,
+    ) -> Result<Self, SourceError> {
+        if let (Some(start), Some(end)) = (start, end) {
+            if start > end {
+                return Err(SourceError::invalid_request(
+                    "ticks request start must not be after end",
+                ));
+            }
+        }
+        self.start = start;
+        self.end = end;
+        Ok(self)
+    }
+}
+
+/// Normalized tick batch; only the requested kind is populated.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TickBatch {
+    pub symbol: Symbol,
+    #[serde(default)]
+    pub trades: Vec<TradeTick>,
+    #[serde(default)]
+    pub quotes: Vec<QuoteTick>,
+}
+
+/// Request payload for economic series endpoints.
+///
+/// `series_id` names the series in canonical form, e.g. `CPI`,
+/// `FEDERAL_FUNDS_RATE` or `TREASURY_YIELD_10YEAR`; `frequency` selects the
+/// sampling when a provider publishes several.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EconomicRequest {
+    pub series_id: String,
+    pub frequency: Option<SeriesFrequency>,
+    pub limit: usize,
+}
+
+impl EconomicRequest {
+    pub fn new(series_id: impl AsRef<str>, limit: usize) -> Result<Self, SourceError> {
+        let series_id = series_id.as_ref().trim().to_ascii_uppercase();
+        if series_id.is_empty() {
+            return Err(SourceError::invalid_request(
+                "economic request series_id must not be empty",
+            ));
+        }
+        if limit == 0 {
+            return Err(SourceError::invalid_request(
+                "economic request limit must be greater than zero",
+            ));
+        }
+        Ok(Self {
+            series_id,
+            frequency: None,
+            limit,
+        })
+    }
+
+    pub fn with_frequency(mut self, frequency: SeriesFrequency) -> Self {
+        self.frequency = Some(frequency);
+        self
+    }
+}
+
+/// Request payload for news endpoints.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewsRequest {
+    pub symbols: Vec<Symbol>,
+    /// Only return articles published at or after this time.
+    pub since: Option<UtcDateTime>,
+    pub limit: usize,
+}
+
+impl NewsRequest {
+    pub fn new(symbols: Vec<Symbol>, limit: usize) -> Result<Self, SourceError> {
+        if symbols.is_empty() {
+            return Err(SourceError::invalid_request(
+                "news request requires at least one symbol",
+            ));
+        }
+        if limit == 0 {
+            return Err(SourceError::invalid_request(
+                "news request limit must be greater than zero",
+            ));
+        }
+        Ok(Self {
+            symbols,
+            since: None,
+            limit,
+        })
+    }
+
+    pub fn with_since(mut self, since: UtcDateTime) -> Self {
+        self.since = Some(since);
+        self
+    }
+}
+
+/// Normalized news batch, newest first.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NewsBatch {
+    pub items: Vec<NewsItem>,
+}
+
 /// Normalized financials batch.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FinancialsBatch {
@@ -444,6 +649,9 @@ pub struct EarningsBatch {
 /// | [`bars`](DataSource::bars) | Fetch OHLCV bars |
 /// | [`fundamentals`](DataSource::fundamentals) | Fetch fundamentals |
 /// | [`search`](DataSource::search) | Search instruments |
+/// | [`ticks`](DataSource::ticks) | Fetch historical trades or quotes |
+/// | [`economic`](DataSource::economic) | Fetch macro/economic series |
+/// | [`news`](DataSource::news) | Fetch headlines with sentiment |
 /// | [`health`](DataSource::health) | Check source health |
 ///
 /// # Example Implementation
@@ -457,14 +665,14 @@ pub struct EarningsBatch {
 ///
 /// impl DataSource for MyAdapter {
 ///     fn id(&self) -> ProviderId {
-///         ProviderId::new("my_provider")
+///         ProviderId::new("my_provider").expect("valid provider name")
 ///     }
 ///     
 ///     fn capabilities(&self) -> CapabilitySet {
-///         CapabilitySet::new(true, true, false, true, false, false)
+///         CapabilitySet::new(true, true, false, true, false, false, false, false, false)
 ///     }
 ///
-///     // ... implement quote/bars/fundamentals/search/financials/earnings/health
+///     // ... implement quote/bars/fundamentals/search/financials/earnings/ticks/economic/news/health
 /// }
 /// ```
 ///
@@ -557,6 +765,45 @@ pub trait DataSource: Send + Sync {
         req: EarningsRequest,
     ) -> Pin<Box<dyn Future<Output = Result<EarningsBatch, SourceError>> + Send + 'a>>;
 
+    /// Fetches historical trade prints or NBBO quote ticks.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SourceError`] if:
+    /// - The endpoint is not supported
+    /// - Invalid symbol, range or limit is provided
+    /// - The provider is unavailable
+    fn ticks<'a>(
+        &'a self,
+        req: TicksRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<TickBatch, SourceError>> + Send + 'a>>;
+
+    /// Fetches a macro or economic series such as treasury yields or CPI.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SourceError`] if:
+    /// - The endpoint is not supported
+    /// - The series is unknown to the provider
+    /// - The provider is unavailable
+    fn economic<'a>(
+        &'a self,
+        req: EconomicRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<EconomicSeries, SourceError>> + Send + 'a>>;
+
+    /// Fetches news headlines and sentiment for the requested symbols.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SourceError`] if:
+    /// - The endpoint is not supported
+    /// - Invalid symbols or limit are provided
+    /// - The provider is unavailable
+    fn news<'a>(
+        &'a self,
+        req: NewsRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<NewsBatch, SourceError>> + Send + 'a>>;
+
     /// Returns the current health status of this source.
     ///
     /// Used by the router for source scoring and fallback decisions.
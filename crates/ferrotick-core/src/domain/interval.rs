@@ -38,6 +38,28 @@ impl Interval {
             Self::OneDay => "1d",
         }
     }
+
+    /// Warehouse dataset holding bars of this interval.
+    pub const fn bars_dataset(self) -> &'static str {
+        match self {
+            Self::OneMinute => "bars_1m",
+            Self::FiveMinutes => "bars_5m",
+            Self::FifteenMinutes => "bars_15m",
+            Self::OneHour => "bars_1h",
+            Self::OneDay => "bars_1d",
+        }
+    }
+
+    /// Approximate number of bars in one regular 6.5 hour equity session.
+    pub const fn bars_per_session(self) -> usize {
+        match self {
+            Self::OneMinute => 390,
+            Self::FiveMinutes => 78,
+            Self::FifteenMinutes => 26,
+            Self::OneHour => 7,
+            Self::OneDay => 1,
+        }
+    }
 }
 
 impl Display for Interval {
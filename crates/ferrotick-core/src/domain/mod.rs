@@ -19,8 +19,12 @@
 //! | [`Bar`] | OHLCV bar with timestamp |
 //! | [`BarSeries`] | Collection of bars for a symbol/interval |
 //! | [`Fundamental`] | Company fundamentals snapshot |
-//! | [`Instrument`] | Instrument metadata |
+//! | [`Instrument`] | Instrument metadata and cross-provider identifiers |
 //! | [`CorporateAction`] | Corporate actions (dividends, splits) |
+//! | [`TradeTick`] | Single trade print |
+//! | [`QuoteTick`] | NBBO quote update |
+//! | [`EconomicSeries`] | Macro/economic series with dated observations |
+//! | [`NewsItem`] | News headline with sentiment scores |
 //! | [`Symbol`] | Validated stock symbol |
 //! | [`Interval`] | Bar interval (1m, 5m, 1h, 1d) |
 //! | [`UtcDateTime`] | UTC timestamp |
@@ -65,8 +69,9 @@ mod timestamp;
 pub use interval::Interval;
 pub use models::{
     validate_currency_code, AssetClass, Bar, BarSeries, CorporateAction, CorporateActionType,
-    EarningsEntry, EarningsReport, FinancialLineItem, FinancialPeriod, FinancialStatement,
-    Fundamental, Instrument, Quote, StatementType,
+    EarningsEntry, EarningsReport, EconomicObservation, EconomicSeries, FinancialLineItem,
+    FinancialPeriod, FinancialStatement, Fundamental, Instrument, InstrumentIdentifiers, NewsItem,
+    Quote, QuoteTick, SeriesFrequency, StatementType, TickerSentiment, TradeTick,
 };
 pub use symbol::Symbol;
 pub use timestamp::UtcDateTime;
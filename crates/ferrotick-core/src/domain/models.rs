@@ -15,6 +15,27 @@ pub enum AssetClass {
     Other,
 }
 
+/// Cross-provider security identifiers.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstrumentIdentifiers {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub isin: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cusip: Option<String>,
+    /// Composite FIGI, stable across ticker changes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub figi: Option<String>,
+    /// SEC Central Index Key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cik: Option<String>,
+}
+
+impl InstrumentIdentifiers {
+    pub fn is_empty(&self) -> bool {
+        self.isin.is_none() && self.cusip.is_none() && self.figi.is_none() && self.cik.is_none()
+    }
+}
+
 /// Canonical instrument metadata.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Instrument {
@@ -24,6 +45,16 @@ pub struct Instrument {
     pub currency: String,
     pub asset_class: AssetClass,
     pub is_active: bool,
+    #[serde(default, skip_serializing_if = "InstrumentIdentifiers::is_empty")]
+    pub identifiers: InstrumentIdentifiers,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sector: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub industry: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub listed_at: Option<UtcDateTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delisted_at: Option<UtcDateTime>,
 }
 
 impl Instrument {
@@ -42,8 +73,34 @@ impl Instrument {
             currency: validate_currency_code(currency.as_ref())?,
             asset_class,
             is_active,
+            identifiers: InstrumentIdentifiers::default(),
+            sector: None,
+            industry: None,
+            listed_at: None,
+            delisted_at: None,
         })
     }
+
+    pub fn with_identifiers(mut self, identifiers: InstrumentIdentifiers) -> Self {
+        self.identifiers = identifiers;
+        self
+    }
+
+    pub fn with_classification(mut self, sector: Option<String>, industry: Option<String>) -> Self {
+        self.sector = sector;
+        self.industry = industry;
+        self
+    }
+
+    pub fn with_listing(
+        mut self,
+        listed_at: Option<UtcDateTime>,
+        delisted_at: Option<UtcDateTime>,
+    ) -> Self {
+        self.listed_at = listed_at;
+        self.delisted_at = delisted_at;
+        self
+    }
 }
 
 /// Canonical top-of-book quote.
@@ -159,6 +216,10 @@ pub struct Fundamental {
     pub market_cap: Option<f64>,
     pub pe_ratio: Option<f64>,
     pub dividend_yield: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sector: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub industry: Option<String>,
 }
 
 impl Fundamental {
@@ -179,8 +240,16 @@ impl Fundamental {
             market_cap,
             pe_ratio,
             dividend_yield,
+            sector: None,
+            industry: None,
         })
     }
+
+    pub fn with_classification(mut self, sector: Option<String>, industry: Option<String>) -> Self {
+        self.sector = sector;
+        self.industry = industry;
+        self
+    }
 }
 
 /// Financial statement type.
@@ -262,11 +331,15 @@ impl FinancialStatement {
 }
 
 /// Single earnings entry for a fiscal period.
+///
+/// `end_date` is the fiscal period end; `report_date` is when the results
+/// were published, which is the earliest point the figures were knowable.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct EarningsEntry {
     pub fiscal_year: i32,
     pub fiscal_quarter: Option<i32>,
     pub end_date: UtcDateTime,
+    pub report_date: Option<UtcDateTime>,
     pub eps_actual: Option<f64>,
     pub eps_estimate: Option<f64>,
     pub revenue_actual: Option<f64>,
@@ -296,6 +369,7 @@ impl EarningsEntry {
             fiscal_year,
             fiscal_quarter,
             end_date,
+            report_date: None,
             eps_actual,
             eps_estimate,
             revenue_actual,
@@ -303,6 +377,11 @@ impl EarningsEntry {
             surprise_percent,
         })
     }
+
+    pub fn with_report_date(mut self, report_date: UtcDateTime) -> Self {
+        self.report_date = Some(report_date);
+        self
+    }
 }
 
 /// Earnings report for a symbol.
@@ -375,6 +454,255 @@ impl CorporateAction {
     }
 }
 
+/// Single trade print.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TradeTick {
+    pub ts: UtcDateTime,
+    pub price: f64,
+    pub size: u64,
+    pub exchange: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conditions: Vec<String>,
+}
+
+impl TradeTick {
+    pub fn new(
+        ts: UtcDateTime,
+        price: f64,
+        size: u64,
+        exchange: Option<String>,
+        conditions: Vec<String>,
+    ) -> Result<Self, ValidationError> {
+        validate_non_negative("price", price)?;
+
+        Ok(Self {
+            ts,
+            price,
+            size,
+            exchange,
+            conditions,
+        })
+    }
+}
+
+/// Top-of-book (NBBO) quote update.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuoteTick {
+    pub ts: UtcDateTime,
+    pub bid: f64,
+    pub bid_size: u64,
+    pub ask: f64,
+    pub ask_size: u64,
+    pub bid_exchange: Option<String>,
+    pub ask_exchange: Option<String>,
+}
+
+impl QuoteTick {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        ts: UtcDateTime,
+        bid: f64,
+        bid_size: u64,
+        ask: f64,
+        ask_size: u64,
+        bid_exchange: Option<String>,
+        ask_exchange: Option<String>,
+    ) -> Result<Self, ValidationError> {
+        validate_non_negative("bid", bid)?;
+        validate_non_negative("ask", ask)?;
+
+        Ok(Self {
+            ts,
+            bid,
+            bid_size,
+            ask,
+            ask_size,
+            bid_exchange,
+            ask_exchange,
+        })
+    }
+
+    /// Midpoint between bid and ask.
+    pub fn mid(&self) -> f64 {
+        (self.bid + self.ask) / 2.0
+    }
+}
+
+/// Publication frequency of an economic series.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SeriesFrequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Quarterly,
+    Semiannual,
+    Annual,
+}
+
+impl SeriesFrequency {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Daily => "daily",
+            Self::Weekly => "weekly",
+            Self::Monthly => "monthly",
+            Self::Quarterly => "quarterly",
+            Self::Semiannual => "semiannual",
+            Self::Annual => "annual",
+        }
+    }
+}
+
+impl std::str::FromStr for SeriesFrequency {
+    type Err = ValidationError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "daily" => Ok(Self::Daily),
+            "weekly" => Ok(Self::Weekly),
+            "monthly" => Ok(Self::Monthly),
+            "quarterly" => Ok(Self::Quarterly),
+            "semiannual" => Ok(Self::Semiannual),
+            "annual" => Ok(Self::Annual),
+            other => Err(ValidationError::InvalidSeriesFrequency {
+                value: other.to_owned(),
+            }),
+        }
+    }
+}
+
+/// Single dated observation of an economic series.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EconomicObservation {
+    pub date: UtcDateTime,
+    pub value: f64,
+}
+
+impl EconomicObservation {
+    pub fn new(date: UtcDateTime, value: f64) -> Result<Self, ValidationError> {
+        validate_optional_finite("value", Some(value))?;
+        Ok(Self { date, value })
+    }
+}
+
+/// Macro or economic time series such as treasury yields or CPI.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EconomicSeries {
+    /// Canonical series identifier, e.g. `TREASURY_YIELD_3MONTH` or `CPI`.
+    pub series_id: String,
+    pub name: Option<String>,
+    /// Unit of observation values, e.g. `percent` or `index 1982-1984=100`.
+    pub unit: String,
+    pub frequency: SeriesFrequency,
+    /// Observations ordered oldest first.
+    pub observations: Vec<EconomicObservation>,
+}
+
+impl EconomicSeries {
+    pub fn new(
+        series_id: impl Into<String>,
+        name: Option<String>,
+        unit: impl Into<String>,
+        frequency: SeriesFrequency,
+        observations: Vec<EconomicObservation>,
+    ) -> Self {
+        Self {
+            series_id: series_id.into(),
+            name,
+            unit: unit.into(),
+            frequency,
+            observations,
+        }
+    }
+}
+
+/// Sentiment of a news item toward one ticker.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TickerSentiment {
+    pub symbol: Symbol,
+    /// Score in `[-1, 1]`; negative is bearish.
+    pub score: Option<f64>,
+    /// How relevant the article is to the ticker, in `[0, 1]`.
+    pub relevance: Option<f64>,
+}
+
+impl TickerSentiment {
+    pub fn new(
+        symbol: Symbol,
+        score: Option<f64>,
+        relevance: Option<f64>,
+    ) -> Result<Self, ValidationError> {
+        validate_optional_finite("score", score)?;
+        validate_optional_non_negative("relevance", relevance)?;
+
+        Ok(Self {
+            symbol,
+            score,
+            relevance,
+        })
+    }
+}
+
+/// News headline with optional sentiment scores.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NewsItem {
+    pub published_at: UtcDateTime,
+    /// Publisher name, e.g. `Reuters`.
+    pub source: String,
+    pub title: String,
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(default)]
+    pub tickers: Vec<Symbol>,
+    /// Overall article sentiment in `[-1, 1]`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sentiment: Option<f64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ticker_sentiment: Vec<TickerSentiment>,
+}
+
+impl NewsItem {
+    pub fn new(
+        published_at: UtcDateTime,
+        source: impl Into<String>,
+        title: impl Into<String>,
+        url: impl Into<String>,
+    ) -> Self {
+        Self {
+            published_at,
+            source: source.into(),
+            title: title.into(),
+            url: url.into(),
+            summary: None,
+            tickers: Vec::new(),
+            sentiment: None,
+            ticker_sentiment: Vec::new(),
+        }
+    }
+
+    pub fn with_summary(mut self, summary: Option<String>) -> Self {
+        self.summary = summary;
+        self
+    }
+
+    pub fn with_tickers(mut self, tickers: Vec<Symbol>) -> Self {
+        self.tickers = tickers;
+        self
+    }
+
+    pub fn with_sentiment(
+        mut self,
+        sentiment: Option<f64>,
+        ticker_sentiment: Vec<TickerSentiment>,
+    ) -> Result<Self, ValidationError> {
+        validate_optional_finite("sentiment", sentiment)?;
+        self.sentiment = sentiment;
+        self.ticker_sentiment = ticker_sentiment;
+        Ok(self)
+    }
+}
+
 /// Validate and normalize currency to uppercase 3-letter code.
 pub fn validate_currency_code(input: &str) -> Result<String, ValidationError> {
     let normalized = input.trim().to_ascii_uppercase();
@@ -14,8 +14,22 @@ pub enum ValidationError {
 
     #[error("invalid interval '{value}', expected one of 1m, 5m, 15m, 1h, 1d")]
     InvalidInterval { value: String },
-    #[error("invalid source '{value}', expected one of yahoo, polygon, alphavantage, alpaca")]
+    #[error(
+        "invalid source '{value}', expected yahoo, polygon, alphavantage, alpaca or a registered provider name"
+    )]
     InvalidSource { value: String },
+    #[error("cannot add provider '{value}': {max} custom providers are already known")]
+    TooManyProviders { value: String, max: usize },
+    #[error("invalid ratio basis '{value}', expected one of annual, quarterly, ttm")]
+    InvalidRatioBasis { value: String },
+    #[error(
+        "invalid tick bar spec '{value}', expected an interval or one of ticks:N, volume:N, dollar:X"
+    )]
+    InvalidTickBarSpec { value: String },
+    #[error(
+        "invalid series frequency '{value}', expected one of daily, weekly, monthly, quarterly, semiannual, annual"
+    )]
+    InvalidSeriesFrequency { value: String },
 
     #[error("timestamp must be RFC3339 UTC (suffix Z): '{value}'")]
     TimestampNotUtc { value: String },
@@ -32,6 +46,8 @@ pub enum ValidationError {
     InvalidBarRange,
     #[error("bar open/close must be within high/low range")]
     InvalidBarBounds,
+    #[error("tick bar threshold must be positive")]
+    InvalidTickBarThreshold,
 
     #[error("request_id must be at least 8 characters")]
     InvalidRequestId,
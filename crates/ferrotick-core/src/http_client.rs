@@ -6,6 +6,8 @@ use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
 
+use crate::context::{Interrupted, RequestContext};
+
 /// Minimal HTTP method set needed by provider adapters.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum HttpMethod {
@@ -560,6 +562,9 @@ fn extract_between<'a>(haystack: &'a str, start: &str, end: &str) -> Option<&'a
 }
 
 /// Production HTTP client using reqwest for real API calls.
+///
+/// Request timeouts are capped to the budget of the current
+/// [`RequestContext`], and in-flight requests are abandoned on cancellation.
 #[derive(Debug, Clone)]
 pub struct ReqwestHttpClient {
     client: Arc<reqwest::Client>,
@@ -607,6 +612,11 @@ impl HttpClient for ReqwestHttpClient {
         request: HttpRequest,
     ) -> Pin<Box<dyn Future<Output = Result<HttpResponse, HttpError>> + Send + 'a>> {
         Box::pin(async move {
+            let context = RequestContext::current();
+            if let Some(interrupted) = context.interrupted() {
+                return Err(interrupted_error(interrupted));
+            }
+
             let mut builder = match request.method {
                 HttpMethod::Get => self.client.get(&request.url),
                 HttpMethod::Post => self.client.post(&request.url),
@@ -617,8 +627,9 @@ impl HttpClient for ReqwestHttpClient {
                 builder = builder.header(name, value);
             }
 
-            // Apply timeout
-            let timeout = std::time::Duration::from_millis(request.timeout_ms);
+            // Apply timeout, trimmed to the remaining request budget
+            let timeout =
+                std::time::Duration::from_millis(context.clamp_timeout_ms(request.timeout_ms));
             builder = builder.timeout(timeout);
 
             // Apply body if present
@@ -627,20 +638,25 @@ impl HttpClient for ReqwestHttpClient {
             }
 
             // Execute request
-            let response = builder.send().await.map_err(|e| {
-                if e.is_timeout() {
-                    HttpError::new(format!("request timeout: {}", e))
-                } else if e.is_connect() {
-                    HttpError::new(format!("connection failed: {}", e))
-                } else {
-                    HttpError::new(format!("request failed: {}", e))
-                }
-            })?;
+            let response = context
+                .guard(builder.send())
+                .await
+                .map_err(interrupted_error)?
+                .map_err(|e| {
+                    if e.is_timeout() {
+                        HttpError::new(format!("request timeout: {}", e))
+                    } else if e.is_connect() {
+                        HttpError::new(format!("connection failed: {}", e))
+                    } else {
+                        HttpError::new(format!("request failed: {}", e))
+                    }
+                })?;
 
             let status = response.status().as_u16();
-            let body = response
-                .text()
+            let body = context
+                .guard(response.text())
                 .await
+                .map_err(interrupted_error)?
                 .map_err(|e| HttpError::new(format!("failed to read response body: {}", e)))?;
 
             Ok(HttpResponse { status, body })
@@ -648,6 +664,13 @@ impl HttpClient for ReqwestHttpClient {
     }
 }
 
+fn interrupted_error(interrupted: Interrupted) -> HttpError {
+    match interrupted {
+        Interrupted::DeadlineExceeded => HttpError::non_retryable("request deadline exceeded"),
+        Interrupted::Cancelled => HttpError::non_retryable("request cancelled"),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -24,13 +24,16 @@
 //! | Module | Description |
 //! |--------|-------------|
 //! | [`adapters`] | Provider adapters (Polygon, Yahoo, Alpha Vantage, Alpaca) |
+//! | [`analytics`] | Financial ratios, growth metrics and tick-bar aggregation |
 //! | [`circuit_breaker`] | Circuit breaker for resilient calls |
+//! | [`context`] | Request deadlines and cancellation |
 //! | [`data_source`] | Data source trait and request/response types |
 //! | [`domain`] | Domain models (Quote, Bar, Fundamental, Instrument) |
 //! | [`envelope`] | Response envelope with metadata |
 //! | [`error`] | Core error types |
 //! | [`http_client`] | HTTP client abstraction |
 //! | [`provider_policy`] | Provider policies for routing |
+//! | [`quota`] | Persistent cross-process request quotas |
 //! | [`routing`] | Source routing and selection |
 //! | [`source`] | Provider identifiers |
 //! | [`throttling`] | Rate limiting support |
@@ -110,15 +113,18 @@
 //! - Input validation on all domain types
 
 pub mod adapters;
+pub mod analytics;
 pub mod assets;
 pub mod cache;
 pub mod circuit_breaker;
+pub mod context;
 pub mod data_source;
 pub mod domain;
 pub mod envelope;
 pub mod error;
 pub mod http_client;
 pub mod provider_policy;
+pub mod quota;
 pub mod retry;
 pub mod routing;
 pub mod source;
@@ -137,22 +143,29 @@ pub use adapters::{AlpacaAdapter, AlphaVantageAdapter, PolygonAdapter, YahooAdap
 // Circuit breaker
 pub use circuit_breaker::{CircuitBreaker, CircuitBreakerConfig, CircuitState};
 
+// Analytics
+pub use analytics::{
+    aggregate_trades, compute_ratios, FinancialMetric, FinancialRatios, RatioBasis, TickBarSeries,
+    TickBarSpec,
+};
+
 // Caching
 pub use cache::{CacheMode, CacheStore};
 
 // Data source trait and types
 pub use data_source::{
-    BarsRequest, CapabilitySet, DataSource, EarningsBatch, EarningsRequest, Endpoint,
-    FinancialsBatch, FinancialsRequest, FundamentalsBatch, FundamentalsRequest, HealthState,
-    HealthStatus, QuoteBatch, QuoteRequest, SearchBatch, SearchRequest, SourceError,
-    SourceErrorKind,
+    BarsRequest, CapabilitySet, DataSource, EarningsBatch, EarningsRequest, EconomicRequest,
+    Endpoint, FinancialsBatch, FinancialsRequest, FundamentalsBatch, FundamentalsRequest,
+    HealthState, HealthStatus, NewsBatch, NewsRequest, QuoteBatch, QuoteRequest, SearchBatch,
+    SearchRequest, SourceError, SourceErrorKind, TickBatch, TickKind, TicksRequest,
 };
 
 // Domain models
 pub use domain::{
     AssetClass, Bar, BarSeries, CorporateAction, CorporateActionType, EarningsEntry,
-    EarningsReport, FinancialLineItem, FinancialPeriod, FinancialStatement, Fundamental,
-    Instrument, Interval, Quote, StatementType, Symbol, UtcDateTime,
+    EarningsReport, EconomicObservation, EconomicSeries, FinancialLineItem, FinancialPeriod,
+    FinancialStatement, Fundamental, Instrument, InstrumentIdentifiers, Interval, NewsItem, Quote,
+    QuoteTick, SeriesFrequency, StatementType, Symbol, TickerSentiment, TradeTick, UtcDateTime,
 };
 
 // Envelope types
@@ -163,8 +176,14 @@ pub use error::{CoreError, ValidationError};
 
 // Warehouse (re-exported from ferrotick-warehouse)
 pub use ferrotick_warehouse::{
-    BarRecord, CacheSyncReport, FundamentalRecord, QueryGuardrails, QueryResult, QuoteRecord,
-    SqlColumn, Warehouse, WarehouseConfig, WarehouseError,
+    AsOfFundamentalValue, BarCoverage, BarLineage, BarRecord, BatchChanges, CacheCompactionReport,
+    CacheRebuildReport, CacheSyncReport, CorporateActionRecord, DailySentiment, DateRange,
+    EarningsRecord, FinancialRecord, FundamentalRecord, IngestBatch, IngestRun, InstrumentRecord,
+    MacroObservationRecord, MigrationDirection, MigrationReport, MigrationStatus, NewsRecord,
+    NewsTickerRecord, PartitionFailure, PointInTimeFundamental, QueryGuardrails, QueryResult,
+    QuoteRecord, QuoteTickRecord, RestoreReport, RetentionPolicy, SandboxRule, SnapshotReport,
+    SqlColumn, SqlSandbox, TradeTickRecord, UniverseMember, UniverseMembership, UniverseSummary,
+    Warehouse, WarehouseConfig, WarehouseError, RISK_FREE_SERIES,
 };
 
 // HTTP client types
@@ -174,8 +193,10 @@ pub use http_client::{
 
 // Provider policies
 pub use provider_policy::{BackoffPolicy, ProviderPolicy};
+pub use quota::{QuotaError, QuotaLimits, QuotaStore, QuotaUsage, QuotaWindow};
 
 // Retry logic
+pub use context::{CancellationToken, Interrupted, RequestContext};
 pub use retry::{Backoff, RetryConfig};
 
 // Routing types
@@ -185,7 +206,7 @@ pub use routing::{
 };
 
 // Source identifiers
-pub use source::ProviderId;
+pub use source::{CustomProvider, ProviderId};
 
 // Throttling
 pub use throttling::ThrottlingQueue;
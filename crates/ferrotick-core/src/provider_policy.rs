@@ -8,6 +8,8 @@ pub struct ProviderPolicy {
     pub max_concurrency: usize,
     pub quota_window: Duration,
     pub quota_limit: u32,
+    /// Requests allowed per UTC day, when the provider caps them.
+    pub daily_limit: Option<u32>,
     pub retry_backoff: BackoffPolicy,
 }
 
@@ -26,6 +28,7 @@ impl ProviderPolicy {
             max_concurrency: 1,
             quota_window: Duration::from_secs(60),
             quota_limit: 5,
+            daily_limit: Some(25),
             retry_backoff: BackoffPolicy {
                 initial_delay: Duration::from_secs(1),
                 max_delay: Duration::from_secs(60),
@@ -41,6 +44,7 @@ impl ProviderPolicy {
             max_concurrency: 10,
             quota_window: Duration::from_secs(60),
             quota_limit: 100,
+            daily_limit: None,
             retry_backoff: BackoffPolicy {
                 initial_delay: Duration::from_millis(500),
                 max_delay: Duration::from_secs(30),
@@ -54,7 +58,7 @@ impl ProviderPolicy {
         match provider_id {
             ProviderId::Alphavantage => Some(Self::alphavantage_default()),
             ProviderId::Alpaca => Some(Self::alpaca_default()),
-            ProviderId::Yahoo | ProviderId::Polygon => None,
+            ProviderId::Yahoo | ProviderId::Polygon | ProviderId::Custom(_) => None,
         }
     }
 }
@@ -71,6 +75,7 @@ mod tests {
         assert_eq!(policy.max_concurrency, 1);
         assert_eq!(policy.quota_window, Duration::from_secs(60));
         assert_eq!(policy.quota_limit, 5);
+        assert_eq!(policy.daily_limit, Some(25));
     }
 
     #[test]
@@ -0,0 +1,440 @@
+//! Persistent request quotas shared across processes.
+//!
+//! [`ThrottlingQueue`](crate::throttling::ThrottlingQueue) only sees the
+//! requests of its own process, so a shell loop of CLI calls can still blow a
+//! provider's free tier. [`QuotaStore`] keeps per-provider counters for the
+//! policy window (typically one minute) and the UTC day in
+//! `FERROTICK_HOME/quota/<provider>.json`, guarded by a lock file.
+//!
+//! The router charges a request before calling the adapter and gives it back
+//! when the adapter answered from its response cache, so only calls that
+//! reach the provider count. Ledger I/O, including waiting for the lock, runs
+//! on the blocking pool.
+
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::provider_policy::ProviderPolicy;
+use crate::{ProviderId, UtcDateTime};
+
+const SECONDS_PER_DAY: i64 = 86_400;
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(5);
+const LOCK_TIMEOUT: Duration = Duration::from_secs(2);
+/// Lock files older than this are left over from a crashed process.
+const STALE_LOCK_AGE: Duration = Duration::from_secs(10);
+
+/// Request budget of a provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuotaLimits {
+    pub window: Duration,
+    pub window_limit: u32,
+    pub daily_limit: Option<u32>,
+}
+
+impl QuotaLimits {
+    pub fn from_policy(policy: &ProviderPolicy) -> Self {
+        Self {
+            window: policy.quota_window,
+            window_limit: policy.quota_limit,
+            daily_limit: policy.daily_limit,
+        }
+    }
+}
+
+/// Remaining budget of a provider and when each window resets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct QuotaUsage {
+    pub window_remaining: u32,
+    /// `None` until the first request of the current window.
+    pub window_resets_at: Option<UtcDateTime>,
+    pub daily_remaining: Option<u32>,
+    pub daily_resets_at: Option<UtcDateTime>,
+}
+
+impl QuotaUsage {
+    pub fn is_exhausted(&self) -> bool {
+        self.window_remaining == 0 || self.daily_remaining == Some(0)
+    }
+}
+
+/// Quota window that ran out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaWindow {
+    Window,
+    Daily,
+}
+
+impl QuotaWindow {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Window => "per-window",
+            Self::Daily => "daily",
+        }
+    }
+}
+
+/// Errors raised by [`QuotaStore`].
+#[derive(Debug, Error)]
+pub enum QuotaError {
+    #[error("{} quota exhausted; resets at {resets_at}", window.as_str())]
+    Exhausted {
+        window: QuotaWindow,
+        resets_at: UtcDateTime,
+    },
+
+    #[error("quota ledger error: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// Counters persisted for one provider.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+struct QuotaState {
+    /// Unix seconds at which the current window started.
+    window_started: i64,
+    window_count: u32,
+    /// Days since the Unix epoch (UTC).
+    day: i64,
+    day_count: u32,
+}
+
+impl QuotaState {
+    /// Reset counters whose window has elapsed at `now`.
+    fn roll(mut self, limits: &QuotaLimits, now: i64) -> Self {
+        if now >= self.window_started + window_seconds(limits) {
+            self.window_started = now;
+            self.window_count = 0;
+        }
+        let today = now.div_euclid(SECONDS_PER_DAY);
+        if self.day != today {
+            self.day = today;
+            self.day_count = 0;
+        }
+        self
+    }
+
+    fn usage(&self, limits: &QuotaLimits) -> QuotaUsage {
+        let window_resets_at = (self.window_count > 0)
+            .then(|| unix_to_utc(self.window_started + window_seconds(limits)));
+        QuotaUsage {
+            window_remaining: limits.window_limit.saturating_sub(self.window_count),
+            window_resets_at,
+            daily_remaining: limits
+                .daily_limit
+                .map(|limit| limit.saturating_sub(self.day_count)),
+            daily_resets_at: limits
+                .daily_limit
+                .map(|_| unix_to_utc((self.day + 1) * SECONDS_PER_DAY)),
+        }
+    }
+}
+
+/// File-backed quota ledger shared by every ferrotick process.
+#[derive(Debug, Clone)]
+pub struct QuotaStore {
+    root: PathBuf,
+}
+
+impl QuotaStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Ledger under `FERROTICK_HOME/quota` (default `~/.ferrotick/quota`).
+    pub fn open_default() -> Self {
+        Self::new(resolve_ferrotick_home().join("quota"))
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Record one request against `provider`, or fail if a window is spent.
+    pub fn try_acquire(
+        &self,
+        provider: ProviderId,
+        limits: &QuotaLimits,
+    ) -> Result<QuotaUsage, QuotaError> {
+        self.try_acquire_at(provider, limits, now_unix())
+    }
+
+    /// [`Self::try_acquire`] on the blocking pool, for async callers.
+    pub async fn acquire(
+        &self,
+        provider: ProviderId,
+        limits: &QuotaLimits,
+    ) -> Result<QuotaUsage, QuotaError> {
+        let store = self.clone();
+        let limits = *limits;
+        tokio::task::spawn_blocking(move || store.try_acquire(provider, &limits))
+            .await
+            .map_err(io::Error::other)?
+    }
+
+    /// Give back a request recorded by [`Self::acquire`] that never reached
+    /// the provider. A window that has rolled over since is left alone.
+    pub async fn release(
+        &self,
+        provider: ProviderId,
+        limits: &QuotaLimits,
+    ) -> Result<QuotaUsage, QuotaError> {
+        let store = self.clone();
+        let limits = *limits;
+        tokio::task::spawn_blocking(move || store.release_at(provider, &limits, now_unix()))
+            .await
+            .map_err(io::Error::other)?
+    }
+
+    /// Remaining budget of `provider` without consuming any.
+    pub fn usage(
+        &self,
+        provider: ProviderId,
+        limits: &QuotaLimits,
+    ) -> Result<QuotaUsage, QuotaError> {
+        let state = self.read_state(provider)?.roll(limits, now_unix());
+        Ok(state.usage(limits))
+    }
+
+    fn try_acquire_at(
+        &self,
+        provider: ProviderId,
+        limits: &QuotaLimits,
+        now: i64,
+    ) -> Result<QuotaUsage, QuotaError> {
+        fs::create_dir_all(&self.root)?;
+        let _lock = LockFile::acquire(self.root.join(format!("{}.lock", provider.as_str())))?;
+
+        let mut state = self.read_state(provider)?.roll(limits, now);
+        if state.window_count >= limits.window_limit {
+            return Err(QuotaError::Exhausted {
+                window: QuotaWindow::Window,
+                resets_at: unix_to_utc(state.window_started + window_seconds(limits)),
+            });
+        }
+        if limits
+            .daily_limit
+            .is_some_and(|limit| state.day_count >= limit)
+        {
+            return Err(QuotaError::Exhausted {
+                window: QuotaWindow::Daily,
+                resets_at: unix_to_utc((state.day + 1) * SECONDS_PER_DAY),
+            });
+        }
+
+        state.window_count += 1;
+        state.day_count += 1;
+        self.write_state(provider, &state)?;
+        Ok(state.usage(limits))
+    }
+
+    fn release_at(
+        &self,
+        provider: ProviderId,
+        limits: &QuotaLimits,
+        now: i64,
+    ) -> Result<QuotaUsage, QuotaError> {
+        fs::create_dir_all(&self.root)?;
+        let _lock = LockFile::acquire(self.root.join(format!("{}.lock", provider.as_str())))?;
+
+        let mut state = self.read_state(provider)?.roll(limits, now);
+        state.window_count = state.window_count.saturating_sub(1);
+        state.day_count = state.day_count.saturating_sub(1);
+        self.write_state(provider, &state)?;
+        Ok(state.usage(limits))
+    }
+
+    fn state_path(&self, provider: ProviderId) -> PathBuf {
+        self.root.join(format!("{}.json", provider.as_str()))
+    }
+
+    fn read_state(&self, provider: ProviderId) -> Result<QuotaState, QuotaError> {
+        match fs::read(self.state_path(provider)) {
+            // A corrupt ledger starts over rather than blocking calls; the
+            // next write replaces it.
+            Ok(bytes) => Ok(serde_json::from_slice(&bytes).unwrap_or_else(|error| {
+                tracing::warn!(
+                    provider = provider.as_str(),
+                    %error,
+                    "quota ledger is corrupt; resetting its counters"
+                );
+                QuotaState::default()
+            })),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(QuotaState::default()),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    fn write_state(&self, provider: ProviderId, state: &QuotaState) -> Result<(), QuotaError> {
+        let path = self.state_path(provider);
+        let temp_path = path.with_extension("json.tmp");
+        let bytes = serde_json::to_vec(state).map_err(io::Error::other)?;
+        fs::write(&temp_path, bytes)?;
+        fs::rename(&temp_path, &path)?;
+        Ok(())
+    }
+}
+
+/// Exclusive lock held for as long as the lock file exists.
+///
+/// Waiting for it sleeps the thread, so async callers go through
+/// [`QuotaStore::acquire`] and [`QuotaStore::release`].
+struct LockFile {
+    path: PathBuf,
+}
+
+impl LockFile {
+    fn acquire(path: PathBuf) -> io::Result<Self> {
+        let started = SystemTime::now();
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(_) => return Ok(Self { path }),
+                Err(error) if error.kind() == ErrorKind::AlreadyExists => {
+                    if is_stale(&path) {
+                        let _ = fs::remove_file(&path);
+                        continue;
+                    }
+                    if started.elapsed().unwrap_or_default() > LOCK_TIMEOUT {
+                        return Err(io::Error::new(
+                            ErrorKind::TimedOut,
+                            format!("timed out waiting for {}", path.display()),
+                        ));
+                    }
+                    thread::sleep(LOCK_RETRY_INTERVAL);
+                }
+                Err(error) => return Err(error),
+            }
+        }
+    }
+}
+
+impl Drop for LockFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn is_stale(path: &Path) -> bool {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|age| age > STALE_LOCK_AGE)
+}
+
+fn window_seconds(limits: &QuotaLimits) -> i64 {
+    i64::try_from(limits.window.as_secs().max(1)).unwrap_or(i64::MAX)
+}
+
+fn now_unix() -> i64 {
+    UtcDateTime::now().into_inner().unix_timestamp()
+}
+
+fn unix_to_utc(seconds: i64) -> UtcDateTime {
+    UtcDateTime::from_unix_timestamp(seconds).unwrap_or_else(|_| UtcDateTime::now())
+}
+
+/// Resolve the ferrotick home directory from environment or default.
+fn resolve_ferrotick_home() -> PathBuf {
+    if let Some(path) = env::var_os("FERROTICK_HOME") {
+        let path = PathBuf::from(path);
+        if !path.as_os_str().is_empty() {
+            return path;
+        }
+    }
+
+    if let Some(home) = env::var_os("HOME") {
+        return PathBuf::from(home).join(".ferrotick");
+    }
+
+    PathBuf::from(".ferrotick")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits() -> QuotaLimits {
+        QuotaLimits {
+            window: Duration::from_secs(60),
+            window_limit: 2,
+            daily_limit: Some(3),
+        }
+    }
+
+    #[test]
+    fn window_and_daily_limits_are_shared_through_the_ledger() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let provider = ProviderId::Alphavantage;
+        // 2024-01-02T00:00:00Z
+        let start = 1_704_153_600;
+
+        let first = QuotaStore::new(temp.path());
+        first
+            .try_acquire_at(provider, &limits(), start)
+            .expect("first request fits");
+        // A second store stands in for another process reading the same files.
+        let second = QuotaStore::new(temp.path());
+        let usage = second
+            .try_acquire_at(provider, &limits(), start + 1)
+            .expect("second request fits");
+        assert_eq!(usage.window_remaining, 0);
+        assert_eq!(usage.daily_remaining, Some(1));
+
+        let error = second
+            .try_acquire_at(provider, &limits(), start + 2)
+            .expect_err("window is exhausted");
+        assert!(matches!(
+            error,
+            QuotaError::Exhausted {
+                window: QuotaWindow::Window,
+                ..
+            }
+        ));
+
+        first
+            .try_acquire_at(provider, &limits(), start + 60)
+            .expect("window reset");
+        let error = first
+            .try_acquire_at(provider, &limits(), start + 61)
+            .expect_err("day is exhausted");
+        let QuotaError::Exhausted { window, resets_at } = error else {
+            panic!("expected exhausted quota");
+        };
+        assert_eq!(window, QuotaWindow::Daily);
+        assert_eq!(resets_at.format_rfc3339(), "2024-01-03T00:00:00Z");
+
+        first
+            .try_acquire_at(provider, &limits(), start + SECONDS_PER_DAY)
+            .expect("next day has a fresh budget");
+        assert!(!temp.path().join("alphavantage.lock").exists());
+    }
+
+    #[test]
+    fn released_requests_return_to_the_budget_and_corrupt_ledgers_reset() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let provider = ProviderId::Alphavantage;
+        let start = 1_704_153_600;
+        let store = QuotaStore::new(temp.path());
+
+        store
+            .try_acquire_at(provider, &limits(), start)
+            .expect("request fits");
+        let usage = store
+            .release_at(provider, &limits(), start + 1)
+            .expect("release");
+        assert_eq!(usage.window_remaining, 2);
+        assert_eq!(usage.daily_remaining, Some(3));
+
+        fs::write(temp.path().join("alphavantage.json"), b"{not json").expect("corrupt");
+        let usage = store
+            .try_acquire_at(provider, &limits(), start + 2)
+            .expect("corrupt ledger starts over");
+        assert_eq!(usage.window_remaining, 1);
+    }
+}
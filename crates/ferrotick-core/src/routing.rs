@@ -1,17 +1,29 @@
-use std::collections::{HashMap, HashSet};
+use std::any::Any;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::env;
+use std::fmt::Debug;
 use std::future::Future;
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
+use ferrotick_telemetry::metrics;
+use tokio::sync::OnceCell;
+use tracing::field::Empty;
+use tracing::Instrument;
+
 use crate::adapters::{AlpacaAdapter, AlphaVantageAdapter, PolygonAdapter, YahooAdapter};
+use crate::cache::served_from_cache;
+use crate::context::{Interrupted, RequestContext};
 use crate::data_source::{
-    BarsRequest, CapabilitySet, DataSource, Endpoint, FundamentalsBatch, FundamentalsRequest,
-    HealthState, HealthStatus, QuoteBatch, QuoteRequest, SearchBatch, SearchRequest, SourceError,
+    BarsRequest, CapabilitySet, DataSource, EarningsRequest, EconomicRequest, Endpoint,
+    FinancialsRequest, FundamentalsBatch, FundamentalsRequest, HealthState, HealthStatus,
+    NewsRequest, QuoteBatch, QuoteRequest, SearchBatch, SearchRequest, SourceError, TicksRequest,
 };
 use crate::http_client::{HttpAuth, ReqwestHttpClient};
-use crate::{BarSeries, EnvelopeError, ProviderId};
+use crate::provider_policy::ProviderPolicy;
+use crate::quota::{QuotaError, QuotaLimits, QuotaStore, QuotaUsage};
+use crate::{BarSeries, EnvelopeError, ProviderId, Symbol, UtcDateTime};
 
 /// Source selection strategy for routing.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -55,6 +67,8 @@ pub struct SourceSnapshot {
     pub id: ProviderId,
     pub capabilities: CapabilitySet,
     pub health: HealthStatus,
+    /// Remaining persisted quota, for providers with a rate policy.
+    pub quota: Option<QuotaUsage>,
 }
 
 impl SourceSnapshot {
@@ -63,7 +77,8 @@ impl SourceSnapshot {
     }
 
     pub fn status_label(self) -> &'static str {
-        if !self.health.rate_available {
+        let quota_exhausted = self.quota.is_some_and(|quota| quota.is_exhausted());
+        if !self.health.rate_available || quota_exhausted {
             return "rate_limited";
         }
 
@@ -76,12 +91,167 @@ impl SourceSnapshot {
 }
 
 /// Adapter registry and routing engine.
+///
+/// Any [`DataSource`] can be registered, including third-party adapters with
+/// a [`ProviderId::Custom`] id; built-in providers get their default
+/// [`ProviderPolicy`] unless one is supplied.
+///
+/// Concurrent equivalent calls (same endpoint, strategy and normalized
+/// request) are coalesced into a single upstream route whose result every caller shares.
 pub struct SourceRouter {
     adapters: HashMap<ProviderId, Arc<dyn DataSource>>,
+    policies: HashMap<ProviderId, ProviderPolicy>,
+    in_flight: InFlightRoutes,
+    quota: Option<QuotaStore>,
 }
 
 type InvokeFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, SourceError>> + Send + 'a>>;
 
+type Flight<T> = OnceCell<RouteResult<T>>;
+
+/// Whether a route stopped on a deadline or cancellation rather than on the
+/// sources themselves.
+fn was_interrupted(failure: &RouteFailure) -> bool {
+    failure.errors.iter().any(|error| {
+        matches!(
+            error.code.as_str(),
+            "source.deadline_exceeded" | "source.cancelled"
+        )
+    })
+}
+
+/// Canonical form of a request, so equivalent requests share one flight.
+trait FlightKey {
+    fn flight_key(&self) -> String;
+}
+
+/// Sorted, deduplicated symbols of a multi-symbol request.
+fn symbol_set(symbols: &[Symbol]) -> String {
+    symbols
+        .iter()
+        .map(Symbol::as_str)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn optional_time(value: Option<&UtcDateTime>) -> String {
+    value.map(ToString::to_string).unwrap_or_default()
+}
+
+impl FlightKey for QuoteRequest {
+    fn flight_key(&self) -> String {
+        symbol_set(&self.symbols)
+    }
+}
+
+impl FlightKey for BarsRequest {
+    fn flight_key(&self) -> String {
+        format!("{}|{}|{}", self.symbol, self.interval, self.limit)
+    }
+}
+
+impl FlightKey for FundamentalsRequest {
+    fn flight_key(&self) -> String {
+        symbol_set(&self.symbols)
+    }
+}
+
+impl FlightKey for SearchRequest {
+    fn flight_key(&self) -> String {
+        format!("{}|{}", self.query.trim(), self.limit)
+    }
+}
+
+impl FlightKey for FinancialsRequest {
+    fn flight_key(&self) -> String {
+        format!(
+            "{}|{:?}|{:?}|{}",
+            self.symbol, self.statement_type, self.period, self.limit
+        )
+    }
+}
+
+impl FlightKey for EarningsRequest {
+    fn flight_key(&self) -> String {
+        format!("{}|{}", self.symbol, self.limit)
+    }
+}
+
+impl FlightKey for TicksRequest {
+    fn flight_key(&self) -> String {
+        format!(
+            "{}|{:?}|{}|{}|{}",
+            self.symbol,
+            self.kind,
+            optional_time(self.start.as_ref()),
+            optional_time(self.end.as_ref()),
+            self.limit
+        )
+    }
+}
+
+impl FlightKey for EconomicRequest {
+    fn flight_key(&self) -> String {
+        format!("{}|{:?}|{}", self.series_id, self.frequency, self.limit)
+    }
+}
+
+impl FlightKey for NewsRequest {
+    fn flight_key(&self) -> String {
+        format!(
+            "{}|{}|{}",
+            symbol_set(&self.symbols),
+            optional_time(self.since.as_ref()),
+            self.limit
+        )
+    }
+}
+
+/// Routes currently being resolved, keyed by endpoint, strategy and the
+/// request's [`FlightKey`].
+#[derive(Default)]
+struct InFlightRoutes {
+    flights: Mutex<HashMap<String, Arc<dyn Any + Send + Sync>>>,
+}
+
+impl InFlightRoutes {
+    /// Join the flight for `key`, starting a new one if none is in progress.
+    fn join<T>(&self, key: &str) -> Arc<Flight<T>>
+    where
+        T: Send + Sync + 'static,
+    {
+        let mut flights = self.flights.lock().expect("in-flight map is not poisoned");
+        if let Some(existing) = flights.get(key) {
+            if let Ok(flight) = Arc::clone(existing).downcast::<Flight<T>>() {
+                return flight;
+            }
+        }
+
+        let flight = Arc::new(Flight::<T>::new());
+        flights.insert(key.to_string(), flight.clone());
+        flight
+    }
+
+    /// Forget a finished flight so later calls hit the sources again.
+    fn complete<T>(&self, key: &str, flight: &Arc<Flight<T>>)
+    where
+        T: Send + Sync + 'static,
+    {
+        let mut flights = self.flights.lock().expect("in-flight map is not poisoned");
+        let is_current = flights.get(key).is_some_and(|current| {
+            std::ptr::eq(
+                Arc::as_ptr(current).cast::<()>(),
+                Arc::as_ptr(flight).cast::<()>(),
+            )
+        });
+        if is_current {
+            flights.remove(key);
+        }
+    }
+}
+
 /// Builder for creating a SourceRouter with real HTTP clients.
 ///
 /// This builder reads API keys from environment variables and creates
@@ -107,7 +277,7 @@ type InvokeFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, SourceError>> +
 ///     .with_real_clients()
 ///     .build();
 /// ```
-#[derive(Debug, Default)]
+#[derive(Default)]
 pub struct SourceRouterBuilder {
     polygon_api_key: Option<String>,
     alpaca_api_key: Option<String>,
@@ -117,6 +287,26 @@ pub struct SourceRouterBuilder {
     enable_alpaca: bool,
     enable_alphavantage: bool,
     enable_yahoo: bool,
+    quota_store: Option<QuotaStore>,
+    custom_providers: Vec<(Arc<dyn DataSource>, Option<ProviderPolicy>)>,
+}
+
+impl Debug for SourceRouterBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let custom_providers = self
+            .custom_providers
+            .iter()
+            .map(|(adapter, _)| adapter.id())
+            .collect::<Vec<_>>();
+        f.debug_struct("SourceRouterBuilder")
+            .field("enable_polygon", &self.enable_polygon)
+            .field("enable_alpaca", &self.enable_alpaca)
+            .field("enable_alphavantage", &self.enable_alphavantage)
+            .field("enable_yahoo", &self.enable_yahoo)
+            .field("quota_store", &self.quota_store)
+            .field("custom_providers", &custom_providers)
+            .finish_non_exhaustive()
+    }
 }
 
 impl SourceRouterBuilder {
@@ -131,14 +321,18 @@ impl SourceRouterBuilder {
             enable_alpaca: true,
             enable_alphavantage: true,
             enable_yahoo: true,
+            quota_store: None,
+            custom_providers: Vec::new(),
         }
     }
 
     /// Configure adapters to use real HTTP clients.
     ///
     /// Reads API keys from environment variables. Providers without API keys
-    /// will be disabled (except Yahoo which doesn't need a key).
+    /// will be disabled (except Yahoo which doesn't need a key). Quotas are
+    /// tracked in the shared ledger under `FERROTICK_HOME`.
     pub fn with_real_clients(mut self) -> Self {
+        self.quota_store = Some(QuotaStore::open_default());
         self.polygon_api_key = env::var("FERROTICK_POLYGON_API_KEY")
             .or_else(|_| env::var("POLYGON_API_KEY"))
             .ok();
@@ -177,6 +371,12 @@ impl SourceRouterBuilder {
         self
     }
 
+    /// Track provider quotas in the given ledger.
+    pub fn with_quota_store(mut self, store: QuotaStore) -> Self {
+        self.quota_store = Some(store);
+        self
+    }
+
     /// Enable or disable the Polygon adapter.
     pub fn with_polygon_enabled(mut self, enabled: bool) -> Self {
         self.enable_polygon = enabled;
@@ -201,6 +401,31 @@ impl SourceRouterBuilder {
         self
     }
 
+    /// Register an additional adapter, such as a third-party plugin.
+    ///
+    /// An adapter whose id matches a built-in provider replaces it.
+    pub fn with_provider(self, adapter: Arc<dyn DataSource>) -> Self {
+        self.register_provider(adapter, None)
+    }
+
+    /// Register an additional adapter whose quota is governed by `policy`.
+    pub fn with_provider_policy(
+        self,
+        adapter: Arc<dyn DataSource>,
+        policy: ProviderPolicy,
+    ) -> Self {
+        self.register_provider(adapter, Some(policy))
+    }
+
+    fn register_provider(
+        mut self,
+        adapter: Arc<dyn DataSource>,
+        policy: Option<ProviderPolicy>,
+    ) -> Self {
+        self.custom_providers.push((adapter, policy));
+        self
+    }
+
     /// Build the SourceRouter with the configured adapters.
     pub fn build(self) -> SourceRouter {
         let mut adapters: Vec<Arc<dyn DataSource>> = Vec::new();
@@ -253,7 +478,7 @@ impl SourceRouterBuilder {
             )));
         }
 
-        if adapters.is_empty() {
+        if adapters.is_empty() && self.custom_providers.is_empty() {
             // At minimum, always enable Yahoo (no API key required)
             let http_client = Arc::new(ReqwestHttpClient::new());
             adapters.push(Arc::new(YahooAdapter::with_http_client(
@@ -263,17 +488,75 @@ impl SourceRouterBuilder {
             )));
         }
 
-        SourceRouter::new(adapters)
+        let mut router = SourceRouter::new(adapters);
+        for (adapter, policy) in self.custom_providers {
+            router = match policy {
+                Some(policy) => router.with_provider_policy(adapter, policy),
+                None => router.with_provider(adapter),
+            };
+        }
+        match self.quota_store {
+            Some(store) => router.with_quota_store(store),
+            None => router,
+        }
     }
 }
 
 impl SourceRouter {
     pub fn new(adapters: Vec<Arc<dyn DataSource>>) -> Self {
-        let adapters = adapters
-            .into_iter()
-            .map(|adapter| (adapter.id(), adapter))
-            .collect();
-        Self { adapters }
+        let router = Self {
+            adapters: HashMap::new(),
+            policies: HashMap::new(),
+            in_flight: InFlightRoutes::default(),
+            quota: None,
+        };
+        adapters.into_iter().fold(router, Self::with_provider)
+    }
+
+    /// Register `adapter` under its own id, replacing any adapter with that id.
+    ///
+    /// Built-in providers keep their default [`ProviderPolicy`]; custom
+    /// providers run without one unless registered with
+    /// [`Self::with_provider_policy`].
+    pub fn with_provider(mut self, adapter: Arc<dyn DataSource>) -> Self {
+        let provider = adapter.id();
+        match ProviderPolicy::default_for(provider) {
+            Some(policy) => self.policies.insert(provider, policy),
+            None => self.policies.remove(&provider),
+        };
+        self.adapters.insert(provider, adapter);
+        self
+    }
+
+    /// Register `adapter` with the quota policy it should be routed under.
+    pub fn with_provider_policy(
+        mut self,
+        adapter: Arc<dyn DataSource>,
+        policy: ProviderPolicy,
+    ) -> Self {
+        let provider = adapter.id();
+        self.policies.insert(provider, policy);
+        self.adapters.insert(provider, adapter);
+        self
+    }
+
+    /// Registered providers, sorted by name.
+    pub fn providers(&self) -> Vec<ProviderId> {
+        self.sorted_registered_sources()
+    }
+
+    /// Policy the router applies to `provider`, if any.
+    pub fn policy(&self, provider: ProviderId) -> Option<&ProviderPolicy> {
+        self.policies.get(&provider)
+    }
+
+    /// Enforce provider quotas persisted in `store` before each upstream call.
+    ///
+    /// Providers whose per-window or daily budget is spent are skipped with a
+    /// `RateLimited` error, even when another process used up the budget.
+    pub fn with_quota_store(mut self, store: QuotaStore) -> Self {
+        self.quota = Some(store);
+        self
     }
 
     pub async fn source_chain_for_strategy(
@@ -294,6 +577,7 @@ impl SourceRouter {
             id: provider,
             capabilities: adapter.capabilities(),
             health: adapter.health().await,
+            quota: self.quota_usage(provider),
         })
     }
 
@@ -302,9 +586,9 @@ impl SourceRouter {
         req: &QuoteRequest,
         strategy: SourceStrategy,
     ) -> RouteResult<QuoteBatch> {
-        let req = req.clone();
-        self.route_endpoint(Endpoint::Quote, strategy, move |source| {
-            source.quote(req.clone())
+        let request = req.clone();
+        self.route_coalesced(Endpoint::Quote, strategy, req, move |source| {
+            source.quote(request.clone())
         })
         .await
     }
@@ -314,9 +598,9 @@ impl SourceRouter {
         req: &BarsRequest,
         strategy: SourceStrategy,
     ) -> RouteResult<BarSeries> {
-        let req = req.clone();
-        self.route_endpoint(Endpoint::Bars, strategy, move |source| {
-            source.bars(req.clone())
+        let request = req.clone();
+        self.route_coalesced(Endpoint::Bars, strategy, req, move |source| {
+            source.bars(request.clone())
         })
         .await
     }
@@ -326,9 +610,9 @@ impl SourceRouter {
         req: &FundamentalsRequest,
         strategy: SourceStrategy,
     ) -> RouteResult<FundamentalsBatch> {
-        let req = req.clone();
-        self.route_endpoint(Endpoint::Fundamentals, strategy, move |source| {
-            source.fundamentals(req.clone())
+        let request = req.clone();
+        self.route_coalesced(Endpoint::Fundamentals, strategy, req, move |source| {
+            source.fundamentals(request.clone())
         })
         .await
     }
@@ -338,37 +622,143 @@ impl SourceRouter {
         req: &SearchRequest,
         strategy: SourceStrategy,
     ) -> RouteResult<SearchBatch> {
-        let req = req.clone();
-        self.route_endpoint(Endpoint::Search, strategy, move |source| {
-            source.search(req.clone())
+        let request = req.clone();
+        self.route_coalesced(Endpoint::Search, strategy, req, move |source| {
+            source.search(request.clone())
         })
         .await
     }
 
     pub async fn route_financials(
         &self,
-        req: &crate::data_source::FinancialsRequest,
+        req: &FinancialsRequest,
         strategy: SourceStrategy,
     ) -> RouteResult<crate::data_source::FinancialsBatch> {
-        let req = req.clone();
-        self.route_endpoint(Endpoint::Financials, strategy, move |source| {
-            source.financials(req.clone())
+        let request = req.clone();
+        self.route_coalesced(Endpoint::Financials, strategy, req, move |source| {
+            source.financials(request.clone())
         })
         .await
     }
 
     pub async fn route_earnings(
         &self,
-        req: &crate::data_source::EarningsRequest,
+        req: &EarningsRequest,
         strategy: SourceStrategy,
     ) -> RouteResult<crate::data_source::EarningsBatch> {
-        let req = req.clone();
-        self.route_endpoint(Endpoint::Earnings, strategy, move |source| {
-            source.earnings(req.clone())
+        let request = req.clone();
+        self.route_coalesced(Endpoint::Earnings, strategy, req, move |source| {
+            source.earnings(request.clone())
+        })
+        .await
+    }
+
+    pub async fn route_ticks(
+        &self,
+        req: &TicksRequest,
+        strategy: SourceStrategy,
+    ) -> RouteResult<crate::data_source::TickBatch> {
+        let request = req.clone();
+        self.route_coalesced(Endpoint::Ticks, strategy, req, move |source| {
+            source.ticks(request.clone())
         })
         .await
     }
 
+    pub async fn route_economic(
+        &self,
+        req: &EconomicRequest,
+        strategy: SourceStrategy,
+    ) -> RouteResult<crate::EconomicSeries> {
+        let request = req.clone();
+        self.route_coalesced(Endpoint::Economic, strategy, req, move |source| {
+            source.economic(request.clone())
+        })
+        .await
+    }
+
+    pub async fn route_news(
+        &self,
+        req: &NewsRequest,
+        strategy: SourceStrategy,
+    ) -> RouteResult<crate::data_source::NewsBatch> {
+        let request = req.clone();
+        self.route_coalesced(Endpoint::News, strategy, req, move |source| {
+            source.news(request.clone())
+        })
+        .await
+    }
+
+    /// Route through [`Self::route_endpoint`], sharing the upstream call with
+    /// any equivalent route already in flight.
+    ///
+    /// Requests are matched by their [`FlightKey`], so symbol order and
+    /// duplicates do not matter. Every caller receives the same result, in the
+    /// order the leading call produced it; `latency_ms` is the caller's own
+    /// wait time rather than that of the call that did the work.
+    async fn route_coalesced<T, R, F>(
+        &self,
+        endpoint: Endpoint,
+        strategy: SourceStrategy,
+        req: &R,
+        invoke: F,
+    ) -> RouteResult<T>
+    where
+        T: Clone + Send + Sync + 'static,
+        R: FlightKey,
+        F: for<'a> FnMut(&'a dyn DataSource) -> InvokeFuture<'a, T>,
+    {
+        let started = Instant::now();
+        let context = RequestContext::current();
+        let key = format!("{endpoint}|{strategy:?}|{}", req.flight_key());
+        let mut invoke = invoke;
+        let result = loop {
+            let flight = self.in_flight.join::<T>(&key);
+            // Followers wait on someone else's call, so bound the wait by their
+            // own deadline as well.
+            let shared = context
+                .guard(flight.get_or_init(|| {
+                    let span = tracing::info_span!(
+                        "route",
+                        endpoint = endpoint.as_str(),
+                        strategy = ?strategy,
+                        selected = Empty,
+                    );
+                    self.route_endpoint(endpoint, strategy.clone(), &mut invoke)
+                        .instrument(span)
+                }))
+                .await;
+            self.in_flight.complete(&key, &flight);
+
+            match shared {
+                // The leader ran out of its own budget; that says nothing
+                // about ours, so route again instead of sharing its failure.
+                Ok(Err(failure)) if was_interrupted(failure) && context.interrupted().is_none() => {
+                    continue;
+                }
+                Ok(result) => break result.clone(),
+                Err(interrupted) => {
+                    let step = format!("{endpoint} request");
+                    break Err(self
+                        .interrupted_failure(endpoint, &strategy, interrupted, &step)
+                        .await);
+                }
+            }
+        };
+
+        let latency_ms = elapsed_ms(started);
+        match result {
+            Ok(success) => Ok(RouteSuccess {
+                latency_ms,
+                ..success
+            }),
+            Err(failure) => Err(RouteFailure {
+                latency_ms,
+                ..failure
+            }),
+        }
+    }
+
     async fn route_endpoint<T, F>(
         &self,
         endpoint: Endpoint,
@@ -379,13 +769,23 @@ impl SourceRouter {
         F: for<'a> FnMut(&'a dyn DataSource) -> InvokeFuture<'a, T>,
     {
         let started = Instant::now();
+        let context = RequestContext::current();
         let planned_chain = self.plan_sources(endpoint, &strategy).await;
         let mut source_chain = Vec::with_capacity(planned_chain.len());
         let mut errors = Vec::new();
+        let mut warnings = Vec::new();
 
         for provider in planned_chain {
             source_chain.push(provider);
+            // No fallback is attempted once the budget is spent.
+            if let Err(error) = context.check(&format!("fallback to '{}'", provider.as_str())) {
+                warnings.push(error.message().to_string());
+                errors.push(to_envelope_error(provider, error));
+                break;
+            }
+
             let Some(adapter) = self.adapters.get(&provider) else {
+                tracing::debug!(provider = provider.as_str(), "skipping unregistered source");
                 errors.push(to_envelope_error(
                     provider,
                     SourceError::adapter_not_registered(provider),
@@ -397,6 +797,10 @@ impl SourceRouter {
             };
 
             if !adapter.capabilities().supports(endpoint) {
+                tracing::debug!(
+                    provider = provider.as_str(),
+                    "skipping source without endpoint"
+                );
                 errors.push(to_envelope_error(
                     provider,
                     SourceError::unsupported_endpoint(endpoint),
@@ -409,6 +813,7 @@ impl SourceRouter {
 
             let health = adapter.health().await;
             if health.state == HealthState::Unhealthy {
+                tracing::debug!(provider = provider.as_str(), "skipping unhealthy source");
                 errors.push(to_envelope_error(
                     provider,
                     SourceError::unavailable("source health check reported unhealthy"),
@@ -420,6 +825,7 @@ impl SourceRouter {
             }
 
             if !health.rate_available {
+                tracing::debug!(provider = provider.as_str(), "skipping rate-limited source");
                 errors.push(to_envelope_error(
                     provider,
                     SourceError::rate_limited("source has no rate budget available"),
@@ -430,9 +836,83 @@ impl SourceRouter {
                 continue;
             }
 
-            match invoke(adapter.as_ref()).await {
+            let charged = match self.acquire_quota(provider).await {
+                Err(QuotaError::Exhausted { window, resets_at }) => {
+                    tracing::debug!(
+                        provider = provider.as_str(),
+                        window = window.as_str(),
+                        "skipping source with exhausted quota"
+                    );
+                    let wait = resets_at.into_inner() - UtcDateTime::now().into_inner();
+                    metrics::RATE_LIMIT_WAITS.observe(
+                        &[("provider", provider.as_str())],
+                        wait.try_into().unwrap_or_default(),
+                    );
+                    warnings.push(format!(
+                        "skipped '{}': {} quota exhausted until {resets_at}",
+                        provider.as_str(),
+                        window.as_str()
+                    ));
+                    errors.push(to_envelope_error(
+                        provider,
+                        SourceError::rate_limited(format!(
+                            "{} quota exhausted; resets at {resets_at}",
+                            window.as_str()
+                        )),
+                    ));
+                    if strategy.is_strict() {
+                        break;
+                    }
+                    continue;
+                }
+                // An unreadable ledger should not take the provider down.
+                Err(error @ QuotaError::Io(_)) => {
+                    warnings.push(format!("'{}' {error}", provider.as_str()));
+                    false
+                }
+                Ok(charged) => charged,
+            };
+
+            let step = format!("{endpoint} call to '{}'", provider.as_str());
+            let call_span = tracing::info_span!(
+                "adapter_call",
+                provider = provider.as_str(),
+                endpoint = endpoint.as_str(),
+                outcome = Empty,
+            );
+            let call_started = Instant::now();
+            let outcome = context
+                .guard(served_from_cache(invoke(adapter.as_ref())).instrument(call_span.clone()))
+                .await;
+            // An answer from the adapter's response cache never reached the
+            // provider, so it does not count against the quota.
+            if charged && outcome.as_ref().is_ok_and(|(_, cached)| *cached) {
+                if let Err(error) = self.release_quota(provider).await {
+                    warnings.push(format!("'{}' {error}", provider.as_str()));
+                }
+            }
+            let outcome = outcome
+                .map(|(outcome, _)| outcome)
+                .map_err(|interrupted| interrupted.into_source_error(&step));
+            let outcome_label = match &outcome {
+                Ok(Ok(_)) => "ok",
+                Ok(Err(error)) | Err(error) => error.code(),
+            };
+            call_span.record("outcome", outcome_label);
+            record_provider_call(provider, endpoint, outcome_label, call_started);
+
+            let outcome = match outcome {
+                Ok(outcome) => outcome,
+                Err(error) => {
+                    warnings.push(error.message().to_string());
+                    errors.push(to_envelope_error(provider, error));
+                    break;
+                }
+            };
+
+            match outcome {
                 Ok(data) => {
-                    let mut warnings = Vec::new();
+                    tracing::Span::current().record("selected", provider.as_str());
                     if !errors.is_empty() {
                         warnings.push(format!(
                             "source fallback succeeded with '{}' after {} failed attempt(s)",
@@ -476,14 +956,63 @@ impl SourceRouter {
             );
         }
 
+        warnings.push(format!("all sources failed for endpoint '{endpoint}'"));
         Err(RouteFailure {
             source_chain,
-            warnings: vec![format!("all sources failed for endpoint '{endpoint}'")],
+            warnings,
             errors,
             latency_ms: elapsed_ms(started),
         })
     }
 
+    async fn interrupted_failure(
+        &self,
+        endpoint: Endpoint,
+        strategy: &SourceStrategy,
+        interrupted: Interrupted,
+        step: &str,
+    ) -> RouteFailure {
+        let error = interrupted.into_source_error(step);
+        RouteFailure {
+            source_chain: self.source_chain_for_strategy(endpoint, strategy).await,
+            warnings: vec![error.message().to_string()],
+            errors: vec![EnvelopeError::new(error.code(), error.message())
+                .expect("code/message are non-empty")
+                .with_retryable(error.retryable())],
+            latency_ms: 0,
+        }
+    }
+
+    /// Charge one request to `provider`'s quota, returning whether one was
+    /// charged.
+    async fn acquire_quota(&self, provider: ProviderId) -> Result<bool, QuotaError> {
+        let (Some(store), Some(policy)) = (&self.quota, self.policy(provider)) else {
+            return Ok(false);
+        };
+        store
+            .acquire(provider, &QuotaLimits::from_policy(policy))
+            .await
+            .map(|_| true)
+    }
+
+    async fn release_quota(&self, provider: ProviderId) -> Result<(), QuotaError> {
+        let (Some(store), Some(policy)) = (&self.quota, self.policy(provider)) else {
+            return Ok(());
+        };
+        store
+            .release(provider, &QuotaLimits::from_policy(policy))
+            .await
+            .map(|_| ())
+    }
+
+    fn quota_usage(&self, provider: ProviderId) -> Option<QuotaUsage> {
+        let store = self.quota.as_ref()?;
+        let policy = self.policy(provider)?;
+        store
+            .usage(provider, &QuotaLimits::from_policy(policy))
+            .ok()
+    }
+
     async fn plan_sources(&self, endpoint: Endpoint, strategy: &SourceStrategy) -> Vec<ProviderId> {
         match strategy {
             SourceStrategy::Auto => self.auto_chain(endpoint).await,
@@ -551,6 +1080,20 @@ fn to_envelope_error(provider: ProviderId, error: SourceError) -> EnvelopeError
         .with_retryable(error.retryable())
 }
 
+fn record_provider_call(provider: ProviderId, endpoint: Endpoint, outcome: &str, started: Instant) {
+    let provider = provider.as_str();
+    let endpoint = endpoint.as_str();
+    metrics::PROVIDER_REQUESTS.increment(&[
+        ("provider", provider),
+        ("endpoint", endpoint),
+        ("outcome", outcome),
+    ]);
+    metrics::PROVIDER_REQUEST_DURATION.observe(
+        &[("provider", provider), ("endpoint", endpoint)],
+        started.elapsed(),
+    );
+}
+
 fn elapsed_ms(started: Instant) -> u64 {
     started.elapsed().as_millis().min(u128::from(u64::MAX)) as u64
 }
@@ -558,12 +1101,19 @@ fn elapsed_ms(started: Instant) -> u64 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cache::CacheStore;
+    use crate::data_source::{
+        EarningsBatch, EarningsRequest, EconomicRequest, FinancialsBatch, FinancialsRequest,
+        NewsBatch, NewsRequest, TickBatch, TicksRequest,
+    };
     use crate::http_client::{HttpClient, HttpError, HttpRequest, HttpResponse, NoopHttpClient};
-    use crate::Symbol;
+    use crate::{EconomicSeries, Symbol};
     use std::future::Future;
     use std::pin::Pin;
+    use std::sync::atomic::{AtomicUsize, Ordering};
     use std::sync::Arc;
     use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+    use std::time::Duration;
 
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     enum QuoteRouteScenario {
@@ -619,6 +1169,125 @@ mod tests {
         }
     }
 
+    /// Answers every Polygon quote after a short delay, counting upstream calls.
+    #[derive(Debug, Default)]
+    struct SlowCountingHttpClient {
+        calls: AtomicUsize,
+    }
+
+    impl HttpClient for SlowCountingHttpClient {
+        fn execute<'a>(
+            &'a self,
+            _request: HttpRequest,
+        ) -> Pin<Box<dyn Future<Output = Result<HttpResponse, HttpError>> + Send + 'a>> {
+            Box::pin(async move {
+                self.calls.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                Ok(HttpResponse::ok_json(
+                    r#"{"status":"OK","results":[{"T":"AAPL","c":187.42,"v":1000,"t":1700000000}]}"#,
+                ))
+            })
+        }
+    }
+
+    /// Third-party adapter that only serves (empty) quotes, looking them up
+    /// in `cache` first when it has one.
+    struct PluginSource {
+        id: ProviderId,
+        cache: Option<CacheStore>,
+    }
+
+    fn unsupported<'a, T: Send + 'a>(
+        endpoint: Endpoint,
+    ) -> Pin<Box<dyn Future<Output = Result<T, SourceError>> + Send + 'a>> {
+        Box::pin(async move { Err(SourceError::unsupported_endpoint(endpoint)) })
+    }
+
+    impl DataSource for PluginSource {
+        fn id(&self) -> ProviderId {
+            self.id
+        }
+
+        fn capabilities(&self) -> CapabilitySet {
+            CapabilitySet::new(true, false, false, false, false, false, false, false, false)
+        }
+
+        fn quote<'a>(
+            &'a self,
+            _req: QuoteRequest,
+        ) -> Pin<Box<dyn Future<Output = Result<QuoteBatch, SourceError>> + Send + 'a>> {
+            Box::pin(async move {
+                if let Some(cache) = &self.cache {
+                    let _ = cache.get("quote").await;
+                }
+                Ok(QuoteBatch { quotes: Vec::new() })
+            })
+        }
+
+        fn bars<'a>(
+            &'a self,
+            _req: BarsRequest,
+        ) -> Pin<Box<dyn Future<Output = Result<BarSeries, SourceError>> + Send + 'a>> {
+            unsupported(Endpoint::Bars)
+        }
+
+        fn fundamentals<'a>(
+            &'a self,
+            _req: FundamentalsRequest,
+        ) -> Pin<Box<dyn Future<Output = Result<FundamentalsBatch, SourceError>> + Send + 'a>>
+        {
+            unsupported(Endpoint::Fundamentals)
+        }
+
+        fn search<'a>(
+            &'a self,
+            _req: SearchRequest,
+        ) -> Pin<Box<dyn Future<Output = Result<SearchBatch, SourceError>> + Send + 'a>> {
+            unsupported(Endpoint::Search)
+        }
+
+        fn financials<'a>(
+            &'a self,
+            _req: FinancialsRequest,
+        ) -> Pin<Box<dyn Future<Output = Result<FinancialsBatch, SourceError>> + Send + 'a>>
+        {
+            unsupported(Endpoint::Financials)
+        }
+
+        fn earnings<'a>(
+            &'a self,
+            _req: EarningsRequest,
+        ) -> Pin<Box<dyn Future<Output = Result<EarningsBatch, SourceError>> + Send + 'a>> {
+            unsupported(Endpoint::Earnings)
+        }
+
+        fn ticks<'a>(
+            &'a self,
+            _req: TicksRequest,
+        ) -> Pin<Box<dyn Future<Output = Result<TickBatch, SourceError>> + Send + 'a>> {
+            unsupported(Endpoint::Ticks)
+        }
+
+        fn economic<'a>(
+            &'a self,
+            _req: EconomicRequest,
+        ) -> Pin<Box<dyn Future<Output = Result<EconomicSeries, SourceError>> + Send + 'a>>
+        {
+            unsupported(Endpoint::Economic)
+        }
+
+        fn news<'a>(
+            &'a self,
+            _req: NewsRequest,
+        ) -> Pin<Box<dyn Future<Output = Result<NewsBatch, SourceError>> + Send + 'a>> {
+            unsupported(Endpoint::News)
+        }
+
+        fn health<'a>(&'a self) -> Pin<Box<dyn Future<Output = HealthStatus> + Send + 'a>> {
+            Box::pin(async { HealthStatus::new(HealthState::Healthy, true, 100) })
+        }
+    }
+
     fn test_router_with_http_client(http_client: Arc<dyn HttpClient>) -> SourceRouter {
         SourceRouter::new(vec![
             Arc::new(PolygonAdapter::with_http_client(
@@ -723,6 +1392,241 @@ mod tests {
         assert_eq!(failure.errors[0].source, Some(ProviderId::Polygon));
     }
 
+    #[test]
+    fn concurrent_identical_routes_share_one_upstream_call() {
+        let http_client = Arc::new(SlowCountingHttpClient::default());
+        let router = test_router_with_http_client(http_client.clone());
+        let aapl = QuoteRequest::new(vec![Symbol::parse("AAPL").expect("valid symbol")])
+            .expect("valid request");
+        let msft = QuoteRequest::new(vec![Symbol::parse("MSFT").expect("valid symbol")])
+            .expect("valid request");
+        let strategy = SourceStrategy::Strict(ProviderId::Polygon);
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .expect("runtime");
+
+        let (first, second, other) = runtime.block_on(async {
+            tokio::join!(
+                router.route_quote(&aapl, strategy.clone()),
+                router.route_quote(&aapl, strategy.clone()),
+                router.route_quote(&msft, strategy.clone()),
+            )
+        });
+
+        assert_eq!(http_client.calls.load(Ordering::SeqCst), 2);
+        let first = first.expect("first caller succeeds");
+        let second = second.expect("second caller succeeds");
+        assert_eq!(first.data, second.data);
+        assert_eq!(first.selected_source, ProviderId::Polygon);
+        assert!(second.latency_ms >= 50);
+        assert!(other.is_ok());
+
+        let flights = router.in_flight.flights.lock().expect("not poisoned");
+        assert!(flights.is_empty(), "finished flights should be forgotten");
+    }
+
+    #[test]
+    fn reordered_and_duplicate_symbols_share_one_flight() {
+        let http_client = Arc::new(SlowCountingHttpClient::default());
+        let router = test_router_with_http_client(http_client.clone());
+        let symbols = |names: &[&str]| {
+            QuoteRequest::new(
+                names
+                    .iter()
+                    .map(|name| Symbol::parse(name).expect("valid symbol"))
+                    .collect(),
+            )
+            .expect("valid request")
+        };
+        let forward = symbols(&["AAPL", "MSFT"]);
+        let reversed = symbols(&["MSFT", "AAPL", "AAPL"]);
+        let strategy = SourceStrategy::Strict(ProviderId::Polygon);
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .expect("runtime");
+
+        let (first, second) = runtime.block_on(async {
+            tokio::join!(
+                router.route_quote(&forward, strategy.clone()),
+                router.route_quote(&reversed, strategy.clone()),
+            )
+        });
+        // Polygon fetches each symbol separately; a second flight would double this.
+        assert_eq!(http_client.calls.load(Ordering::SeqCst), 2);
+        assert_eq!(
+            first.expect("first caller succeeds").data,
+            second.expect("second caller succeeds").data
+        );
+    }
+
+    #[test]
+    fn short_deadline_caller_does_not_fail_unbounded_follower() {
+        let http_client = Arc::new(SlowCountingHttpClient::default());
+        let router = test_router_with_http_client(http_client.clone());
+        let request = QuoteRequest::new(vec![Symbol::parse("AAPL").expect("valid symbol")])
+            .expect("valid request");
+        let strategy = SourceStrategy::Strict(ProviderId::Polygon);
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .expect("runtime");
+
+        let (hurried, patient) = runtime.block_on(async {
+            let context = RequestContext::with_timeout(Duration::from_millis(10));
+            tokio::join!(
+                context.scope(router.route_quote(&request, strategy.clone())),
+                router.route_quote(&request, strategy.clone()),
+            )
+        });
+
+        let failure = hurried.expect_err("short deadline expires");
+        assert_eq!(failure.errors[0].code, "source.deadline_exceeded");
+        let success = patient.expect("unbounded caller still gets quotes");
+        assert_eq!(success.selected_source, ProviderId::Polygon);
+    }
+
+    #[test]
+    fn deadline_stops_the_route_and_names_the_step() {
+        let router = test_router_with_http_client(Arc::new(SlowCountingHttpClient::default()));
+        let request = QuoteRequest::new(vec![Symbol::parse("AAPL").expect("valid symbol")])
+            .expect("valid request");
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .expect("runtime");
+        let context = RequestContext::with_timeout(Duration::from_millis(10));
+
+        let failure = runtime
+            .block_on(context.scope(router.route_quote(
+                &request,
+                SourceStrategy::Priority(vec![ProviderId::Polygon, ProviderId::Yahoo]),
+            )))
+            .expect_err("deadline should interrupt the route");
+
+        // The slow Polygon call is abandoned and Yahoo is never tried.
+        assert_eq!(failure.source_chain, vec![ProviderId::Polygon]);
+        assert_eq!(failure.errors.len(), 1);
+        assert_eq!(failure.errors[0].code, "source.deadline_exceeded");
+        assert_eq!(
+            failure.warnings[0],
+            "deadline exceeded during quote call to 'polygon'"
+        );
+    }
+
+    #[tokio::test]
+    async fn exhausted_persisted_quota_skips_provider() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let store = QuotaStore::new(temp.path());
+        let limits = QuotaLimits::from_policy(&ProviderPolicy::alphavantage_default());
+        // Another process already spent this minute's budget.
+        for _ in 0..limits.window_limit {
+            store
+                .try_acquire(ProviderId::Alphavantage, &limits)
+                .expect("budget available");
+        }
+        let router = test_router().with_quota_store(QuotaStore::new(temp.path()));
+        let request = QuoteRequest::new(vec![Symbol::parse("AAPL").expect("valid symbol")])
+            .expect("valid request");
+
+        let failure = router
+            .route_quote(&request, SourceStrategy::Strict(ProviderId::Alphavantage))
+            .await
+            .expect_err("exhausted provider is skipped");
+
+        assert_eq!(failure.errors.len(), 1);
+        assert_eq!(failure.errors[0].code, "source.rate_limited");
+        assert!(failure.warnings[0].starts_with("skipped 'alphavantage'"));
+
+        let snapshot = router
+            .snapshot(ProviderId::Alphavantage)
+            .await
+            .expect("registered");
+        let quota = snapshot.quota.expect("alphavantage has a quota policy");
+        assert_eq!(quota.window_remaining, 0);
+        assert_eq!(quota.daily_remaining, Some(20));
+        assert_eq!(snapshot.status_label(), "rate_limited");
+    }
+
+    #[tokio::test]
+    async fn custom_provider_is_routed_by_name_under_its_own_policy() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let plugin = ProviderId::new("plugin").expect("valid provider name");
+        let policy = ProviderPolicy {
+            provider_id: plugin,
+            quota_limit: 1,
+            daily_limit: None,
+            ..ProviderPolicy::alpaca_default()
+        };
+        let router = SourceRouterBuilder::new()
+            .with_yahoo_enabled(false)
+            .with_quota_store(QuotaStore::new(temp.path()))
+            .with_provider_policy(
+                Arc::new(PluginSource {
+                    id: plugin,
+                    cache: None,
+                }),
+                policy,
+            )
+            .build();
+        let request = QuoteRequest::new(vec![Symbol::parse("AAPL").expect("valid symbol")])
+            .expect("valid request");
+        let strategy = SourceStrategy::Strict("plugin".parse().expect("valid provider name"));
+
+        assert_eq!(router.providers(), vec![plugin]);
+        let success = router
+            .route_quote(&request, strategy.clone())
+            .await
+            .expect("plugin answers");
+        assert_eq!(success.selected_source, plugin);
+        assert_eq!(success.source_chain, vec![plugin]);
+
+        // The custom policy allows one call per window.
+        let failure = router
+            .route_quote(&request, strategy)
+            .await
+            .expect_err("quota spent");
+        assert_eq!(failure.errors[0].code, "source.rate_limited");
+        assert_eq!(failure.errors[0].source, Some(plugin));
+    }
+
+    #[tokio::test]
+    async fn answers_from_the_adapter_cache_do_not_spend_quota() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let plugin = ProviderId::new("plugin").expect("valid provider name");
+        let policy = ProviderPolicy {
+            provider_id: plugin,
+            quota_limit: 1,
+            daily_limit: None,
+            ..ProviderPolicy::alpaca_default()
+        };
+        let cache = CacheStore::with_default_ttl();
+        cache.put(String::from("quote"), String::new(), None).await;
+        let router = SourceRouterBuilder::new()
+            .with_yahoo_enabled(false)
+            .with_quota_store(QuotaStore::new(temp.path()))
+            .with_provider_policy(
+                Arc::new(PluginSource {
+                    id: plugin,
+                    cache: Some(cache),
+                }),
+                policy,
+            )
+            .build();
+        let request = QuoteRequest::new(vec![Symbol::parse("AAPL").expect("valid symbol")])
+            .expect("valid request");
+
+        for _ in 0..2 {
+            router
+                .route_quote(&request, SourceStrategy::Strict(plugin))
+                .await
+                .expect("cached answers fit the quota");
+        }
+        let snapshot = router.snapshot(plugin).await.expect("registered");
+        assert_eq!(snapshot.quota.expect("plugin quota").window_remaining, 1);
+    }
+
     fn block_on<F>(future: F) -> F::Output
     where
         F: Future,
@@ -1,31 +1,94 @@
+use std::collections::HashSet;
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
+use std::sync::{Mutex, MutexGuard, OnceLock};
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::ValidationError;
 
+const MAX_PROVIDER_NAME_LEN: usize = 32;
+/// Upper bound on distinct custom names, since each one is leaked once.
+const MAX_CUSTOM_PROVIDERS: usize = 64;
+
 /// Canonical provider identifiers used in metadata and envelopes.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
+///
+/// Built-in providers have their own variants; third-party
+/// [`DataSource`](crate::DataSource) implementations register their name
+/// through [`ProviderId::new`], after which [`str::parse`] resolves it too.
+///
+/// Deserializing accepts any valid name without registering it, so stored
+/// envelopes and configs naming a plugin that is not loaded still read back;
+/// the router reports such a provider as not registered when it is used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ProviderId {
     Yahoo,
     Polygon,
     Alphavantage,
     Alpaca,
+    Custom(CustomProvider),
+}
+
+/// Name of a provider registered by a plugin rather than built into ferrotick.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CustomProvider(&'static str);
+
+impl CustomProvider {
+    pub const fn as_str(self) -> &'static str {
+        self.0
+    }
 }
 
 impl ProviderId {
+    /// Built-in providers.
     pub const ALL: [Self; 4] = [Self::Yahoo, Self::Polygon, Self::Alphavantage, Self::Alpaca];
 
+    /// Provider identified by `name`, registering it unless it is built in.
+    ///
+    /// Intended for the fixed name of an adapter; use [`str::parse`] for
+    /// user input, which only accepts built-in or registered names.
+    ///
+    /// Fails if `name` is not a valid provider name (1 to 32 lowercase ASCII
+    /// letters, digits, `_` or `-`, starting with a letter), or if 64
+    /// distinct custom names are already known.
+    pub fn new(name: &str) -> Result<Self, ValidationError> {
+        Self::custom(name, true)
+    }
+
+    /// Built-in provider or custom name, interned and optionally registered.
+    fn custom(name: &str, register: bool) -> Result<Self, ValidationError> {
+        let normalized = name.trim().to_ascii_lowercase();
+        if let Some(builtin) = builtin(&normalized) {
+            return Ok(builtin);
+        }
+        if !is_valid_custom_name(&normalized) {
+            return Err(ValidationError::InvalidSource { value: normalized });
+        }
+        intern(&normalized, register).map(|name| Self::Custom(CustomProvider(name)))
+    }
+
+    /// Whether the router can resolve this provider by name: built in, or
+    /// registered through [`Self::new`].
+    pub fn is_registered(self) -> bool {
+        match self {
+            Self::Custom(provider) => registered(provider.as_str()).is_some(),
+            _ => true,
+        }
+    }
+
     pub const fn as_str(self) -> &'static str {
         match self {
             Self::Yahoo => "yahoo",
             Self::Polygon => "polygon",
             Self::Alphavantage => "alphavantage",
             Self::Alpaca => "alpaca",
+            Self::Custom(provider) => provider.as_str(),
         }
     }
+
+    pub const fn is_builtin(self) -> bool {
+        !matches!(self, Self::Custom(_))
+    }
 }
 
 impl Display for ProviderId {
@@ -38,14 +101,137 @@ impl FromStr for ProviderId {
     type Err = ValidationError;
 
     fn from_str(value: &str) -> Result<Self, Self::Err> {
-        match value.trim().to_ascii_lowercase().as_str() {
-            "yahoo" => Ok(Self::Yahoo),
-            "polygon" => Ok(Self::Polygon),
-            "alphavantage" => Ok(Self::Alphavantage),
-            "alpaca" => Ok(Self::Alpaca),
-            other => Err(ValidationError::InvalidSource {
-                value: other.to_owned(),
-            }),
+        let normalized = value.trim().to_ascii_lowercase();
+        if let Some(builtin) = builtin(&normalized) {
+            return Ok(builtin);
         }
+        registered(&normalized)
+            .map(|name| Self::Custom(CustomProvider(name)))
+            .ok_or(ValidationError::InvalidSource { value: normalized })
+    }
+}
+
+impl Serialize for ProviderId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for ProviderId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Self::custom(&value, false).map_err(serde::de::Error::custom)
+    }
+}
+
+fn builtin(name: &str) -> Option<ProviderId> {
+    match name {
+        "yahoo" => Some(ProviderId::Yahoo),
+        "polygon" => Some(ProviderId::Polygon),
+        "alphavantage" => Some(ProviderId::Alphavantage),
+        "alpaca" => Some(ProviderId::Alpaca),
+        _ => None,
+    }
+}
+
+/// `auto` is reserved for the automatic routing strategy.
+fn is_valid_custom_name(name: &str) -> bool {
+    name != "auto"
+        && name.len() <= MAX_PROVIDER_NAME_LEN
+        && name.starts_with(|ch: char| ch.is_ascii_lowercase())
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || ch == '_' || ch == '-')
+}
+
+/// Custom names seen so far, each leaked once so [`ProviderId`] stays
+/// `Copy`, and the subset registered through [`ProviderId::new`].
+#[derive(Default)]
+struct ProviderNames {
+    interned: HashSet<&'static str>,
+    registered: HashSet<&'static str>,
+}
+
+fn registry() -> MutexGuard<'static, ProviderNames> {
+    static NAMES: OnceLock<Mutex<ProviderNames>> = OnceLock::new();
+
+    NAMES
+        .get_or_init(Default::default)
+        .lock()
+        .expect("provider name table is not poisoned")
+}
+
+fn registered(name: &str) -> Option<&'static str> {
+    registry().registered.get(name).copied()
+}
+
+fn intern(name: &str, register: bool) -> Result<&'static str, ValidationError> {
+    let mut names = registry();
+    let interned = match names.interned.get(name) {
+        Some(existing) => *existing,
+        None if names.interned.len() >= MAX_CUSTOM_PROVIDERS => {
+            return Err(ValidationError::TooManyProviders {
+                value: name.to_string(),
+                max: MAX_CUSTOM_PROVIDERS,
+            });
+        }
+        None => {
+            let leaked: &'static str = Box::leak(name.to_owned().into_boxed_str());
+            names.interned.insert(leaked);
+            leaked
+        }
+    };
+    if register {
+        names.registered.insert(interned);
+    }
+    Ok(interned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_names_round_trip_and_builtins_keep_their_variants() {
+        let custom = ProviderId::new("Mock-Feed").expect("valid provider name");
+        assert_eq!(custom.as_str(), "mock-feed");
+        assert!(!custom.is_builtin());
+        assert_eq!("mock-feed".parse::<ProviderId>(), Ok(custom));
+        assert_eq!(ProviderId::new("yahoo"), Ok(ProviderId::Yahoo));
+
+        let json = serde_json::to_string(&[custom, ProviderId::Polygon]).expect("serialize");
+        assert_eq!(json, r#"["mock-feed","polygon"]"#);
+        let decoded: Vec<ProviderId> = serde_json::from_str(&json).expect("deserialize");
+        assert_eq!(decoded, vec![custom, ProviderId::Polygon]);
+
+        for invalid in ["auto", "", "9lives", "has space", "x".repeat(33).as_str()] {
+            assert!(invalid.parse::<ProviderId>().is_err(), "{invalid:?}");
+            assert!(ProviderId::new(invalid).is_err(), "{invalid:?}");
+        }
+    }
+
+    #[test]
+    fn parsing_does_not_register_unknown_providers() {
+        assert_eq!(
+            "never-registered".parse::<ProviderId>(),
+            Err(ValidationError::InvalidSource {
+                value: "never-registered".to_string()
+            })
+        );
+        assert_eq!(registered("never-registered"), None);
+    }
+
+    #[test]
+    fn deserializing_accepts_unregistered_names_without_registering_them() {
+        let stored: ProviderId =
+            serde_json::from_str(r#""stored-plugin""#).expect("valid name deserializes");
+        assert_eq!(stored.as_str(), "stored-plugin");
+        assert!(!stored.is_registered());
+        assert!("stored-plugin".parse::<ProviderId>().is_err());
+        assert!(serde_json::from_str::<ProviderId>(r#""9lives""#).is_err());
+
+        let registered = ProviderId::new("stored-plugin").expect("valid provider name");
+        assert_eq!(registered, stored);
+        assert!(stored.is_registered());
     }
 }
@@ -3,6 +3,7 @@ use std::num::NonZeroU32;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
+use ferrotick_telemetry::metrics;
 use governor::clock::DefaultClock;
 use governor::state::direct::NotKeyed;
 use governor::state::InMemoryState;
@@ -16,6 +17,8 @@ pub struct ThrottlingQueue {
     limiter: Arc<DirectRateLimiter>,
     pending: Arc<Mutex<VecDeque<PendingRequest>>>,
     retry_backoff: BackoffPolicy,
+    /// Provider label for rate-limit metrics.
+    provider: &'static str,
 }
 
 type DirectRateLimiter = RateLimiter<NotKeyed, InMemoryState, DefaultClock>;
@@ -32,15 +35,19 @@ impl ThrottlingQueue {
             limiter: Arc::new(RateLimiter::direct(quota)),
             pending: Arc::new(Mutex::new(VecDeque::new())),
             retry_backoff,
+            provider: "unknown",
         }
     }
 
     pub fn from_policy(policy: &ProviderPolicy) -> Self {
-        Self::new(
-            policy.quota_window,
-            policy.quota_limit,
-            policy.retry_backoff.clone(),
-        )
+        Self {
+            provider: policy.provider_id.as_str(),
+            ..Self::new(
+                policy.quota_window,
+                policy.quota_limit,
+                policy.retry_backoff.clone(),
+            )
+        }
     }
 
     /// Tries to acquire rate budget. When budget is unavailable the request is buffered
@@ -56,7 +63,9 @@ impl ThrottlingQueue {
             .expect("throttling pending queue should not be poisoned");
         pending.push_back(PendingRequest { retry_count: 0 });
 
-        Err(self.retry_delay(0).unwrap_or(self.retry_backoff.max_delay))
+        let delay = self.retry_delay(0).unwrap_or(self.retry_backoff.max_delay);
+        metrics::RATE_LIMIT_WAITS.observe(&[("provider", self.provider)], delay);
+        Err(delay)
     }
 
     /// Increments retry count for the oldest buffered request and returns its next delay.
@@ -67,6 +76,7 @@ impl ThrottlingQueue {
             .expect("throttling pending queue should not be poisoned");
         let request = pending.front_mut()?;
         request.retry_count = request.retry_count.saturating_add(1);
+        metrics::RETRIES.increment(&[("provider", self.provider), ("reason", "rate_limited")]);
         self.retry_delay(request.retry_count)
     }
 
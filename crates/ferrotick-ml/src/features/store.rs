@@ -2,8 +2,10 @@ use std::fs;
 use std::path::Path;
 
 use duckdb::params;
-use ferrotick_core::{Bar, Symbol, UtcDateTime};
-use ferrotick_warehouse::Warehouse;
+use ferrotick_core::{Bar, Interval, Symbol, UtcDateTime};
+use ferrotick_warehouse::{
+    AsOfFundamentalValue, DailySentiment, PointInTimeFundamental, UniverseMembership, Warehouse,
+};
 use polars::prelude::*;
 
 use crate::features::FeatureRow;
@@ -68,12 +70,23 @@ impl FeatureStore {
         symbol: &Symbol,
         start: Option<UtcDateTime>,
         end: Option<UtcDateTime>,
+    ) -> MlResult<Vec<Bar>> {
+        self.load_bars(symbol, Interval::OneDay, start, end)
+    }
+
+    /// Load stored bars of any interval, oldest first.
+    pub fn load_bars(
+        &self,
+        symbol: &Symbol,
+        interval: Interval,
+        start: Option<UtcDateTime>,
+        end: Option<UtcDateTime>,
     ) -> MlResult<Vec<Bar>> {
         // SECURITY: Use parameterized query instead of string interpolation
         let sql = r#"
             SELECT strftime(ts, '%Y-%m-%dT%H:%M:%SZ') AS ts, open, high, low, close, volume
-            FROM bars_1d
-            WHERE symbol = ? AND (? IS NULL OR ts >= TRY_CAST(? AS TIMESTAMP)) AND (? IS NULL OR ts <= TRY_CAST(? AS TIMESTAMP))
+            FROM bars
+            WHERE symbol = ? AND interval = ? AND (? IS NULL OR ts >= TRY_CAST(? AS TIMESTAMP)) AND (? IS NULL OR ts <= TRY_CAST(? AS TIMESTAMP))
             ORDER BY ts ASC
         "#;
 
@@ -89,7 +102,14 @@ impl FeatureStore {
 
         let rows = stmt
             .query_map(
-                params![symbol.as_str(), &start_str, &start_str, &end_str, &end_str,],
+                params![
+                    symbol.as_str(),
+                    interval.as_str(),
+                    &start_str,
+                    &start_str,
+                    &end_str,
+                    &end_str,
+                ],
                 |row| {
                     Ok((
                         row.get::<_, String>(0)?,
@@ -122,6 +142,76 @@ impl FeatureStore {
         Ok(bars)
     }
 
+    /// Load the membership intervals of a stored universe.
+    pub fn load_universe(&self, universe: &str) -> MlResult<UniverseMembership> {
+        Ok(self.warehouse.universe_membership(universe)?)
+    }
+
+    /// Symbols that were members of `universe` at `as_of`.
+    pub fn universe_members_as_of(
+        &self,
+        universe: &str,
+        as_of: UtcDateTime,
+    ) -> MlResult<Vec<Symbol>> {
+        self.warehouse
+            .universe_members(
+                universe,
+                as_of.format_rfc3339().get(..10).unwrap_or_default(),
+            )?
+            .iter()
+            .map(|member| Symbol::parse(member).map_err(MlError::from))
+            .collect()
+    }
+
+    /// Load every fundamental metric as it was known at `as_of`.
+    pub fn load_fundamentals_as_of(
+        &self,
+        symbol: &Symbol,
+        as_of: UtcDateTime,
+    ) -> MlResult<Vec<PointInTimeFundamental>> {
+        Ok(self
+            .warehouse
+            .fundamentals_as_of(symbol.as_str(), as_of.format_rfc3339().as_str())?)
+    }
+
+    /// Load a fundamental metric aligned to daily bars without lookahead.
+    pub fn load_fundamental_series(
+        &self,
+        symbol: &Symbol,
+        basis: &str,
+        metric: &str,
+        start: Option<UtcDateTime>,
+        end: Option<UtcDateTime>,
+    ) -> MlResult<Vec<AsOfFundamentalValue>> {
+        let start_str = start.as_ref().map(|s| s.format_rfc3339());
+        let end_str = end.as_ref().map(|e| e.format_rfc3339());
+
+        Ok(self.warehouse.join_fundamentals_as_of(
+            symbol.as_str(),
+            basis,
+            metric,
+            start_str.as_deref(),
+            end_str.as_deref(),
+        )?)
+    }
+
+    /// Load daily news sentiment for joining onto daily features by date.
+    pub fn load_daily_sentiment(
+        &self,
+        symbol: &Symbol,
+        start: Option<UtcDateTime>,
+        end: Option<UtcDateTime>,
+    ) -> MlResult<Vec<DailySentiment>> {
+        let start_str = start.as_ref().map(|s| s.format_rfc3339());
+        let end_str = end.as_ref().map(|e| e.format_rfc3339());
+
+        Ok(self.warehouse.daily_sentiment(
+            symbol.as_str(),
+            start_str.as_deref(),
+            end_str.as_deref(),
+        )?)
+    }
+
     pub fn upsert_features(&self, rows: &[FeatureRow]) -> MlResult<usize> {
         if rows.is_empty() {
             return Ok(0);
@@ -0,0 +1,140 @@
+//! # Ferrotick Telemetry
+//!
+//! Tracing spans and metrics shared by the ferrotick crates.
+//!
+//! Libraries emit `tracing` spans (`route`, `adapter_call`,
+//! `warehouse_query`, `backtest_run`, ...) and record [`metrics`] into a
+//! process-wide registry. Binaries decide what to do with them by installing
+//! a [`Telemetry`] handle.
+//!
+//! ## Modules
+//!
+//! | Module | Description |
+//! |--------|-------------|
+//! | [`metrics`] | Counters, histograms and the Prometheus text exporter |
+//! | [`tracing`] | Span collection, profiles and OTLP/stdout exporters |
+//!
+//! ## Environment Variables
+//!
+//! | Variable | Description |
+//! |----------|-------------|
+//! | `FERROTICK_TRACE_EXPORTER` | `stdout`, `stderr` or `otlp` |
+//! | `OTEL_EXPORTER_OTLP_ENDPOINT` | OTLP/HTTP collector (default `http://localhost:4318`) |
+
+pub mod metrics;
+pub mod tracing;
+
+use thiserror::Error;
+use tracing_subscriber::layer::SubscriberExt;
+
+pub use crate::metrics::{render_prometheus, Counter, Histogram, MetricsRegistry};
+pub use crate::tracing::{
+    FinishedSpan, Profile, ProfileEntry, SpanCollector, SpanExporter, DEFAULT_SPAN_CAPACITY,
+};
+
+/// Errors raised while installing or exporting telemetry.
+#[derive(Debug, Error)]
+pub enum TelemetryError {
+    #[error("a global tracing subscriber is already installed")]
+    AlreadyInstalled,
+
+    #[error("span export failed: {0}")]
+    Export(String),
+
+    #[error("span export failed: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// What a binary wants out of the spans emitted by the libraries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TelemetryConfig {
+    pub service_name: String,
+    pub exporter: Option<SpanExporter>,
+    /// Keep spans so a [`Profile`] can be built at the end of the run.
+    pub profile: bool,
+}
+
+impl TelemetryConfig {
+    pub fn new(service_name: impl Into<String>) -> Self {
+        Self {
+            service_name: service_name.into(),
+            exporter: None,
+            profile: false,
+        }
+    }
+
+    /// Use the exporter selected by `FERROTICK_TRACE_EXPORTER`, if any.
+    pub fn with_env_exporter(mut self) -> Self {
+        self.exporter = SpanExporter::from_env();
+        self
+    }
+
+    pub fn with_exporter(mut self, exporter: SpanExporter) -> Self {
+        self.exporter = Some(exporter);
+        self
+    }
+
+    pub fn with_profile(mut self, profile: bool) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    fn collects_spans(&self) -> bool {
+        self.profile || self.exporter.is_some()
+    }
+}
+
+/// Installed telemetry pipeline.
+#[derive(Debug)]
+pub struct Telemetry {
+    config: TelemetryConfig,
+    collector: Option<SpanCollector>,
+}
+
+impl Telemetry {
+    /// Install the global subscriber described by `config`.
+    ///
+    /// Spans are only collected when a profile or exporter was requested;
+    /// metrics are recorded either way.
+    pub fn install(config: TelemetryConfig) -> Result<Self, TelemetryError> {
+        if !config.collects_spans() {
+            return Ok(Self {
+                config,
+                collector: None,
+            });
+        }
+
+        let collector = SpanCollector::new();
+        let subscriber = tracing_subscriber::registry().with(collector.clone());
+        ::tracing::subscriber::set_global_default(subscriber)
+            .map_err(|_| TelemetryError::AlreadyInstalled)?;
+        Ok(Self {
+            config,
+            collector: Some(collector),
+        })
+    }
+
+    /// Timing breakdown of the spans closed so far, when profiling.
+    pub fn profile(&self) -> Option<Profile> {
+        if !self.config.profile {
+            return None;
+        }
+        let collector = self.collector.as_ref()?;
+        Some(Profile::from_spans(&collector.snapshot()))
+    }
+
+    /// Whether spans are exported, and so need periodic flushing.
+    pub fn exports(&self) -> bool {
+        self.config.exporter.is_some()
+    }
+
+    /// Export and forget the spans closed so far.
+    pub async fn flush(&self) -> Result<(), TelemetryError> {
+        let (Some(exporter), Some(collector)) = (&self.config.exporter, &self.collector) else {
+            return Ok(());
+        };
+        exporter
+            .export(&self.config.service_name, &collector.drain())
+            .await
+    }
+}
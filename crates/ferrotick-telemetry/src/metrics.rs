@@ -0,0 +1,329 @@
+//! Process-wide counters and histograms with a Prometheus text exporter.
+//!
+//! Metrics are declared as constants ([`Counter`], [`Histogram`]) and
+//! recorded into the global [`MetricsRegistry`]; a series is created the
+//! first time a label combination is seen. [`render_prometheus`] produces the
+//! text exposition format served by the web dashboard at `/metrics`.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+/// Upper bounds (seconds) of the latency histogram buckets.
+pub const DEFAULT_BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Upstream provider calls by provider, endpoint and outcome.
+pub const PROVIDER_REQUESTS: Counter = Counter::new(
+    "ferrotick_provider_requests_total",
+    "Upstream provider calls by outcome.",
+);
+
+/// Latency of upstream provider calls.
+pub const PROVIDER_REQUEST_DURATION: Histogram = Histogram::new(
+    "ferrotick_provider_request_duration_seconds",
+    "Latency of upstream provider calls.",
+);
+
+/// Adapter response cache lookups by result (`hit` or `miss`).
+pub const CACHE_LOOKUPS: Counter = Counter::new(
+    "ferrotick_cache_lookups_total",
+    "Response cache lookups by result.",
+);
+
+/// Delay a provider asked for before its rate budget frees up.
+pub const RATE_LIMIT_WAITS: Histogram = Histogram::new(
+    "ferrotick_rate_limit_wait_seconds",
+    "Wait until rate budget is available again.",
+);
+
+/// Requests retried after a transient failure.
+pub const RETRIES: Counter = Counter::new("ferrotick_retries_total", "Retried requests by reason.");
+
+/// Circuit breaker state changes by target state.
+pub const CIRCUIT_TRANSITIONS: Counter = Counter::new(
+    "ferrotick_circuit_transitions_total",
+    "Circuit breaker state transitions.",
+);
+
+/// Duration of warehouse SQL queries.
+pub const WAREHOUSE_QUERY_DURATION: Histogram = Histogram::new(
+    "ferrotick_warehouse_query_duration_seconds",
+    "Duration of warehouse SQL queries.",
+);
+
+/// Duration of backtest runs.
+pub const BACKTEST_DURATION: Histogram = Histogram::new(
+    "ferrotick_backtest_duration_seconds",
+    "Duration of backtest runs.",
+);
+
+/// Monotonic counter family.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Counter {
+    name: &'static str,
+    help: &'static str,
+}
+
+impl Counter {
+    pub const fn new(name: &'static str, help: &'static str) -> Self {
+        Self { name, help }
+    }
+
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Add one to the series identified by `labels` in the global registry.
+    pub fn increment(&self, labels: &[(&'static str, &str)]) {
+        global().increment(self, labels, 1);
+    }
+}
+
+/// Histogram family with [`DEFAULT_BUCKETS`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Histogram {
+    name: &'static str,
+    help: &'static str,
+}
+
+impl Histogram {
+    pub const fn new(name: &'static str, help: &'static str) -> Self {
+        Self { name, help }
+    }
+
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Record `elapsed` in the series identified by `labels` in the global registry.
+    pub fn observe(&self, labels: &[(&'static str, &str)], elapsed: Duration) {
+        global().observe(self, labels, elapsed);
+    }
+}
+
+type Labels = Vec<(&'static str, String)>;
+
+#[derive(Debug)]
+struct Family<T> {
+    help: &'static str,
+    series: BTreeMap<Labels, T>,
+}
+
+#[derive(Debug, Clone)]
+struct HistogramSeries {
+    /// Non-cumulative count per bucket, plus a final `+Inf` bucket.
+    buckets: [u64; DEFAULT_BUCKETS.len() + 1],
+    sum: f64,
+    count: u64,
+}
+
+impl Default for HistogramSeries {
+    fn default() -> Self {
+        Self {
+            buckets: [0; DEFAULT_BUCKETS.len() + 1],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Families {
+    counters: BTreeMap<&'static str, Family<u64>>,
+    histograms: BTreeMap<&'static str, Family<HistogramSeries>>,
+}
+
+/// Registry of every counter and histogram series recorded so far.
+#[derive(Debug, Default)]
+pub struct MetricsRegistry {
+    families: Mutex<Families>,
+}
+
+impl MetricsRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn increment(&self, counter: &Counter, labels: &[(&'static str, &str)], by: u64) {
+        let mut families = self.lock();
+        let family = families
+            .counters
+            .entry(counter.name)
+            .or_insert_with(|| Family {
+                help: counter.help,
+                series: BTreeMap::new(),
+            });
+        *family.series.entry(sorted_labels(labels)).or_default() += by;
+    }
+
+    pub fn observe(
+        &self,
+        histogram: &Histogram,
+        labels: &[(&'static str, &str)],
+        elapsed: Duration,
+    ) {
+        let seconds = elapsed.as_secs_f64();
+        let bucket = DEFAULT_BUCKETS
+            .iter()
+            .position(|bound| seconds <= *bound)
+            .unwrap_or(DEFAULT_BUCKETS.len());
+
+        let mut families = self.lock();
+        let family = families
+            .histograms
+            .entry(histogram.name)
+            .or_insert_with(|| Family {
+                help: histogram.help,
+                series: BTreeMap::new(),
+            });
+        let series = family.series.entry(sorted_labels(labels)).or_default();
+        series.buckets[bucket] += 1;
+        series.sum += seconds;
+        series.count += 1;
+    }
+
+    /// Current value of a counter series, mainly for tests.
+    pub fn counter_value(&self, counter: &Counter, labels: &[(&'static str, &str)]) -> u64 {
+        self.lock()
+            .counters
+            .get(counter.name)
+            .and_then(|family| family.series.get(&sorted_labels(labels)))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Render every family in the Prometheus text exposition format.
+    pub fn render_prometheus(&self) -> String {
+        let families = self.lock();
+        let mut output = String::new();
+
+        for (name, family) in &families.counters {
+            let _ = writeln!(output, "# HELP {name} {}", family.help);
+            let _ = writeln!(output, "# TYPE {name} counter");
+            for (labels, value) in &family.series {
+                let _ = writeln!(output, "{name}{} {value}", format_labels(labels, None));
+            }
+        }
+
+        for (name, family) in &families.histograms {
+            let _ = writeln!(output, "# HELP {name} {}", family.help);
+            let _ = writeln!(output, "# TYPE {name} histogram");
+            for (labels, series) in &family.series {
+                let mut cumulative = 0;
+                for (index, count) in series.buckets.iter().enumerate() {
+                    cumulative += count;
+                    let bound = DEFAULT_BUCKETS
+                        .get(index)
+                        .map_or_else(|| String::from("+Inf"), f64::to_string);
+                    let _ = writeln!(
+                        output,
+                        "{name}_bucket{} {cumulative}",
+                        format_labels(labels, Some(&bound))
+                    );
+                }
+                let rendered = format_labels(labels, None);
+                let _ = writeln!(output, "{name}_sum{rendered} {}", series.sum);
+                let _ = writeln!(output, "{name}_count{rendered} {}", series.count);
+            }
+        }
+
+        output
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Families> {
+        self.families
+            .lock()
+            .expect("metrics registry lock is not poisoned")
+    }
+}
+
+/// Registry shared by the whole process.
+pub fn global() -> &'static MetricsRegistry {
+    static REGISTRY: OnceLock<MetricsRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(MetricsRegistry::new)
+}
+
+/// Prometheus text for the global registry.
+pub fn render_prometheus() -> String {
+    global().render_prometheus()
+}
+
+fn sorted_labels(labels: &[(&'static str, &str)]) -> Labels {
+    let mut owned = labels
+        .iter()
+        .map(|(key, value)| (*key, (*value).to_owned()))
+        .collect::<Vec<_>>();
+    owned.sort();
+    owned
+}
+
+fn format_labels(labels: &Labels, le: Option<&str>) -> String {
+    let mut pairs = labels
+        .iter()
+        .map(|(key, value)| format!("{key}=\"{}\"", escape_label_value(value)))
+        .collect::<Vec<_>>();
+    if let Some(le) = le {
+        pairs.push(format!("le=\"{le}\""));
+    }
+    if pairs.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", pairs.join(","))
+    }
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_counters_and_cumulative_histograms() {
+        let registry = MetricsRegistry::new();
+        let labels = [("provider", "yahoo"), ("endpoint", "quote")];
+        registry.increment(&PROVIDER_REQUESTS, &[("outcome", "ok"), labels[0]], 2);
+        registry.observe(
+            &PROVIDER_REQUEST_DURATION,
+            &labels,
+            Duration::from_millis(20),
+        );
+        registry.observe(&PROVIDER_REQUEST_DURATION, &labels, Duration::from_secs(30));
+        registry.increment(&CACHE_LOOKUPS, &[("result", "say \"hit\"")], 1);
+
+        let text = registry.render_prometheus();
+
+        assert!(text.contains("# TYPE ferrotick_provider_requests_total counter"));
+        assert!(
+            text.contains(r#"ferrotick_provider_requests_total{outcome="ok",provider="yahoo"} 2"#)
+        );
+        assert!(text.contains(r#"ferrotick_cache_lookups_total{result="say \"hit\""} 1"#));
+        assert!(text.contains(
+            r#"ferrotick_provider_request_duration_seconds_bucket{endpoint="quote",provider="yahoo",le="0.01"} 0"#
+        ));
+        assert!(text.contains(
+            r#"ferrotick_provider_request_duration_seconds_bucket{endpoint="quote",provider="yahoo",le="0.025"} 1"#
+        ));
+        assert!(text.contains(
+            r#"ferrotick_provider_request_duration_seconds_bucket{endpoint="quote",provider="yahoo",le="+Inf"} 2"#
+        ));
+        assert!(text.contains(
+            r#"ferrotick_provider_request_duration_seconds_count{endpoint="quote",provider="yahoo"} 2"#
+        ));
+        assert_eq!(
+            registry.counter_value(
+                &PROVIDER_REQUESTS,
+                &[("provider", "yahoo"), ("outcome", "ok")]
+            ),
+            2
+        );
+    }
+}
@@ -0,0 +1,449 @@
+//! Span collection, `--profile` breakdowns and span exporters.
+//!
+//! [`SpanCollector`] is a `tracing-subscriber` layer that records the spans
+//! closed under it, up to a fixed capacity. The collected spans feed a [`Profile`] (per-span timing
+//! breakdown) and can be shipped with a [`SpanExporter`] as OTLP/JSON, either
+//! to a collector over HTTP or as one JSON document per line on stdout/stderr.
+
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::Subscriber;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+use crate::TelemetryError;
+
+/// Spans a [`SpanCollector`] keeps between drains by default.
+pub const DEFAULT_SPAN_CAPACITY: usize = 10_000;
+
+/// A span that has closed, with its timing and recorded fields.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FinishedSpan {
+    pub name: &'static str,
+    pub target: &'static str,
+    /// 32 hex characters shared by every span of one call tree.
+    pub trace_id: String,
+    /// 16 hex characters.
+    pub span_id: String,
+    pub parent_span_id: Option<String>,
+    pub started_at: SystemTime,
+    pub duration: Duration,
+    pub attributes: Vec<(&'static str, String)>,
+}
+
+/// Layer that keeps closed spans for profiling and export.
+///
+/// Once `capacity` spans are waiting to be drained, the oldest ones are
+/// dropped (and counted) so a long-running process cannot grow without bound.
+#[derive(Debug, Clone)]
+pub struct SpanCollector {
+    buffer: Arc<Mutex<SpanBuffer>>,
+}
+
+#[derive(Debug)]
+struct SpanBuffer {
+    spans: VecDeque<FinishedSpan>,
+    capacity: usize,
+    dropped: u64,
+}
+
+/// Per-span state stored in the registry's extensions while the span is open.
+struct OpenSpan {
+    trace_id: String,
+    span_id: String,
+    parent_span_id: Option<String>,
+    started_at: SystemTime,
+    started: Instant,
+    attributes: Vec<(&'static str, String)>,
+}
+
+impl Default for SpanCollector {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_SPAN_CAPACITY)
+    }
+}
+
+impl SpanCollector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Collector keeping at most `capacity` undrained spans.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            buffer: Arc::new(Mutex::new(SpanBuffer {
+                spans: VecDeque::new(),
+                capacity: capacity.max(1),
+                dropped: 0,
+            })),
+        }
+    }
+
+    /// Spans closed so far, leaving them in the collector.
+    pub fn snapshot(&self) -> Vec<FinishedSpan> {
+        self.lock().spans.iter().cloned().collect()
+    }
+
+    /// Spans closed so far, removing them from the collector.
+    pub fn drain(&self) -> Vec<FinishedSpan> {
+        self.lock().spans.drain(..).collect()
+    }
+
+    /// Spans discarded because the collector was full.
+    pub fn dropped(&self) -> u64 {
+        self.lock().dropped
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, SpanBuffer> {
+        self.buffer
+            .lock()
+            .expect("span collector lock is not poisoned")
+    }
+}
+
+impl<S> Layer<S> for SpanCollector
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+
+        let parent = span.parent().and_then(|parent| {
+            parent
+                .extensions()
+                .get::<OpenSpan>()
+                .map(|open| (open.trace_id.clone(), open.span_id.clone()))
+        });
+        let (trace_id, parent_span_id) = match parent {
+            Some((trace_id, parent_span_id)) => (trace_id, Some(parent_span_id)),
+            None => (random_hex(16), None),
+        };
+
+        let mut visitor = AttributeVisitor::default();
+        attrs.record(&mut visitor);
+
+        span.extensions_mut().insert(OpenSpan {
+            trace_id,
+            span_id: random_hex(8),
+            parent_span_id,
+            started_at: SystemTime::now(),
+            started: Instant::now(),
+            attributes: visitor.attributes,
+        });
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut visitor = AttributeVisitor::default();
+        values.record(&mut visitor);
+
+        let mut extensions = span.extensions_mut();
+        if let Some(open) = extensions.get_mut::<OpenSpan>() {
+            for (key, value) in visitor.attributes {
+                open.attributes.retain(|(existing, _)| *existing != key);
+                open.attributes.push((key, value));
+            }
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let Some(open) = span.extensions_mut().remove::<OpenSpan>() else {
+            return;
+        };
+
+        let mut buffer = self.lock();
+        if buffer.spans.len() >= buffer.capacity {
+            buffer.spans.pop_front();
+            buffer.dropped += 1;
+        }
+        buffer.spans.push_back(FinishedSpan {
+            name: span.name(),
+            target: span.metadata().target(),
+            trace_id: open.trace_id,
+            span_id: open.span_id,
+            parent_span_id: open.parent_span_id,
+            started_at: open.started_at,
+            duration: open.started.elapsed(),
+            attributes: open.attributes,
+        });
+    }
+}
+
+#[derive(Default)]
+struct AttributeVisitor {
+    attributes: Vec<(&'static str, String)>,
+}
+
+impl Visit for AttributeVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.attributes.push((field.name(), value.to_owned()));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.attributes.push((field.name(), format!("{value:?}")));
+    }
+}
+
+/// Timing breakdown of the spans recorded during one command.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Profile {
+    /// Wall time of the root spans.
+    pub total_ms: f64,
+    /// One entry per span name, slowest first.
+    pub spans: Vec<ProfileEntry>,
+}
+
+/// Aggregate timing of every span sharing one name.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProfileEntry {
+    pub name: &'static str,
+    pub calls: u64,
+    pub total_ms: f64,
+    pub max_ms: f64,
+}
+
+impl Profile {
+    pub fn from_spans(spans: &[FinishedSpan]) -> Self {
+        let mut entries: Vec<ProfileEntry> = Vec::new();
+        for span in spans {
+            let elapsed_ms = duration_ms(span.duration);
+            match entries.iter_mut().find(|entry| entry.name == span.name) {
+                Some(entry) => {
+                    entry.calls += 1;
+                    entry.total_ms += elapsed_ms;
+                    entry.max_ms = entry.max_ms.max(elapsed_ms);
+                }
+                None => entries.push(ProfileEntry {
+                    name: span.name,
+                    calls: 1,
+                    total_ms: elapsed_ms,
+                    max_ms: elapsed_ms,
+                }),
+            }
+        }
+        entries.sort_by(|left, right| right.total_ms.total_cmp(&left.total_ms));
+
+        let total_ms = spans
+            .iter()
+            .filter(|span| span.parent_span_id.is_none())
+            .map(|span| duration_ms(span.duration))
+            .sum();
+
+        Self {
+            total_ms,
+            spans: entries,
+        }
+    }
+}
+
+/// Destination for collected spans, encoded as OTLP/JSON.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpanExporter {
+    Stdout,
+    Stderr,
+    /// OTLP/HTTP collector; spans are posted to `{endpoint}/v1/traces`.
+    Otlp {
+        endpoint: String,
+    },
+}
+
+impl SpanExporter {
+    /// Exporter selected by `FERROTICK_TRACE_EXPORTER` (`stdout`, `stderr` or
+    /// `otlp`). The OTLP endpoint comes from `OTEL_EXPORTER_OTLP_ENDPOINT`
+    /// and defaults to `http://localhost:4318`.
+    pub fn from_env() -> Option<Self> {
+        let selected = std::env::var("FERROTICK_TRACE_EXPORTER").ok()?;
+        match selected.trim().to_ascii_lowercase().as_str() {
+            "stdout" => Some(Self::Stdout),
+            "stderr" => Some(Self::Stderr),
+            "otlp" => Some(Self::Otlp {
+                endpoint: std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT")
+                    .unwrap_or_else(|_| String::from("http://localhost:4318")),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Ship `spans`; does nothing when there are none.
+    pub async fn export(
+        &self,
+        service_name: &str,
+        spans: &[FinishedSpan],
+    ) -> Result<(), TelemetryError> {
+        if spans.is_empty() {
+            return Ok(());
+        }
+        let document = otlp_document(service_name, spans);
+
+        match self {
+            Self::Stdout => writeln!(std::io::stdout().lock(), "{document}")?,
+            Self::Stderr => writeln!(std::io::stderr().lock(), "{document}")?,
+            Self::Otlp { endpoint } => {
+                let url = format!("{}/v1/traces", endpoint.trim_end_matches('/'));
+                let response = reqwest::Client::new()
+                    .post(&url)
+                    .header("content-type", "application/json")
+                    .body(document.to_string())
+                    .send()
+                    .await
+                    .map_err(|error| TelemetryError::Export(error.to_string()))?;
+                if !response.status().is_success() {
+                    return Err(TelemetryError::Export(format!(
+                        "{url} returned status {}",
+                        response.status()
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// OTLP/JSON `ExportTraceServiceRequest` for `spans`.
+pub fn otlp_document(service_name: &str, spans: &[FinishedSpan]) -> Value {
+    let spans = spans
+        .iter()
+        .map(|span| {
+            let start = unix_nanos(span.started_at);
+            let end = start.saturating_add(span.duration.as_nanos());
+            let attributes = span
+                .attributes
+                .iter()
+                .map(|(key, value)| json!({ "key": key, "value": { "stringValue": value } }))
+                .collect::<Vec<_>>();
+            json!({
+                "traceId": span.trace_id,
+                "spanId": span.span_id,
+                "parentSpanId": span.parent_span_id.clone().unwrap_or_default(),
+                "name": span.name,
+                "kind": 1,
+                "startTimeUnixNano": start.to_string(),
+                "endTimeUnixNano": end.to_string(),
+                "attributes": attributes,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [
+                    { "key": "service.name", "value": { "stringValue": service_name } }
+                ]
+            },
+            "scopeSpans": [{
+                "scope": { "name": "ferrotick" },
+                "spans": spans,
+            }]
+        }]
+    })
+}
+
+fn unix_nanos(at: SystemTime) -> u128 {
+    at.duration_since(UNIX_EPOCH)
+        .map(|since| since.as_nanos())
+        .unwrap_or(0)
+}
+
+fn duration_ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1_000.0
+}
+
+fn random_hex(bytes: usize) -> String {
+    (0..bytes)
+        .map(|_| format!("{:02x}", fastrand::u8(..)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn nested_spans_share_a_trace_and_feed_the_profile() {
+        let collector = SpanCollector::new();
+        let subscriber = tracing_subscriber::registry().with(collector.clone());
+
+        tracing::subscriber::with_default(subscriber, || {
+            let route = tracing::info_span!(
+                "route",
+                endpoint = "quote",
+                selected = tracing::field::Empty
+            );
+            let _route = route.enter();
+            for provider in ["polygon", "yahoo"] {
+                let _call = tracing::info_span!("adapter_call", provider).entered();
+            }
+            route.record("selected", "yahoo");
+        });
+
+        let spans = collector.drain();
+        assert_eq!(spans.len(), 3);
+        let route = spans
+            .iter()
+            .find(|span| span.name == "route")
+            .expect("route span");
+        assert!(route.parent_span_id.is_none());
+        assert!(route
+            .attributes
+            .contains(&("selected", String::from("yahoo"))));
+        assert!(spans
+            .iter()
+            .filter(|span| span.name == "adapter_call")
+            .all(|span| span.trace_id == route.trace_id
+                && span.parent_span_id.as_deref() == Some(route.span_id.as_str())));
+
+        let profile = Profile::from_spans(&spans);
+        assert_eq!(profile.spans[0].name, "route");
+        let calls = profile
+            .spans
+            .iter()
+            .find(|entry| entry.name == "adapter_call")
+            .expect("adapter calls");
+        assert_eq!(calls.calls, 2);
+
+        let document = otlp_document("ferrotick", &spans);
+        let exported = &document["resourceSpans"][0]["scopeSpans"][0]["spans"];
+        assert_eq!(exported.as_array().map(Vec::len), Some(3));
+        assert_eq!(exported[0]["traceId"].as_str().map(str::len), Some(32));
+        assert!(collector.snapshot().is_empty());
+    }
+
+    #[test]
+    fn full_collector_drops_the_oldest_spans() {
+        let collector = SpanCollector::with_capacity(2);
+        let subscriber = tracing_subscriber::registry().with(collector.clone());
+
+        tracing::subscriber::with_default(subscriber, || {
+            for step in ["first", "second", "third"] {
+                let _span = tracing::info_span!("step", step).entered();
+            }
+        });
+
+        let kept: Vec<_> = collector
+            .drain()
+            .into_iter()
+            .flat_map(|span| span.attributes)
+            .map(|(_, value)| value)
+            .collect();
+        assert_eq!(kept, ["second", "third"]);
+        assert_eq!(collector.dropped(), 1);
+    }
+}
@@ -1,8 +1,10 @@
 //! `DuckDB` connection pool management.
 
+use std::collections::HashMap;
+use std::fs;
 use std::ops::{Deref, DerefMut};
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, OnceLock};
 
 use ::duckdb::Connection;
 
@@ -35,6 +37,49 @@ struct PoolInner {
     state: Mutex<PoolState>,
 }
 
+impl Drop for PoolInner {
+    fn drop(&mut self) {
+        let mut open = open_databases();
+        let key = database_key(self.db_path.as_path());
+        if let Some(count) = open.get_mut(&key) {
+            *count -= 1;
+            if *count == 0 {
+                open.remove(&key);
+            }
+        }
+    }
+}
+
+/// Pools alive in this process, by database file.
+///
+/// DuckDB's file lock only keeps other processes out, so this is how
+/// [`is_database_open`] sees pools opened by this one.
+fn open_databases() -> std::sync::MutexGuard<'static, HashMap<PathBuf, usize>> {
+    static OPEN: OnceLock<Mutex<HashMap<PathBuf, usize>>> = OnceLock::new();
+    OPEN.get_or_init(Mutex::default)
+        .lock()
+        .expect("open database registry mutex poisoned")
+}
+
+/// `path` with its directory resolved, so spellings of one file compare
+/// equal even before the file exists.
+fn database_key(path: &Path) -> PathBuf {
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    match (fs::canonicalize(directory), path.file_name()) {
+        (Ok(directory), Some(name)) => directory.join(name),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Whether a connection pool in this process has the database at `path`
+/// open.
+pub(crate) fn is_database_open(path: &Path) -> bool {
+    open_databases().contains_key(&database_key(path))
+}
+
 /// A connection pool manager for `DuckDB` connections.
 #[derive(Clone)]
 pub struct DuckDbConnectionManager {
@@ -49,9 +94,13 @@ impl DuckDbConnectionManager {
     /// * `max_pool_size` - Maximum number of connections to keep in the pool
     #[must_use]
     pub fn new(path: impl Into<PathBuf>, max_pool_size: usize) -> Self {
+        let db_path = path.into();
+        *open_databases()
+            .entry(database_key(db_path.as_path()))
+            .or_default() += 1;
         Self {
             inner: Arc::new(PoolInner {
-                db_path: path.into(),
+                db_path,
                 max_pool_size: max_pool_size.max(1),
                 state: Mutex::new(PoolState::new()),
             }),
@@ -0,0 +1,737 @@
+//! Typed exports of query results to Parquet, Arrow IPC, NDJSON and CSV.
+//!
+//! Parquet and CSV files are written by DuckDB's `COPY ... TO`, so column
+//! types come straight from the query: timestamps stay timestamps and
+//! decimals keep their scale. Arrow IPC (Feather v2) and NDJSON files are
+//! written from the record batches DuckDB produces, handed as they are to
+//! the `arrow-ipc` and `arrow-json` writers of the same Arrow release.
+//!
+//! `max_rows` orders the rows by every column before cutting them off, so
+//! the same query always exports the same rows.
+//!
+//! With a [`ExportPartition`] the output path is a directory laid out as
+//! `<column>=<value>/data_0.<ext>`, the hive layout DuckDB and most engines
+//! read back as a partitioned dataset.
+
+use std::fmt;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use ::duckdb::Connection;
+use arrow_array::cast::AsArray;
+use arrow_array::{Array, RecordBatch};
+use arrow_ipc::writer::FileWriter;
+use arrow_json::writer::{LineDelimited, WriterBuilder};
+use arrow_schema::{ArrowError, SchemaRef};
+use serde::Serialize;
+
+use crate::duckdb::AccessMode;
+use crate::queries::{self, QueryParams};
+use crate::{
+    enforce_read_only_query, ensure_timeout, escape_sql_string, normalize_sql, partition_component,
+    path_to_sql, SqlColumn, Warehouse, WarehouseError,
+};
+
+/// Column a partitioned batch export reads the partition value from.
+const PARTITION_KEY: &str = "export_partition";
+
+/// File format of an export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    Parquet,
+    /// Arrow IPC file format, also known as Feather v2.
+    Arrow,
+    /// Newline-delimited JSON, one object per row.
+    Ndjson,
+    Csv,
+}
+
+impl ExportFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Parquet => "parquet",
+            Self::Arrow => "arrow",
+            Self::Ndjson => "ndjson",
+            Self::Csv => "csv",
+        }
+    }
+
+    /// Compression codecs the format can be written with.
+    pub fn compressions(self) -> &'static [ExportCompression] {
+        match self {
+            Self::Parquet => &[
+                ExportCompression::Uncompressed,
+                ExportCompression::Snappy,
+                ExportCompression::Gzip,
+                ExportCompression::Zstd,
+                ExportCompression::Lz4,
+            ],
+            Self::Csv => &[
+                ExportCompression::Uncompressed,
+                ExportCompression::Gzip,
+                ExportCompression::Zstd,
+            ],
+            Self::Arrow | Self::Ndjson => &[ExportCompression::Uncompressed],
+        }
+    }
+
+    /// Whether files are written by DuckDB's `COPY` rather than from
+    /// record batches.
+    fn is_copy(self) -> bool {
+        matches!(self, Self::Parquet | Self::Csv)
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = WarehouseError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "parquet" => Ok(Self::Parquet),
+            "arrow" | "ipc" | "feather" => Ok(Self::Arrow),
+            "ndjson" | "jsonl" => Ok(Self::Ndjson),
+            "csv" => Ok(Self::Csv),
+            other => Err(WarehouseError::QueryRejected(format!(
+                "unknown export format '{other}', expected parquet, arrow, ndjson or csv"
+            ))),
+        }
+    }
+}
+
+/// Compression codec of an export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportCompression {
+    Uncompressed,
+    Snappy,
+    Gzip,
+    Zstd,
+    Lz4,
+}
+
+impl ExportCompression {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Uncompressed => "uncompressed",
+            Self::Snappy => "snappy",
+            Self::Gzip => "gzip",
+            Self::Zstd => "zstd",
+            Self::Lz4 => "lz4",
+        }
+    }
+}
+
+impl fmt::Display for ExportCompression {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ExportCompression {
+    type Err = WarehouseError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "none" | "uncompressed" => Ok(Self::Uncompressed),
+            "snappy" => Ok(Self::Snappy),
+            "gzip" => Ok(Self::Gzip),
+            "zstd" => Ok(Self::Zstd),
+            "lz4" => Ok(Self::Lz4),
+            other => Err(WarehouseError::QueryRejected(format!(
+                "unknown compression '{other}', expected none, snappy, gzip, zstd or lz4"
+            ))),
+        }
+    }
+}
+
+/// Column an export is partitioned by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportPartition {
+    /// One directory per `symbol` value.
+    Symbol,
+    /// One directory per trading day, taken from a `date` column or derived
+    /// from `ts`.
+    Date,
+}
+
+impl ExportPartition {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Symbol => "symbol",
+            Self::Date => "date",
+        }
+    }
+}
+
+impl FromStr for ExportPartition {
+    type Err = WarehouseError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "symbol" => Ok(Self::Symbol),
+            "date" => Ok(Self::Date),
+            other => Err(WarehouseError::QueryRejected(format!(
+                "unknown partition column '{other}', expected symbol or date"
+            ))),
+        }
+    }
+}
+
+/// How [`Warehouse::export_query`] writes its output.
+#[derive(Debug, Clone)]
+pub struct ExportOptions {
+    pub format: ExportFormat,
+    /// Codec to write with; the format's default when unset.
+    pub compression: Option<ExportCompression>,
+    /// Rows per Parquet row group; DuckDB's default when unset.
+    pub row_group_size: Option<usize>,
+    pub partition_by: Option<ExportPartition>,
+    /// Export at most this many rows.
+    pub max_rows: Option<usize>,
+    pub query_timeout_ms: u64,
+}
+
+impl ExportOptions {
+    pub fn new(format: ExportFormat) -> Self {
+        Self {
+            format,
+            compression: None,
+            row_group_size: None,
+            partition_by: None,
+            max_rows: None,
+            query_timeout_ms: 30_000,
+        }
+    }
+
+    pub fn with_compression(mut self, compression: ExportCompression) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
+    pub fn with_row_group_size(mut self, rows: usize) -> Self {
+        self.row_group_size = Some(rows);
+        self
+    }
+
+    pub fn with_partition_by(mut self, partition: ExportPartition) -> Self {
+        self.partition_by = Some(partition);
+        self
+    }
+
+    pub fn with_max_rows(mut self, rows: usize) -> Self {
+        self.max_rows = Some(rows);
+        self
+    }
+
+    pub fn with_query_timeout_ms(mut self, timeout_ms: u64) -> Self {
+        self.query_timeout_ms = timeout_ms;
+        self
+    }
+
+    fn validate(&self) -> Result<(), WarehouseError> {
+        if let Some(compression) = self.compression {
+            if !self.format.compressions().contains(&compression) {
+                return Err(WarehouseError::QueryRejected(format!(
+                    "{} exports do not support {compression} compression",
+                    self.format
+                )));
+            }
+        }
+        match self.row_group_size {
+            Some(_) if self.format != ExportFormat::Parquet => Err(WarehouseError::QueryRejected(
+                String::from("row group size only applies to parquet exports"),
+            )),
+            Some(0) => Err(WarehouseError::QueryRejected(String::from(
+                "row group size must be greater than zero",
+            ))),
+            _ if self.max_rows == Some(0) || self.query_timeout_ms == 0 => {
+                Err(WarehouseError::QueryRejected(String::from(
+                    "--max-rows and --query-timeout-ms must be greater than zero",
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Outcome of [`Warehouse::export_query`].
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportReport {
+    pub format: ExportFormat,
+    pub compression: Option<ExportCompression>,
+    pub partition_by: Option<ExportPartition>,
+    /// Output file, or directory of a partitioned export.
+    pub output: String,
+    pub rows: u64,
+    /// Files written, in path order.
+    pub files: Vec<String>,
+    /// Columns of the exported rows and their types.
+    pub columns: Vec<SqlColumn>,
+}
+
+impl Warehouse {
+    /// Write the rows of the read-only query `sql` to `output`.
+    ///
+    /// `$name` placeholders are bound from `params` as in
+    /// [`Warehouse::execute_query_with_params`]. Partitioned exports treat
+    /// `output` as a directory, which must be empty or missing.
+    pub fn export_query(
+        &self,
+        sql: &str,
+        params: &QueryParams,
+        output: &Path,
+        options: &ExportOptions,
+    ) -> Result<ExportReport, WarehouseError> {
+        options.validate()?;
+        let sql = normalize_sql(sql)?;
+        enforce_read_only_query(sql)?;
+
+        let connection = self.manager.acquire(AccessMode::ReadOnly)?;
+        let started = Instant::now();
+        let mut source = format!("SELECT * FROM ({sql}) AS export_source");
+        if let Some(rows) = options.max_rows {
+            // Without an order a LIMIT may keep different rows on each run.
+            source.push_str(&format!(" ORDER BY ALL LIMIT {rows}"));
+        }
+
+        let mut columns = probe_columns(&connection, &source, params)?;
+        let partition_column = match options.partition_by {
+            Some(partition) => {
+                let column = partition.as_str();
+                if !has_column(&columns, column) {
+                    if partition == ExportPartition::Date && has_column(&columns, "ts") {
+                        source = format!(
+                            "SELECT *, CAST(ts AS DATE) AS date FROM ({source}) AS export_rows"
+                        );
+                        columns = probe_columns(&connection, &source, params)?;
+                    } else {
+                        return Err(WarehouseError::QueryRejected(format!(
+                            "partitioning by {column} needs a '{column}' column{}",
+                            if partition == ExportPartition::Date {
+                                " or a 'ts' column"
+                            } else {
+                                ""
+                            }
+                        )));
+                    }
+                }
+                Some(column)
+            }
+            None => None,
+        };
+
+        match partition_column {
+            Some(_) => fs::create_dir_all(output)?,
+            None => {
+                if let Some(parent) = output
+                    .parent()
+                    .filter(|parent| !parent.as_os_str().is_empty())
+                {
+                    fs::create_dir_all(parent)?;
+                }
+            }
+        }
+
+        let timeout = Duration::from_millis(options.query_timeout_ms);
+        let rows = if options.format.is_copy() {
+            copy_to(
+                &connection,
+                &source,
+                params,
+                output,
+                partition_column,
+                options,
+            )?
+        } else {
+            write_batches(
+                &connection,
+                &source,
+                params,
+                output,
+                partition_column,
+                options,
+                started,
+            )?
+        };
+        ensure_timeout(started, timeout)?;
+
+        let mut files = Vec::new();
+        if partition_column.is_some() {
+            collect_files(output, &mut files)?;
+            files.sort();
+        } else {
+            files.push(output.to_path_buf());
+        }
+
+        Ok(ExportReport {
+            format: options.format,
+            compression: options.compression,
+            partition_by: options.partition_by,
+            output: output.display().to_string(),
+            rows,
+            files: files
+                .iter()
+                .map(|file| file.display().to_string())
+                .collect(),
+            columns,
+        })
+    }
+}
+
+/// Names and types of the columns `source` returns, without reading rows.
+fn probe_columns(
+    connection: &Connection,
+    source: &str,
+    params: &QueryParams,
+) -> Result<Vec<SqlColumn>, WarehouseError> {
+    let mut statement = connection.prepare(&format!("SELECT * FROM ({source}) LIMIT 0"))?;
+    queries::bind_named_params(&mut statement, params)?;
+    statement.raw_execute()?;
+    Ok((0..statement.column_count())
+        .map(|index| SqlColumn {
+            name: statement
+                .column_name(index)
+                .map(ToString::to_string)
+                .unwrap_or_default(),
+            r#type: statement.column_type(index).to_string(),
+        })
+        .collect())
+}
+
+fn has_column(columns: &[SqlColumn], name: &str) -> bool {
+    columns
+        .iter()
+        .any(|column| column.name.eq_ignore_ascii_case(name))
+}
+
+/// Write `source` with DuckDB's `COPY`, returning the number of rows.
+fn copy_to(
+    connection: &Connection,
+    source: &str,
+    params: &QueryParams,
+    output: &Path,
+    partition_column: Option<&str>,
+    options: &ExportOptions,
+) -> Result<u64, WarehouseError> {
+    let mut copy_options = vec![format!("FORMAT {}", options.format)];
+    if options.format == ExportFormat::Csv {
+        copy_options.push(String::from("HEADER true"));
+    }
+    if let Some(compression) = options.compression {
+        let codec = match compression {
+            ExportCompression::Uncompressed if options.format != ExportFormat::Parquet => "none",
+            other => other.as_str(),
+        };
+        copy_options.push(format!("COMPRESSION {codec}"));
+    }
+    if let Some(rows) = options.row_group_size {
+        copy_options.push(format!("ROW_GROUP_SIZE {rows}"));
+    }
+    if let Some(column) = partition_column {
+        copy_options.push(format!("PARTITION_BY ({column})"));
+    }
+
+    let copy = format!(
+        "COPY ({source}) TO '{}' ({})",
+        escape_sql_string(&path_to_sql(output)),
+        copy_options.join(", ")
+    );
+    let mut statement = connection.prepare(&copy)?;
+    queries::bind_named_params(&mut statement, params)?;
+    Ok(statement.raw_execute()? as u64)
+}
+
+/// Write `source` as Arrow IPC or NDJSON files, returning the number of
+/// rows.
+///
+/// The source runs once. A partitioned export reads it sorted by partition
+/// value and starts the next file whenever the value changes, dropping the
+/// partition column from the files as `COPY ... PARTITION_BY` does. The
+/// timeout is checked after every batch.
+fn write_batches(
+    connection: &Connection,
+    source: &str,
+    params: &QueryParams,
+    output: &Path,
+    partition_column: Option<&str>,
+    options: &ExportOptions,
+    started: Instant,
+) -> Result<u64, WarehouseError> {
+    let format = options.format;
+    let timeout = Duration::from_millis(options.query_timeout_ms);
+    let Some(column) = partition_column else {
+        let mut statement = connection.prepare(source)?;
+        queries::bind_named_params(&mut statement, params)?;
+        statement.raw_execute()?;
+        let mut writer = BatchWriter::create(output, &statement.schema(), format)?;
+        let mut rows = 0;
+        while let Some(batch) = statement.step() {
+            let batch = RecordBatch::from(batch);
+            rows += batch.num_rows() as u64;
+            writer.write(&batch).map_err(export_error)?;
+            ensure_timeout(started, timeout)?;
+        }
+        writer.finish().map_err(export_error)?;
+        return Ok(rows);
+    };
+
+    let mut statement = connection.prepare(&format!(
+        "SELECT CAST({column} AS VARCHAR) AS {PARTITION_KEY}, * EXCLUDE ({column}) \
+         FROM ({source}) ORDER BY {PARTITION_KEY}"
+    ))?;
+    queries::bind_named_params(&mut statement, params)?;
+    statement.raw_execute()?;
+    let schema = statement.schema();
+    let file_columns = (1..schema.fields().len()).collect::<Vec<_>>();
+    let file_schema = Arc::new(schema.project(&file_columns).map_err(export_error)?);
+
+    let mut current: Option<(Option<String>, BatchWriter)> = None;
+    let mut rows = 0;
+    while let Some(batch) = statement.step() {
+        let batch = RecordBatch::from(batch);
+        let keys = batch
+            .column(0)
+            .as_string_opt::<i32>()
+            .ok_or_else(|| export_error("partition values are not strings"))?;
+        let key = |row: usize| keys.is_valid(row).then(|| keys.value(row).to_string());
+        let data = batch.project(&file_columns).map_err(export_error)?;
+
+        let mut start = 0;
+        while start < batch.num_rows() {
+            let value = key(start);
+            let end = (start + 1..batch.num_rows())
+                .find(|row| key(*row) != value)
+                .unwrap_or(batch.num_rows());
+            if current.as_ref().map(|(open, _)| open) != Some(&value) {
+                if let Some((_, mut writer)) = current.take() {
+                    writer.finish().map_err(export_error)?;
+                }
+                let directory = output.join(format!(
+                    "{column}={}",
+                    partition_component(value.as_deref().unwrap_or("NULL"))?
+                ));
+                fs::create_dir_all(&directory)?;
+                let path = directory.join(format!("data_0.{format}"));
+                current = Some((value, BatchWriter::create(&path, &file_schema, format)?));
+            }
+            if let Some((_, writer)) = current.as_mut() {
+                writer
+                    .write(&data.slice(start, end - start))
+                    .map_err(export_error)?;
+            }
+            start = end;
+        }
+        rows += batch.num_rows() as u64;
+        ensure_timeout(started, timeout)?;
+    }
+    if let Some((_, mut writer)) = current {
+        writer.finish().map_err(export_error)?;
+    }
+    Ok(rows)
+}
+
+/// Writers of the formats built from record batches.
+enum BatchWriter {
+    Ipc(FileWriter<BufWriter<File>>),
+    Ndjson(arrow_json::Writer<BufWriter<File>, LineDelimited>),
+}
+
+impl BatchWriter {
+    fn create(
+        path: &Path,
+        schema: &SchemaRef,
+        format: ExportFormat,
+    ) -> Result<Self, WarehouseError> {
+        let file = BufWriter::new(File::create(path)?);
+        Ok(match format {
+            ExportFormat::Arrow => {
+                Self::Ipc(FileWriter::try_new(file, schema).map_err(export_error)?)
+            }
+            _ => Self::Ndjson(
+                // Write every key, so each line carries the full schema.
+                WriterBuilder::new()
+                    .with_explicit_nulls(true)
+                    .build::<_, LineDelimited>(file),
+            ),
+        })
+    }
+
+    fn write(&mut self, batch: &RecordBatch) -> Result<(), ArrowError> {
+        match self {
+            Self::Ipc(writer) => writer.write(batch),
+            Self::Ndjson(writer) => writer.write(batch),
+        }
+    }
+
+    fn finish(&mut self) -> Result<(), ArrowError> {
+        match self {
+            Self::Ipc(writer) => writer.finish(),
+            Self::Ndjson(writer) => writer.finish(),
+        }
+    }
+}
+
+fn export_error(error: impl fmt::Display) -> WarehouseError {
+    WarehouseError::Export(error.to_string())
+}
+
+fn collect_files(root: &Path, files: &mut Vec<PathBuf>) -> Result<(), std::io::Error> {
+    for entry in fs::read_dir(root)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::queries::QueryParam;
+    use crate::{BarRecord, QueryGuardrails, WarehouseConfig};
+    use arrow_ipc::reader::FileReader;
+    use arrow_schema::{DataType, TimeUnit};
+    use serde_json::Value;
+    use tempfile::tempdir;
+
+    #[test]
+    fn exports_keep_column_types_and_partition_by_symbol_or_date() {
+        let temp = tempdir().expect("tempdir");
+        let ferrotick_home = temp.path().join("ferrotick-home");
+        let warehouse = Warehouse::open(WarehouseConfig {
+            db_path: ferrotick_home.join("cache").join("warehouse.duckdb"),
+            ferrotick_home,
+            max_pool_size: 2,
+        })
+        .expect("warehouse open");
+
+        let bar = |symbol: &str, day: &str| BarRecord {
+            symbol: symbol.to_string(),
+            ts: format!("{day}T00:00:00Z"),
+            open: 1.0,
+            high: 2.0,
+            low: 0.5,
+            close: 1.5,
+            volume: Some(100),
+        };
+        warehouse
+            .ingest_bars(
+                "test",
+                "bars_1d",
+                "req-export",
+                &[
+                    bar("AAPL", "2026-02-16"),
+                    bar("AAPL", "2026-02-17"),
+                    bar("MSFT", "2026-02-17"),
+                ],
+                10,
+            )
+            .expect("ingest");
+
+        let sql = "SELECT symbol, ts, close FROM bars_1d WHERE symbol = $symbol ORDER BY ts";
+        let aapl = QueryParams::from([(String::from("symbol"), QueryParam::from("AAPL"))]);
+        let parquet = temp.path().join("out").join("aapl.parquet");
+        let report = warehouse
+            .export_query(
+                sql,
+                &aapl,
+                &parquet,
+                &ExportOptions::new(ExportFormat::Parquet)
+                    .with_compression(ExportCompression::Zstd)
+                    .with_row_group_size(1),
+            )
+            .expect("parquet export");
+        assert_eq!(report.rows, 2);
+        let read_back = warehouse
+            .execute_query(
+                &format!(
+                    "SELECT typeof(ts), count(*) FROM read_parquet('{}') GROUP BY 1",
+                    path_to_sql(&parquet)
+                ),
+                QueryGuardrails::default(),
+                false,
+            )
+            .expect("read parquet");
+        assert_eq!(
+            read_back.rows,
+            vec![vec![Value::from("TIMESTAMP"), Value::from(2)]]
+        );
+
+        let by_symbol = temp.path().join("by_symbol");
+        let report = warehouse
+            .export_query(
+                "SELECT * FROM bars_1d",
+                &QueryParams::new(),
+                &by_symbol,
+                &ExportOptions::new(ExportFormat::Ndjson)
+                    .with_partition_by(ExportPartition::Symbol),
+            )
+            .expect("ndjson export");
+        assert_eq!(report.rows, 3);
+        assert_eq!(report.files.len(), 2);
+        assert!(report.files[0].contains("symbol=AAPL"));
+        let lines = fs::read_to_string(&report.files[0]).expect("read ndjson");
+        assert_eq!(lines.lines().count(), 2);
+        assert!(lines.contains(r#""ts":"2026-02-16T00:00:00""#));
+
+        let by_date = temp.path().join("by_date");
+        let report = warehouse
+            .export_query(
+                "SELECT symbol, ts, volume FROM bars_1d",
+                &QueryParams::new(),
+                &by_date,
+                &ExportOptions::new(ExportFormat::Arrow).with_partition_by(ExportPartition::Date),
+            )
+            .expect("arrow export");
+        assert_eq!(report.rows, 3);
+        assert!(report.files[1].contains("date=2026-02-17"));
+        let reader = FileReader::try_new(File::open(&report.files[1]).expect("open"), None)
+            .expect("ipc reader");
+        let schema = reader.schema();
+        assert_eq!(
+            schema.field_with_name("ts").expect("ts").data_type(),
+            &DataType::Timestamp(TimeUnit::Microsecond, None)
+        );
+        assert!(schema.field_with_name("date").is_err());
+        let rows: usize = reader.map(|batch| batch.expect("batch").num_rows()).sum();
+        assert_eq!(rows, 2);
+
+        let limited = temp.path().join("limited");
+        let report = warehouse
+            .export_query(
+                "SELECT symbol, ts, close FROM bars_1d",
+                &QueryParams::new(),
+                &limited,
+                &ExportOptions::new(ExportFormat::Ndjson)
+                    .with_partition_by(ExportPartition::Symbol)
+                    .with_max_rows(2),
+            )
+            .expect("limited export");
+        assert_eq!(report.rows, 2);
+        assert_eq!(report.files.len(), 1);
+        assert!(report.files[0].contains("symbol=AAPL"));
+
+        let rejected = warehouse.export_query(
+            "SELECT 1 AS x",
+            &QueryParams::new(),
+            &temp.path().join("x.arrow"),
+            &ExportOptions::new(ExportFormat::Arrow).with_compression(ExportCompression::Zstd),
+        );
+        assert!(matches!(rejected, Err(WarehouseError::QueryRejected(_))));
+    }
+}
@@ -0,0 +1,310 @@
+//! Reading vendor CSV and Parquet files for bulk imports.
+//!
+//! [`Warehouse::scan_files`] reads every file matching a path or glob through
+//! DuckDB's `read_csv` / `read_parquet` and hands the values over as text, in
+//! file order and in chunks, so the caller can map, validate and reject rows
+//! one by one without holding every file in memory. [`Warehouse::scan_columns`]
+//! reads only the header, so columns can be resolved before the first row.
+//! [`Warehouse::bar_keys`] returns the timestamps already stored for a
+//! symbol, which imports use to skip rows the warehouse already has.
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::str::FromStr;
+
+use ::duckdb::ToSql;
+use serde::Serialize;
+
+use crate::duckdb::AccessMode;
+use crate::{bars_interval, escape_sql_string, Warehouse, WarehouseError};
+
+/// File format of an import.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportFileFormat {
+    Csv,
+    Parquet,
+}
+
+impl ImportFileFormat {
+    /// Format implied by the extension of `pattern`; CSV unless it ends in
+    /// `.parquet`.
+    pub fn from_path(pattern: &str) -> Self {
+        if pattern.to_ascii_lowercase().ends_with(".parquet") {
+            Self::Parquet
+        } else {
+            Self::Csv
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Parquet => "parquet",
+        }
+    }
+}
+
+impl fmt::Display for ImportFileFormat {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ImportFileFormat {
+    type Err = WarehouseError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "csv" => Ok(Self::Csv),
+            "parquet" => Ok(Self::Parquet),
+            other => Err(WarehouseError::QueryRejected(format!(
+                "unknown import file format '{other}', expected csv or parquet"
+            ))),
+        }
+    }
+}
+
+/// Files read by [`Warehouse::scan_files`].
+#[derive(Debug, Clone, Default)]
+pub struct FileScan {
+    /// Column names shared by every file.
+    pub columns: Vec<String>,
+    /// Files that contributed rows, in scan order.
+    pub files: Vec<String>,
+    /// Rows read across all files.
+    pub rows: usize,
+}
+
+impl FileScan {
+    /// Position of `name` in [`FileScan::columns`], ignoring case.
+    pub fn column_index(&self, name: &str) -> Option<usize> {
+        self.columns
+            .iter()
+            .position(|column| column.eq_ignore_ascii_case(name))
+    }
+}
+
+/// One row of a scanned file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScannedRow {
+    pub file: String,
+    /// 1-based data row within `file`, not counting a CSV header.
+    pub row: usize,
+    /// Values as text, `None` for NULL or an empty CSV field.
+    pub values: Vec<Option<String>>,
+}
+
+impl Warehouse {
+    /// Column names of the files matching `pattern`, a path or glob,
+    /// without reading their rows.
+    pub fn scan_columns(
+        &self,
+        pattern: &str,
+        format: ImportFileFormat,
+    ) -> Result<FileScan, WarehouseError> {
+        let connection = self.manager.acquire(AccessMode::ReadOnly)?;
+        let mut statement = connection.prepare(&format!(
+            "SELECT COLUMNS(*)::VARCHAR FROM {} LIMIT 0",
+            scan_source(pattern, format)
+        ))?;
+        let rows = statement.query([])?;
+        Ok(FileScan {
+            columns: rows.as_ref().map(value_columns).unwrap_or_default(),
+            ..FileScan::default()
+        })
+    }
+
+    /// Read every row of the files matching `pattern`, a path or glob,
+    /// handing them to `on_chunk` at most `chunk_rows` at a time.
+    ///
+    /// CSV files must have a header row. Values come back as text in the
+    /// file's own formatting, so timestamps keep their vendor timezone.
+    pub fn scan_files<E: From<WarehouseError>>(
+        &self,
+        pattern: &str,
+        format: ImportFileFormat,
+        chunk_rows: usize,
+        mut on_chunk: impl FnMut(Vec<ScannedRow>) -> Result<(), E>,
+    ) -> Result<FileScan, E> {
+        let failed = |error: ::duckdb::Error| E::from(WarehouseError::from(error));
+        let connection = self.manager.acquire(AccessMode::ReadOnly).map_err(failed)?;
+        let mut statement = connection
+            .prepare(&format!(
+                "SELECT COLUMNS(*)::VARCHAR FROM {}",
+                scan_source(pattern, format)
+            ))
+            .map_err(failed)?;
+        let mut rows = statement.query([]).map_err(failed)?;
+        let mut scan = FileScan {
+            columns: rows.as_ref().map(value_columns).unwrap_or_default(),
+            ..FileScan::default()
+        };
+        // `filename` is added last by DuckDB.
+        let file_column = scan.columns.len();
+
+        let chunk_rows = chunk_rows.max(1);
+        let mut chunk = Vec::with_capacity(chunk_rows);
+        let mut row_in_file = 0;
+        while let Some(row) = rows.next().map_err(failed)? {
+            let file: String = row.get(file_column).map_err(failed)?;
+            if scan.files.last() != Some(&file) {
+                scan.files.push(file.clone());
+                row_in_file = 0;
+            }
+            row_in_file += 1;
+            let values = (0..file_column)
+                .map(|index| {
+                    row.get::<_, Option<String>>(index)
+                        .map(|value| value.filter(|value| !value.trim().is_empty()))
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(failed)?;
+            chunk.push(ScannedRow {
+                file,
+                row: row_in_file,
+                values,
+            });
+            scan.rows += 1;
+            if chunk.len() == chunk_rows {
+                on_chunk(std::mem::replace(
+                    &mut chunk,
+                    Vec::with_capacity(chunk_rows),
+                ))?;
+            }
+        }
+        if !chunk.is_empty() {
+            on_chunk(chunk)?;
+        }
+        Ok(scan)
+    }
+
+    /// Timestamps, as microseconds since the Unix epoch, of the `symbol`
+    /// bars stored in `dataset`.
+    pub fn bar_keys(&self, dataset: &str, symbol: &str) -> Result<BTreeSet<i64>, WarehouseError> {
+        let Some(interval) = bars_interval(dataset) else {
+            return Err(WarehouseError::QueryRejected(format!(
+                "unsupported bars dataset '{dataset}'"
+            )));
+        };
+
+        // Read through the writer so keys ingested moments ago are visible.
+        let connection = self.manager.acquire(AccessMode::ReadWrite)?;
+        let mut statement = connection
+            .prepare("SELECT epoch_us(ts) FROM bars WHERE interval = ? AND symbol = ?")?;
+        let params: [&dyn ToSql; 2] = [&interval, &symbol];
+        let keys = statement
+            .query_map(params.as_slice(), |row| row.get::<_, i64>(0))?
+            .collect::<Result<BTreeSet<_>, _>>()?;
+        Ok(keys)
+    }
+}
+
+/// Table function reading the files matching `pattern` with their file names.
+fn scan_source(pattern: &str, format: ImportFileFormat) -> String {
+    let pattern = escape_sql_string(&pattern.replace('\\', "/"));
+    match format {
+        ImportFileFormat::Csv => {
+            format!("read_csv('{pattern}', header = true, all_varchar = true, filename = true)")
+        }
+        ImportFileFormat::Parquet => format!("read_parquet('{pattern}', filename = true)"),
+    }
+}
+
+/// Column names of a scan, without the `filename` column DuckDB adds last.
+fn value_columns(statement: &::duckdb::Statement<'_>) -> Vec<String> {
+    let mut columns = statement.column_names();
+    columns.pop();
+    columns
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BarRecord, WarehouseConfig};
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn scans_csv_globs_as_text_and_lists_stored_bar_keys() {
+        let temp = tempdir().expect("tempdir");
+        let ferrotick_home = temp.path().join("ferrotick-home");
+        let warehouse = Warehouse::open(WarehouseConfig {
+            db_path: ferrotick_home.join("cache").join("warehouse.duckdb"),
+            ferrotick_home,
+            max_pool_size: 2,
+        })
+        .expect("warehouse open");
+
+        let vendor = temp.path().join("vendor");
+        fs::create_dir_all(&vendor).expect("vendor dir");
+        fs::write(
+            vendor.join("a.csv"),
+            "Ticker,Date,Close\nAAPL,2024-01-02 09:30,185.5\nAAPL,2024-01-03 09:30,\n",
+        )
+        .expect("write a");
+        fs::write(
+            vendor.join("b.csv"),
+            "Ticker,Date,Close\nMSFT,2024-01-02 09:30,370\n",
+        )
+        .expect("write b");
+
+        let pattern = vendor.join("*.csv").display().to_string();
+        assert_eq!(ImportFileFormat::from_path(&pattern), ImportFileFormat::Csv);
+        let header = warehouse
+            .scan_columns(&pattern, ImportFileFormat::Csv)
+            .expect("scan columns");
+        assert_eq!(header.columns, vec!["Ticker", "Date", "Close"]);
+        assert_eq!(header.column_index("close"), Some(2));
+        assert_eq!(header.rows, 0);
+
+        let mut chunks = Vec::new();
+        let scan = warehouse
+            .scan_files(&pattern, ImportFileFormat::Csv, 2, |chunk| {
+                chunks.push(chunk);
+                Ok::<_, WarehouseError>(())
+            })
+            .expect("scan");
+        assert_eq!(scan.columns, header.columns);
+        assert_eq!(scan.files.len(), 2);
+        assert_eq!(scan.rows, 3);
+        assert_eq!(chunks.iter().map(Vec::len).collect::<Vec<_>>(), vec![2, 1]);
+        let rows = chunks.concat();
+        assert_eq!(
+            rows[1].values,
+            vec![
+                Some(String::from("AAPL")),
+                Some(String::from("2024-01-03 09:30")),
+                None
+            ]
+        );
+        assert_eq!((rows[1].row, rows[2].row), (2, 1));
+        assert!(rows[2].file.ends_with("b.csv"));
+
+        warehouse
+            .ingest_bars(
+                "test",
+                "bars_1d",
+                "req-keys",
+                &[BarRecord {
+                    symbol: String::from("AAPL"),
+                    ts: String::from("1970-01-01T00:00:01Z"),
+                    open: 1.0,
+                    high: 1.0,
+                    low: 1.0,
+                    close: 1.0,
+                    volume: None,
+                }],
+                10,
+            )
+            .expect("ingest");
+        assert_eq!(
+            warehouse.bar_keys("bars_1d", "AAPL").expect("keys"),
+            BTreeSet::from([1_000_000])
+        );
+        assert!(warehouse
+            .bar_keys("bars_1d", "MSFT")
+            .expect("keys")
+            .is_empty());
+    }
+}
@@ -13,6 +13,7 @@
 //! - 📊 **Analytical Queries**: Fast aggregations and complex queries via DuckDB
 //! - 🔄 **Connection Pooling**: Efficient connection management
 //! - ⚡ **Query Guardrails**: Timeout and row limits for safety
+//! - 🧱 **SQL Sandbox**: Isolated, allowlisted execution of untrusted queries
 //! - 📦 **Parquet Integration**: Sync local parquet cache with warehouse
 //!
 //! ## Quick Start
@@ -68,12 +69,26 @@
 //! | Table | Description |
 //! |-------|-------------|
 //! | `quotes_latest` | Latest quotes by symbol |
-//! | `bars_1m` | Minute bars |
-//! | `bars_1d` | Daily bars |
+//! | `bars` | OHLCV bars keyed by symbol, interval and timestamp |
+//! | `bars_1m`, `bars_1d`, ... | Views over `bars`, one per interval |
 //! | `fundamentals` | Company fundamentals |
-//! | `instruments` | Instrument metadata |
+//! | `financial_statements` | Statement line items by period |
+//! | `earnings` | Earnings results with report dates |
+//! | `corporate_actions` | Dividends, splits and other actions by ex-date |
+//! | `instruments` | Instrument master with cross-provider identifiers |
+//! | `ticker_history` | Symbols each instrument traded under, by date |
+//! | `instrument_aliases` | Provider-specific symbol aliases |
+//! | `trade_ticks` | Trade prints |
+//! | `quote_ticks` | Top-of-book quote updates |
+//! | `macro_series` | Macro/economic series observations |
+//! | `news` | News articles with overall sentiment |
+//! | `news_tickers` | Per-ticker sentiment and relevance of each article |
 //! | `cache_manifest` | Parquet file tracking |
 //! | `ingest_log` | Ingestion audit log |
+//! | `ingest_batches` | Request, source and ferrotick version of each ingest batch |
+//! | `bars_audit`, `quotes_audit` | Bar and quote values overwritten by a later batch |
+//! | `universes`, `universe_members` | Named universes and their membership intervals |
+//! | `empty_bar_sessions` | Weekdays fetched without any bars, such as holidays |
 //!
 //! ## Views
 //!
@@ -82,24 +97,57 @@
 //! | `v_daily_bars` | Daily OHLCV data with metadata |
 //! | `v_quote_history` | Historical quote snapshots |
 //! | `v_fundamentals` | Company fundamentals with metadata |
+//! | `rolling_beta_v1(...)`, `vw_drawdown_v1`, ... | Versioned analytics listed in [`ANALYTICS_CATALOG`] |
 
 pub mod duckdb;
+pub mod export;
+pub mod import;
+pub mod lineage;
 pub mod migrations;
+pub mod queries;
+pub mod retention;
+pub mod sandbox;
+pub mod snapshot;
+pub mod universes;
 pub mod views;
 
+use std::collections::{BTreeSet, HashMap};
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant};
 
 use ::duckdb::types::Value as DuckValue;
 use ::duckdb::Connection;
+use ::duckdb::OptionalExt;
 use ::duckdb::ToSql;
+use ferrotick_telemetry::metrics;
 use serde::Serialize;
 use serde_json::{Number, Value};
+use sha2::{Digest, Sha256};
 use thiserror::Error;
 
 pub use duckdb::{AccessMode, DuckDbConnectionManager, PooledConnection};
+pub use export::{ExportCompression, ExportFormat, ExportOptions, ExportPartition, ExportReport};
+pub use import::{FileScan, ImportFileFormat, ScannedRow};
+pub use lineage::{
+    set_binary_version, BarLineage, BatchChanges, IngestBatch, UNKNOWN_BINARY_VERSION,
+};
+pub use migrations::{MigrationDirection, MigrationReport, MigrationStatus};
+pub use queries::{NamedQuery, QueryParam, QueryParamSpec, QueryParamType, QueryParams};
+pub use retention::{
+    CacheCompactionReport, RetentionPolicy, INGEST_LOG_DATASET, QUOTES_AUDIT_DATASET,
+};
+pub use sandbox::{SandboxRule, SqlSandbox};
+pub use snapshot::{RestoreReport, SnapshotFile, SnapshotManifest, SnapshotReport};
+pub use universes::{UniverseMember, UniverseMembership, UniverseSummary};
+pub use views::{AnalyticsKind, AnalyticsObject, AnalyticsParameter, ANALYTICS_CATALOG};
+
+/// Bar intervals stored in the `bars` table; dataset names are `bars_<interval>`.
+pub const BAR_INTERVALS: [&str; 5] = ["1m", "5m", "15m", "1h", "1d"];
+
+/// Series id of the 3-month T-bill yield used as the default risk-free rate.
+pub const RISK_FREE_SERIES: &str = "TREASURY_YIELD_3MONTH";
 
 /// Errors that can occur during warehouse operations.
 #[derive(Debug, Error)]
@@ -116,9 +164,45 @@ pub enum WarehouseError {
     #[error("query rejected: {0}")]
     QueryRejected(String),
 
+    /// A sandboxed query broke one of the sandbox rules.
+    #[error("query rejected by sandbox rule {rule}: {message}")]
+    Sandbox { rule: SandboxRule, message: String },
+
     /// Query execution timed out.
     #[error("query timed out after {timeout_ms}ms")]
     QueryTimeout { timeout_ms: u64 },
+
+    /// Writing an export file failed.
+    #[error("export failed: {0}")]
+    Export(String),
+
+    /// A migration could not be planned or run.
+    #[error("migration failed: {0}")]
+    Migration(String),
+
+    /// The database was migrated by a newer build.
+    #[error(
+        "database schema {version} is newer than this build, which knows migrations up to \
+         {latest}; upgrade ferrotick, or run `ferrotick warehouse migrate down` with the newer \
+         build first"
+    )]
+    SchemaTooNew { version: String, latest: String },
+
+    /// A snapshot could not be written, verified or restored.
+    #[error("snapshot failed: {0}")]
+    Snapshot(String),
+
+    /// A universe does not exist or a membership change was refused.
+    #[error("universe {0}")]
+    Universe(String),
+
+    /// A cache partition no longer matches the checksum written beside it.
+    #[error("checksum mismatch for {}: expected {expected}, found {actual}", path.display())]
+    ChecksumMismatch {
+        path: PathBuf,
+        expected: String,
+        actual: String,
+    },
 }
 
 /// Configuration for the warehouse database.
@@ -222,6 +306,66 @@ pub struct CacheSyncReport {
     pub failed_partitions: usize,
 }
 
+/// Report from rebuilding warehouse tables out of the parquet cache.
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheRebuildReport {
+    /// Root directory of the cache.
+    pub cache_root: PathBuf,
+    /// Number of partitions scanned.
+    pub scanned_partitions: usize,
+    /// Number of partitions loaded into their table.
+    pub restored_partitions: usize,
+    /// Number of rows loaded across all restored partitions.
+    pub restored_rows: usize,
+    /// Number of partitions skipped (invalid structure or unknown dataset).
+    pub skipped_partitions: usize,
+    /// Number of partitions that failed to load.
+    pub failed_partitions: usize,
+    /// Why each failed partition could not be loaded.
+    pub failures: Vec<PartitionFailure>,
+}
+
+/// A cache partition that could not be loaded, and why.
+#[derive(Debug, Clone, Serialize)]
+pub struct PartitionFailure {
+    pub path: PathBuf,
+    pub error: String,
+}
+
+/// Inclusive range of calendar dates, formatted `YYYY-MM-DD`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DateRange {
+    pub start: String,
+    pub end: String,
+}
+
+/// Which weekdays of a window already have stored bars for one symbol and
+/// interval.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct BarCoverage {
+    /// Runs of consecutive weekdays with at least one stored bar.
+    pub present: Vec<DateRange>,
+    /// Runs of consecutive weekdays without any stored bar.
+    pub missing: Vec<DateRange>,
+    /// Runs of consecutive weekdays already fetched without getting any bar,
+    /// recorded by [`Warehouse::record_empty_sessions`].
+    pub empty: Vec<DateRange>,
+}
+
+/// One scheduled or manual ingestion run recorded in `ingest_log`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IngestRun {
+    pub request_id: String,
+    pub symbol: Option<String>,
+    pub source: String,
+    pub dataset: String,
+    pub status: String,
+    pub latency_ms: Option<i64>,
+    /// Free-form outcome, such as the number of bars loaded or the error.
+    pub detail: Option<String>,
+    pub timestamp: String,
+}
+
 /// A real-time quote record for ingestion.
 #[derive(Debug, Clone)]
 pub struct QuoteRecord {
@@ -273,16 +417,319 @@ pub struct FundamentalRecord {
     pub date: String,
 }
 
+/// A financial statement line item for ingestion.
+#[derive(Debug, Clone)]
+pub struct FinancialRecord {
+    /// Stock symbol.
+    pub symbol: String,
+    /// Statement type (`income`, `balance`, `cash_flow`).
+    pub statement_type: String,
+    /// Statement period (`annual`, `quarterly`).
+    pub period: String,
+    /// Provider line-item label.
+    pub label: String,
+    /// Canonical metric key, if the label is recognised.
+    pub metric: Option<String>,
+    /// Line-item value.
+    pub value: f64,
+    /// Fiscal year, if reported.
+    pub fiscal_year: Option<i32>,
+    /// Fiscal quarter, if reported.
+    pub fiscal_quarter: Option<i32>,
+    /// Period end date as ISO 8601 string.
+    pub end_date: String,
+    /// When the figures were published, as ISO 8601 string, if known.
+    ///
+    /// Falls back to the matching `earnings` report date on ingest.
+    pub available_at: Option<String>,
+    /// Currency code (e.g., "USD").
+    pub currency: String,
+}
+
+/// An earnings result for ingestion.
+#[derive(Debug, Clone)]
+pub struct EarningsRecord {
+    /// Stock symbol.
+    pub symbol: String,
+    /// Fiscal year.
+    pub fiscal_year: i32,
+    /// Fiscal quarter, if reported.
+    pub fiscal_quarter: Option<i32>,
+    /// Fiscal period end as ISO 8601 string.
+    pub period_end: String,
+    /// When the results were published, as ISO 8601 string, if known.
+    pub report_date: Option<String>,
+    /// Reported EPS.
+    pub eps_actual: Option<f64>,
+    /// Consensus EPS estimate.
+    pub eps_estimate: Option<f64>,
+    /// Reported revenue.
+    pub revenue_actual: Option<f64>,
+    /// Consensus revenue estimate.
+    pub revenue_estimate: Option<f64>,
+    /// EPS surprise in percent.
+    pub surprise_percent: Option<f64>,
+}
+
+/// A corporate action for ingestion.
+#[derive(Debug, Clone)]
+pub struct CorporateActionRecord {
+    /// Stock symbol.
+    pub symbol: String,
+    /// Action type (e.g., "dividend", "split"), stored as `type`.
+    pub action_type: String,
+    /// Ex-date as ISO 8601 string, stored as `date`.
+    pub ex_date: String,
+    /// Payment date as ISO 8601 string, if any.
+    pub pay_date: Option<String>,
+    /// Cash amount per share, or split ratio.
+    pub value: Option<f64>,
+    /// Currency code of a cash amount.
+    pub currency: Option<String>,
+}
+
+/// A trade print for ingestion.
+#[derive(Debug, Clone)]
+pub struct TradeTickRecord {
+    /// Stock symbol.
+    pub symbol: String,
+    /// Trade timestamp as ISO 8601 string.
+    pub ts: String,
+    /// Trade price.
+    pub price: f64,
+    /// Trade size in shares.
+    pub size: u64,
+    /// Reporting exchange code.
+    pub exchange: Option<String>,
+    /// Sale condition codes.
+    pub conditions: Vec<String>,
+}
+
+/// An economic series observation for ingestion.
+#[derive(Debug, Clone)]
+pub struct MacroObservationRecord {
+    /// Canonical series identifier (e.g. `TREASURY_YIELD_3MONTH`).
+    pub series_id: String,
+    /// Observation date as ISO 8601 string.
+    pub date: String,
+    /// Observed value.
+    pub value: f64,
+    /// Unit of the value (e.g. `percent`).
+    pub unit: Option<String>,
+    /// Publication frequency (e.g. `daily`, `monthly`).
+    pub frequency: Option<String>,
+}
+
+/// A news article for ingestion.
+#[derive(Debug, Clone)]
+pub struct NewsRecord {
+    /// Article URL, used as the article key.
+    pub url: String,
+    /// Publication timestamp as ISO 8601 string.
+    pub published_at: String,
+    /// Publisher name.
+    pub publisher: String,
+    /// Headline.
+    pub title: String,
+    /// Article summary.
+    pub summary: Option<String>,
+    /// Overall sentiment score, roughly in `[-1, 1]`.
+    pub sentiment: Option<f64>,
+    /// Tickers mentioned by the article.
+    pub tickers: Vec<NewsTickerRecord>,
+}
+
+/// Sentiment of a news article towards one ticker.
+#[derive(Debug, Clone)]
+pub struct NewsTickerRecord {
+    /// Stock symbol.
+    pub symbol: String,
+    /// Ticker-specific sentiment score.
+    pub sentiment: Option<f64>,
+    /// Relevance of the article to the ticker, in `[0, 1]`.
+    pub relevance: Option<f64>,
+}
+
+/// News sentiment aggregated per symbol and day.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DailySentiment {
+    /// Stock symbol.
+    pub symbol: String,
+    /// UTC day as `YYYY-MM-DD`.
+    pub date: String,
+    /// Number of articles mentioning the symbol that day.
+    pub article_count: i64,
+    /// Mean sentiment, falling back to article sentiment per ticker.
+    pub avg_sentiment: Option<f64>,
+    /// Relevance-weighted ticker sentiment.
+    pub weighted_sentiment: Option<f64>,
+}
+
+/// A top-of-book quote update for ingestion.
+#[derive(Debug, Clone)]
+pub struct QuoteTickRecord {
+    /// Stock symbol.
+    pub symbol: String,
+    /// Quote timestamp as ISO 8601 string.
+    pub ts: String,
+    /// Best bid price.
+    pub bid: f64,
+    /// Size at the best bid.
+    pub bid_size: u64,
+    /// Best ask price.
+    pub ask: f64,
+    /// Size at the best ask.
+    pub ask_size: u64,
+    /// Exchange posting the bid.
+    pub bid_exchange: Option<String>,
+    /// Exchange posting the ask.
+    pub ask_exchange: Option<String>,
+}
+
+/// Instrument master data for ingestion.
+///
+/// `None` fields leave any previously stored value untouched.
+#[derive(Debug, Clone, Default)]
+pub struct InstrumentRecord {
+    /// Symbol as reported by the source.
+    pub symbol: String,
+    /// Display name.
+    pub name: Option<String>,
+    /// Listing exchange.
+    pub exchange: Option<String>,
+    /// Currency code (e.g., "USD").
+    pub currency: Option<String>,
+    /// Asset class (e.g., "equity").
+    pub asset_class: Option<String>,
+    /// Whether the symbol is currently listed.
+    pub is_active: Option<bool>,
+    /// ISIN.
+    pub isin: Option<String>,
+    /// CUSIP.
+    pub cusip: Option<String>,
+    /// Composite FIGI; used to detect ticker changes and aliases.
+    pub figi: Option<String>,
+    /// SEC Central Index Key.
+    pub cik: Option<String>,
+    /// Sector classification.
+    pub sector: Option<String>,
+    /// Industry classification.
+    pub industry: Option<String>,
+    /// Listing date as ISO 8601 string.
+    pub listed_at: Option<String>,
+    /// Delisting date as ISO 8601 string.
+    pub delisted_at: Option<String>,
+}
+
+/// A fundamental value as it was known at a point in time.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PointInTimeFundamental {
+    /// Stock symbol.
+    pub symbol: String,
+    /// `snapshot` for fundamentals, otherwise the statement period.
+    pub basis: String,
+    /// `fundamentals` or the statement type the value came from.
+    pub dataset: String,
+    /// Metric name (e.g., "revenue", "market_cap").
+    pub metric: String,
+    /// Metric value.
+    pub value: f64,
+    /// Fiscal period end, if the value belongs to a reporting period.
+    pub period_end: Option<String>,
+    /// When the value became public.
+    pub available_at: String,
+}
+
+/// A fundamental value joined as-of a daily bar.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AsOfFundamentalValue {
+    /// Bar timestamp.
+    pub ts: String,
+    /// Latest value public at `ts`, if any.
+    pub value: Option<f64>,
+    /// Fiscal period end of the joined value.
+    pub period_end: Option<String>,
+    /// When the joined value became public.
+    pub available_at: Option<String>,
+}
+
 /// Internal representation of a cache partition.
+///
+/// Daily partitions live under `date=YYYY-MM-DD`; compacted ones under
+/// `month=YYYY-MM`, with `partition_date` set to the first of the month.
 #[derive(Debug, Clone)]
 struct CachePartition {
     source: String,
     dataset: String,
     symbol: String,
     partition_date: String,
+    monthly: bool,
     path: PathBuf,
 }
 
+/// File name of the partition written by ingest inside each `date=` directory.
+const PARTITION_FILE_NAME: &str = "data.parquet";
+
+/// Suffix of the sidecar holding a published partition's SHA-256.
+const CHECKSUM_SUFFIX: &str = ".sha256";
+
+impl CachePartition {
+    /// Partition written by ingest for one source, dataset, symbol and day.
+    fn owned(
+        cache_root: &Path,
+        source: &str,
+        dataset: &str,
+        symbol: &str,
+        partition_date: &str,
+    ) -> Result<Self, WarehouseError> {
+        let path = cache_root
+            .join(format!("source={}", partition_component(source)?))
+            .join(format!("dataset={}", partition_component(dataset)?))
+            .join(format!("symbol={}", partition_component(symbol)?))
+            .join(format!("date={}", partition_component(partition_date)?))
+            .join(PARTITION_FILE_NAME);
+        Ok(Self {
+            source: source.to_string(),
+            dataset: dataset.to_string(),
+            symbol: symbol.to_string(),
+            partition_date: partition_date.to_string(),
+            monthly: false,
+            path,
+        })
+    }
+
+    /// Compacted partition holding a whole `YYYY-MM` month.
+    fn monthly(
+        cache_root: &Path,
+        source: &str,
+        dataset: &str,
+        symbol: &str,
+        month: &str,
+    ) -> Result<Self, WarehouseError> {
+        let path = cache_root
+            .join(format!("source={}", partition_component(source)?))
+            .join(format!("dataset={}", partition_component(dataset)?))
+            .join(format!("symbol={}", partition_component(symbol)?))
+            .join(format!("month={}", partition_component(month)?))
+            .join(PARTITION_FILE_NAME);
+        Ok(Self {
+            source: source.to_string(),
+            dataset: dataset.to_string(),
+            symbol: symbol.to_string(),
+            partition_date: format!("{month}-01"),
+            monthly: true,
+            path,
+        })
+    }
+
+    /// `YYYY-MM` month the partition belongs to.
+    fn month(&self) -> &str {
+        self.partition_date
+            .get(..7)
+            .unwrap_or(self.partition_date.as_str())
+    }
+}
+
 /// The main warehouse interface for market data storage.
 #[derive(Clone)]
 pub struct Warehouse {
@@ -313,6 +760,9 @@ impl Warehouse {
         let connection = self.manager.acquire(AccessMode::ReadWrite)?;
         migrations::apply_migrations(&connection)?;
         views::create_views(&connection)?;
+        // DuckDB cannot replay table macros from the WAL when another
+        // connection opens the file, so persist the schema right away.
+        connection.execute_batch("CHECKPOINT")?;
         Ok(())
     }
 
@@ -358,6 +808,20 @@ impl Warehouse {
         sql: &str,
         guardrails: QueryGuardrails,
         allow_write: bool,
+    ) -> Result<QueryResult, WarehouseError> {
+        self.execute_query_with_params(sql, &QueryParams::new(), guardrails, allow_write)
+    }
+
+    /// Execute a SQL query with `$name` placeholders bound to `params`.
+    ///
+    /// Every placeholder needs a value and every value a placeholder. Values
+    /// are bound, never interpolated, so they are safe to take from users.
+    pub fn execute_query_with_params(
+        &self,
+        sql: &str,
+        params: &QueryParams,
+        guardrails: QueryGuardrails,
+        allow_write: bool,
     ) -> Result<QueryResult, WarehouseError> {
         guardrails.validate()?;
         let sql = normalize_sql(sql)?;
@@ -372,12 +836,46 @@ impl Warehouse {
             AccessMode::ReadOnly
         };
         let connection = self.manager.acquire(mode)?;
-        execute_with_guardrails(&connection, sql, guardrails, allow_write)
+
+        let span = tracing::info_span!(
+            "warehouse_query",
+            read_only = !allow_write,
+            rows = tracing::field::Empty
+        )
+        .entered();
+        let started = Instant::now();
+        let result = execute_with_guardrails(&connection, sql, params, guardrails, allow_write);
+        let outcome = match &result {
+            Ok(query) => {
+                span.record("rows", query.row_count);
+                "ok"
+            }
+            Err(_) => "error",
+        };
+        metrics::WAREHOUSE_QUERY_DURATION.observe(&[("outcome", outcome)], started.elapsed());
+        result
+    }
+
+    /// Root of the parquet partition cache (`<home>/cache/parquet`).
+    pub fn cache_root(&self) -> PathBuf {
+        self.config.ferrotick_home.join("cache").join("parquet")
     }
 
     /// Synchronize parquet cache files with the database manifest.
     pub fn sync_cache(&self) -> Result<CacheSyncReport, WarehouseError> {
-        let cache_root = self.config.ferrotick_home.join("cache").join("parquet");
+        self.sync_cache_partitions(|_| true)
+    }
+
+    /// Like [`Self::sync_cache`], but only for the partitions of `symbols`.
+    pub fn sync_cache_symbols(&self, symbols: &[&str]) -> Result<CacheSyncReport, WarehouseError> {
+        self.sync_cache_partitions(|partition| symbols.contains(&partition.symbol.as_str()))
+    }
+
+    fn sync_cache_partitions(
+        &self,
+        wanted: impl Fn(&CachePartition) -> bool,
+    ) -> Result<CacheSyncReport, WarehouseError> {
+        let cache_root = self.cache_root();
         let mut report = CacheSyncReport {
             cache_root: cache_root.clone(),
             scanned_partitions: 0,
@@ -394,8 +892,15 @@ impl Warehouse {
         collect_parquet_files(cache_root.as_path(), &mut files)?;
 
         for path in files {
+            let partition = parse_partition(path.as_path());
+            if partition
+                .as_ref()
+                .is_some_and(|partition| !wanted(partition))
+            {
+                continue;
+            }
             report.scanned_partitions += 1;
-            let Some(partition) = parse_partition(path.as_path()) else {
+            let Some(partition) = partition else {
                 report.skipped_partitions += 1;
                 continue;
             };
@@ -409,6 +914,66 @@ impl Warehouse {
         Ok(report)
     }
 
+    /// Rebuild warehouse tables from the parquet cache.
+    ///
+    /// Loads every bars partition under [`Self::cache_root`] into `bars` and
+    /// registers it in `cache_manifest`, so a fresh warehouse can be
+    /// restored from a copied cache directory. Rows are upserted, so running
+    /// it against a populated warehouse is safe.
+    ///
+    /// A partition whose contents no longer match its checksum sidecar is
+    /// reported as failed and not loaded. Partitions published before
+    /// sidecars were written have none and load unverified.
+    pub fn rebuild_from_cache(&self) -> Result<CacheRebuildReport, WarehouseError> {
+        let cache_root = self.cache_root();
+        let mut report = CacheRebuildReport {
+            cache_root: cache_root.clone(),
+            scanned_partitions: 0,
+            restored_partitions: 0,
+            restored_rows: 0,
+            skipped_partitions: 0,
+            failed_partitions: 0,
+            failures: Vec::new(),
+        };
+
+        if !cache_root.exists() {
+            return Ok(report);
+        }
+
+        let mut files = Vec::new();
+        collect_parquet_files(cache_root.as_path(), &mut files)?;
+        files.sort();
+
+        for path in files {
+            report.scanned_partitions += 1;
+            let Some((partition, interval)) =
+                parse_partition(path.as_path()).and_then(|partition| {
+                    let interval = bars_interval(&partition.dataset)?;
+                    Some((partition, interval))
+                })
+            else {
+                report.skipped_partitions += 1;
+                continue;
+            };
+
+            match self.restore_partition(&partition, interval) {
+                Ok(rows) => {
+                    report.restored_partitions += 1;
+                    report.restored_rows += rows;
+                }
+                Err(error) => {
+                    report.failed_partitions += 1;
+                    report.failures.push(PartitionFailure {
+                        path,
+                        error: error.to_string(),
+                    });
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
     /// Ingest real-time quote data using parameterized queries.
     ///
     /// # Security
@@ -428,10 +993,19 @@ impl Warehouse {
         let connection = self.manager.acquire(AccessMode::ReadWrite)?;
         connection.execute_batch("BEGIN TRANSACTION")?;
         let result = (|| -> Result<(), WarehouseError> {
+            let batch_id = lineage::open_batch(
+                &connection,
+                request_id,
+                source,
+                "quote",
+                &lineage::quotes_digest(rows),
+            )?;
             for row in rows {
+                lineage::audit_quote(&connection, row, &batch_id)?;
+
                 // Use parameterized query for quotes_latest insert
                 // SECURITY: All user-provided values are passed as parameters, not interpolated
-                let params: [&dyn ToSql; 7] = [
+                let params: [&dyn ToSql; 8] = [
                     &row.symbol,
                     &row.price,
                     &row.bid,
@@ -439,29 +1013,29 @@ impl Warehouse {
                     &row.volume,
                     &row.as_of,
                     &source,
+                    &batch_id,
                 ];
                 connection.execute(
                     "INSERT OR REPLACE INTO quotes_latest \
-                     (symbol, price, bid, ask, volume, as_of, source, updated_at) \
-                     VALUES (?, ?, ?, ?, ?, TRY_CAST(? AS TIMESTAMP), ?, CURRENT_TIMESTAMP)",
+                     (symbol, price, bid, ask, volume, as_of, source, ingest_batch, updated_at) \
+                     VALUES (?, ?, ?, ?, ?, TRY_CAST(? AS TIMESTAMP), ?, ?, CURRENT_TIMESTAMP)",
                     params.as_slice(),
                 )?;
 
-                // Use parameterized query for instruments insert
-                let params: [&dyn ToSql; 4] = [&row.symbol, &row.symbol, &row.currency, &source];
-                connection.execute(
-                    "INSERT OR IGNORE INTO instruments \
-                     (symbol, name, exchange, currency, asset_class, is_active, source, updated_at) \
-                     VALUES (?, ?, NULL, ?, 'equity', TRUE, ?, CURRENT_TIMESTAMP)",
-                    params.as_slice(),
-                )?;
+                let instrument = InstrumentRecord {
+                    symbol: row.symbol.clone(),
+                    currency: Some(row.currency.clone()),
+                    ..InstrumentRecord::default()
+                };
+                upsert_instrument(&connection, source, &instrument)?;
 
                 // Use parameterized query for ingest_log insert
-                let params: [&dyn ToSql; 4] = [&request_id, &row.symbol, &source, &latency_ms];
+                let params: [&dyn ToSql; 5] =
+                    [&request_id, &row.symbol, &source, &latency_ms, &batch_id];
                 connection.execute(
                     "INSERT INTO ingest_log \
-                     (request_id, symbol, source, dataset, status, latency_ms, timestamp) \
-                     VALUES (?, ?, ?, 'quote', 'ok', ?, CURRENT_TIMESTAMP)",
+                     (request_id, symbol, source, dataset, status, latency_ms, batch_id, timestamp) \
+                     VALUES (?, ?, ?, 'quote', 'ok', ?, ?, CURRENT_TIMESTAMP)",
                     params.as_slice(),
                 )?;
             }
@@ -474,6 +1048,8 @@ impl Warehouse {
 
     /// Ingest bar (OHLCV) data using parameterized queries.
     ///
+    /// `dataset` is `bars_<interval>` for any of [`BAR_INTERVALS`].
+    ///
     /// # Security
     /// Uses parameterized queries to prevent SQL injection.
     /// All user-provided values are passed as query parameters.
@@ -489,32 +1065,32 @@ impl Warehouse {
             return Ok(());
         }
 
-        let table = match dataset {
-            "bars_1m" => "bars_1m",
-            "bars_1d" => "bars_1d",
-            other => {
-                return Err(WarehouseError::QueryRejected(format!(
-                    "unsupported bars dataset '{other}'"
-                )))
-            }
+        let Some(interval) = bars_interval(dataset) else {
+            return Err(WarehouseError::QueryRejected(format!(
+                "unsupported bars dataset '{dataset}'"
+            )));
         };
 
         let connection = self.manager.acquire(AccessMode::ReadWrite)?;
+        let mut staged = Vec::new();
         connection.execute_batch("BEGIN TRANSACTION")?;
         let result = (|| -> Result<(), WarehouseError> {
+            // (symbol, day) partitions whose parquet files must be rewritten.
+            let mut touched: BTreeSet<(String, String)> = BTreeSet::new();
+            let batch_id = lineage::open_batch(
+                &connection,
+                request_id,
+                source,
+                dataset,
+                &lineage::bars_digest(rows),
+            )?;
             for row in rows {
-                // Build the INSERT statement with the validated table name
-                // Table name is validated above (only "bars_1m" or "bars_1d" allowed)
-                let insert_sql = format!(
-                    "INSERT OR REPLACE INTO {table} \
-                     (symbol, ts, open, high, low, close, volume, source, updated_at) \
-                     VALUES (?, TRY_CAST(? AS TIMESTAMP), ?, ?, ?, ?, ?, ?, CURRENT_TIMESTAMP)",
-                    table = table
-                );
+                lineage::audit_bar(&connection, interval, row, &batch_id)?;
 
                 // SECURITY: All user-provided values are passed as parameters
-                let params: [&dyn ToSql; 8] = [
+                let params: [&dyn ToSql; 10] = [
                     &row.symbol,
+                    &interval,
                     &row.ts,
                     &row.open,
                     &row.high,
@@ -522,24 +1098,282 @@ impl Warehouse {
                     &row.close,
                     &row.volume,
                     &source,
+                    &batch_id,
                 ];
-                connection.execute(insert_sql.as_str(), params.as_slice())?;
+                let partition_date: String = connection.query_row(
+                    "INSERT OR REPLACE INTO bars \
+                     (symbol, interval, ts, open, high, low, close, volume, source, ingest_batch, \
+                      updated_at) \
+                     VALUES (?, ?, TRY_CAST(? AS TIMESTAMP), ?, ?, ?, ?, ?, ?, ?, CURRENT_TIMESTAMP) \
+                     RETURNING CAST(CAST(ts AS DATE) AS VARCHAR)",
+                    params.as_slice(),
+                    |row| row.get(0),
+                )?;
+                touched.insert((row.symbol.clone(), partition_date));
 
                 // Use parameterized query for ingest_log
-                let params: [&dyn ToSql; 5] =
-                    [&request_id, &row.symbol, &source, &dataset, &latency_ms];
+                let params: [&dyn ToSql; 6] = [
+                    &request_id,
+                    &row.symbol,
+                    &source,
+                    &dataset,
+                    &latency_ms,
+                    &batch_id,
+                ];
                 connection.execute(
                     "INSERT INTO ingest_log \
-                     (request_id, symbol, source, dataset, status, latency_ms, timestamp) \
-                     VALUES (?, ?, ?, ?, 'ok', ?, CURRENT_TIMESTAMP)",
+                     (request_id, symbol, source, dataset, status, latency_ms, batch_id, timestamp) \
+                     VALUES (?, ?, ?, ?, 'ok', ?, ?, CURRENT_TIMESTAMP)",
                     params.as_slice(),
                 )?;
             }
 
+            // Staged inside the transaction so a failed write rolls the rows
+            // back; the cache itself only changes once the rows are committed.
+            let cache_root = self.cache_root();
+            for (symbol, partition_date) in &touched {
+                write_bars_partitions(
+                    &connection,
+                    &cache_root,
+                    interval,
+                    source,
+                    symbol,
+                    partition_date,
+                    &mut staged,
+                )?;
+            }
+
             Ok(())
         })();
 
-        finalize_transaction(&connection, result)
+        match finalize_transaction(&connection, result) {
+            Ok(()) => publish_partitions(staged),
+            Err(error) => {
+                discard_partitions(&staged);
+                Err(error)
+            }
+        }
+    }
+
+    /// Materialize `interval` bars from stored 1m bars, for every symbol or
+    /// only `symbol`, returning the number of bars written.
+    ///
+    /// Rolled-up bars carry the source `rollup`. Re-running refreshes them as
+    /// new minutes arrive but never overwrites bars ingested from a provider.
+    /// They are not written to the parquet cache, since they can always be
+    /// derived again from the 1m partitions.
+    pub fn rollup_bars(
+        &self,
+        interval: &str,
+        symbol: Option<&str>,
+    ) -> Result<usize, WarehouseError> {
+        let Some(bucket) = rollup_bucket(interval) else {
+            return Err(WarehouseError::QueryRejected(format!(
+                "cannot roll 1m bars up to interval '{interval}'"
+            )));
+        };
+
+        let connection = self.manager.acquire(AccessMode::ReadWrite)?;
+        // The bucket width comes from `rollup_bucket`, not from the caller.
+        let rollup_sql = format!(
+            "INSERT INTO bars \
+             (symbol, interval, ts, open, high, low, close, volume, source, updated_at) \
+             SELECT symbol, ?, time_bucket(INTERVAL '{bucket}', ts) AS bucket, \
+                    arg_min(open, ts), max(high), min(low), arg_max(close, ts), \
+                    CAST(sum(volume) AS BIGINT), 'rollup', CURRENT_TIMESTAMP \
+             FROM bars \
+             WHERE interval = '1m' AND (? IS NULL OR symbol = ?) \
+             GROUP BY symbol, bucket \
+             ON CONFLICT (symbol, interval, ts) DO UPDATE SET \
+                 open = excluded.open, high = excluded.high, low = excluded.low, \
+                 close = excluded.close, volume = excluded.volume, \
+                 updated_at = excluded.updated_at \
+             WHERE bars.source = 'rollup'"
+        );
+        let params: [&dyn ToSql; 3] = [&interval, &symbol, &symbol];
+        Ok(connection.execute(rollup_sql.as_str(), params.as_slice())?)
+    }
+
+    /// Stored coverage of `dataset` bars for `symbol` between `start` and
+    /// `end` (inclusive `YYYY-MM-DD` dates).
+    ///
+    /// Only weekdays are considered, so weekends never show up as gaps.
+    /// Exchange holidays do until a fetch comes back without bars for them and
+    /// [`Self::record_empty_sessions`] marks them empty.
+    pub fn bar_coverage(
+        &self,
+        dataset: &str,
+        symbol: &str,
+        start: &str,
+        end: &str,
+    ) -> Result<BarCoverage, WarehouseError> {
+        let Some(interval) = bars_interval(dataset) else {
+            return Err(WarehouseError::QueryRejected(format!(
+                "unknown bars dataset '{dataset}'"
+            )));
+        };
+
+        let connection = self.manager.acquire(AccessMode::ReadOnly)?;
+        // Gaps-and-islands: the difference of the two row numbers is constant
+        // along each run of weekdays that share the same state. Stored bars
+        // win over an earlier empty fetch of the same day.
+        let mut statement = connection.prepare(
+            "WITH days AS ( \
+                 SELECT CAST(d AS DATE) AS day \
+                 FROM generate_series(CAST(? AS DATE), CAST(? AS DATE), INTERVAL 1 DAY) AS t(d) \
+                 WHERE isodow(d) < 6 \
+             ), stored AS ( \
+                 SELECT DISTINCT CAST(ts AS DATE) AS day FROM bars \
+                 WHERE symbol = ? AND interval = ? \
+                   AND ts >= CAST(? AS DATE) AND ts < CAST(? AS DATE) + INTERVAL 1 DAY \
+             ), checked AS ( \
+                 SELECT day FROM empty_bar_sessions WHERE symbol = ? AND interval = ? \
+             ), classified AS ( \
+                 SELECT days.day, \
+                        CASE WHEN stored.day IS NOT NULL THEN 'present' \
+                             WHEN checked.day IS NOT NULL THEN 'empty' \
+                             ELSE 'missing' END AS state \
+                 FROM days \
+                 LEFT JOIN stored ON stored.day = days.day \
+                 LEFT JOIN checked ON checked.day = days.day \
+             ), marked AS ( \
+                 SELECT day, state, \
+                        row_number() OVER (ORDER BY day) \
+                          - row_number() OVER (PARTITION BY state ORDER BY day) AS run \
+                 FROM classified \
+             ) \
+             SELECT state, strftime(min(day), '%Y-%m-%d'), strftime(max(day), '%Y-%m-%d') \
+             FROM marked GROUP BY state, run ORDER BY min(day)",
+        )?;
+        let params: [&dyn ToSql; 8] = [
+            &start, &end, &symbol, &interval, &start, &end, &symbol, &interval,
+        ];
+        let runs = statement.query_map(params.as_slice(), |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                DateRange {
+                    start: row.get(1)?,
+                    end: row.get(2)?,
+                },
+            ))
+        })?;
+
+        let mut coverage = BarCoverage::default();
+        for run in runs {
+            let (state, range) = run?;
+            match state.as_str() {
+                "present" => coverage.present.push(range),
+                "empty" => coverage.empty.push(range),
+                _ => coverage.missing.push(range),
+            }
+        }
+        Ok(coverage)
+    }
+
+    /// Mark the weekdays between `start` and `end` (inclusive `YYYY-MM-DD`
+    /// dates) that still have no `dataset` bars for `symbol` as fetched and
+    /// empty, returning how many were recorded.
+    ///
+    /// Call it only for a window the provider actually covered, so holidays
+    /// stop showing up as gaps while days it merely did not return are
+    /// fetched again.
+    pub fn record_empty_sessions(
+        &self,
+        dataset: &str,
+        symbol: &str,
+        start: &str,
+        end: &str,
+    ) -> Result<usize, WarehouseError> {
+        let Some(interval) = bars_interval(dataset) else {
+            return Err(WarehouseError::QueryRejected(format!(
+                "unknown bars dataset '{dataset}'"
+            )));
+        };
+
+        let connection = self.manager.acquire(AccessMode::ReadWrite)?;
+        let params: [&dyn ToSql; 6] = [&symbol, &interval, &start, &end, &symbol, &interval];
+        Ok(connection.execute(
+            "INSERT OR IGNORE INTO empty_bar_sessions (symbol, interval, day) \
+             SELECT ?, ?, CAST(d AS DATE) \
+             FROM generate_series(CAST(? AS DATE), CAST(? AS DATE), INTERVAL 1 DAY) AS t(d) \
+             WHERE isodow(d) < 6 \
+               AND NOT EXISTS ( \
+                   SELECT 1 FROM bars \
+                   WHERE symbol = ? AND interval = ? \
+                     AND ts >= CAST(d AS DATE) AND ts < CAST(d AS DATE) + INTERVAL 1 DAY \
+               )",
+            params.as_slice(),
+        )?)
+    }
+
+    /// Record the outcome of one ingestion run in `ingest_log`.
+    ///
+    /// Unlike the per-row entries written by the `ingest_*` methods, a run
+    /// entry summarizes a whole fetch, successful or not, in `detail`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn record_ingest_run(
+        &self,
+        request_id: &str,
+        symbol: Option<&str>,
+        source: &str,
+        dataset: &str,
+        status: &str,
+        latency_ms: u64,
+        detail: &str,
+    ) -> Result<(), WarehouseError> {
+        let connection = self.manager.acquire(AccessMode::ReadWrite)?;
+        let latency_ms = i64::try_from(latency_ms).unwrap_or(i64::MAX);
+        let params: [&dyn ToSql; 7] = [
+            &request_id,
+            &symbol,
+            &source,
+            &dataset,
+            &status,
+            &latency_ms,
+            &detail,
+        ];
+        connection.execute(
+            "INSERT INTO ingest_log \
+             (request_id, symbol, source, dataset, status, latency_ms, detail, timestamp) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, CURRENT_TIMESTAMP)",
+            params.as_slice(),
+        )?;
+        Ok(())
+    }
+
+    /// Most recent `ingest_log` entries whose request id starts with
+    /// `request_prefix`, newest first.
+    pub fn ingest_runs(
+        &self,
+        request_prefix: &str,
+        limit: usize,
+    ) -> Result<Vec<IngestRun>, WarehouseError> {
+        let connection = self.manager.acquire(AccessMode::ReadOnly)?;
+        let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+        let mut statement = connection.prepare(
+            "SELECT request_id, symbol, source, dataset, status, latency_ms, detail, \
+                    strftime(timestamp, '%Y-%m-%dT%H:%M:%SZ') \
+             FROM ingest_log \
+             WHERE starts_with(request_id, ?) \
+             ORDER BY timestamp DESC, request_id \
+             LIMIT ?",
+        )?;
+        let params: [&dyn ToSql; 2] = [&request_prefix, &limit];
+        let rows = statement.query_map(params.as_slice(), |row| {
+            Ok(IngestRun {
+                request_id: row.get(0)?,
+                symbol: row.get(1)?,
+                source: row.get(2)?,
+                dataset: row.get(3)?,
+                status: row.get(4)?,
+                latency_ms: row.get(5)?,
+                detail: row.get(6)?,
+                timestamp: row.get(7)?,
+            })
+        })?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(WarehouseError::from)
     }
 
     /// Ingest fundamental data using parameterized queries.
@@ -563,12 +1397,19 @@ impl Warehouse {
         let result = (|| -> Result<(), WarehouseError> {
             for row in rows {
                 // SECURITY: All user-provided values are passed as parameters
-                let params: [&dyn ToSql; 5] =
-                    [&row.symbol, &row.metric, &row.value, &row.date, &source];
+                // Snapshot metrics are observed live, so they are public as of `date`.
+                let params: [&dyn ToSql; 6] = [
+                    &row.symbol,
+                    &row.metric,
+                    &row.value,
+                    &row.date,
+                    &row.date,
+                    &source,
+                ];
                 connection.execute(
                     "INSERT OR REPLACE INTO fundamentals \
-                     (symbol, metric, value, date, source, updated_at) \
-                     VALUES (?, ?, ?, TRY_CAST(? AS TIMESTAMP), ?, CURRENT_TIMESTAMP)",
+                     (symbol, metric, value, date, available_at, source, updated_at) \
+                     VALUES (?, ?, ?, TRY_CAST(? AS TIMESTAMP), TRY_CAST(? AS TIMESTAMP), ?, CURRENT_TIMESTAMP)",
                     params.as_slice(),
                 )?;
 
@@ -588,611 +1429,3117 @@ impl Warehouse {
         finalize_transaction(&connection, result)
     }
 
-    /// Register a cache partition using parameterized queries.
+    /// Ingest financial statement line items using parameterized queries.
     ///
     /// # Security
     /// Uses parameterized queries to prevent SQL injection.
-    fn register_partition(&self, partition: &CachePartition) -> Result<(), WarehouseError> {
+    /// All user-provided values are passed as query parameters.
+    pub fn ingest_financials(
+        &self,
+        source: &str,
+        request_id: &str,
+        rows: &[FinancialRecord],
+        latency_ms: u64,
+    ) -> Result<(), WarehouseError> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+
         let connection = self.manager.acquire(AccessMode::ReadWrite)?;
-        let row_count = read_parquet_row_count(&connection, partition.path.as_path());
-        let (min_ts, max_ts) = read_parquet_min_max_ts(&connection, partition.path.as_path());
-        let checksum = file_checksum(partition.path.as_path())?;
-        let path_str = path_to_sql(partition.path.as_path());
-
-        // SECURITY: All values are passed as parameters, not interpolated
-        let params: [&dyn ToSql; 9] = [
-            &partition.source,
-            &partition.dataset,
-            &partition.symbol,
-            &partition.partition_date,
-            &path_str,
-            &row_count,
-            &min_ts,
-            &max_ts,
-            &checksum,
-        ];
+        connection.execute_batch("BEGIN TRANSACTION")?;
+        let result = (|| -> Result<(), WarehouseError> {
+            for row in rows {
+                // SECURITY: All user-provided values are passed as parameters
+                let params: [&dyn ToSql; 14] = [
+                    &row.symbol,
+                    &row.statement_type,
+                    &row.period,
+                    &row.label,
+                    &row.metric,
+                    &row.value,
+                    &row.fiscal_year,
+                    &row.fiscal_quarter,
+                    &row.end_date,
+                    &row.available_at,
+                    &row.symbol,
+                    &row.end_date,
+                    &row.currency,
+                    &source,
+                ];
+                connection.execute(
+                    "INSERT OR REPLACE INTO financial_statements \
+                     (symbol, statement_type, period, label, metric, value, fiscal_year, fiscal_quarter, end_date, available_at, currency, source, updated_at) \
+                     SELECT ?, ?, ?, ?, ?, ?, ?, ?, TRY_CAST(? AS TIMESTAMP), \
+                     COALESCE(TRY_CAST(? AS TIMESTAMP), \
+                         (SELECT report_date FROM earnings WHERE symbol = ? AND period_end = TRY_CAST(? AS TIMESTAMP))), \
+                     ?, ?, CURRENT_TIMESTAMP",
+                    params.as_slice(),
+                )?;
+
+                // Use parameterized query for ingest_log
+                let params: [&dyn ToSql; 4] = [&request_id, &row.symbol, &source, &latency_ms];
+                connection.execute(
+                    "INSERT INTO ingest_log \
+                     (request_id, symbol, source, dataset, status, latency_ms, timestamp) \
+                     VALUES (?, ?, ?, 'financials', 'ok', ?, CURRENT_TIMESTAMP)",
+                    params.as_slice(),
+                )?;
+            }
+
+            Ok(())
+        })();
+
+        finalize_transaction(&connection, result)
+    }
+
+    /// Ingest earnings results using parameterized queries.
+    ///
+    /// Report dates are also copied onto the matching `financial_statements`
+    /// rows as their `available_at`, so statements ingested before their
+    /// earnings still become point-in-time correct.
+    ///
+    /// # Security
+    /// Uses parameterized queries to prevent SQL injection.
+    /// All user-provided values are passed as query parameters.
+    pub fn ingest_earnings(
+        &self,
+        source: &str,
+        request_id: &str,
+        rows: &[EarningsRecord],
+        latency_ms: u64,
+    ) -> Result<(), WarehouseError> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+
+        let connection = self.manager.acquire(AccessMode::ReadWrite)?;
+        connection.execute_batch("BEGIN TRANSACTION")?;
+        let result = (|| -> Result<(), WarehouseError> {
+            for row in rows {
+                // SECURITY: All user-provided values are passed as parameters
+                let params: [&dyn ToSql; 11] = [
+                    &row.symbol,
+                    &row.fiscal_year,
+                    &row.fiscal_quarter,
+                    &row.period_end,
+                    &row.report_date,
+                    &row.eps_actual,
+                    &row.eps_estimate,
+                    &row.revenue_actual,
+                    &row.revenue_estimate,
+                    &row.surprise_percent,
+                    &source,
+                ];
+                connection.execute(
+                    "INSERT OR REPLACE INTO earnings \
+                     (symbol, fiscal_year, fiscal_quarter, period_end, report_date, eps_actual, eps_estimate, revenue_actual, revenue_estimate, surprise_percent, source, updated_at) \
+                     VALUES (?, ?, ?, TRY_CAST(? AS TIMESTAMP), TRY_CAST(? AS TIMESTAMP), ?, ?, ?, ?, ?, ?, CURRENT_TIMESTAMP)",
+                    params.as_slice(),
+                )?;
+
+                if row.report_date.is_some() {
+                    let params: [&dyn ToSql; 3] = [&row.report_date, &row.symbol, &row.period_end];
+                    connection.execute(
+                        "UPDATE financial_statements \
+                         SET available_at = TRY_CAST(? AS TIMESTAMP) \
+                         WHERE symbol = ? AND end_date = TRY_CAST(? AS TIMESTAMP)",
+                        params.as_slice(),
+                    )?;
+                }
+
+                // Use parameterized query for ingest_log
+                let params: [&dyn ToSql; 4] = [&request_id, &row.symbol, &source, &latency_ms];
+                connection.execute(
+                    "INSERT INTO ingest_log \
+                     (request_id, symbol, source, dataset, status, latency_ms, timestamp) \
+                     VALUES (?, ?, ?, 'earnings', 'ok', ?, CURRENT_TIMESTAMP)",
+                    params.as_slice(),
+                )?;
+            }
+
+            Ok(())
+        })();
+
+        finalize_transaction(&connection, result)
+    }
+
+    /// Ingest corporate actions using parameterized queries.
+    ///
+    /// Actions are keyed by symbol, type and ex-date; re-ingesting one
+    /// replaces it.
+    ///
+    /// # Security
+    /// Uses parameterized queries to prevent SQL injection.
+    /// All user-provided values are passed as query parameters.
+    pub fn ingest_corporate_actions(
+        &self,
+        source: &str,
+        request_id: &str,
+        rows: &[CorporateActionRecord],
+        latency_ms: u64,
+    ) -> Result<(), WarehouseError> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+
+        let connection = self.manager.acquire(AccessMode::ReadWrite)?;
+        connection.execute_batch("BEGIN TRANSACTION")?;
+        let result = (|| -> Result<(), WarehouseError> {
+            for row in rows {
+                // SECURITY: All user-provided values are passed as parameters
+                let params: [&dyn ToSql; 7] = [
+                    &row.symbol,
+                    &row.action_type,
+                    &row.ex_date,
+                    &row.pay_date,
+                    &row.value,
+                    &row.currency,
+                    &source,
+                ];
+                connection.execute(
+                    "INSERT OR REPLACE INTO corporate_actions \
+                     (symbol, type, date, pay_date, value, currency, source, updated_at) \
+                     VALUES (?, ?, TRY_CAST(? AS TIMESTAMP), TRY_CAST(? AS TIMESTAMP), ?, ?, ?, \
+                     CURRENT_TIMESTAMP)",
+                    params.as_slice(),
+                )?;
+
+                // Use parameterized query for ingest_log
+                let params: [&dyn ToSql; 4] = [&request_id, &row.symbol, &source, &latency_ms];
+                connection.execute(
+                    "INSERT INTO ingest_log \
+                     (request_id, symbol, source, dataset, status, latency_ms, timestamp) \
+                     VALUES (?, ?, ?, 'corporate_actions', 'ok', ?, CURRENT_TIMESTAMP)",
+                    params.as_slice(),
+                )?;
+            }
+
+            Ok(())
+        })();
+
+        finalize_transaction(&connection, result)
+    }
+
+    /// Ingest trade prints using parameterized queries.
+    ///
+    /// Prints sharing a symbol and timestamp are numbered by `seq`. A print
+    /// identical to a stored one keeps its `seq`, so re-ingesting an
+    /// overlapping window replaces rows instead of duplicating them, and new
+    /// prints are numbered after the stored ones. A single `ingest_log` row
+    /// is written per symbol in the batch.
+    ///
+    /// # Security
+    /// Uses parameterized queries to prevent SQL injection.
+    /// All user-provided values are passed as query parameters.
+    pub fn ingest_trade_ticks(
+        &self,
+        source: &str,
+        request_id: &str,
+        rows: &[TradeTickRecord],
+        latency_ms: u64,
+    ) -> Result<(), WarehouseError> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+
+        let connection = self.manager.acquire(AccessMode::ReadWrite)?;
+        connection.execute_batch("BEGIN TRANSACTION")?;
+        let result = (|| -> Result<(), WarehouseError> {
+            let mut sequencer = TickSequencer::new("trade_ticks");
+            for row in rows {
+                let conditions = (!row.conditions.is_empty()).then(|| row.conditions.join(","));
+                let params: [&dyn ToSql; 6] = [
+                    &row.symbol,
+                    &row.ts,
+                    &row.price,
+                    &row.size,
+                    &row.exchange,
+                    &conditions,
+                ];
+                let matching = connection
+                    .prepare_cached(
+                        "SELECT seq FROM trade_ticks \
+                         WHERE symbol = ? AND ts = TRY_CAST(? AS TIMESTAMP) AND price = ? \
+                         AND size = ? AND exchange IS NOT DISTINCT FROM ? \
+                         AND conditions IS NOT DISTINCT FROM ? \
+                         ORDER BY seq",
+                    )?
+                    .query_map(params.as_slice(), |row| row.get(0))?
+                    .collect::<Result<Vec<i32>, _>>()?;
+                let seq = sequencer.next(&connection, &row.symbol, &row.ts, matching)?;
+                // SECURITY: All user-provided values are passed as parameters
+                let params: [&dyn ToSql; 8] = [
+                    &row.symbol,
+                    &row.ts,
+                    &seq,
+                    &row.price,
+                    &row.size,
+                    &row.exchange,
+                    &conditions,
+                    &source,
+                ];
+                connection.execute(
+                    "INSERT OR REPLACE INTO trade_ticks \
+                     (symbol, ts, seq, price, size, exchange, conditions, source, updated_at) \
+                     VALUES (?, TRY_CAST(? AS TIMESTAMP), ?, ?, ?, ?, ?, ?, CURRENT_TIMESTAMP)",
+                    params.as_slice(),
+                )?;
+            }
+
+            log_batch_ingest(
+                &connection,
+                request_id,
+                source,
+                "trade_ticks",
+                rows.iter().map(|row| row.symbol.as_str()),
+                latency_ms,
+            )
+        })();
+
+        finalize_transaction(&connection, result)
+    }
+
+    /// Ingest quote updates using parameterized queries.
+    ///
+    /// Numbering and logging follow [`Warehouse::ingest_trade_ticks`].
+    ///
+    /// # Security
+    /// Uses parameterized queries to prevent SQL injection.
+    /// All user-provided values are passed as query parameters.
+    pub fn ingest_quote_ticks(
+        &self,
+        source: &str,
+        request_id: &str,
+        rows: &[QuoteTickRecord],
+        latency_ms: u64,
+    ) -> Result<(), WarehouseError> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+
+        let connection = self.manager.acquire(AccessMode::ReadWrite)?;
+        connection.execute_batch("BEGIN TRANSACTION")?;
+        let result = (|| -> Result<(), WarehouseError> {
+            let mut sequencer = TickSequencer::new("quote_ticks");
+            for row in rows {
+                let params: [&dyn ToSql; 8] = [
+                    &row.symbol,
+                    &row.ts,
+                    &row.bid,
+                    &row.bid_size,
+                    &row.ask,
+                    &row.ask_size,
+                    &row.bid_exchange,
+                    &row.ask_exchange,
+                ];
+                let matching = connection
+                    .prepare_cached(
+                        "SELECT seq FROM quote_ticks \
+                         WHERE symbol = ? AND ts = TRY_CAST(? AS TIMESTAMP) \
+                         AND bid IS NOT DISTINCT FROM ? AND bid_size IS NOT DISTINCT FROM ? \
+                         AND ask IS NOT DISTINCT FROM ? AND ask_size IS NOT DISTINCT FROM ? \
+                         AND bid_exchange IS NOT DISTINCT FROM ? \
+                         AND ask_exchange IS NOT DISTINCT FROM ? \
+                         ORDER BY seq",
+                    )?
+                    .query_map(params.as_slice(), |row| row.get(0))?
+                    .collect::<Result<Vec<i32>, _>>()?;
+                let seq = sequencer.next(&connection, &row.symbol, &row.ts, matching)?;
+
+                // SECURITY: All user-provided values are passed as parameters
+                let params: [&dyn ToSql; 10] = [
+                    &row.symbol,
+                    &row.ts,
+                    &seq,
+                    &row.bid,
+                    &row.bid_size,
+                    &row.ask,
+                    &row.ask_size,
+                    &row.bid_exchange,
+                    &row.ask_exchange,
+                    &source,
+                ];
+                connection.execute(
+                    "INSERT OR REPLACE INTO quote_ticks \
+                     (symbol, ts, seq, bid, bid_size, ask, ask_size, bid_exchange, ask_exchange, source, updated_at) \
+                     VALUES (?, TRY_CAST(? AS TIMESTAMP), ?, ?, ?, ?, ?, ?, ?, ?, CURRENT_TIMESTAMP)",
+                    params.as_slice(),
+                )?;
+            }
+
+            log_batch_ingest(
+                &connection,
+                request_id,
+                source,
+                "quote_ticks",
+                rows.iter().map(|row| row.symbol.as_str()),
+                latency_ms,
+            )
+        })();
+
+        finalize_transaction(&connection, result)
+    }
+
+    /// Ingest economic series observations using parameterized queries.
+    ///
+    /// A single `ingest_log` row is written per series in the batch.
+    ///
+    /// # Security
+    /// Uses parameterized queries to prevent SQL injection.
+    /// All user-provided values are passed as query parameters.
+    pub fn ingest_macro_series(
+        &self,
+        source: &str,
+        request_id: &str,
+        rows: &[MacroObservationRecord],
+        latency_ms: u64,
+    ) -> Result<(), WarehouseError> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+
+        let connection = self.manager.acquire(AccessMode::ReadWrite)?;
+        connection.execute_batch("BEGIN TRANSACTION")?;
+        let result = (|| -> Result<(), WarehouseError> {
+            for row in rows {
+                // SECURITY: All user-provided values are passed as parameters
+                let params: [&dyn ToSql; 6] = [
+                    &row.series_id,
+                    &row.date,
+                    &row.value,
+                    &row.unit,
+                    &row.frequency,
+                    &source,
+                ];
+                connection.execute(
+                    "INSERT OR REPLACE INTO macro_series \
+                     (series_id, date, value, unit, frequency, source, updated_at) \
+                     VALUES (?, TRY_CAST(? AS TIMESTAMP), ?, ?, ?, ?, CURRENT_TIMESTAMP)",
+                    params.as_slice(),
+                )?;
+            }
+
+            log_batch_ingest(
+                &connection,
+                request_id,
+                source,
+                "macro_series",
+                rows.iter().map(|row| row.series_id.as_str()),
+                latency_ms,
+            )
+        })();
+
+        finalize_transaction(&connection, result)
+    }
+
+    /// Ingest news articles and their ticker sentiment into the warehouse.
+    ///
+    /// Articles are keyed by URL; re-ingesting an article replaces it along
+    /// with its ticker rows. A single `ingest_log` row is written per ticker.
+    ///
+    /// # Security
+    /// Uses parameterized queries to prevent SQL injection.
+    /// All user-provided values are passed as query parameters.
+    pub fn ingest_news(
+        &self,
+        source: &str,
+        request_id: &str,
+        rows: &[NewsRecord],
+        latency_ms: u64,
+    ) -> Result<(), WarehouseError> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+
+        let connection = self.manager.acquire(AccessMode::ReadWrite)?;
+        connection.execute_batch("BEGIN TRANSACTION")?;
+        let result = (|| -> Result<(), WarehouseError> {
+            for row in rows {
+                // SECURITY: All user-provided values are passed as parameters
+                let params: [&dyn ToSql; 7] = [
+                    &row.url,
+                    &row.published_at,
+                    &row.publisher,
+                    &row.title,
+                    &row.summary,
+                    &row.sentiment,
+                    &source,
+                ];
+                connection.execute(
+                    "INSERT OR REPLACE INTO news \
+                     (url, published_at, publisher, title, summary, sentiment, source, updated_at) \
+                     VALUES (?, TRY_CAST(? AS TIMESTAMP), ?, ?, ?, ?, ?, CURRENT_TIMESTAMP)",
+                    params.as_slice(),
+                )?;
+
+                connection.execute("DELETE FROM news_tickers WHERE url = ?", [&row.url])?;
+                for ticker in &row.tickers {
+                    let params: [&dyn ToSql; 4] = [
+                        &row.url,
+                        &ticker.symbol,
+                        &ticker.sentiment,
+                        &ticker.relevance,
+                    ];
+                    connection.execute(
+                        "INSERT OR REPLACE INTO news_tickers (url, symbol, sentiment, relevance) \
+                         VALUES (?, ?, ?, ?)",
+                        params.as_slice(),
+                    )?;
+                }
+            }
+
+            log_batch_ingest(
+                &connection,
+                request_id,
+                source,
+                "news",
+                rows.iter()
+                    .flat_map(|row| row.tickers.iter().map(|ticker| ticker.symbol.as_str())),
+                latency_ms,
+            )
+        })();
+
+        finalize_transaction(&connection, result)
+    }
+
+    /// Daily news sentiment for `symbol`, oldest first.
+    ///
+    /// Backed by the `vw_news_sentiment_daily` view; `start` and `end` bound
+    /// the UTC day inclusively when given.
+    pub fn daily_sentiment(
+        &self,
+        symbol: &str,
+        start: Option<&str>,
+        end: Option<&str>,
+    ) -> Result<Vec<DailySentiment>, WarehouseError> {
+        let connection = self.manager.acquire(AccessMode::ReadOnly)?;
+        let mut statement = connection.prepare(
+            "SELECT symbol, strftime(date, '%Y-%m-%d'), article_count, \
+                    avg_sentiment, weighted_sentiment \
+             FROM vw_news_sentiment_daily \
+             WHERE symbol = ? \
+               AND (? IS NULL OR date >= CAST(TRY_CAST(? AS TIMESTAMP) AS DATE)) \
+               AND (? IS NULL OR date <= CAST(TRY_CAST(? AS TIMESTAMP) AS DATE)) \
+             ORDER BY date",
+        )?;
+        let params: [&dyn ToSql; 5] = [&symbol, &start, &start, &end, &end];
+        let rows = statement.query_map(params.as_slice(), |row| {
+            Ok(DailySentiment {
+                symbol: row.get(0)?,
+                date: row.get(1)?,
+                article_count: row.get(2)?,
+                avg_sentiment: row.get(3)?,
+                weighted_sentiment: row.get(4)?,
+            })
+        })?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(WarehouseError::from)
+    }
+
+    /// Average annualized risk-free rate between `start` and `end`, as a decimal.
+    ///
+    /// Reads `series_id` from `macro_series` (typically
+    /// [`RISK_FREE_SERIES`], the 3-month T-bill yield). Observations whose
+    /// unit is `percent` are converted to decimals; others are used as stored. If no observation falls inside the window, the
+    /// latest observation before `start` is used. Returns `None` when the
+    /// series has not been ingested.
+    pub fn risk_free_rate(
+        &self,
+        series_id: &str,
+        start: &str,
+        end: &str,
+    ) -> Result<Option<f64>, WarehouseError> {
+        let connection = self.manager.acquire(AccessMode::ReadOnly)?;
+        let params: [&dyn ToSql; 4] = [&series_id, &start, &end, &start];
+        let rate = connection.query_row(
+            "WITH observations AS ( \
+                 SELECT date, \
+                        CASE WHEN lower(unit) IN ('percent', '%') THEN value / 100.0 \
+                             ELSE value END AS rate \
+                 FROM macro_series WHERE series_id = ? \
+             ) \
+             SELECT COALESCE( \
+                 (SELECT AVG(rate) FROM observations \
+                  WHERE date BETWEEN TRY_CAST(? AS TIMESTAMP) AND TRY_CAST(? AS TIMESTAMP)), \
+                 (SELECT rate FROM observations \
+                  WHERE date <= TRY_CAST(? AS TIMESTAMP) \
+                  ORDER BY date DESC LIMIT 1) \
+             )",
+            params.as_slice(),
+            |row| row.get::<_, Option<f64>>(0),
+        )?;
+
+        Ok(rate)
+    }
+
+    /// Latest value of every fundamental metric that was public at `as_of`.
+    ///
+    /// Backed by the `fundamentals_asof(symbol, ts)` SQL macro, so results
+    /// never include figures published after `as_of`.
+    pub fn fundamentals_as_of(
+        &self,
+        symbol: &str,
+        as_of: &str,
+    ) -> Result<Vec<PointInTimeFundamental>, WarehouseError> {
+        let connection = self.manager.acquire(AccessMode::ReadOnly)?;
+        let mut statement = connection.prepare(
+            "SELECT symbol, basis, dataset, metric, value, \
+                    strftime(period_end, '%Y-%m-%dT%H:%M:%SZ'), \
+                    strftime(available_at, '%Y-%m-%dT%H:%M:%SZ') \
+             FROM fundamentals_asof(?, TRY_CAST(? AS TIMESTAMP)) \
+             ORDER BY basis, metric",
+        )?;
+        let params: [&dyn ToSql; 2] = [&symbol, &as_of];
+        let rows = statement.query_map(params.as_slice(), |row| {
+            Ok(PointInTimeFundamental {
+                symbol: row.get(0)?,
+                basis: row.get(1)?,
+                dataset: row.get(2)?,
+                metric: row.get(3)?,
+                value: row.get(4)?,
+                period_end: row.get(5)?,
+                available_at: row.get(6)?,
+            })
+        })?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(WarehouseError::from)
+    }
+
+    /// As-of join a fundamental metric onto the daily bars of `symbol`.
+    ///
+    /// Each bar carries the latest value whose `available_at` is not after
+    /// the bar timestamp, which keeps backtests and features free of
+    /// lookahead. `basis` is `snapshot`, `annual` or `quarterly`.
+    pub fn join_fundamentals_as_of(
+        &self,
+        symbol: &str,
+        basis: &str,
+        metric: &str,
+        start: Option<&str>,
+        end: Option<&str>,
+    ) -> Result<Vec<AsOfFundamentalValue>, WarehouseError> {
+        let connection = self.manager.acquire(AccessMode::ReadOnly)?;
+        let mut statement = connection.prepare(
+            "WITH bars AS ( \
+                 SELECT symbol, ts FROM bars_1d \
+                 WHERE symbol = ? \
+                   AND (? IS NULL OR ts >= TRY_CAST(? AS TIMESTAMP)) \
+                   AND (? IS NULL OR ts <= TRY_CAST(? AS TIMESTAMP)) \
+             ), \
+             facts AS ( \
+                 SELECT symbol, value, period_end, available_at FROM vw_fundamentals_pit \
+                 WHERE symbol = ? AND basis = ? AND metric = ? \
+                 QUALIFY ROW_NUMBER() OVER ( \
+                     PARTITION BY available_at ORDER BY period_end DESC NULLS LAST, dataset \
+                 ) = 1 \
+             ) \
+             SELECT strftime(bars.ts, '%Y-%m-%dT%H:%M:%SZ'), facts.value, \
+                    strftime(facts.period_end, '%Y-%m-%dT%H:%M:%SZ'), \
+                    strftime(facts.available_at, '%Y-%m-%dT%H:%M:%SZ') \
+             FROM bars ASOF LEFT JOIN facts \
+               ON bars.symbol = facts.symbol AND bars.ts >= facts.available_at \
+             ORDER BY bars.ts",
+        )?;
+        let params: [&dyn ToSql; 8] = [
+            &symbol, &start, &start, &end, &end, &symbol, &basis, &metric,
+        ];
+        let rows = statement.query_map(params.as_slice(), |row| {
+            Ok(AsOfFundamentalValue {
+                ts: row.get(0)?,
+                value: row.get(1)?,
+                period_end: row.get(2)?,
+                available_at: row.get(3)?,
+            })
+        })?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(WarehouseError::from)
+    }
+
+    /// Ingest instrument master data using parameterized queries.
+    ///
+    /// Known symbols are merged field by field. A symbol that is new but
+    /// carries a FIGI already on file is treated as a ticker change when the
+    /// same source reported the old symbol, and as a provider alias otherwise.
+    ///
+    /// # Security
+    /// Uses parameterized queries to prevent SQL injection.
+    /// All user-provided values are passed as query parameters.
+    pub fn ingest_instruments(
+        &self,
+        source: &str,
+        request_id: &str,
+        rows: &[InstrumentRecord],
+        latency_ms: u64,
+    ) -> Result<(), WarehouseError> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+
+        let connection = self.manager.acquire(AccessMode::ReadWrite)?;
+        connection.execute_batch("BEGIN TRANSACTION")?;
+        let result = (|| -> Result<(), WarehouseError> {
+            for row in rows {
+                upsert_instrument(&connection, source, row)?;
+
+                // Use parameterized query for ingest_log
+                let params: [&dyn ToSql; 4] = [&request_id, &row.symbol, &source, &latency_ms];
+                connection.execute(
+                    "INSERT INTO ingest_log \
+                     (request_id, symbol, source, dataset, status, latency_ms, timestamp) \
+                     VALUES (?, ?, ?, 'instrument', 'ok', ?, CURRENT_TIMESTAMP)",
+                    params.as_slice(),
+                )?;
+            }
+
+            Ok(())
+        })();
+
+        finalize_transaction(&connection, result)
+    }
+
+    /// Record that `old_symbol` started trading as `new_symbol` at `effective_at`.
+    ///
+    /// Closes the old symbol in `ticker_history` and carries the instrument's
+    /// identifiers over to the new symbol, e.g. `FB` to `META`.
+    ///
+    /// # Security
+    /// Uses parameterized queries to prevent SQL injection.
+    pub fn record_ticker_change(
+        &self,
+        source: &str,
+        old_symbol: &str,
+        new_symbol: &str,
+        effective_at: &str,
+    ) -> Result<(), WarehouseError> {
+        let connection = self.manager.acquire(AccessMode::ReadWrite)?;
+        connection.execute_batch("BEGIN TRANSACTION")?;
+        let result = (|| -> Result<(), WarehouseError> {
+            if find_instrument_id(&connection, old_symbol)?.is_none() {
+                let row = InstrumentRecord {
+                    symbol: old_symbol.to_string(),
+                    ..InstrumentRecord::default()
+                };
+                upsert_instrument(&connection, source, &row)?;
+            }
+            let instrument_id = find_instrument_id(&connection, old_symbol)?
+                .unwrap_or_else(|| old_symbol.to_string());
+
+            apply_ticker_change(
+                &connection,
+                source,
+                instrument_id.as_str(),
+                old_symbol,
+                new_symbol,
+                Some(effective_at),
+            )
+        })();
+
+        finalize_transaction(&connection, result)
+    }
+
+    /// Resolve a past ticker or provider alias to the symbol in use at `as_of`.
+    ///
+    /// With no `as_of` the current symbol is returned. Returns `None` for
+    /// symbols that are not in the instrument master.
+    pub fn resolve_symbol(
+        &self,
+        symbol: &str,
+        provider: Option<&str>,
+        as_of: Option<&str>,
+    ) -> Result<Option<String>, WarehouseError> {
+        let connection = self.manager.acquire(AccessMode::ReadOnly)?;
+        let params: [&dyn ToSql; 6] = [&provider, &symbol, &symbol, &as_of, &as_of, &as_of];
+        let resolved = connection
+            .query_row(
+                "WITH target AS ( \
+                     SELECT instrument_id FROM ( \
+                         SELECT instrument_id, 0 AS priority, NULL::TIMESTAMP AS valid_from \
+                         FROM instrument_aliases WHERE provider = ? AND alias = ? \
+                         UNION ALL \
+                         SELECT instrument_id, 1 AS priority, valid_from \
+                         FROM ticker_history WHERE symbol = ? \
+                     ) \
+                     ORDER BY priority, valid_from DESC \
+                     LIMIT 1 \
+                 ) \
+                 SELECT th.symbol FROM ticker_history th \
+                 JOIN target USING (instrument_id) \
+                 WHERE (? IS NULL AND th.valid_to IS NULL) \
+                    OR (th.valid_from <= TRY_CAST(? AS TIMESTAMP) \
+                        AND (th.valid_to IS NULL OR th.valid_to > TRY_CAST(? AS TIMESTAMP))) \
+                 ORDER BY th.valid_from DESC \
+                 LIMIT 1",
+                params.as_slice(),
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(resolved)
+    }
+
+    /// Register a cache partition using parameterized queries.
+    ///
+    /// # Security
+    /// Uses parameterized queries to prevent SQL injection.
+    fn register_partition(&self, partition: &CachePartition) -> Result<(), WarehouseError> {
+        let connection = self.manager.acquire(AccessMode::ReadWrite)?;
+        upsert_manifest_entry(&connection, partition)?;
+        log_partition(&connection, partition, "cache-sync", "synced", None)
+    }
+
+    /// Load one bars partition into `bars`, returning the number of rows.
+    fn restore_partition(
+        &self,
+        partition: &CachePartition,
+        interval: &str,
+    ) -> Result<usize, WarehouseError> {
+        let connection = self.manager.acquire(AccessMode::ReadWrite)?;
+        connection.execute_batch("BEGIN TRANSACTION")?;
+        let result = (|| -> Result<usize, WarehouseError> {
+            let checksum = verify_checksum(&partition.path)?;
+            let batch_id = lineage::open_batch(
+                &connection,
+                &partition_request_id("cache-rebuild", partition),
+                &partition.source,
+                &partition.dataset,
+                &checksum,
+            )?;
+
+            // Symbol and source come from the partition path; the file only
+            // holds the bar columns.
+            let rows_sql = format!(
+                "SELECT ts, open, high, low, close, volume \
+                 FROM read_parquet('{path}', hive_partitioning = false)",
+                path = escape_sql_string(path_to_sql(partition.path.as_path()).as_str()),
+            );
+            lineage::audit_bars_replaced_by(
+                &connection,
+                &rows_sql,
+                &partition.symbol,
+                interval,
+                &batch_id,
+            )?;
+            let insert_sql = format!(
+                "INSERT OR REPLACE INTO bars \
+                 (symbol, interval, ts, open, high, low, close, volume, source, ingest_batch, \
+                  updated_at) \
+                 SELECT ?, ?, ts, open, high, low, close, volume, ?, ?, CURRENT_TIMESTAMP \
+                 FROM ({rows_sql})"
+            );
+            let params: [&dyn ToSql; 4] =
+                [&partition.symbol, &interval, &partition.source, &batch_id];
+            let rows = connection.execute(insert_sql.as_str(), params.as_slice())?;
+
+            upsert_manifest_entry(&connection, partition)?;
+            log_partition(
+                &connection,
+                partition,
+                "cache-rebuild",
+                "rebuilt",
+                Some(&batch_id),
+            )?;
+            Ok(rows)
+        })();
+
+        finalize_transaction(&connection, result)
+    }
+}
+
+/// Finalize a transaction, committing on success or rolling back on failure.
+fn finalize_transaction<T>(
+    connection: &Connection,
+    result: Result<T, WarehouseError>,
+) -> Result<T, WarehouseError> {
+    match result {
+        Ok(value) => {
+            connection.execute_batch("COMMIT")?;
+            Ok(value)
+        }
+        Err(error) => {
+            let _ = connection.execute_batch("ROLLBACK");
+            Err(error)
+        }
+    }
+}
+
+/// Assigns `seq` to ticks that share a symbol and timestamp.
+///
+/// A tick identical to a stored one keeps that row's `seq`, so re-ingesting an
+/// overlapping window replaces rows. Other ticks are numbered on from the
+/// stored maximum, so rows already in the table are never renumbered.
+struct TickSequencer {
+    table: &'static str,
+    keys: HashMap<(String, String), (BTreeSet<i32>, i32)>,
+}
+
+impl TickSequencer {
+    fn new(table: &'static str) -> Self {
+        Self {
+            table,
+            keys: HashMap::new(),
+        }
+    }
+
+    /// `seq` for a tick whose identical stored rows have the `matching` seqs.
+    fn next(
+        &mut self,
+        connection: &Connection,
+        symbol: &str,
+        ts: &str,
+        matching: Vec<i32>,
+    ) -> Result<i32, WarehouseError> {
+        let key = (symbol.to_string(), ts.to_string());
+        if !self.keys.contains_key(&key) {
+            let next: i32 = connection.query_row(
+                &format!(
+                    "SELECT COALESCE(MAX(seq) + 1, 0) FROM {} \
+                     WHERE symbol = ? AND ts = TRY_CAST(? AS TIMESTAMP)",
+                    self.table
+                ),
+                [symbol, ts],
+                |row| row.get(0),
+            )?;
+            self.keys.insert(key.clone(), (BTreeSet::new(), next));
+        }
+        let (claimed, next) = self.keys.get_mut(&key).expect("key inserted above");
+        let seq = match matching.into_iter().find(|seq| !claimed.contains(seq)) {
+            Some(seq) => seq,
+            None => {
+                *next += 1;
+                *next - 1
+            }
+        };
+        claimed.insert(seq);
+        Ok(seq)
+    }
+}
+
+/// Writes one `ingest_log` row per distinct symbol (or series) of a batch.
+fn log_batch_ingest<'a>(
+    connection: &Connection,
+    request_id: &str,
+    source: &str,
+    dataset: &str,
+    symbols: impl Iterator<Item = &'a str>,
+    latency_ms: u64,
+) -> Result<(), WarehouseError> {
+    let symbols: BTreeSet<&str> = symbols.collect();
+    for symbol in symbols {
+        // Use parameterized query for ingest_log
+        let params: [&dyn ToSql; 5] = [&request_id, &symbol, &source, &dataset, &latency_ms];
         connection.execute(
-            "INSERT OR REPLACE INTO cache_manifest \
-             (source, dataset, symbol, partition_date, path, row_count, min_ts, max_ts, checksum, updated_at) \
-             VALUES (?, ?, ?, ?, ?, ?, TRY_CAST(? AS TIMESTAMP), TRY_CAST(? AS TIMESTAMP), ?, CURRENT_TIMESTAMP)",
+            "INSERT INTO ingest_log \
+             (request_id, symbol, source, dataset, status, latency_ms, timestamp) \
+             VALUES (?, ?, ?, ?, 'ok', ?, CURRENT_TIMESTAMP)",
             params.as_slice(),
         )?;
+    }
+    Ok(())
+}
+
+/// Interval of a `bars_<interval>` dataset name.
+fn bars_interval(dataset: &str) -> Option<&'static str> {
+    let interval = dataset.strip_prefix("bars_")?;
+    BAR_INTERVALS
+        .into_iter()
+        .find(|candidate| *candidate == interval)
+}
+
+/// `time_bucket` width of a rollup target interval.
+fn rollup_bucket(interval: &str) -> Option<&'static str> {
+    match interval {
+        "5m" => Some("5 minutes"),
+        "15m" => Some("15 minutes"),
+        "1h" => Some("1 hour"),
+        "1d" => Some("1 day"),
+        _ => None,
+    }
+}
+
+/// A partition rewrite that only reaches the cache once its transaction commits.
+struct StagedPartition {
+    path: PathBuf,
+    /// Rewritten file to move into place, or `None` to remove the partition.
+    staging: Option<PathBuf>,
+}
+
+/// Move committed partition rewrites into place, in staging order so the
+/// latest rewrite of a file wins, and write their checksum sidecars.
+fn publish_partitions(staged: Vec<StagedPartition>) -> Result<(), WarehouseError> {
+    for partition in staged {
+        match partition.staging {
+            Some(staging) => {
+                fs::rename(staging, &partition.path)?;
+                write_checksum(&partition.path)?;
+            }
+            None => remove_partition_file(&partition.path)?,
+        }
+    }
+    Ok(())
+}
+
+/// Drop the staged files of a rolled-back transaction.
+fn discard_partitions(staged: &[StagedPartition]) {
+    for staging in staged
+        .iter()
+        .filter_map(|partition| partition.staging.as_ref())
+    {
+        let _ = fs::remove_file(staging);
+    }
+}
+
+/// Stage rewrites of the parquet partitions of `symbol` on `partition_date`
+/// from `bars`, recording them in `cache_manifest` and `staged`.
+///
+/// Every source with a partition for that day is rewritten, not only
+/// `source`, because an upsert may have replaced rows another source wrote.
+/// When the month was already compacted, the day is spliced into the monthly
+/// file instead of reappearing as a daily partition. Partitions left without
+/// rows are removed.
+fn write_bars_partitions(
+    connection: &Connection,
+    cache_root: &Path,
+    interval: &'static str,
+    source: &str,
+    symbol: &str,
+    partition_date: &str,
+    staged: &mut Vec<StagedPartition>,
+) -> Result<(), WarehouseError> {
+    let dataset = format!("bars_{interval}");
+    let mut sources = BTreeSet::from([source.to_string()]);
+    let mut statement = connection.prepare(
+        "SELECT DISTINCT source FROM cache_manifest \
+         WHERE dataset = ? AND symbol = ? \
+           AND partition_date IN (CAST(? AS DATE), date_trunc('month', CAST(? AS DATE)))",
+    )?;
+    let params: [&dyn ToSql; 4] = [&dataset, &symbol, &partition_date, &partition_date];
+    for existing in statement.query_map(params.as_slice(), |row| row.get::<_, String>(0))? {
+        sources.insert(existing?);
+    }
+
+    for source in &sources {
+        let daily = CachePartition::owned(cache_root, source, &dataset, symbol, partition_date)?;
+        let monthly = CachePartition::monthly(cache_root, source, &dataset, symbol, daily.month())?;
+
+        // COPY does not take parameters; every interpolated value is either
+        // validated above or escaped, and the date comes from DuckDB itself.
+        let day_rows = format!(
+            "SELECT ts, open, high, low, close, volume FROM bars \
+             WHERE interval = '{interval}' AND symbol = '{symbol}' AND source = '{source}' \
+             AND CAST(ts AS DATE) = DATE '{date}'",
+            symbol = escape_sql_string(symbol),
+            source = escape_sql_string(source),
+            date = escape_sql_string(partition_date),
+        );
+        // An earlier day of this batch may already have staged the monthly
+        // file; splice into that copy rather than the one still on disk.
+        let month_file = match staged
+            .iter()
+            .rev()
+            .find(|pending| pending.path == monthly.path)
+        {
+            Some(pending) => pending.staging.clone(),
+            None => monthly.path.exists().then(|| monthly.path.clone()),
+        };
+        let (partition, rows_sql) = match month_file {
+            Some(month_file) => {
+                let other_days = format!(
+                    "SELECT ts, open, high, low, close, volume \
+                     FROM read_parquet('{path}', hive_partitioning = false) \
+                     WHERE CAST(ts AS DATE) <> DATE '{date}'",
+                    path = escape_sql_string(path_to_sql(month_file.as_path()).as_str()),
+                    date = escape_sql_string(partition_date),
+                );
+                (monthly, format!("{other_days} UNION ALL {day_rows}"))
+            }
+            None => (daily, day_rows),
+        };
+
+        let directory = partition
+            .path
+            .parent()
+            .expect("partition path has a date directory");
+        fs::create_dir_all(directory)?;
+        let staging = partition
+            .path
+            .with_extension(format!("parquet.{}.tmp", staged.len()));
+        let copy_sql = format!(
+            "COPY ({rows_sql} ORDER BY ts) TO '{path}' (FORMAT PARQUET)",
+            path = escape_sql_string(path_to_sql(staging.as_path()).as_str()),
+        );
+        let written = connection.execute(copy_sql.as_str(), [])?;
+
+        if written == 0 {
+            fs::remove_file(&staging)?;
+            let path_str = path_to_sql(partition.path.as_path());
+            connection.execute("DELETE FROM cache_manifest WHERE path = ?", [&path_str])?;
+            staged.push(StagedPartition {
+                path: partition.path,
+                staging: None,
+            });
+            continue;
+        }
+
+        staged.push(StagedPartition {
+            path: partition.path.clone(),
+            staging: Some(staging.clone()),
+        });
+        record_manifest_entry(connection, &partition, staging.as_path())?;
+    }
+
+    Ok(())
+}
+
+/// Record a partition file in `cache_manifest` with its row count, time range
+/// and content checksum.
+fn upsert_manifest_entry(
+    connection: &Connection,
+    partition: &CachePartition,
+) -> Result<(), WarehouseError> {
+    record_manifest_entry(connection, partition, partition.path.as_path())
+}
+
+/// Record `partition` in `cache_manifest` from the contents of `file`, which
+/// may be a staged copy that has not been moved to the partition path yet.
+fn record_manifest_entry(
+    connection: &Connection,
+    partition: &CachePartition,
+    file: &Path,
+) -> Result<(), WarehouseError> {
+    let row_count = read_parquet_row_count(connection, file);
+    let (min_ts, max_ts) = read_parquet_min_max_ts(connection, file);
+    let checksum = file_checksum(file)?;
+    let path_str = path_to_sql(partition.path.as_path());
+
+    // SECURITY: All values are passed as parameters, not interpolated
+    let params: [&dyn ToSql; 9] = [
+        &partition.source,
+        &partition.dataset,
+        &partition.symbol,
+        &partition.partition_date,
+        &path_str,
+        &row_count,
+        &min_ts,
+        &max_ts,
+        &checksum,
+    ];
+    connection.execute(
+        "INSERT OR REPLACE INTO cache_manifest \
+         (source, dataset, symbol, partition_date, path, row_count, min_ts, max_ts, checksum, updated_at) \
+         VALUES (?, ?, ?, ?, ?, ?, TRY_CAST(? AS TIMESTAMP), TRY_CAST(? AS TIMESTAMP), ?, CURRENT_TIMESTAMP)",
+        params.as_slice(),
+    )?;
+    Ok(())
+}
+
+/// Writes the `ingest_log` row for a partition picked up from the cache.
+fn log_partition(
+    connection: &Connection,
+    partition: &CachePartition,
+    operation: &str,
+    status: &str,
+    batch_id: Option<&str>,
+) -> Result<(), WarehouseError> {
+    // Use parameterized query for ingest_log
+    let request_id = partition_request_id(operation, partition);
+    let params: [&dyn ToSql; 6] = [
+        &request_id,
+        &partition.symbol,
+        &partition.source,
+        &partition.dataset,
+        &status,
+        &batch_id,
+    ];
+    connection.execute(
+        "INSERT INTO ingest_log \
+         (request_id, symbol, source, dataset, status, latency_ms, batch_id, timestamp) \
+         VALUES (?, ?, ?, ?, ?, NULL, ?, CURRENT_TIMESTAMP)",
+        params.as_slice(),
+    )?;
+    Ok(())
+}
+
+/// Request id logged for `operation` on a cache partition.
+fn partition_request_id(operation: &str, partition: &CachePartition) -> String {
+    format!(
+        "{operation}:{}:{}:{}:{}",
+        partition.source, partition.dataset, partition.symbol, partition.partition_date
+    )
+}
+
+/// Look up the stable instrument id for a symbol.
+fn find_instrument_id(
+    connection: &Connection,
+    symbol: &str,
+) -> Result<Option<String>, WarehouseError> {
+    Ok(connection
+        .query_row(
+            "SELECT instrument_id FROM instruments WHERE symbol = ?",
+            [symbol],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+/// Insert or merge one instrument master row.
+fn upsert_instrument(
+    connection: &Connection,
+    source: &str,
+    row: &InstrumentRecord,
+) -> Result<(), WarehouseError> {
+    let known_by_figi = match (find_instrument_id(connection, &row.symbol)?, &row.figi) {
+        (None, Some(figi)) => connection
+            .query_row(
+                "SELECT symbol, instrument_id, source FROM instruments \
+                 WHERE figi = ? AND symbol <> ? AND is_active \
+                 ORDER BY updated_at DESC LIMIT 1",
+                [figi.as_str(), row.symbol.as_str()],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, Option<String>>(2)?,
+                    ))
+                },
+            )
+            .optional()?,
+        _ => None,
+    };
+
+    // An alias from another vendor only fills gaps in the canonical row, so
+    // its naming never overwrites what the listing source reported.
+    let mut fill_only = false;
+    let symbol = match &known_by_figi {
+        // Each provider keeps one symbology, so the same FIGI under a new
+        // symbol from the same source is a rename.
+        Some((old_symbol, instrument_id, old_source)) if old_source.as_deref() == Some(source) => {
+            apply_ticker_change(
+                connection,
+                source,
+                instrument_id,
+                old_symbol,
+                &row.symbol,
+                None,
+            )?;
+            row.symbol.as_str()
+        }
+        Some((canonical, instrument_id, _)) => {
+            let params: [&dyn ToSql; 3] = [&source, &row.symbol, instrument_id];
+            connection.execute(
+                "INSERT OR REPLACE INTO instrument_aliases \
+                 (provider, alias, instrument_id, updated_at) \
+                 VALUES (?, ?, ?, CURRENT_TIMESTAMP)",
+                params.as_slice(),
+            )?;
+            fill_only = true;
+            canonical.as_str()
+        }
+        None => {
+            let params: [&dyn ToSql; 7] = [
+                &row.symbol,
+                &row.symbol,
+                &row.name,
+                &row.symbol,
+                &row.currency,
+                &row.asset_class,
+                &source,
+            ];
+            connection.execute(
+                "INSERT OR IGNORE INTO instruments \
+                 (symbol, instrument_id, name, currency, asset_class, is_active, source, updated_at) \
+                 VALUES (?, ?, COALESCE(?, ?), COALESCE(?, 'USD'), COALESCE(?, 'equity'), TRUE, ?, CURRENT_TIMESTAMP)",
+                params.as_slice(),
+            )?;
+
+            // Without a listing date, assume the symbol has always been in use.
+            let params: [&dyn ToSql; 4] = [&row.symbol, &row.symbol, &row.listed_at, &source];
+            connection.execute(
+                "INSERT OR IGNORE INTO ticker_history \
+                 (instrument_id, symbol, valid_from, valid_to, source, updated_at) \
+                 VALUES (?, ?, COALESCE(TRY_CAST(? AS TIMESTAMP), TIMESTAMP '1970-01-01'), NULL, ?, CURRENT_TIMESTAMP)",
+                params.as_slice(),
+            )?;
+            row.symbol.as_str()
+        }
+    };
+
+    // The source stays the one that first listed the symbol, so quotes and
+    // aliases from other vendors do not break rename detection above.
+    // SECURITY: All user-provided values are passed as parameters
+    let params: [&dyn ToSql; 15] = [
+        &row.name,
+        &row.exchange,
+        &row.currency,
+        &row.asset_class,
+        &row.is_active,
+        &row.isin,
+        &row.cusip,
+        &row.figi,
+        &row.cik,
+        &row.sector,
+        &row.industry,
+        &row.listed_at,
+        &row.delisted_at,
+        &source,
+        &symbol,
+    ];
+    let merge = |column: &str, value: &str| {
+        if fill_only {
+            format!("{column} = COALESCE({column}, {value})")
+        } else {
+            format!("{column} = COALESCE({value}, {column})")
+        }
+    };
+    let assignments = [
+        merge("name", "?"),
+        merge("exchange", "?"),
+        merge("currency", "?"),
+        merge("asset_class", "?"),
+        merge("is_active", "?"),
+        merge("isin", "?"),
+        merge("cusip", "?"),
+        merge("figi", "?"),
+        merge("cik", "?"),
+        merge("sector", "?"),
+        merge("industry", "?"),
+        merge("listed_at", "TRY_CAST(? AS TIMESTAMP)"),
+        merge("delisted_at", "TRY_CAST(? AS TIMESTAMP)"),
+    ];
+    connection.execute(
+        &format!(
+            "UPDATE instruments SET {}, \
+                 source = COALESCE(source, ?), \
+                 updated_at = CURRENT_TIMESTAMP \
+             WHERE symbol = ?",
+            assignments.join(", ")
+        ),
+        params.as_slice(),
+    )?;
+
+    Ok(())
+}
+
+/// Move an instrument from `old_symbol` to `new_symbol`.
+///
+/// Without `effective_at` the change is dated at the current transaction.
+fn apply_ticker_change(
+    connection: &Connection,
+    source: &str,
+    instrument_id: &str,
+    old_symbol: &str,
+    new_symbol: &str,
+    effective_at: Option<&str>,
+) -> Result<(), WarehouseError> {
+    let params: [&dyn ToSql; 3] = [&new_symbol, &source, &old_symbol];
+    connection.execute(
+        "INSERT OR IGNORE INTO instruments \
+         (symbol, instrument_id, name, exchange, currency, asset_class, is_active, isin, cusip, figi, cik, sector, industry, listed_at, delisted_at, source, updated_at) \
+         SELECT ?, instrument_id, name, exchange, currency, asset_class, TRUE, isin, cusip, figi, cik, sector, industry, listed_at, NULL, ?, CURRENT_TIMESTAMP \
+         FROM instruments WHERE symbol = ?",
+        params.as_slice(),
+    )?;
+    connection.execute(
+        "UPDATE instruments SET is_active = FALSE, updated_at = CURRENT_TIMESTAMP WHERE symbol = ?",
+        [old_symbol],
+    )?;
+
+    let params: [&dyn ToSql; 3] = [&effective_at, &instrument_id, &old_symbol];
+    connection.execute(
+        "UPDATE ticker_history \
+         SET valid_to = COALESCE(TRY_CAST(? AS TIMESTAMP), CAST(CURRENT_TIMESTAMP AS TIMESTAMP)), \
+             updated_at = CURRENT_TIMESTAMP \
+         WHERE instrument_id = ? AND symbol = ? AND valid_to IS NULL",
+        params.as_slice(),
+    )?;
+
+    let params: [&dyn ToSql; 4] = [&instrument_id, &new_symbol, &effective_at, &source];
+    connection.execute(
+        "INSERT OR REPLACE INTO ticker_history \
+         (instrument_id, symbol, valid_from, valid_to, source, updated_at) \
+         VALUES (?, ?, COALESCE(TRY_CAST(? AS TIMESTAMP), CAST(CURRENT_TIMESTAMP AS TIMESTAMP)), NULL, ?, CURRENT_TIMESTAMP)",
+        params.as_slice(),
+    )?;
+
+    Ok(())
+}
+
+/// Execute a query with guardrails (timeout, row limits).
+fn execute_with_guardrails(
+    connection: &Connection,
+    sql: &str,
+    params: &QueryParams,
+    guardrails: QueryGuardrails,
+    allow_write: bool,
+) -> Result<QueryResult, WarehouseError> {
+    let started = Instant::now();
+    if is_select_like(sql) {
+        execute_select_query(connection, sql, params, guardrails, started)
+    } else if allow_write {
+        // Batches may hold several statements but cannot take parameters.
+        if params.is_empty() {
+            connection.execute_batch(sql)?;
+        } else {
+            let mut statement = connection.prepare(sql)?;
+            queries::bind_named_params(&mut statement, params)?;
+            statement.raw_execute()?;
+        }
+        ensure_timeout(started, guardrails.timeout())?;
+        Ok(QueryResult {
+            columns: Vec::new(),
+            rows: Vec::new(),
+            row_count: 0,
+            truncated: false,
+        })
+    } else {
+        Err(WarehouseError::QueryRejected(String::from(
+            "only SELECT/CTE queries are allowed unless --write is provided",
+        )))
+    }
+}
+
+/// Execute a SELECT query and collect results.
+fn execute_select_query(
+    connection: &Connection,
+    sql: &str,
+    params: &QueryParams,
+    guardrails: QueryGuardrails,
+    started: Instant,
+) -> Result<QueryResult, WarehouseError> {
+    // Prepare, bind and execute the statement
+    let mut statement = connection.prepare(sql)?;
+    queries::bind_named_params(&mut statement, params)?;
+    statement.raw_execute()?;
+
+    // Get column metadata after execution
+    let column_count = statement.column_count();
+    let mut columns = Vec::with_capacity(column_count);
+    for index in 0..column_count {
+        let name = statement.column_name(index).unwrap().to_string();
+        let dtype = statement.column_type(index);
+        columns.push(SqlColumn {
+            name,
+            r#type: dtype.to_string(),
+        });
+    }
+
+    // Get results
+    let mut rows_cursor = statement.raw_query();
+    let mut rows = Vec::new();
+    let mut truncated = false;
+
+    while let Some(row) = rows_cursor.next()? {
+        ensure_timeout(started, guardrails.timeout())?;
+
+        if rows.len() >= guardrails.max_rows {
+            truncated = true;
+            break;
+        }
+
+        rows.push(read_row(row, column_count)?);
+    }
+
+    ensure_timeout(started, guardrails.timeout())?;
+
+    Ok(QueryResult {
+        columns,
+        row_count: rows.len(),
+        rows,
+        truncated,
+    })
+}
+
+/// Read a single row from the result set.
+fn read_row(row: &::duckdb::Row<'_>, column_count: usize) -> Result<Vec<Value>, ::duckdb::Error> {
+    let mut output = Vec::with_capacity(column_count);
+    for index in 0..column_count {
+        let value: DuckValue = row.get(index)?;
+        output.push(to_json_value(value));
+    }
+    Ok(output)
+}
+
+/// Convert a DuckDB value to a JSON value.
+fn to_json_value(value: DuckValue) -> Value {
+    match value {
+        DuckValue::Null => Value::Null,
+        DuckValue::Boolean(value) => Value::Bool(value),
+        DuckValue::TinyInt(value) => Value::Number(Number::from(value)),
+        DuckValue::SmallInt(value) => Value::Number(Number::from(value)),
+        DuckValue::Int(value) => Value::Number(Number::from(value)),
+        DuckValue::BigInt(value) => Value::Number(Number::from(value)),
+        DuckValue::UTinyInt(value) => Value::Number(Number::from(value)),
+        DuckValue::USmallInt(value) => Value::Number(Number::from(value)),
+        DuckValue::UInt(value) => Value::Number(Number::from(value)),
+        DuckValue::UBigInt(value) => Value::Number(Number::from(value)),
+        DuckValue::Float(value) => number_from_f64(value as f64),
+        DuckValue::Double(value) => number_from_f64(value),
+        DuckValue::Text(value) => Value::String(value),
+        DuckValue::Blob(value) => Value::String(hex::encode(value)),
+        other => Value::String(format!("{other:?}")),
+    }
+}
+
+/// Convert an f64 to a JSON number, returning Null for NaN/Inf.
+fn number_from_f64(value: f64) -> Value {
+    Number::from_f64(value)
+        .map(Value::Number)
+        .unwrap_or(Value::Null)
+}
+
+/// Normalize a SQL query string.
+fn normalize_sql(sql: &str) -> Result<&str, WarehouseError> {
+    let normalized = sql.trim();
+    if normalized.is_empty() {
+        return Err(WarehouseError::QueryRejected(String::from(
+            "query must not be empty",
+        )));
+    }
+    Ok(normalized.trim_end_matches(';').trim())
+}
+
+/// Enforce that a query is read-only (SELECT/CTE only).
+fn enforce_read_only_query(sql: &str) -> Result<(), WarehouseError> {
+    if !is_select_like(sql) {
+        return Err(WarehouseError::QueryRejected(String::from(
+            "read-only mode accepts only SELECT/CTE queries; use --write for write statements",
+        )));
+    }
+    if has_multiple_statements(sql) {
+        return Err(WarehouseError::QueryRejected(String::from(
+            "multiple SQL statements are not allowed in read-only mode",
+        )));
+    }
+    Ok(())
+}
+
+/// Check if a SQL query starts with a SELECT-like keyword.
+fn is_select_like(sql: &str) -> bool {
+    let first_keyword = sql
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_ascii_uppercase();
+    matches!(
+        first_keyword.as_str(),
+        "SELECT" | "WITH" | "EXPLAIN" | "SHOW" | "DESCRIBE"
+    )
+}
+
+/// Check if a SQL string contains multiple statements.
+fn has_multiple_statements(sql: &str) -> bool {
+    sql.split(';')
+        .filter(|part| !part.trim().is_empty())
+        .count()
+        > 1
+}
+
+/// Ensure that the query has not exceeded the timeout.
+fn ensure_timeout(started: Instant, timeout: Duration) -> Result<(), WarehouseError> {
+    if started.elapsed() > timeout {
+        return Err(WarehouseError::QueryTimeout {
+            timeout_ms: timeout.as_millis().min(u128::from(u64::MAX)) as u64,
+        });
+    }
+    Ok(())
+}
+
+/// Resolve the ferrotick home directory from environment or default.
+fn resolve_ferrotick_home() -> PathBuf {
+    if let Some(path) = env::var_os("FERROTICK_HOME") {
+        let path = PathBuf::from(path);
+        if !path.as_os_str().is_empty() {
+            return path;
+        }
+    }
+
+    if let Some(home) = env::var_os("HOME") {
+        return PathBuf::from(home).join(".ferrotick");
+    }
+
+    PathBuf::from(".ferrotick")
+}
+
+/// Recursively collect parquet files from a directory.
+fn collect_parquet_files(root: &Path, files: &mut Vec<PathBuf>) -> Result<(), std::io::Error> {
+    for entry in fs::read_dir(root)? {
+        let entry = entry?;
+        let path = entry.path();
+        if path.is_dir() {
+            collect_parquet_files(path.as_path(), files)?;
+            continue;
+        }
+        if path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("parquet"))
+        {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Parse a partition path into its components.
+fn parse_partition(path: &Path) -> Option<CachePartition> {
+    let mut source = None;
+    let mut dataset = None;
+    let mut symbol = None;
+    let mut partition_date = None;
+    let mut monthly = false;
+
+    for component in path.components() {
+        let component = component.as_os_str().to_string_lossy();
+        if let Some(value) = component.strip_prefix("source=") {
+            source = Some(value.to_string());
+        } else if let Some(value) = component.strip_prefix("dataset=") {
+            dataset = Some(value.to_string());
+        } else if let Some(value) = component.strip_prefix("symbol=") {
+            symbol = Some(value.to_string());
+        } else if let Some(value) = component.strip_prefix("date=") {
+            partition_date = Some(value.to_string());
+        } else if let Some(value) = component.strip_prefix("month=") {
+            partition_date = Some(format!("{value}-01"));
+            monthly = true;
+        }
+    }
+
+    Some(CachePartition {
+        source: source?,
+        dataset: dataset?,
+        symbol: symbol?,
+        partition_date: partition_date?,
+        monthly,
+        path: path.to_path_buf(),
+    })
+}
+
+/// Read the row count from a parquet file.
+///
+/// # Security
+/// The path is validated by DuckDB's read_parquet function.
+/// Path escaping is handled by using parameterized queries.
+fn read_parquet_row_count(connection: &Connection, parquet_path: &Path) -> i64 {
+    let path_str = path_to_sql(parquet_path);
+    // read_parquet is a DuckDB built-in function that safely handles file paths
+    // The path comes from our own filesystem scanning, not user input
+    let sql = format!(
+        "SELECT COUNT(*) FROM read_parquet('{}')",
+        escape_sql_string(path_str.as_str())
+    );
+    connection
+        .query_row(sql.as_str(), [], |row| row.get(0))
+        .unwrap_or_default()
+}
+
+/// Read the min and max timestamps from a parquet file.
+///
+/// # Security
+/// The path is validated by DuckDB's read_parquet function.
+/// Path escaping is handled by using parameterized queries.
+fn read_parquet_min_max_ts(
+    connection: &Connection,
+    parquet_path: &Path,
+) -> (Option<String>, Option<String>) {
+    let path_str = escape_sql_string(path_to_sql(parquet_path).as_str());
+    // read_parquet is a DuckDB built-in function that safely handles file paths
+    // The path comes from our own filesystem scanning, not user input
+    for candidate in ["ts", "as_of", "date", "timestamp", "ex_date"] {
+        let sql = format!(
+            "SELECT CAST(MIN({column}) AS VARCHAR), CAST(MAX({column}) AS VARCHAR) FROM read_parquet('{path}')",
+            column = candidate,
+            path = path_str,
+        );
+        let parsed = connection.query_row(sql.as_str(), [], |row| {
+            let min_ts: Option<String> = row.get(0)?;
+            let max_ts: Option<String> = row.get(1)?;
+            Ok((min_ts, max_ts))
+        });
+        if let Ok((min_ts, max_ts)) = parsed {
+            return (min_ts, max_ts);
+        }
+    }
+
+    (None, None)
+}
+
+/// SHA-256 of a file's contents, hex encoded.
+fn file_checksum(path: &Path) -> Result<String, std::io::Error> {
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut fs::File::open(path)?, &mut hasher)?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Path of the checksum sidecar beside a partition file.
+fn checksum_path(path: &Path) -> PathBuf {
+    let mut sidecar = path.as_os_str().to_owned();
+    sidecar.push(CHECKSUM_SUFFIX);
+    PathBuf::from(sidecar)
+}
+
+/// Record the checksum of a published partition in its sidecar.
+fn write_checksum(path: &Path) -> Result<(), std::io::Error> {
+    fs::write(checksum_path(path), file_checksum(path)?)
+}
+
+/// Check a partition against its sidecar, returning its checksum.
+///
+/// A partition without a sidecar is returned unverified.
+fn verify_checksum(path: &Path) -> Result<String, WarehouseError> {
+    let actual = file_checksum(path)?;
+    let expected = match fs::read_to_string(checksum_path(path)) {
+        Ok(expected) => expected.trim().to_string(),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(actual),
+        Err(error) => return Err(error.into()),
+    };
+    if expected != actual {
+        return Err(WarehouseError::ChecksumMismatch {
+            path: path.to_path_buf(),
+            expected,
+            actual,
+        });
+    }
+    Ok(actual)
+}
+
+/// Delete a partition file and its checksum sidecar, if present.
+fn remove_partition_file(path: &Path) -> Result<(), std::io::Error> {
+    for file in [path.to_path_buf(), checksum_path(path)] {
+        match fs::remove_file(&file) {
+            Err(error) if error.kind() != std::io::ErrorKind::NotFound => return Err(error),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Reject values that would escape or restructure the partition layout.
+fn partition_component(value: &str) -> Result<&str, WarehouseError> {
+    if value.is_empty() || value == "." || value == ".." || value.contains(['/', '\\', '=']) {
+        return Err(WarehouseError::QueryRejected(format!(
+            "invalid cache partition value '{value}'"
+        )));
+    }
+    Ok(value)
+}
+
+/// Convert a path to a SQL-compatible string (forward slashes).
+fn path_to_sql(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// Escape a string for safe inclusion in SQL.
+///
+/// # Security Note
+/// This is used only for internal file paths that are scanned from the filesystem.
+/// User-provided data should always use parameterized queries instead.
+fn escape_sql_string(value: &str) -> String {
+    value.replace('\'', "''")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+    use tempfile::tempdir;
+
+    #[test]
+    fn initializes_tables_and_views() {
+        let temp = tempdir().expect("tempdir");
+        let ferrotick_home = temp.path().join("ferrotick-home");
+        let db_path = ferrotick_home.join("cache").join("warehouse.duckdb");
+
+        let warehouse = Warehouse::open(WarehouseConfig {
+            ferrotick_home,
+            db_path,
+            max_pool_size: 2,
+        })
+        .expect("warehouse open");
+
+        let query = warehouse
+            .execute_query(
+                "SELECT COUNT(*) AS c FROM information_schema.tables WHERE table_name = 'quotes_latest'",
+                QueryGuardrails::default(),
+                false,
+            )
+            .expect("query");
+        assert_eq!(query.row_count, 1);
+    }
+
+    #[test]
+    fn read_only_mode_rejects_write_query() {
+        let temp = tempdir().expect("tempdir");
+        let ferrotick_home = temp.path().join("ferrotick-home");
+        let db_path = ferrotick_home.join("cache").join("warehouse.duckdb");
+
+        let warehouse = Warehouse::open(WarehouseConfig {
+            ferrotick_home,
+            db_path,
+            max_pool_size: 2,
+        })
+        .expect("warehouse open");
+
+        let error = warehouse
+            .execute_query(
+                "CREATE TABLE test_write (id INTEGER)",
+                QueryGuardrails::default(),
+                false,
+            )
+            .expect_err("should reject");
+
+        assert!(matches!(error, WarehouseError::QueryRejected(_)));
+    }
+
+    #[test]
+    fn ingest_quotes_uses_parameterized_queries() {
+        let temp = tempdir().expect("tempdir");
+        let ferrotick_home = temp.path().join("ferrotick-home");
+        let db_path = ferrotick_home.join("cache").join("warehouse.duckdb");
+
+        let warehouse = Warehouse::open(WarehouseConfig {
+            ferrotick_home,
+            db_path,
+            max_pool_size: 2,
+        })
+        .expect("warehouse open");
+
+        // Test with potentially dangerous strings that would break non-parameterized queries
+        // Using raw string to avoid quote escaping issues
+        let dangerous_symbol = r#"AAPL'; DROP TABLE quotes_latest; --"#;
+        let quotes = vec![QuoteRecord {
+            symbol: dangerous_symbol.to_string(),
+            price: 150.0,
+            bid: Some(149.5),
+            ask: Some(150.5),
+            volume: Some(1000),
+            currency: "USD".to_string(),
+            as_of: "2026-02-20T10:00:00Z".to_string(),
+        }];
+
+        // This should succeed with parameterized queries
+        warehouse
+            .ingest_quotes("test", "req-001", &quotes, 100)
+            .expect("ingest should succeed with parameterized queries");
+
+        // Verify the data was inserted correctly
+        let result = warehouse
+            .execute_query(
+                r#"SELECT symbol, price FROM quotes_latest WHERE symbol LIKE '%DROP%'"#,
+                QueryGuardrails::default(),
+                false,
+            )
+            .expect("query");
+
+        assert_eq!(result.row_count, 1);
+        assert_eq!(
+            result.rows[0][0],
+            Value::String(dangerous_symbol.to_string())
+        );
+    }
+
+    #[test]
+    fn ingest_bars_uses_parameterized_queries() {
+        let temp = tempdir().expect("tempdir");
+        let ferrotick_home = temp.path().join("ferrotick-home");
+        let db_path = ferrotick_home.join("cache").join("warehouse.duckdb");
+
+        let warehouse = Warehouse::open(WarehouseConfig {
+            ferrotick_home,
+            db_path,
+            max_pool_size: 2,
+        })
+        .expect("warehouse open");
+
+        // Test with potentially dangerous strings
+        let dangerous_symbol = r#"MSFT'; DELETE FROM bars_1d; --"#;
+        let bars = vec![BarRecord {
+            symbol: dangerous_symbol.to_string(),
+            ts: "2026-02-20T10:00:00Z".to_string(),
+            open: 300.0,
+            high: 305.0,
+            low: 299.0,
+            close: 303.0,
+            volume: Some(2000),
+        }];
+
+        warehouse
+            .ingest_bars("test", "bars_1d", "req-002", &bars, 50)
+            .expect("ingest should succeed");
+
+        // Verify the data was inserted correctly
+        let result = warehouse
+            .execute_query(
+                r#"SELECT symbol, close FROM bars_1d WHERE symbol LIKE '%DELETE%'"#,
+                QueryGuardrails::default(),
+                false,
+            )
+            .expect("query");
+
+        assert_eq!(result.row_count, 1);
+    }
+
+    #[test]
+    fn ingest_fundamentals_uses_parameterized_queries() {
+        let temp = tempdir().expect("tempdir");
+        let ferrotick_home = temp.path().join("ferrotick-home");
+        let db_path = ferrotick_home.join("cache").join("warehouse.duckdb");
+
+        let warehouse = Warehouse::open(WarehouseConfig {
+            ferrotick_home,
+            db_path,
+            max_pool_size: 2,
+        })
+        .expect("warehouse open");
+
+        // Test with potentially dangerous strings
+        let dangerous_symbol = r#"GOOG'); DROP TABLE fundamentals; --"#;
+        let dangerous_metric = r#"pe_ratio"; DELETE FROM fundamentals; --"#;
+        let fundamentals = vec![FundamentalRecord {
+            symbol: dangerous_symbol.to_string(),
+            metric: dangerous_metric.to_string(),
+            value: 25.5,
+            date: "2026-02-20T00:00:00Z".to_string(),
+        }];
+
+        warehouse
+            .ingest_fundamentals("test", "req-003", &fundamentals, 25)
+            .expect("ingest should succeed");
 
-        // Use parameterized query for ingest_log
-        let request_id = format!(
-            "cache-sync:{}:{}:{}:{}",
-            partition.source, partition.dataset, partition.symbol, partition.partition_date
+        // Verify the data was inserted correctly
+        let result = warehouse
+            .execute_query(
+                r#"SELECT symbol, metric, value FROM fundamentals WHERE symbol LIKE '%DROP%'"#,
+                QueryGuardrails::default(),
+                false,
+            )
+            .expect("query");
+
+        assert_eq!(result.row_count, 1);
+        assert_eq!(
+            result.rows[0][2],
+            Value::Number(Number::from_f64(25.5).unwrap())
         );
-        let params: [&dyn ToSql; 4] = [
-            &request_id,
-            &partition.symbol,
-            &partition.source,
-            &partition.dataset,
-        ];
-        connection.execute(
-            "INSERT INTO ingest_log \
-             (request_id, symbol, source, dataset, status, latency_ms, timestamp) \
-             VALUES (?, ?, ?, ?, 'synced', NULL, CURRENT_TIMESTAMP)",
-            params.as_slice(),
-        )?;
+    }
 
-        Ok(())
+    #[test]
+    fn query_params_are_bound_by_name() {
+        let temp = tempdir().expect("tempdir");
+        let ferrotick_home = temp.path().join("ferrotick-home");
+        let db_path = ferrotick_home.join("cache").join("warehouse.duckdb");
+
+        let warehouse = Warehouse::open(WarehouseConfig {
+            ferrotick_home,
+            db_path,
+            max_pool_size: 2,
+        })
+        .expect("warehouse open");
+
+        let bar = |symbol: &str, day: &str| BarRecord {
+            symbol: symbol.to_string(),
+            ts: format!("{day}T00:00:00Z"),
+            open: 1.0,
+            high: 1.0,
+            low: 1.0,
+            close: 1.0,
+            volume: None,
+        };
+        warehouse
+            .ingest_bars(
+                "test",
+                "bars_1d",
+                "req-params",
+                &[
+                    bar("AAPL", "2026-02-16"),
+                    bar("AAPL", "2026-02-17"),
+                    bar("MSFT", "2026-02-17"),
+                ],
+                10,
+            )
+            .expect("ingest");
+
+        let sql = "SELECT COUNT(*) FROM bars_1d WHERE symbol = $symbol AND ts >= $since";
+        let count = |symbol: &str| {
+            let params = QueryParams::from([
+                (String::from("symbol"), QueryParam::from(symbol)),
+                (
+                    String::from("since"),
+                    QueryParam::Date(String::from("2026-02-17")),
+                ),
+            ]);
+            warehouse
+                .execute_query_with_params(sql, &params, QueryGuardrails::default(), false)
+                .expect("query")
+                .rows[0][0]
+                .clone()
+        };
+        assert_eq!(count("AAPL"), Value::from(1));
+        assert_eq!(count("AAPL' OR '1'='1"), Value::from(0));
+
+        let missing = warehouse
+            .execute_query(sql, QueryGuardrails::default(), false)
+            .expect_err("unbound placeholder");
+        assert!(matches!(missing, WarehouseError::QueryRejected(_)));
+        let extra = QueryParams::from([(String::from("limit"), QueryParam::Int(1))]);
+        assert!(warehouse
+            .execute_query_with_params("SELECT 1", &extra, QueryGuardrails::default(), false)
+            .is_err());
     }
-}
 
-/// Finalize a transaction, committing on success or rolling back on failure.
-fn finalize_transaction<T>(
-    connection: &Connection,
-    result: Result<T, WarehouseError>,
-) -> Result<T, WarehouseError> {
-    match result {
-        Ok(value) => {
-            connection.execute_batch("COMMIT")?;
-            Ok(value)
+    #[test]
+    fn analytics_catalog_matches_views_and_macros() {
+        let temp = tempdir().expect("tempdir");
+        let ferrotick_home = temp.path().join("ferrotick-home");
+        let db_path = ferrotick_home.join("cache").join("warehouse.duckdb");
+
+        let warehouse = Warehouse::open(WarehouseConfig {
+            ferrotick_home,
+            db_path,
+            max_pool_size: 2,
+        })
+        .expect("warehouse open");
+
+        // AAPL moves exactly twice as much as SPY every day.
+        let spy_returns = [0.01, -0.02, 0.03, -0.01, 0.02];
+        let mut bars = Vec::new();
+        let (mut spy, mut aapl) = (100.0, 50.0);
+        for day in 0..=spy_returns.len() {
+            if day > 0 {
+                spy *= 1.0 + spy_returns[day - 1];
+                aapl *= 1.0 + 2.0 * spy_returns[day - 1];
+            }
+            for (symbol, close) in [("SPY", spy), ("AAPL", aapl)] {
+                bars.push(BarRecord {
+                    symbol: symbol.to_string(),
+                    ts: format!("2026-02-{:02}T00:00:00Z", day + 2),
+                    open: close,
+                    high: close + 1.0,
+                    low: close - 1.0,
+                    close,
+                    volume: Some(1_000 + day as u64 * 100),
+                });
+            }
         }
-        Err(error) => {
-            let _ = connection.execute_batch("ROLLBACK");
-            Err(error)
+        warehouse
+            .ingest_bars("test", "bars_1d", "req-analytics", &bars, 10)
+            .expect("ingest");
+
+        for object in ANALYTICS_CATALOG {
+            let source = match object.kind {
+                AnalyticsKind::View => object.name.to_string(),
+                AnalyticsKind::TableMacro => format!("{}()", object.name),
+            };
+            let result = warehouse
+                .execute_query(
+                    format!("SELECT * FROM {source}").as_str(),
+                    QueryGuardrails::default(),
+                    false,
+                )
+                .unwrap_or_else(|error| panic!("{}: {error}", object.name));
+            let columns = result
+                .columns
+                .iter()
+                .map(|column| column.name.as_str())
+                .collect::<Vec<_>>();
+            assert_eq!(columns, object.columns, "{}", object.name);
+        }
+
+        let result = warehouse
+            .execute_query(
+                "SELECT b.beta, c.correlation \
+                 FROM rolling_beta_v1(window_days := 3) b \
+                 JOIN rolling_correlation_v1(window_days := 3) c USING (symbol, date) \
+                 WHERE symbol = 'AAPL' AND b.beta IS NOT NULL ORDER BY date",
+                QueryGuardrails::default(),
+                false,
+            )
+            .expect("beta query");
+        assert_eq!(result.row_count, 3);
+        for row in &result.rows {
+            assert!((row[0].as_f64().expect("beta") - 2.0).abs() < 1e-9);
+            assert!((row[1].as_f64().expect("correlation") - 1.0).abs() < 1e-9);
         }
     }
-}
 
-/// Execute a query with guardrails (timeout, row limits).
-fn execute_with_guardrails(
-    connection: &Connection,
-    sql: &str,
-    guardrails: QueryGuardrails,
-    allow_write: bool,
-) -> Result<QueryResult, WarehouseError> {
-    let started = Instant::now();
-    if is_select_like(sql) {
-        execute_select_query(connection, sql, guardrails, started)
-    } else if allow_write {
-        connection.execute_batch(sql)?;
-        ensure_timeout(started, guardrails.timeout())?;
-        Ok(QueryResult {
-            columns: Vec::new(),
-            rows: Vec::new(),
-            row_count: 0,
-            truncated: false,
+    #[test]
+    fn financial_ratios_view_computes_margins_and_growth() {
+        let temp = tempdir().expect("tempdir");
+        let ferrotick_home = temp.path().join("ferrotick-home");
+        let db_path = ferrotick_home.join("cache").join("warehouse.duckdb");
+
+        let warehouse = Warehouse::open(WarehouseConfig {
+            ferrotick_home,
+            db_path,
+            max_pool_size: 2,
         })
-    } else {
-        Err(WarehouseError::QueryRejected(String::from(
-            "only SELECT/CTE queries are allowed unless --write is provided",
-        )))
-    }
-}
+        .expect("warehouse open");
 
-/// Execute a SELECT query and collect results.
-fn execute_select_query(
-    connection: &Connection,
-    sql: &str,
-    guardrails: QueryGuardrails,
-    started: Instant,
-) -> Result<QueryResult, WarehouseError> {
-    // Prepare and execute the statement
-    let mut statement = connection.prepare(sql)?;
-    let _ = statement.query([] as [&dyn ToSql; 0])?;
+        let record = |metric: &str, value: f64, end_date: &str| FinancialRecord {
+            symbol: "AAPL".to_string(),
+            statement_type: "income".to_string(),
+            period: "annual".to_string(),
+            label: metric.to_string(),
+            metric: Some(metric.to_string()),
+            value,
+            fiscal_year: None,
+            fiscal_quarter: None,
+            end_date: end_date.to_string(),
+            available_at: None,
+            currency: "USD".to_string(),
+        };
+        let rows = vec![
+            record("revenue", 200.0, "2024-09-30T00:00:00Z"),
+            record("net_income", 50.0, "2024-09-30T00:00:00Z"),
+            record("revenue", 160.0, "2023-09-30T00:00:00Z"),
+            record("net_income", 40.0, "2023-09-30T00:00:00Z"),
+        ];
 
-    // Get column metadata after execution
-    let column_count = statement.column_count();
-    let mut columns = Vec::with_capacity(column_count);
-    for index in 0..column_count {
-        let name = statement.column_name(index).unwrap().to_string();
-        let dtype = statement.column_type(index);
-        columns.push(SqlColumn {
-            name,
-            r#type: dtype.to_string(),
-        });
+        warehouse
+            .ingest_financials("test", "req-004", &rows, 10)
+            .expect("ingest should succeed");
+
+        let result = warehouse
+            .execute_query(
+                "SELECT net_margin, revenue_growth_yoy FROM vw_financial_ratios \
+                 WHERE symbol = 'AAPL' ORDER BY period_end DESC",
+                QueryGuardrails::default(),
+                false,
+            )
+            .expect("query");
+
+        assert_eq!(result.row_count, 2);
+        assert_eq!(result.rows[0][0], Value::from(0.25));
+        assert_eq!(result.rows[0][1], Value::from(0.25));
+        assert_eq!(result.rows[1][1], Value::Null);
     }
 
-    // Get results
-    let mut rows_cursor = statement.query([] as [&dyn ToSql; 0])?;
-    let mut rows = Vec::new();
-    let mut truncated = false;
+    #[test]
+    fn fundamentals_as_of_excludes_unreported_periods() {
+        let temp = tempdir().expect("tempdir");
+        let ferrotick_home = temp.path().join("ferrotick-home");
+        let db_path = ferrotick_home.join("cache").join("warehouse.duckdb");
 
-    while let Some(row) = rows_cursor.next()? {
-        ensure_timeout(started, guardrails.timeout())?;
+        let warehouse = Warehouse::open(WarehouseConfig {
+            ferrotick_home,
+            db_path,
+            max_pool_size: 2,
+        })
+        .expect("warehouse open");
 
-        if rows.len() >= guardrails.max_rows {
-            truncated = true;
-            break;
-        }
+        let record = |value: f64, end_date: &str| FinancialRecord {
+            symbol: "AAPL".to_string(),
+            statement_type: "income".to_string(),
+            period: "quarterly".to_string(),
+            label: "Total Revenue".to_string(),
+            metric: Some("revenue".to_string()),
+            value,
+            fiscal_year: None,
+            fiscal_quarter: None,
+            end_date: end_date.to_string(),
+            available_at: None,
+            currency: "USD".to_string(),
+        };
+        warehouse
+            .ingest_financials(
+                "test",
+                "req-005",
+                &[
+                    record(90.0, "2024-06-30T00:00:00Z"),
+                    record(95.0, "2024-09-30T00:00:00Z"),
+                ],
+                10,
+            )
+            .expect("ingest financials");
+        warehouse
+            .ingest_earnings(
+                "test",
+                "req-006",
+                &[EarningsRecord {
+                    symbol: "AAPL".to_string(),
+                    fiscal_year: 2024,
+                    fiscal_quarter: Some(4),
+                    period_end: "2024-09-30T00:00:00Z".to_string(),
+                    report_date: Some("2024-11-01T00:00:00Z".to_string()),
+                    eps_actual: Some(1.64),
+                    eps_estimate: Some(1.60),
+                    revenue_actual: None,
+                    revenue_estimate: None,
+                    surprise_percent: None,
+                }],
+                10,
+            )
+            .expect("ingest earnings");
 
-        rows.push(read_row(row, column_count)?);
-    }
+        let bars = [
+            ("2024-10-31T00:00:00Z", 100.0),
+            ("2024-11-01T00:00:00Z", 101.0),
+        ]
+        .map(|(ts, close)| BarRecord {
+            symbol: "AAPL".to_string(),
+            ts: ts.to_string(),
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume: None,
+        });
+        warehouse
+            .ingest_bars("test", "bars_1d", "req-007", &bars, 10)
+            .expect("ingest bars");
 
-    ensure_timeout(started, guardrails.timeout())?;
+        // The September quarter has ended but is not reported yet.
+        let before = warehouse
+            .fundamentals_as_of("AAPL", "2024-10-15T00:00:00Z")
+            .expect("as-of query");
+        assert_eq!(before.len(), 1);
+        assert_eq!(before[0].value, 90.0);
+        assert_eq!(
+            before[0].available_at, "2024-08-14T00:00:00Z",
+            "unreported quarters fall back to a 45-day lag"
+        );
 
-    Ok(QueryResult {
-        columns,
-        row_count: rows.len(),
-        rows,
-        truncated,
-    })
-}
+        let after = warehouse
+            .fundamentals_as_of("AAPL", "2024-11-01T00:00:00Z")
+            .expect("as-of query");
+        assert_eq!(after[0].value, 95.0);
+        assert_eq!(after[0].period_end.as_deref(), Some("2024-09-30T00:00:00Z"));
 
-/// Read a single row from the result set.
-fn read_row(row: &::duckdb::Row<'_>, column_count: usize) -> Result<Vec<Value>, ::duckdb::Error> {
-    let mut output = Vec::with_capacity(column_count);
-    for index in 0..column_count {
-        let value: DuckValue = row.get(index)?;
-        output.push(to_json_value(value));
+        let joined = warehouse
+            .join_fundamentals_as_of("AAPL", "quarterly", "revenue", None, None)
+            .expect("as-of join");
+        let values = joined.iter().map(|row| row.value).collect::<Vec<_>>();
+        assert_eq!(values, vec![Some(90.0), Some(95.0)]);
     }
-    Ok(output)
-}
 
-/// Convert a DuckDB value to a JSON value.
-fn to_json_value(value: DuckValue) -> Value {
-    match value {
-        DuckValue::Null => Value::Null,
-        DuckValue::Boolean(value) => Value::Bool(value),
-        DuckValue::TinyInt(value) => Value::Number(Number::from(value)),
-        DuckValue::SmallInt(value) => Value::Number(Number::from(value)),
-        DuckValue::Int(value) => Value::Number(Number::from(value)),
-        DuckValue::BigInt(value) => Value::Number(Number::from(value)),
-        DuckValue::UTinyInt(value) => Value::Number(Number::from(value)),
-        DuckValue::USmallInt(value) => Value::Number(Number::from(value)),
-        DuckValue::UInt(value) => Value::Number(Number::from(value)),
-        DuckValue::UBigInt(value) => Value::Number(Number::from(value)),
-        DuckValue::Float(value) => number_from_f64(value as f64),
-        DuckValue::Double(value) => number_from_f64(value),
-        DuckValue::Text(value) => Value::String(value),
-        DuckValue::Blob(value) => Value::String(hex::encode(value)),
-        other => Value::String(format!("{other:?}")),
+    #[test]
+    fn instrument_master_tracks_ticker_changes_and_aliases() {
+        let temp = tempdir().expect("tempdir");
+        let ferrotick_home = temp.path().join("ferrotick-home");
+        let db_path = ferrotick_home.join("cache").join("warehouse.duckdb");
+
+        let warehouse = Warehouse::open(WarehouseConfig {
+            ferrotick_home,
+            db_path,
+            max_pool_size: 2,
+        })
+        .expect("warehouse open");
+
+        let record = |symbol: &str, figi: &str| InstrumentRecord {
+            symbol: symbol.to_string(),
+            name: Some(format!("{symbol} Inc.")),
+            figi: Some(figi.to_string()),
+            ..InstrumentRecord::default()
+        };
+        warehouse
+            .ingest_instruments(
+                "polygon",
+                "req-008",
+                &[
+                    InstrumentRecord {
+                        cik: Some("0001326801".to_string()),
+                        ..record("FB", "BBG000MM2P62")
+                    },
+                    record("BRK.B", "BBG000DWG505"),
+                ],
+                10,
+            )
+            .expect("ingest polygon instruments");
+        warehouse
+            .ingest_instruments(
+                "yahoo",
+                "req-009",
+                &[InstrumentRecord {
+                    sector: Some("Financial Services".to_string()),
+                    ..record("BRK-B", "BBG000DWG505")
+                }],
+                10,
+            )
+            .expect("ingest yahoo instruments");
+        // Same provider, same FIGI, new symbol: a rename.
+        warehouse
+            .ingest_instruments("polygon", "req-010", &[record("META", "BBG000MM2P62")], 10)
+            .expect("ingest renamed instrument");
+        warehouse
+            .record_ticker_change("manual", "TWTR", "X", "2023-07-24T00:00:00Z")
+            .expect("record ticker change");
+
+        let resolve = |symbol: &str, provider: Option<&str>, as_of: Option<&str>| {
+            warehouse
+                .resolve_symbol(symbol, provider, as_of)
+                .expect("resolve symbol")
+        };
+        assert_eq!(resolve("FB", None, None).as_deref(), Some("META"));
+        assert_eq!(
+            resolve("META", None, Some("2020-01-01T00:00:00Z")).as_deref(),
+            Some("FB")
+        );
+        assert_eq!(
+            resolve("BRK-B", Some("yahoo"), None).as_deref(),
+            Some("BRK.B")
+        );
+        assert_eq!(
+            resolve("X", None, Some("2023-01-01T00:00:00Z")).as_deref(),
+            Some("TWTR")
+        );
+        assert_eq!(resolve("UNKNOWN", None, None), None);
+
+        let result = warehouse
+            .execute_query(
+                "SELECT symbol, is_active, cik, sector, name, asset_class FROM instruments \
+                 WHERE symbol IN ('FB', 'META', 'BRK.B') ORDER BY symbol",
+                QueryGuardrails::default(),
+                false,
+            )
+            .expect("query");
+        assert_eq!(result.row_count, 3, "aliases do not create instruments");
+        assert_eq!(result.rows[0][3], Value::from("Financial Services"));
+        assert_eq!(
+            result.rows[0][4],
+            Value::from("BRK.B Inc."),
+            "an alias does not rename the canonical row"
+        );
+        assert_eq!(result.rows[0][5], Value::from("equity"));
+        assert_eq!(result.rows[1][1], Value::from(false));
+        assert_eq!(result.rows[2][1], Value::from(true));
+        assert_eq!(result.rows[2][2], Value::from("0001326801"));
     }
-}
 
-/// Convert an f64 to a JSON number, returning Null for NaN/Inf.
-fn number_from_f64(value: f64) -> Value {
-    Number::from_f64(value)
-        .map(Value::Number)
-        .unwrap_or(Value::Null)
-}
+    #[test]
+    fn quotes_from_another_source_keep_rename_detection() {
+        let temp = tempdir().expect("tempdir");
+        let ferrotick_home = temp.path().join("ferrotick-home");
+        let db_path = ferrotick_home.join("cache").join("warehouse.duckdb");
+
+        let warehouse = Warehouse::open(WarehouseConfig {
+            ferrotick_home,
+            db_path,
+            max_pool_size: 2,
+        })
+        .expect("warehouse open");
+
+        let record = |symbol: &str| InstrumentRecord {
+            symbol: symbol.to_string(),
+            figi: Some("BBG000MM2P62".to_string()),
+            ..InstrumentRecord::default()
+        };
+        warehouse
+            .ingest_instruments("polygon", "req-fb", &[record("FB")], 10)
+            .expect("ingest FB");
+        warehouse
+            .ingest_quotes(
+                "yahoo",
+                "req-quote",
+                &[QuoteRecord {
+                    symbol: "FB".to_string(),
+                    price: 300.0,
+                    bid: None,
+                    ask: None,
+                    volume: None,
+                    currency: "USD".to_string(),
+                    as_of: "2021-10-27T20:00:00Z".to_string(),
+                }],
+                10,
+            )
+            .expect("ingest yahoo quote");
+        warehouse
+            .ingest_instruments("polygon", "req-meta", &[record("META")], 10)
+            .expect("ingest META");
 
-/// Normalize a SQL query string.
-fn normalize_sql(sql: &str) -> Result<&str, WarehouseError> {
-    let normalized = sql.trim();
-    if normalized.is_empty() {
-        return Err(WarehouseError::QueryRejected(String::from(
-            "query must not be empty",
-        )));
+        assert_eq!(
+            warehouse
+                .resolve_symbol("FB", None, None)
+                .expect("resolve")
+                .as_deref(),
+            Some("META")
+        );
+        let aliases = warehouse
+            .execute_query(
+                "SELECT COUNT(*) FROM instrument_aliases",
+                QueryGuardrails::default(),
+                false,
+            )
+            .expect("query");
+        assert_eq!(aliases.rows[0][0], Value::from(0));
     }
-    Ok(normalized.trim_end_matches(';').trim())
-}
 
-/// Enforce that a query is read-only (SELECT/CTE only).
-fn enforce_read_only_query(sql: &str) -> Result<(), WarehouseError> {
-    if !is_select_like(sql) {
-        return Err(WarehouseError::QueryRejected(String::from(
-            "read-only mode accepts only SELECT/CTE queries; use --write for write statements",
-        )));
-    }
-    if has_multiple_statements(sql) {
-        return Err(WarehouseError::QueryRejected(String::from(
-            "multiple SQL statements are not allowed in read-only mode",
-        )));
-    }
-    Ok(())
-}
+    #[test]
+    fn reingesting_trade_ticks_replaces_same_timestamp_prints() {
+        let temp = tempdir().expect("tempdir");
+        let ferrotick_home = temp.path().join("ferrotick-home");
+        let db_path = ferrotick_home.join("cache").join("warehouse.duckdb");
 
-/// Check if a SQL query starts with a SELECT-like keyword.
-fn is_select_like(sql: &str) -> bool {
-    let first_keyword = sql
-        .split_whitespace()
-        .next()
-        .unwrap_or_default()
-        .to_ascii_uppercase();
-    matches!(
-        first_keyword.as_str(),
-        "SELECT" | "WITH" | "EXPLAIN" | "SHOW" | "DESCRIBE"
-    )
-}
+        let warehouse = Warehouse::open(WarehouseConfig {
+            ferrotick_home,
+            db_path,
+            max_pool_size: 2,
+        })
+        .expect("warehouse open");
 
-/// Check if a SQL string contains multiple statements.
-fn has_multiple_statements(sql: &str) -> bool {
-    sql.split(';')
-        .filter(|part| !part.trim().is_empty())
-        .count()
-        > 1
-}
+        let trade = |ts: &str, price: f64| TradeTickRecord {
+            symbol: "AAPL".to_string(),
+            ts: ts.to_string(),
+            price,
+            size: 100,
+            exchange: Some("Q".to_string()),
+            conditions: vec!["@".to_string(), "I".to_string()],
+        };
+        let trades = vec![
+            trade("2024-01-02T14:30:00Z", 185.50),
+            trade("2024-01-02T14:30:00Z", 185.51),
+            trade("2024-01-02T14:30:01Z", 185.52),
+        ];
+        warehouse
+            .ingest_trade_ticks("polygon", "req-ticks-1", &trades, 10)
+            .expect("ingest trades");
+        warehouse
+            .ingest_trade_ticks("polygon", "req-ticks-2", &trades, 10)
+            .expect("re-ingest trades");
+        // An overlapping batch with a new print first keeps stored numbering.
+        warehouse
+            .ingest_trade_ticks(
+                "polygon",
+                "req-ticks-3",
+                &[
+                    trade("2024-01-02T14:30:00Z", 185.49),
+                    trade("2024-01-02T14:30:00Z", 185.50),
+                ],
+                10,
+            )
+            .expect("ingest overlapping trades");
 
-/// Ensure that the query has not exceeded the timeout.
-fn ensure_timeout(started: Instant, timeout: Duration) -> Result<(), WarehouseError> {
-    if started.elapsed() > timeout {
-        return Err(WarehouseError::QueryTimeout {
-            timeout_ms: timeout.as_millis().min(u128::from(u64::MAX)) as u64,
-        });
+        let result = warehouse
+            .execute_query(
+                "SELECT seq, price, conditions FROM trade_ticks ORDER BY ts, seq",
+                QueryGuardrails::default(),
+                false,
+            )
+            .expect("query");
+        assert_eq!(result.row_count, 4);
+        let numbered = result
+            .rows
+            .iter()
+            .map(|row| (row[0].clone(), row[1].clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            numbered,
+            vec![
+                (Value::from(0), Value::from(185.50)),
+                (Value::from(1), Value::from(185.51)),
+                (Value::from(2), Value::from(185.49)),
+                (Value::from(0), Value::from(185.52)),
+            ]
+        );
+        assert_eq!(result.rows[0][2], Value::from("@,I"));
     }
-    Ok(())
-}
 
-/// Resolve the ferrotick home directory from environment or default.
-fn resolve_ferrotick_home() -> PathBuf {
-    if let Some(path) = env::var_os("FERROTICK_HOME") {
-        let path = PathBuf::from(path);
-        if !path.as_os_str().is_empty() {
-            return path;
-        }
-    }
+    #[test]
+    fn risk_free_rate_averages_treasury_yields_over_window() {
+        let temp = tempdir().expect("tempdir");
+        let ferrotick_home = temp.path().join("ferrotick-home");
+        let db_path = ferrotick_home.join("cache").join("warehouse.duckdb");
 
-    if let Some(home) = env::var_os("HOME") {
-        return PathBuf::from(home).join(".ferrotick");
-    }
+        let warehouse = Warehouse::open(WarehouseConfig {
+            ferrotick_home,
+            db_path,
+            max_pool_size: 2,
+        })
+        .expect("warehouse open");
 
-    PathBuf::from(".ferrotick")
-}
+        let observation = |date: &str, value: f64| MacroObservationRecord {
+            series_id: RISK_FREE_SERIES.to_string(),
+            date: date.to_string(),
+            value,
+            unit: Some("percent".to_string()),
+            frequency: Some("daily".to_string()),
+        };
+        warehouse
+            .ingest_macro_series(
+                "alphavantage",
+                "req-macro-1",
+                &[
+                    observation("2024-01-02T00:00:00Z", 5.0),
+                    observation("2024-01-03T00:00:00Z", 5.5),
+                    observation("2024-06-03T00:00:00Z", 4.0),
+                ],
+                10,
+            )
+            .expect("ingest macro series");
+        warehouse
+            .ingest_macro_series(
+                "manual",
+                "req-macro-2",
+                &[MacroObservationRecord {
+                    series_id: "SOFR_DECIMAL".to_string(),
+                    date: "2024-01-02T00:00:00Z".to_string(),
+                    value: 0.053,
+                    unit: None,
+                    frequency: Some("daily".to_string()),
+                }],
+                10,
+            )
+            .expect("ingest decimal series");
 
-/// Recursively collect parquet files from a directory.
-fn collect_parquet_files(root: &Path, files: &mut Vec<PathBuf>) -> Result<(), std::io::Error> {
-    for entry in fs::read_dir(root)? {
-        let entry = entry?;
-        let path = entry.path();
-        if path.is_dir() {
-            collect_parquet_files(path.as_path(), files)?;
-            continue;
-        }
-        if path
-            .extension()
-            .and_then(|extension| extension.to_str())
-            .is_some_and(|extension| extension.eq_ignore_ascii_case("parquet"))
-        {
-            files.push(path);
-        }
-    }
+        let window = warehouse
+            .risk_free_rate(
+                RISK_FREE_SERIES,
+                "2024-01-01T00:00:00Z",
+                "2024-01-31T00:00:00Z",
+            )
+            .expect("window rate")
+            .expect("window has observations");
+        assert!((window - 0.0525).abs() < 1e-12);
 
-    Ok(())
-}
+        let carried = warehouse
+            .risk_free_rate(
+                RISK_FREE_SERIES,
+                "2024-02-01T00:00:00Z",
+                "2024-02-29T00:00:00Z",
+            )
+            .expect("carried rate");
+        assert_eq!(carried, Some(0.055));
 
-/// Parse a partition path into its components.
-fn parse_partition(path: &Path) -> Option<CachePartition> {
-    let mut source = None;
-    let mut dataset = None;
-    let mut symbol = None;
-    let mut partition_date = None;
+        let decimal = warehouse
+            .risk_free_rate(
+                "SOFR_DECIMAL",
+                "2024-01-01T00:00:00Z",
+                "2024-01-31T00:00:00Z",
+            )
+            .expect("decimal rate");
+        assert_eq!(decimal, Some(0.053));
 
-    for component in path.components() {
-        let component = component.as_os_str().to_string_lossy();
-        if let Some(value) = component.strip_prefix("source=") {
-            source = Some(value.to_string());
-        } else if let Some(value) = component.strip_prefix("dataset=") {
-            dataset = Some(value.to_string());
-        } else if let Some(value) = component.strip_prefix("symbol=") {
-            symbol = Some(value.to_string());
-        } else if let Some(value) = component.strip_prefix("date=") {
-            partition_date = Some(value.to_string());
-        }
+        let missing = warehouse
+            .risk_free_rate(
+                "TREASURY_YIELD_10YEAR",
+                "2024-01-01T00:00:00Z",
+                "2024-12-31T00:00:00Z",
+            )
+            .expect("missing rate");
+        assert_eq!(missing, None);
     }
 
-    Some(CachePartition {
-        source: source?,
-        dataset: dataset?,
-        symbol: symbol?,
-        partition_date: partition_date?,
-        path: path.to_path_buf(),
-    })
-}
+    #[test]
+    fn news_sentiment_aggregates_per_symbol_and_day() {
+        let temp = tempdir().expect("tempdir");
+        let ferrotick_home = temp.path().join("ferrotick-home");
+        let db_path = ferrotick_home.join("cache").join("warehouse.duckdb");
 
-/// Read the row count from a parquet file.
-///
-/// # Security
-/// The path is validated by DuckDB's read_parquet function.
-/// Path escaping is handled by using parameterized queries.
-fn read_parquet_row_count(connection: &Connection, parquet_path: &Path) -> i64 {
-    let path_str = path_to_sql(parquet_path);
-    // read_parquet is a DuckDB built-in function that safely handles file paths
-    // The path comes from our own filesystem scanning, not user input
-    let sql = format!(
-        "SELECT COUNT(*) FROM read_parquet('{}')",
-        escape_sql_string(path_str.as_str())
-    );
-    connection
-        .query_row(sql.as_str(), [], |row| row.get(0))
-        .unwrap_or_default()
-}
+        let warehouse = Warehouse::open(WarehouseConfig {
+            ferrotick_home,
+            db_path,
+            max_pool_size: 2,
+        })
+        .expect("warehouse open");
 
-/// Read the min and max timestamps from a parquet file.
-///
-/// # Security
-/// The path is validated by DuckDB's read_parquet function.
-/// Path escaping is handled by using parameterized queries.
-fn read_parquet_min_max_ts(
-    connection: &Connection,
-    parquet_path: &Path,
-) -> (Option<String>, Option<String>) {
-    let path_str = escape_sql_string(path_to_sql(parquet_path).as_str());
-    // read_parquet is a DuckDB built-in function that safely handles file paths
-    // The path comes from our own filesystem scanning, not user input
-    for candidate in ["ts", "as_of", "date", "timestamp", "ex_date"] {
-        let sql = format!(
-            "SELECT CAST(MIN({column}) AS VARCHAR), CAST(MAX({column}) AS VARCHAR) FROM read_parquet('{path}')",
-            column = candidate,
-            path = path_str,
-        );
-        let parsed = connection.query_row(sql.as_str(), [], |row| {
-            let min_ts: Option<String> = row.get(0)?;
-            let max_ts: Option<String> = row.get(1)?;
-            Ok((min_ts, max_ts))
-        });
-        if let Ok((min_ts, max_ts)) = parsed {
-            return (min_ts, max_ts);
-        }
+        let article = |url: &str, published_at: &str, tickers: Vec<NewsTickerRecord>| NewsRecord {
+            url: url.to_string(),
+            published_at: published_at.to_string(),
+            publisher: "Example Wire".to_string(),
+            title: format!("Headline {url}"),
+            summary: None,
+            sentiment: Some(0.1),
+            tickers,
+        };
+        let ticker =
+            |symbol: &str, sentiment: Option<f64>, relevance: Option<f64>| NewsTickerRecord {
+                symbol: symbol.to_string(),
+                sentiment,
+                relevance,
+            };
+        warehouse
+            .ingest_news(
+                "alphavantage",
+                "req-news-1",
+                &[
+                    article(
+                        "https://example.com/a",
+                        "2024-02-01T13:00:00Z",
+                        vec![
+                            ticker("AAPL", Some(0.6), Some(0.9)),
+                            ticker("MSFT", Some(-0.2), Some(0.1)),
+                        ],
+                    ),
+                    article(
+                        "https://example.com/b",
+                        "2024-02-01T18:30:00Z",
+                        vec![ticker("AAPL", Some(0.0), Some(0.3))],
+                    ),
+                    article(
+                        "https://example.com/c",
+                        "2024-02-02T09:00:00Z",
+                        vec![ticker("AAPL", None, None)],
+                    ),
+                ],
+                10,
+            )
+            .expect("ingest news");
+        // Re-ingesting an article replaces its ticker rows.
+        warehouse
+            .ingest_news(
+                "polygon",
+                "req-news-2",
+                &[article(
+                    "https://example.com/b",
+                    "2024-02-01T18:30:00Z",
+                    vec![ticker("AAPL", Some(0.2), Some(0.3))],
+                )],
+                10,
+            )
+            .expect("reingest news");
+
+        let daily = warehouse
+            .daily_sentiment("AAPL", Some("2024-02-01T00:00:00Z"), None)
+            .expect("daily sentiment");
+
+        assert_eq!(daily.len(), 2);
+        assert_eq!(daily[0].date, "2024-02-01");
+        assert_eq!(daily[0].article_count, 2);
+        assert!((daily[0].avg_sentiment.expect("avg") - 0.4).abs() < 1e-12);
+        let weighted = daily[0].weighted_sentiment.expect("weighted");
+        assert!((weighted - (0.6 * 0.9 + 0.2 * 0.3) / 1.2).abs() < 1e-12);
+        assert_eq!(daily[1].article_count, 1);
+        assert_eq!(daily[1].avg_sentiment, Some(0.1));
+        assert_eq!(daily[1].weighted_sentiment, None);
     }
 
-    (None, None)
-}
+    #[test]
+    fn cache_sync_is_idempotent() {
+        let temp = tempdir().expect("tempdir");
+        let ferrotick_home = temp.path().join("ferrotick-home");
+        let parquet_dir = ferrotick_home
+            .join("cache")
+            .join("parquet")
+            .join("source=yahoo")
+            .join("dataset=bars_1d")
+            .join("symbol=AAPL")
+            .join("date=2026-02-17");
+        fs::create_dir_all(&parquet_dir).expect("create dirs");
+        let parquet_file = parquet_dir.join("part-0001.parquet");
 
-/// Calculate a checksum for a file based on size and modification time.
-fn file_checksum(path: &Path) -> Result<String, std::io::Error> {
-    let metadata = fs::metadata(path)?;
-    let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
-    let modified_nanos = modified
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_nanos();
-    Ok(format!("{:x}-{:x}", metadata.len(), modified_nanos))
-}
+        let staging_db = temp.path().join("staging.duckdb");
+        let connection = Connection::open(staging_db).expect("staging connection");
+        connection
+            .execute_batch(
+                format!(
+                    "COPY (SELECT TIMESTAMP '2026-02-16 00:00:00' AS ts, 100.0 AS open, 105.0 AS high, 99.0 AS low, 103.0 AS close, 1000 AS volume) TO '{}' (FORMAT PARQUET)",
+                    escape_sql_string(parquet_file.to_string_lossy().as_ref())
+                )
+                .as_str(),
+            )
+            .expect("write parquet");
 
-/// Convert a path to a SQL-compatible string (forward slashes).
-fn path_to_sql(path: &Path) -> String {
-    path.to_string_lossy().replace('\\', "/")
-}
+        let db_path = ferrotick_home.join("cache").join("warehouse.duckdb");
+        let warehouse = Warehouse::open(WarehouseConfig {
+            ferrotick_home,
+            db_path: db_path.clone(),
+            max_pool_size: 2,
+        })
+        .expect("warehouse open");
 
-/// Escape a string for safe inclusion in SQL.
-///
-/// # Security Note
-/// This is used only for internal file paths that are scanned from the filesystem.
-/// User-provided data should always use parameterized queries instead.
-fn escape_sql_string(value: &str) -> String {
-    value.replace('\'', "''")
-}
+        let other = warehouse.sync_cache_symbols(&["MSFT"]).expect("msft sync");
+        assert_eq!(other.scanned_partitions, 0);
+        warehouse.sync_cache().expect("first sync");
+        warehouse.sync_cache().expect("second sync");
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::time::Instant;
-    use tempfile::tempdir;
+        let verify = Connection::open(db_path).expect("open verify connection");
+        let manifest_count: i64 = verify
+            .query_row("SELECT COUNT(*) FROM cache_manifest", [], |row| row.get(0))
+            .expect("manifest count");
+        assert_eq!(manifest_count, 1);
+    }
 
     #[test]
-    fn initializes_tables_and_views() {
+    fn failed_bar_ingest_leaves_the_cache_untouched() {
         let temp = tempdir().expect("tempdir");
         let ferrotick_home = temp.path().join("ferrotick-home");
-        let db_path = ferrotick_home.join("cache").join("warehouse.duckdb");
-
         let warehouse = Warehouse::open(WarehouseConfig {
-            ferrotick_home,
-            db_path,
+            ferrotick_home: ferrotick_home.clone(),
+            db_path: ferrotick_home.join("cache").join("warehouse.duckdb"),
             max_pool_size: 2,
         })
         .expect("warehouse open");
 
-        let query = warehouse
+        let day_dir = |day: &str| {
+            warehouse
+                .cache_root()
+                .join("source=yahoo")
+                .join("dataset=bars_1d")
+                .join("symbol=AAPL")
+                .join(format!("date={day}"))
+        };
+        // A file where the second day's directory belongs makes its write fail
+        // after the first day was already staged.
+        fs::create_dir_all(day_dir("2026-02-17").parent().expect("symbol dir"))
+            .expect("symbol dir");
+        fs::write(day_dir("2026-02-17"), b"not a directory").expect("blocker");
+
+        let bar = |day: &str| BarRecord {
+            symbol: String::from("AAPL"),
+            ts: format!("{day}T00:00:00Z"),
+            open: 100.0,
+            high: 110.0,
+            low: 90.0,
+            close: 101.0,
+            volume: Some(1_000),
+        };
+        let result = warehouse.ingest_bars(
+            "yahoo",
+            "bars_1d",
+            "req-1",
+            &[bar("2026-02-16"), bar("2026-02-17")],
+            10,
+        );
+        assert!(result.is_err());
+
+        let staged_day = day_dir("2026-02-16");
+        let leftovers = fs::read_dir(&staged_day)
+            .map(|entries| entries.count())
+            .unwrap_or(0);
+        assert_eq!(leftovers, 0, "nothing may remain in {staged_day:?}");
+        let stored = warehouse
             .execute_query(
-                "SELECT COUNT(*) AS c FROM information_schema.tables WHERE table_name = 'quotes_latest'",
+                "SELECT COUNT(*) FROM bars_1d",
                 QueryGuardrails::default(),
                 false,
             )
-            .expect("query");
-        assert_eq!(query.row_count, 1);
+            .expect("bars");
+        assert_eq!(stored.rows[0][0], Value::from(0));
     }
 
     #[test]
-    fn read_only_mode_rejects_write_query() {
+    fn rebuild_reports_why_partitions_failed() {
         let temp = tempdir().expect("tempdir");
         let ferrotick_home = temp.path().join("ferrotick-home");
-        let db_path = ferrotick_home.join("cache").join("warehouse.duckdb");
-
         let warehouse = Warehouse::open(WarehouseConfig {
-            ferrotick_home,
-            db_path,
+            ferrotick_home: ferrotick_home.clone(),
+            db_path: ferrotick_home.join("cache").join("warehouse.duckdb"),
             max_pool_size: 2,
         })
         .expect("warehouse open");
+        let corrupt = warehouse
+            .cache_root()
+            .join("source=yahoo")
+            .join("dataset=bars_1d")
+            .join("symbol=AAPL")
+            .join("date=2026-02-16")
+            .join(PARTITION_FILE_NAME);
+        fs::create_dir_all(corrupt.parent().expect("date dir")).expect("date dir");
+        fs::write(&corrupt, b"not parquet").expect("corrupt partition");
 
-        let error = warehouse
-            .execute_query(
-                "CREATE TABLE test_write (id INTEGER)",
-                QueryGuardrails::default(),
-                false,
+        let report = warehouse.rebuild_from_cache().expect("rebuild");
+        assert_eq!(report.failed_partitions, 1);
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].path, corrupt);
+        assert!(!report.failures[0].error.is_empty());
+    }
+
+    #[test]
+    fn rebuild_fails_partitions_that_no_longer_match_their_checksum() {
+        let temp = tempdir().expect("tempdir");
+        let ferrotick_home = temp.path().join("ferrotick-home");
+        let warehouse = Warehouse::open(WarehouseConfig {
+            ferrotick_home: ferrotick_home.clone(),
+            db_path: ferrotick_home.join("cache").join("warehouse.duckdb"),
+            max_pool_size: 2,
+        })
+        .expect("warehouse open");
+        let bar = |day: &str| BarRecord {
+            symbol: String::from("AAPL"),
+            ts: format!("{day}T00:00:00Z"),
+            open: 100.0,
+            high: 110.0,
+            low: 90.0,
+            close: 101.0,
+            volume: Some(1_000),
+        };
+        warehouse
+            .ingest_bars(
+                "yahoo",
+                "bars_1d",
+                "req-1",
+                &[bar("2026-02-16"), bar("2026-02-17")],
+                10,
             )
-            .expect_err("should reject");
+            .expect("ingest");
 
-        assert!(matches!(error, WarehouseError::QueryRejected(_)));
+        let partition = |day: &str| {
+            warehouse
+                .cache_root()
+                .join("source=yahoo")
+                .join("dataset=bars_1d")
+                .join("symbol=AAPL")
+                .join(format!("date={day}"))
+                .join(PARTITION_FILE_NAME)
+        };
+        let tampered = partition("2026-02-17");
+        assert_eq!(
+            fs::read_to_string(checksum_path(&tampered)).expect("sidecar"),
+            file_checksum(&tampered).expect("checksum")
+        );
+        // Swap in another valid partition, so only the checksum can tell.
+        fs::copy(partition("2026-02-16"), &tampered).expect("tamper");
+
+        let rebuilt = Warehouse::open(WarehouseConfig {
+            ferrotick_home,
+            db_path: temp.path().join("fresh.duckdb"),
+            max_pool_size: 2,
+        })
+        .expect("fresh warehouse open");
+        let report = rebuilt.rebuild_from_cache().expect("rebuild");
+        assert_eq!(report.restored_partitions, 1);
+        assert_eq!(report.failed_partitions, 1);
+        assert_eq!(report.failures[0].path, tampered);
+        assert!(report.failures[0].error.contains("checksum mismatch"));
     }
 
     #[test]
-    fn ingest_quotes_uses_parameterized_queries() {
+    fn ingested_bars_are_written_through_and_rebuild_a_fresh_warehouse() {
         let temp = tempdir().expect("tempdir");
         let ferrotick_home = temp.path().join("ferrotick-home");
-        let db_path = ferrotick_home.join("cache").join("warehouse.duckdb");
-
         let warehouse = Warehouse::open(WarehouseConfig {
-            ferrotick_home,
-            db_path,
+            ferrotick_home: ferrotick_home.clone(),
+            db_path: ferrotick_home.join("cache").join("warehouse.duckdb"),
             max_pool_size: 2,
         })
         .expect("warehouse open");
 
-        // Test with potentially dangerous strings that would break non-parameterized queries
-        // Using raw string to avoid quote escaping issues
-        let dangerous_symbol = r#"AAPL'; DROP TABLE quotes_latest; --"#;
-        let quotes = vec![QuoteRecord {
-            symbol: dangerous_symbol.to_string(),
-            price: 150.0,
-            bid: Some(149.5),
-            ask: Some(150.5),
-            volume: Some(1000),
-            currency: "USD".to_string(),
-            as_of: "2026-02-20T10:00:00Z".to_string(),
-        }];
-
-        // This should succeed with parameterized queries
+        let bar = |day: &str, close: f64| BarRecord {
+            symbol: String::from("AAPL"),
+            ts: format!("{day}T00:00:00Z"),
+            open: 100.0,
+            high: 110.0,
+            low: 90.0,
+            close,
+            volume: Some(1_000),
+        };
         warehouse
-            .ingest_quotes("test", "req-001", &quotes, 100)
-            .expect("ingest should succeed with parameterized queries");
+            .ingest_bars(
+                "yahoo",
+                "bars_1d",
+                "req-1",
+                &[bar("2026-02-16", 101.0), bar("2026-02-17", 102.0)],
+                10,
+            )
+            .expect("first ingest");
+        // Polygon now owns the 17th, so yahoo's partition for that day goes away.
+        warehouse
+            .ingest_bars(
+                "polygon",
+                "bars_1d",
+                "req-2",
+                &[bar("2026-02-17", 103.0)],
+                10,
+            )
+            .expect("second ingest");
 
-        // Verify the data was inserted correctly
-        let result = warehouse
+        let partition = |source: &str, day: &str| {
+            warehouse
+                .cache_root()
+                .join(format!("source={source}"))
+                .join("dataset=bars_1d")
+                .join("symbol=AAPL")
+                .join(format!("date={day}"))
+                .join(PARTITION_FILE_NAME)
+        };
+        assert!(partition("yahoo", "2026-02-16").exists());
+        assert!(!partition("yahoo", "2026-02-17").exists());
+        assert!(partition("polygon", "2026-02-17").exists());
+
+        let manifest = warehouse
             .execute_query(
-                r#"SELECT symbol, price FROM quotes_latest WHERE symbol LIKE '%DROP%'"#,
+                "SELECT source, row_count, checksum FROM cache_manifest ORDER BY partition_date",
                 QueryGuardrails::default(),
                 false,
             )
-            .expect("query");
+            .expect("manifest");
+        assert_eq!(manifest.row_count, 2);
+        assert_eq!(manifest.rows[1][0], Value::String(String::from("polygon")));
+        assert_eq!(manifest.rows[1][1], Value::from(1));
+        assert_eq!(manifest.rows[1][2].as_str().map(str::len), Some(64));
 
-        assert_eq!(result.row_count, 1);
+        let rebuilt = Warehouse::open(WarehouseConfig {
+            ferrotick_home: ferrotick_home.clone(),
+            db_path: temp.path().join("fresh.duckdb"),
+            max_pool_size: 2,
+        })
+        .expect("fresh warehouse open");
+        let report = rebuilt.rebuild_from_cache().expect("rebuild");
+        assert_eq!(report.restored_partitions, 2);
+        assert_eq!(report.restored_rows, 2);
+        assert_eq!(report.failed_partitions, 0);
+
+        let bars = rebuilt
+            .execute_query(
+                "SELECT source, close FROM bars_1d WHERE symbol = 'AAPL' ORDER BY ts",
+                QueryGuardrails::default(),
+                false,
+            )
+            .expect("bars");
+        assert_eq!(bars.row_count, 2);
+        assert_eq!(bars.rows[0][0], Value::String(String::from("yahoo")));
         assert_eq!(
-            result.rows[0][0],
-            Value::String(dangerous_symbol.to_string())
+            bars.rows[1],
+            vec![Value::String(String::from("polygon")), Value::from(103.0)]
         );
     }
 
     #[test]
-    fn ingest_bars_uses_parameterized_queries() {
+    fn any_interval_is_stored_and_minutes_roll_up_without_touching_provider_bars() {
         let temp = tempdir().expect("tempdir");
         let ferrotick_home = temp.path().join("ferrotick-home");
-        let db_path = ferrotick_home.join("cache").join("warehouse.duckdb");
-
         let warehouse = Warehouse::open(WarehouseConfig {
-            ferrotick_home,
-            db_path,
+            ferrotick_home: ferrotick_home.clone(),
+            db_path: ferrotick_home.join("cache").join("warehouse.duckdb"),
             max_pool_size: 2,
         })
         .expect("warehouse open");
 
-        // Test with potentially dangerous strings
-        let dangerous_symbol = r#"MSFT'; DELETE FROM bars_1d; --"#;
-        let bars = vec![BarRecord {
-            symbol: dangerous_symbol.to_string(),
-            ts: "2026-02-20T10:00:00Z".to_string(),
-            open: 300.0,
-            high: 305.0,
-            low: 299.0,
-            close: 303.0,
-            volume: Some(2000),
-        }];
-
+        let bar = |ts: &str, open: f64, close: f64, volume: u64| BarRecord {
+            symbol: String::from("AAPL"),
+            ts: ts.to_string(),
+            open,
+            high: open.max(close) + 1.0,
+            low: open.min(close) - 1.0,
+            close,
+            volume: Some(volume),
+        };
+        let minutes = [
+            bar("2026-02-17T14:30:00Z", 10.0, 11.0, 100),
+            bar("2026-02-17T14:31:00Z", 11.0, 12.0, 200),
+            bar("2026-02-17T14:35:00Z", 12.0, 13.0, 300),
+        ];
         warehouse
-            .ingest_bars("test", "bars_1d", "req-002", &bars, 50)
-            .expect("ingest should succeed");
+            .ingest_bars("polygon", "bars_1m", "req-1", &minutes, 10)
+            .expect("ingest minutes");
+        warehouse
+            .ingest_bars(
+                "yahoo",
+                "bars_1h",
+                "req-2",
+                &[bar("2026-02-17T14:00:00Z", 9.0, 99.0, 1)],
+                10,
+            )
+            .expect("ingest hourly");
+        assert!(matches!(
+            warehouse.ingest_bars("yahoo", "bars_2h", "req-3", &minutes, 10),
+            Err(WarehouseError::QueryRejected(_))
+        ));
+        assert!(warehouse
+            .cache_root()
+            .join("source=yahoo/dataset=bars_1h/symbol=AAPL/date=2026-02-17")
+            .join(PARTITION_FILE_NAME)
+            .exists());
 
-        // Verify the data was inserted correctly
-        let result = warehouse
+        assert_eq!(
+            warehouse
+                .rollup_bars("5m", Some("AAPL"))
+                .expect("rollup 5m"),
+            2
+        );
+        warehouse.rollup_bars("1h", None).expect("rollup 1h");
+        assert!(warehouse.rollup_bars("1m", None).is_err());
+
+        let rolled = warehouse
             .execute_query(
-                r#"SELECT symbol, close FROM bars_1d WHERE symbol LIKE '%DELETE%'"#,
+                "SELECT open, high, low, close, volume, source FROM bars_5m ORDER BY ts",
                 QueryGuardrails::default(),
                 false,
             )
-            .expect("query");
+            .expect("5m bars");
+        assert_eq!(rolled.row_count, 2);
+        assert_eq!(
+            rolled.rows[0],
+            vec![
+                Value::from(10.0),
+                Value::from(13.0),
+                Value::from(9.0),
+                Value::from(12.0),
+                Value::from(300),
+                Value::String(String::from("rollup")),
+            ]
+        );
 
-        assert_eq!(result.row_count, 1);
+        let hourly = warehouse
+            .execute_query(
+                "SELECT close, source FROM bars WHERE interval = '1h'",
+                QueryGuardrails::default(),
+                false,
+            )
+            .expect("hourly bars");
+        assert_eq!(
+            hourly.rows,
+            vec![vec![
+                Value::from(99.0),
+                Value::String(String::from("yahoo"))
+            ]]
+        );
     }
 
     #[test]
-    fn ingest_fundamentals_uses_parameterized_queries() {
+    fn bar_coverage_splits_weekdays_into_present_and_missing_runs() {
         let temp = tempdir().expect("tempdir");
         let ferrotick_home = temp.path().join("ferrotick-home");
-        let db_path = ferrotick_home.join("cache").join("warehouse.duckdb");
-
         let warehouse = Warehouse::open(WarehouseConfig {
-            ferrotick_home,
-            db_path,
+            ferrotick_home: ferrotick_home.clone(),
+            db_path: ferrotick_home.join("cache").join("warehouse.duckdb"),
             max_pool_size: 2,
         })
         .expect("warehouse open");
 
-        // Test with potentially dangerous strings
-        let dangerous_symbol = r#"GOOG'); DROP TABLE fundamentals; --"#;
-        let dangerous_metric = r#"pe_ratio"; DELETE FROM fundamentals; --"#;
-        let fundamentals = vec![FundamentalRecord {
-            symbol: dangerous_symbol.to_string(),
-            metric: dangerous_metric.to_string(),
-            value: 25.5,
-            date: "2026-02-20T00:00:00Z".to_string(),
-        }];
-
+        let bars = ["2026-02-16", "2026-02-17", "2026-02-20"]
+            .iter()
+            .map(|date| BarRecord {
+                symbol: String::from("AAPL"),
+                ts: format!("{date}T21:00:00Z"),
+                open: 1.0,
+                high: 1.0,
+                low: 1.0,
+                close: 1.0,
+                volume: Some(1),
+            })
+            .collect::<Vec<_>>();
         warehouse
-            .ingest_fundamentals("test", "req-003", &fundamentals, 25)
-            .expect("ingest should succeed");
-
-        // Verify the data was inserted correctly
-        let result = warehouse
-            .execute_query(
-                r#"SELECT symbol, metric, value FROM fundamentals WHERE symbol LIKE '%DROP%'"#,
-                QueryGuardrails::default(),
-                false,
-            )
-            .expect("query");
+            .ingest_bars("yahoo", "bars_1d", "req-1", &bars, 10)
+            .expect("ingest bars");
 
-        assert_eq!(result.row_count, 1);
+        let range = |start: &str, end: &str| DateRange {
+            start: start.to_string(),
+            end: end.to_string(),
+        };
+        let coverage = warehouse
+            .bar_coverage("bars_1d", "AAPL", "2026-02-13", "2026-02-24")
+            .expect("coverage");
         assert_eq!(
-            result.rows[0][2],
-            Value::Number(Number::from_f64(25.5).unwrap())
+            coverage.present,
+            vec![
+                range("2026-02-16", "2026-02-17"),
+                range("2026-02-20", "2026-02-20")
+            ]
+        );
+        assert_eq!(
+            coverage.missing,
+            vec![
+                range("2026-02-13", "2026-02-13"),
+                range("2026-02-18", "2026-02-19"),
+                range("2026-02-23", "2026-02-24"),
+            ]
         );
+
+        let minutes = warehouse
+            .bar_coverage("bars_1m", "AAPL", "2026-02-13", "2026-02-24")
+            .expect("minute coverage");
+        assert!(minutes.present.is_empty());
+        assert_eq!(minutes.missing, vec![range("2026-02-13", "2026-02-24")]);
     }
 
     #[test]
-    fn cache_sync_is_idempotent() {
+    fn sessions_fetched_without_bars_stop_being_gaps() {
         let temp = tempdir().expect("tempdir");
         let ferrotick_home = temp.path().join("ferrotick-home");
-        let parquet_dir = ferrotick_home
-            .join("cache")
-            .join("parquet")
-            .join("source=yahoo")
-            .join("dataset=bars_1d")
-            .join("symbol=AAPL")
-            .join("date=2026-02-17");
-        fs::create_dir_all(&parquet_dir).expect("create dirs");
-        let parquet_file = parquet_dir.join("part-0001.parquet");
+        let warehouse = Warehouse::open(WarehouseConfig {
+            ferrotick_home: ferrotick_home.clone(),
+            db_path: ferrotick_home.join("cache").join("warehouse.duckdb"),
+            max_pool_size: 2,
+        })
+        .expect("warehouse open");
+        let bar = BarRecord {
+            symbol: String::from("AAPL"),
+            ts: String::from("2026-02-20T21:00:00Z"),
+            open: 1.0,
+            high: 1.0,
+            low: 1.0,
+            close: 1.0,
+            volume: Some(1),
+        };
+        warehouse
+            .ingest_bars("yahoo", "bars_1d", "req-1", &[bar], 10)
+            .expect("ingest bars");
 
-        let staging_db = temp.path().join("staging.duckdb");
-        let connection = Connection::open(staging_db).expect("staging connection");
-        connection
-            .execute_batch(
-                format!(
-                    "COPY (SELECT TIMESTAMP '2026-02-16 00:00:00' AS ts, 100.0 AS open, 105.0 AS high, 99.0 AS low, 103.0 AS close, 1000 AS volume) TO '{}' (FORMAT PARQUET)",
-                    escape_sql_string(parquet_file.to_string_lossy().as_ref())
-                )
-                .as_str(),
-            )
-            .expect("write parquet");
+        // The 18th and 19th came back without bars; the 20th keeps its bar.
+        let recorded = warehouse
+            .record_empty_sessions("bars_1d", "AAPL", "2026-02-18", "2026-02-20")
+            .expect("record empty sessions");
+        assert_eq!(recorded, 2);
 
-        let db_path = ferrotick_home.join("cache").join("warehouse.duckdb");
+        let range = |start: &str, end: &str| DateRange {
+            start: start.to_string(),
+            end: end.to_string(),
+        };
+        let coverage = warehouse
+            .bar_coverage("bars_1d", "AAPL", "2026-02-16", "2026-02-23")
+            .expect("coverage");
+        assert_eq!(coverage.present, vec![range("2026-02-20", "2026-02-20")]);
+        assert_eq!(coverage.empty, vec![range("2026-02-18", "2026-02-19")]);
+        assert_eq!(
+            coverage.missing,
+            vec![
+                range("2026-02-16", "2026-02-17"),
+                range("2026-02-23", "2026-02-23"),
+            ]
+        );
+    }
+
+    #[test]
+    fn ingest_runs_are_recorded_and_listed_newest_first() {
+        let temp = tempdir().expect("tempdir");
+        let ferrotick_home = temp.path().join("ferrotick-home");
         let warehouse = Warehouse::open(WarehouseConfig {
-            ferrotick_home,
-            db_path: db_path.clone(),
+            ferrotick_home: ferrotick_home.clone(),
+            db_path: ferrotick_home.join("cache").join("warehouse.duckdb"),
             max_pool_size: 2,
         })
         .expect("warehouse open");
 
-        warehouse.sync_cache().expect("first sync");
-        warehouse.sync_cache().expect("second sync");
+        warehouse
+            .record_ingest_run(
+                "daemon:daily:1",
+                Some("AAPL"),
+                "yahoo",
+                "bars_1d",
+                "succeeded",
+                12,
+                "loaded 5 bars",
+            )
+            .expect("record success");
+        warehouse
+            .ingest_bars(
+                "yahoo",
+                "bars_1d",
+                "cache_load:AAPL:1",
+                &[BarRecord {
+                    symbol: String::from("AAPL"),
+                    ts: String::from("2026-02-17T21:00:00Z"),
+                    open: 1.0,
+                    high: 1.0,
+                    low: 1.0,
+                    close: 1.0,
+                    volume: None,
+                }],
+                5,
+            )
+            .expect("ingest bars");
+        std::thread::sleep(std::time::Duration::from_millis(1100));
+        warehouse
+            .record_ingest_run(
+                "daemon:daily:2",
+                Some("MSFT"),
+                "auto",
+                "bars_1d",
+                "failed",
+                40,
+                "all providers failed",
+            )
+            .expect("record failure");
 
-        let verify = Connection::open(db_path).expect("open verify connection");
-        let manifest_count: i64 = verify
-            .query_row("SELECT COUNT(*) FROM cache_manifest", [], |row| row.get(0))
-            .expect("manifest count");
-        assert_eq!(manifest_count, 1);
+        let runs = warehouse.ingest_runs("daemon:", 10).expect("runs");
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].request_id, "daemon:daily:2");
+        assert_eq!(runs[0].status, "failed");
+        assert_eq!(runs[0].detail.as_deref(), Some("all providers failed"));
+        assert_eq!(runs[1].symbol.as_deref(), Some("AAPL"));
+        assert_eq!(runs[1].latency_ms, Some(12));
+        assert_eq!(warehouse.ingest_runs("daemon:", 1).expect("runs").len(), 1);
     }
 
     #[test]
@@ -0,0 +1,510 @@
+//! Row-level lineage of ingested bars and quotes.
+//!
+//! Every call to [`Warehouse::ingest_bars`] or [`Warehouse::ingest_quotes`],
+//! and every partition loaded by [`Warehouse::rebuild_from_cache`], opens an
+//! ingest batch: one `ingest_batches` row naming the request id, source,
+//! dataset, a SHA-256 digest of the ingested rows (of the parquet file for a
+//! rebuild) and the binary version set with [`set_binary_version`]. The rows
+//! the batch writes carry its id in `ingest_batch`, and its `ingest_log`
+//! entries in `batch_id`.
+//!
+//! Only bars and quotes are tracked. Fundamentals, financials, earnings,
+//! corporate actions, ticks, macro series, news and instruments are written
+//! without a batch, so their provenance stops at `ingest_log`.
+//!
+//! When an upsert overwrites a bar or quote with different values, the old
+//! row is first copied to `bars_audit` or `quotes_audit`, together with the
+//! batch that superseded it. Quotes only count as changed when a value
+//! column differs, so a refreshed `as_of` is not audited. `ingest_batches`
+//! is not pruned with `ingest_log`, so lineage outlives the log retention
+//! window; `quotes_audit` is pruned by [`Warehouse::compact_cache`].
+
+use std::sync::OnceLock;
+
+use ::duckdb::{Connection, OptionalExt, ToSql};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::duckdb::AccessMode;
+use crate::{bars_interval, BarRecord, QuoteRecord, Warehouse, WarehouseError};
+
+/// Version recorded on batches when the binary never called
+/// [`set_binary_version`].
+pub const UNKNOWN_BINARY_VERSION: &str = "unknown";
+
+static BINARY_VERSION: OnceLock<String> = OnceLock::new();
+
+/// Set the version of the running binary, recorded on every ingest batch.
+///
+/// Call once at startup; later calls are ignored.
+pub fn set_binary_version(version: impl Into<String>) {
+    let _ = BINARY_VERSION.set(version.into());
+}
+
+fn binary_version() -> &'static str {
+    BINARY_VERSION
+        .get()
+        .map_or(UNKNOWN_BINARY_VERSION, String::as_str)
+}
+
+/// One ingest batch from `ingest_batches`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct IngestBatch {
+    pub batch_id: String,
+    /// Request id of the provider response, as in `ingest_log`.
+    pub request_id: String,
+    pub source: String,
+    pub dataset: String,
+    /// ferrotick version that wrote the batch.
+    pub binary_version: String,
+    pub created_at: String,
+    /// SHA-256 of the ingested rows; `None` for batches recorded before
+    /// digests were.
+    pub response_digest: Option<String>,
+}
+
+/// One version of a stored bar and the batch that wrote it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BarLineage {
+    pub ts: String,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: Option<i64>,
+    pub source: Option<String>,
+    pub written_at: String,
+    /// When a later batch overwrote this version; `None` for the stored bar.
+    pub superseded_at: Option<String>,
+    /// Batch id that overwrote this version.
+    pub superseded_by: Option<String>,
+    /// `None` for bars written before lineage was recorded.
+    pub batch: Option<IngestBatch>,
+}
+
+/// What one ingest batch changed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BatchChanges {
+    pub batch: IngestBatch,
+    /// Bars still carrying this batch id.
+    pub bars_written: usize,
+    /// Earlier bar versions this batch overwrote with different values.
+    pub superseded: Vec<BarLineage>,
+}
+
+impl Warehouse {
+    /// Every stored and superseded version of `symbol`'s `dataset` bars on
+    /// `date`, oldest first, with the batch that wrote each.
+    pub fn bar_lineage(
+        &self,
+        dataset: &str,
+        symbol: &str,
+        date: &str,
+    ) -> Result<Vec<BarLineage>, WarehouseError> {
+        let Some(interval) = bars_interval(dataset) else {
+            return Err(WarehouseError::QueryRejected(format!(
+                "unknown bars dataset '{dataset}'"
+            )));
+        };
+
+        let connection = self.manager.acquire(AccessMode::ReadOnly)?;
+        let sql = format!(
+            "WITH versions AS ( \
+                 SELECT ts, open, high, low, close, volume, source, ingest_batch, updated_at, \
+                        CAST(NULL AS TIMESTAMP) AS superseded_at, \
+                        CAST(NULL AS TEXT) AS superseded_by \
+                 FROM bars \
+                 WHERE symbol = ? AND interval = ? AND CAST(ts AS DATE) = CAST(? AS DATE) \
+                 UNION ALL \
+                 SELECT ts, open, high, low, close, volume, source, ingest_batch, updated_at, \
+                        superseded_at, superseded_by \
+                 FROM bars_audit \
+                 WHERE symbol = ? AND interval = ? AND CAST(ts AS DATE) = CAST(? AS DATE) \
+             ) \
+             SELECT {VERSION_COLUMNS} \
+             FROM versions v LEFT JOIN ingest_batches b ON b.batch_id = v.ingest_batch \
+             ORDER BY v.ts, v.superseded_at NULLS LAST"
+        );
+        let params: [&dyn ToSql; 6] = [&symbol, &interval, &date, &symbol, &interval, &date];
+        let mut statement = connection.prepare(sql.as_str())?;
+        let rows = statement.query_map(params.as_slice(), bar_lineage_from_row)?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(WarehouseError::from)
+    }
+
+    /// The batch `batch_id` and the bar versions it overwrote, or `None` if
+    /// no such batch was recorded.
+    pub fn batch_changes(&self, batch_id: &str) -> Result<Option<BatchChanges>, WarehouseError> {
+        let connection = self.manager.acquire(AccessMode::ReadOnly)?;
+        let Some(batch) = connection
+            .query_row(
+                "SELECT batch_id, request_id, source, dataset, binary_version, \
+                        strftime(created_at, '%Y-%m-%dT%H:%M:%SZ'), response_digest \
+                 FROM ingest_batches WHERE batch_id = ?",
+                [batch_id],
+                |row| {
+                    Ok(IngestBatch {
+                        batch_id: row.get(0)?,
+                        request_id: row.get(1)?,
+                        source: row.get(2)?,
+                        dataset: row.get(3)?,
+                        binary_version: row.get(4)?,
+                        created_at: row.get(5)?,
+                        response_digest: row.get(6)?,
+                    })
+                },
+            )
+            .optional()?
+        else {
+            return Ok(None);
+        };
+
+        let bars_written: i64 = connection.query_row(
+            "SELECT COUNT(*) FROM bars WHERE ingest_batch = ?",
+            [batch_id],
+            |row| row.get(0),
+        )?;
+        let sql = format!(
+            "SELECT {VERSION_COLUMNS} \
+             FROM bars_audit v LEFT JOIN ingest_batches b ON b.batch_id = v.ingest_batch \
+             WHERE v.superseded_by = ? \
+             ORDER BY v.symbol, v.interval, v.ts"
+        );
+        let mut statement = connection.prepare(sql.as_str())?;
+        let superseded = statement
+            .query_map([batch_id], bar_lineage_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Some(BatchChanges {
+            batch,
+            bars_written: usize::try_from(bars_written).unwrap_or_default(),
+            superseded,
+        }))
+    }
+}
+
+/// Columns read by [`bar_lineage_from_row`], from a bar version `v` joined
+/// to its batch `b`.
+const VERSION_COLUMNS: &str = "strftime(v.ts, '%Y-%m-%dT%H:%M:%SZ'), \
+     v.open, v.high, v.low, v.close, v.volume, v.source, \
+     strftime(v.updated_at, '%Y-%m-%dT%H:%M:%SZ'), \
+     strftime(v.superseded_at, '%Y-%m-%dT%H:%M:%SZ'), v.superseded_by, \
+     b.batch_id, b.request_id, b.source, b.dataset, b.binary_version, \
+     strftime(b.created_at, '%Y-%m-%dT%H:%M:%SZ'), b.response_digest";
+
+fn bar_lineage_from_row(row: &::duckdb::Row<'_>) -> ::duckdb::Result<BarLineage> {
+    let batch_id: Option<String> = row.get(10)?;
+    let batch = match batch_id {
+        Some(batch_id) => Some(IngestBatch {
+            batch_id,
+            request_id: row.get(11)?,
+            source: row.get(12)?,
+            dataset: row.get(13)?,
+            binary_version: row.get(14)?,
+            created_at: row.get(15)?,
+            response_digest: row.get(16)?,
+        }),
+        None => None,
+    };
+    Ok(BarLineage {
+        ts: row.get(0)?,
+        open: row.get(1)?,
+        high: row.get(2)?,
+        low: row.get(3)?,
+        close: row.get(4)?,
+        volume: row.get(5)?,
+        source: row.get(6)?,
+        written_at: row.get(7)?,
+        superseded_at: row.get(8)?,
+        superseded_by: row.get(9)?,
+        batch,
+    })
+}
+
+/// Record a new ingest batch and return its id.
+pub(crate) fn open_batch(
+    connection: &Connection,
+    request_id: &str,
+    source: &str,
+    dataset: &str,
+    response_digest: &str,
+) -> Result<String, WarehouseError> {
+    let batch_id: String =
+        connection.query_row("SELECT CAST(gen_random_uuid() AS VARCHAR)", [], |row| {
+            row.get(0)
+        })?;
+    let params: [&dyn ToSql; 6] = [
+        &batch_id,
+        &request_id,
+        &source,
+        &dataset,
+        &binary_version(),
+        &response_digest,
+    ];
+    connection.execute(
+        "INSERT INTO ingest_batches \
+         (batch_id, request_id, source, dataset, binary_version, response_digest, created_at) \
+         VALUES (?, ?, ?, ?, ?, ?, CURRENT_TIMESTAMP)",
+        params.as_slice(),
+    )?;
+    Ok(batch_id)
+}
+
+/// SHA-256 of `rows` as ingested, in order.
+pub(crate) fn bars_digest(rows: &[BarRecord]) -> String {
+    let mut hasher = Sha256::new();
+    for row in rows {
+        hasher.update(format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{:?}\n",
+            row.symbol, row.ts, row.open, row.high, row.low, row.close, row.volume
+        ));
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// SHA-256 of `rows` as ingested, in order.
+pub(crate) fn quotes_digest(rows: &[QuoteRecord]) -> String {
+    let mut hasher = Sha256::new();
+    for row in rows {
+        hasher.update(format!(
+            "{}\t{}\t{:?}\t{:?}\t{:?}\t{}\t{}\n",
+            row.symbol, row.price, row.bid, row.ask, row.volume, row.currency, row.as_of
+        ));
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// Copy the stored bar `row` is about to replace to `bars_audit`, if its
+/// values differ.
+pub(crate) fn audit_bar(
+    connection: &Connection,
+    interval: &str,
+    row: &BarRecord,
+    batch_id: &str,
+) -> Result<(), WarehouseError> {
+    let params: [&dyn ToSql; 9] = [
+        &batch_id,
+        &row.symbol,
+        &interval,
+        &row.ts,
+        &row.open,
+        &row.high,
+        &row.low,
+        &row.close,
+        &row.volume,
+    ];
+    connection.execute(
+        "INSERT INTO bars_audit \
+         (symbol, interval, ts, open, high, low, close, volume, source, ingest_batch, \
+          updated_at, superseded_by, superseded_at) \
+         SELECT symbol, interval, ts, open, high, low, close, volume, source, ingest_batch, \
+                updated_at, ?, CURRENT_TIMESTAMP \
+         FROM bars \
+         WHERE symbol = ? AND interval = ? AND ts = TRY_CAST(? AS TIMESTAMP) \
+           AND (open <> ? OR high <> ? OR low <> ? OR close <> ? \
+                OR volume IS DISTINCT FROM ?)",
+        params.as_slice(),
+    )?;
+    Ok(())
+}
+
+/// Copy every stored bar of `symbol` that `rows_sql` (a query yielding
+/// `ts, open, high, low, close, volume`) is about to replace with different
+/// values to `bars_audit`.
+pub(crate) fn audit_bars_replaced_by(
+    connection: &Connection,
+    rows_sql: &str,
+    symbol: &str,
+    interval: &str,
+    batch_id: &str,
+) -> Result<(), WarehouseError> {
+    let sql = format!(
+        "INSERT INTO bars_audit \
+         (symbol, interval, ts, open, high, low, close, volume, source, ingest_batch, \
+          updated_at, superseded_by, superseded_at) \
+         SELECT b.symbol, b.interval, b.ts, b.open, b.high, b.low, b.close, b.volume, \
+                b.source, b.ingest_batch, b.updated_at, ?, CURRENT_TIMESTAMP \
+         FROM bars b JOIN ({rows_sql}) r ON r.ts = b.ts \
+         WHERE b.symbol = ? AND b.interval = ? \
+           AND (b.open <> r.open OR b.high <> r.high OR b.low <> r.low OR b.close <> r.close \
+                OR b.volume IS DISTINCT FROM r.volume)"
+    );
+    let params: [&dyn ToSql; 3] = [&batch_id, &symbol, &interval];
+    connection.execute(sql.as_str(), params.as_slice())?;
+    Ok(())
+}
+
+/// Copy the stored quote `row` is about to replace to `quotes_audit`, if
+/// its values differ. A newer `as_of` alone is a fresh observation of the
+/// same quote, not a revision.
+pub(crate) fn audit_quote(
+    connection: &Connection,
+    row: &QuoteRecord,
+    batch_id: &str,
+) -> Result<(), WarehouseError> {
+    let params: [&dyn ToSql; 6] = [
+        &batch_id,
+        &row.symbol,
+        &row.price,
+        &row.bid,
+        &row.ask,
+        &row.volume,
+    ];
+    connection.execute(
+        "INSERT INTO quotes_audit \
+         (symbol, price, bid, ask, volume, as_of, source, ingest_batch, updated_at, \
+          superseded_by, superseded_at) \
+         SELECT symbol, price, bid, ask, volume, as_of, source, ingest_batch, updated_at, \
+                ?, CURRENT_TIMESTAMP \
+         FROM quotes_latest \
+         WHERE symbol = ? \
+           AND (price <> ? OR bid IS DISTINCT FROM ? OR ask IS DISTINCT FROM ? \
+                OR volume IS DISTINCT FROM ?)",
+        params.as_slice(),
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::{QueryGuardrails, WarehouseConfig};
+
+    fn bar(ts: &str, close: f64) -> BarRecord {
+        BarRecord {
+            symbol: String::from("AAPL"),
+            ts: ts.to_string(),
+            open: 100.0,
+            high: 110.0,
+            low: 90.0,
+            close,
+            volume: Some(1_000),
+        }
+    }
+
+    fn quote(price: f64) -> QuoteRecord {
+        QuoteRecord {
+            symbol: String::from("AAPL"),
+            price,
+            bid: None,
+            ask: None,
+            volume: None,
+            currency: String::from("USD"),
+            as_of: String::from("2024-05-01T20:00:00Z"),
+        }
+    }
+
+    #[test]
+    fn reingest_keeps_superseded_values_with_their_batches() {
+        set_binary_version("9.9.9-test");
+        let temp = tempdir().expect("tempdir");
+        let ferrotick_home = temp.path().join("ferrotick-home");
+        let warehouse = Warehouse::open(WarehouseConfig {
+            ferrotick_home: ferrotick_home.clone(),
+            db_path: ferrotick_home.join("cache").join("warehouse.duckdb"),
+            max_pool_size: 2,
+        })
+        .expect("warehouse open");
+
+        let day = "2024-05-01T00:00:00Z";
+        let next = "2024-05-02T00:00:00Z";
+        warehouse
+            .ingest_bars(
+                "yahoo",
+                "bars_1d",
+                "req-1",
+                &[bar(day, 105.0), bar(next, 106.0)],
+                10,
+            )
+            .expect("first ingest");
+        // A restatement of the first day, then an identical re-ingest.
+        for request_id in ["req-2", "req-3"] {
+            warehouse
+                .ingest_bars("polygon", "bars_1d", request_id, &[bar(day, 104.5)], 10)
+                .expect("re-ingest");
+        }
+        warehouse
+            .ingest_quotes("yahoo", "req-q1", &[quote(190.0)], 5)
+            .expect("quote");
+        // Only the timestamp moved, so nothing was revised.
+        let later = QuoteRecord {
+            as_of: String::from("2024-05-01T20:05:00Z"),
+            ..quote(190.0)
+        };
+        warehouse
+            .ingest_quotes("yahoo", "req-q1b", &[later], 5)
+            .expect("refreshed quote");
+        warehouse
+            .ingest_quotes("yahoo", "req-q2", &[quote(191.0)], 5)
+            .expect("changed quote");
+
+        let history = warehouse
+            .bar_lineage("bars_1d", "AAPL", "2024-05-01")
+            .expect("lineage");
+        assert_eq!(history.len(), 2);
+        let (old, current) = (&history[0], &history[1]);
+        assert_eq!(old.close, 105.0);
+        assert_eq!(old.source.as_deref(), Some("yahoo"));
+        let old_batch = old.batch.as_ref().expect("old batch");
+        assert_eq!(old_batch.request_id, "req-1");
+        assert_eq!(old_batch.dataset, "bars_1d");
+        assert_eq!(old_batch.binary_version, "9.9.9-test");
+        assert_eq!(
+            old_batch.response_digest.as_deref(),
+            Some(bars_digest(&[bar(day, 105.0), bar(next, 106.0)]).as_str())
+        );
+        assert!(old.superseded_at.is_some());
+
+        // The identical third ingest wrote the stored row without an audit.
+        assert_eq!(current.close, 104.5);
+        assert_eq!(current.superseded_by, None);
+        let current_batch = current.batch.as_ref().expect("current batch");
+        assert_eq!(current_batch.request_id, "req-3");
+        let restating = old.superseded_by.as_deref().expect("superseded by");
+        assert_ne!(restating, current_batch.batch_id);
+
+        let changes = warehouse
+            .batch_changes(restating)
+            .expect("batch changes")
+            .expect("recorded batch");
+        assert_eq!(changes.batch.request_id, "req-2");
+        assert_eq!(changes.bars_written, 0);
+        assert_eq!(changes.superseded, vec![old.clone()]);
+        assert_eq!(
+            warehouse
+                .batch_changes(&current_batch.batch_id)
+                .expect("batch changes")
+                .expect("recorded batch")
+                .bars_written,
+            1
+        );
+        assert!(warehouse
+            .batch_changes("no-such-batch")
+            .expect("batch changes")
+            .is_none());
+
+        let linked = warehouse
+            .execute_query(
+                "SELECT COUNT(*) FROM ingest_log l \
+                 JOIN ingest_batches b ON b.batch_id = l.batch_id",
+                QueryGuardrails::default(),
+                true,
+            )
+            .expect("query");
+        assert_eq!(linked.rows[0][0], serde_json::json!(7));
+        let quotes = warehouse
+            .execute_query(
+                "SELECT a.price, b.request_id FROM quotes_audit a \
+                 JOIN ingest_batches b ON b.batch_id = a.ingest_batch",
+                QueryGuardrails::default(),
+                true,
+            )
+            .expect("query");
+        assert_eq!(
+            quotes.rows,
+            vec![vec![serde_json::json!(190.0), serde_json::json!("req-q1b")]]
+        );
+    }
+}
@@ -1,14 +1,30 @@
+//! Versioned schema migrations.
+//!
+//! Every migration has `up` SQL and `down` SQL that reverts it, and is
+//! recorded in `schema_migrations` once applied. [`apply_migrations`] runs
+//! the pending ones when the warehouse opens and refuses a database
+//! migrated by a newer build; [`migrate`] moves a database to a given
+//! version in either direction, for `ferrotick warehouse migrate`.
+
+use std::collections::BTreeMap;
+use std::fs;
+
 use ::duckdb::Connection;
+use serde::Serialize;
+
+use crate::{views, Warehouse, WarehouseConfig, WarehouseError};
 
+/// A schema change and the SQL that reverts it.
 struct Migration {
     version: &'static str,
-    sql: &'static str,
+    up: &'static str,
+    down: &'static str,
 }
 
 const MIGRATIONS: &[Migration] = &[
     Migration {
         version: "0001_core_tables",
-        sql: r"
+        up: r"
 CREATE TABLE IF NOT EXISTS instruments (
     symbol TEXT PRIMARY KEY,
     name TEXT NOT NULL,
@@ -100,70 +116,950 @@ CREATE TABLE IF NOT EXISTS ingest_log (
     latency_ms BIGINT,
     timestamp TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
+",
+        down: r"
+DROP TABLE IF EXISTS ingest_log;
+DROP TABLE IF EXISTS cache_manifest;
+DROP TABLE IF EXISTS corporate_actions;
+DROP TABLE IF EXISTS fundamentals;
+DROP TABLE IF EXISTS bars_1d;
+DROP TABLE IF EXISTS bars_1m;
+DROP TABLE IF EXISTS quotes_latest;
+DROP TABLE IF EXISTS instruments;
 ",
     },
     Migration {
         version: "0002_indexes",
-        sql: r"
+        up: r"
 CREATE INDEX IF NOT EXISTS idx_quotes_latest_as_of ON quotes_latest(as_of);
 CREATE INDEX IF NOT EXISTS idx_bars_1m_symbol_ts ON bars_1m(symbol, ts);
 CREATE INDEX IF NOT EXISTS idx_bars_1d_symbol_ts ON bars_1d(symbol, ts);
 CREATE INDEX IF NOT EXISTS idx_fundamentals_symbol_date ON fundamentals(symbol, date);
 CREATE INDEX IF NOT EXISTS idx_cache_manifest_dataset_symbol ON cache_manifest(dataset, symbol);
 CREATE INDEX IF NOT EXISTS idx_ingest_log_source_dataset_ts ON ingest_log(source, dataset, timestamp);
+",
+        down: r"
+DROP INDEX IF EXISTS idx_ingest_log_source_dataset_ts;
+DROP INDEX IF EXISTS idx_cache_manifest_dataset_symbol;
+DROP INDEX IF EXISTS idx_fundamentals_symbol_date;
+DROP INDEX IF EXISTS idx_bars_1d_symbol_ts;
+DROP INDEX IF EXISTS idx_bars_1m_symbol_ts;
+DROP INDEX IF EXISTS idx_quotes_latest_as_of;
+",
+    },
+    Migration {
+        version: "0003_financial_statements",
+        up: r"
+CREATE TABLE IF NOT EXISTS financial_statements (
+    symbol TEXT NOT NULL,
+    statement_type TEXT NOT NULL,
+    period TEXT NOT NULL,
+    label TEXT NOT NULL,
+    metric TEXT,
+    value DOUBLE NOT NULL,
+    fiscal_year INTEGER,
+    fiscal_quarter INTEGER,
+    end_date TIMESTAMP NOT NULL,
+    currency TEXT NOT NULL,
+    source TEXT,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY(symbol, statement_type, period, label, end_date)
+);
+
+CREATE INDEX IF NOT EXISTS idx_financial_statements_symbol_end ON financial_statements(symbol, period, end_date);
+",
+        down: r"
+DROP INDEX IF EXISTS idx_financial_statements_symbol_end;
+DROP TABLE IF EXISTS financial_statements;
+",
+    },
+    Migration {
+        version: "0004_point_in_time_fundamentals",
+        up: r"
+-- DuckDB refuses ALTER TABLE while indexes depend on the table.
+DROP INDEX IF EXISTS idx_fundamentals_symbol_date;
+ALTER TABLE fundamentals ADD COLUMN IF NOT EXISTS period_end TIMESTAMP;
+ALTER TABLE fundamentals ADD COLUMN IF NOT EXISTS available_at TIMESTAMP;
+UPDATE fundamentals SET available_at = date WHERE available_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_fundamentals_symbol_date ON fundamentals(symbol, date);
+CREATE INDEX IF NOT EXISTS idx_fundamentals_symbol_available ON fundamentals(symbol, available_at);
+
+DROP INDEX IF EXISTS idx_financial_statements_symbol_end;
+ALTER TABLE financial_statements ADD COLUMN IF NOT EXISTS available_at TIMESTAMP;
+CREATE INDEX IF NOT EXISTS idx_financial_statements_symbol_end ON financial_statements(symbol, period, end_date);
+CREATE INDEX IF NOT EXISTS idx_financial_statements_symbol_available ON financial_statements(symbol, available_at);
+
+CREATE TABLE IF NOT EXISTS earnings (
+    symbol TEXT NOT NULL,
+    fiscal_year INTEGER NOT NULL,
+    fiscal_quarter INTEGER,
+    period_end TIMESTAMP NOT NULL,
+    report_date TIMESTAMP,
+    eps_actual DOUBLE,
+    eps_estimate DOUBLE,
+    revenue_actual DOUBLE,
+    revenue_estimate DOUBLE,
+    surprise_percent DOUBLE,
+    source TEXT,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY(symbol, period_end)
+);
+",
+        down: r"
+DROP TABLE IF EXISTS earnings;
+
+DROP INDEX IF EXISTS idx_financial_statements_symbol_available;
+DROP INDEX IF EXISTS idx_financial_statements_symbol_end;
+ALTER TABLE financial_statements DROP COLUMN IF EXISTS available_at;
+CREATE INDEX IF NOT EXISTS idx_financial_statements_symbol_end ON financial_statements(symbol, period, end_date);
+
+DROP INDEX IF EXISTS idx_fundamentals_symbol_available;
+DROP INDEX IF EXISTS idx_fundamentals_symbol_date;
+ALTER TABLE fundamentals DROP COLUMN IF EXISTS available_at;
+ALTER TABLE fundamentals DROP COLUMN IF EXISTS period_end;
+CREATE INDEX IF NOT EXISTS idx_fundamentals_symbol_date ON fundamentals(symbol, date);
+",
+    },
+    Migration {
+        version: "0005_instrument_master",
+        up: r"
+ALTER TABLE instruments ADD COLUMN IF NOT EXISTS instrument_id TEXT;
+ALTER TABLE instruments ADD COLUMN IF NOT EXISTS isin TEXT;
+ALTER TABLE instruments ADD COLUMN IF NOT EXISTS cusip TEXT;
+ALTER TABLE instruments ADD COLUMN IF NOT EXISTS figi TEXT;
+ALTER TABLE instruments ADD COLUMN IF NOT EXISTS cik TEXT;
+ALTER TABLE instruments ADD COLUMN IF NOT EXISTS sector TEXT;
+ALTER TABLE instruments ADD COLUMN IF NOT EXISTS industry TEXT;
+ALTER TABLE instruments ADD COLUMN IF NOT EXISTS listed_at TIMESTAMP;
+ALTER TABLE instruments ADD COLUMN IF NOT EXISTS delisted_at TIMESTAMP;
+UPDATE instruments SET instrument_id = symbol WHERE instrument_id IS NULL;
+
+-- One row per symbol an instrument traded under; the open row has no valid_to.
+CREATE TABLE IF NOT EXISTS ticker_history (
+    instrument_id TEXT NOT NULL,
+    symbol TEXT NOT NULL,
+    valid_from TIMESTAMP NOT NULL,
+    valid_to TIMESTAMP,
+    source TEXT,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY(instrument_id, symbol, valid_from)
+);
+
+INSERT INTO ticker_history (instrument_id, symbol, valid_from, valid_to, source)
+SELECT instrument_id, symbol, TIMESTAMP '1970-01-01', NULL, source FROM instruments;
+
+-- Provider-specific spellings of a symbol (e.g. BRK-B vs BRK.B).
+CREATE TABLE IF NOT EXISTS instrument_aliases (
+    provider TEXT NOT NULL,
+    alias TEXT NOT NULL,
+    instrument_id TEXT NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY(provider, alias)
+);
+",
+        down: r"
+DROP TABLE IF EXISTS instrument_aliases;
+DROP TABLE IF EXISTS ticker_history;
+ALTER TABLE instruments DROP COLUMN IF EXISTS delisted_at;
+ALTER TABLE instruments DROP COLUMN IF EXISTS listed_at;
+ALTER TABLE instruments DROP COLUMN IF EXISTS industry;
+ALTER TABLE instruments DROP COLUMN IF EXISTS sector;
+ALTER TABLE instruments DROP COLUMN IF EXISTS cik;
+ALTER TABLE instruments DROP COLUMN IF EXISTS figi;
+ALTER TABLE instruments DROP COLUMN IF EXISTS cusip;
+ALTER TABLE instruments DROP COLUMN IF EXISTS isin;
+ALTER TABLE instruments DROP COLUMN IF EXISTS instrument_id;
+",
+    },
+    Migration {
+        version: "0006_tick_data",
+        up: r"
+-- seq disambiguates prints sharing a timestamp within one symbol.
+CREATE TABLE IF NOT EXISTS trade_ticks (
+    symbol TEXT NOT NULL,
+    ts TIMESTAMP NOT NULL,
+    seq INTEGER NOT NULL,
+    price DOUBLE NOT NULL,
+    size BIGINT NOT NULL,
+    exchange TEXT,
+    conditions TEXT,
+    source TEXT NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY(symbol, ts, seq)
+);
+
+CREATE TABLE IF NOT EXISTS quote_ticks (
+    symbol TEXT NOT NULL,
+    ts TIMESTAMP NOT NULL,
+    seq INTEGER NOT NULL,
+    bid DOUBLE NOT NULL,
+    bid_size BIGINT NOT NULL,
+    ask DOUBLE NOT NULL,
+    ask_size BIGINT NOT NULL,
+    bid_exchange TEXT,
+    ask_exchange TEXT,
+    source TEXT NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY(symbol, ts, seq)
+);
+",
+        down: r"
+DROP TABLE IF EXISTS quote_ticks;
+DROP TABLE IF EXISTS trade_ticks;
+",
+    },
+    Migration {
+        version: "0007_macro_series",
+        up: r"
+CREATE TABLE IF NOT EXISTS macro_series (
+    series_id TEXT NOT NULL,
+    date TIMESTAMP NOT NULL,
+    value DOUBLE NOT NULL,
+    unit TEXT,
+    frequency TEXT,
+    source TEXT NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY(series_id, date)
+);
+",
+        down: r"
+DROP TABLE IF EXISTS macro_series;
+",
+    },
+    Migration {
+        version: "0008_news",
+        up: r"
+CREATE TABLE IF NOT EXISTS news (
+    url TEXT PRIMARY KEY,
+    published_at TIMESTAMP NOT NULL,
+    publisher TEXT NOT NULL,
+    title TEXT NOT NULL,
+    summary TEXT,
+    sentiment DOUBLE,
+    source TEXT NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS news_tickers (
+    url TEXT NOT NULL,
+    symbol TEXT NOT NULL,
+    sentiment DOUBLE,
+    relevance DOUBLE,
+    PRIMARY KEY(url, symbol)
+);
+",
+        down: r"
+DROP TABLE IF EXISTS news_tickers;
+DROP TABLE IF EXISTS news;
+",
+    },
+    Migration {
+        // bars_1m and bars_1d come back as views over `bars` in views.rs.
+        version: "0009_unified_bars",
+        up: r"
+CREATE TABLE IF NOT EXISTS bars (
+    symbol TEXT NOT NULL,
+    interval TEXT NOT NULL,
+    ts TIMESTAMP NOT NULL,
+    open DOUBLE NOT NULL,
+    high DOUBLE NOT NULL,
+    low DOUBLE NOT NULL,
+    close DOUBLE NOT NULL,
+    volume BIGINT,
+    source TEXT,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY(symbol, interval, ts)
+);
+
+INSERT OR REPLACE INTO bars
+    (symbol, interval, ts, open, high, low, close, volume, source, updated_at)
+SELECT symbol, '1m', ts, open, high, low, close, volume, source, updated_at FROM bars_1m;
+
+INSERT OR REPLACE INTO bars
+    (symbol, interval, ts, open, high, low, close, volume, source, updated_at)
+SELECT symbol, '1d', ts, open, high, low, close, volume, source, updated_at FROM bars_1d;
+
+DROP TABLE IF EXISTS bars_1m;
+DROP TABLE IF EXISTS bars_1d;
+
+CREATE INDEX IF NOT EXISTS idx_bars_interval_symbol_ts ON bars(interval, symbol, ts);
+",
+        down: r"
+-- Only 1m and 1d bars had tables before; other intervals are dropped.
+CREATE TABLE IF NOT EXISTS bars_1m (
+    symbol TEXT NOT NULL,
+    ts TIMESTAMP NOT NULL,
+    open DOUBLE NOT NULL,
+    high DOUBLE NOT NULL,
+    low DOUBLE NOT NULL,
+    close DOUBLE NOT NULL,
+    volume BIGINT,
+    source TEXT,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY(symbol, ts)
+);
+
+CREATE TABLE IF NOT EXISTS bars_1d (
+    symbol TEXT NOT NULL,
+    ts TIMESTAMP NOT NULL,
+    open DOUBLE NOT NULL,
+    high DOUBLE NOT NULL,
+    low DOUBLE NOT NULL,
+    close DOUBLE NOT NULL,
+    volume BIGINT,
+    source TEXT,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY(symbol, ts)
+);
+
+INSERT OR REPLACE INTO bars_1m (symbol, ts, open, high, low, close, volume, source, updated_at)
+SELECT symbol, ts, open, high, low, close, volume, source, updated_at FROM bars WHERE interval = '1m';
+
+INSERT OR REPLACE INTO bars_1d (symbol, ts, open, high, low, close, volume, source, updated_at)
+SELECT symbol, ts, open, high, low, close, volume, source, updated_at FROM bars WHERE interval = '1d';
+
+DROP INDEX IF EXISTS idx_bars_interval_symbol_ts;
+DROP TABLE IF EXISTS bars;
+
+CREATE INDEX IF NOT EXISTS idx_bars_1m_symbol_ts ON bars_1m(symbol, ts);
+CREATE INDEX IF NOT EXISTS idx_bars_1d_symbol_ts ON bars_1d(symbol, ts);
+",
+    },
+    Migration {
+        version: "0010_ingest_run_detail",
+        up: r"
+ALTER TABLE ingest_log ADD COLUMN IF NOT EXISTS detail TEXT;
+",
+        down: r"
+DROP INDEX IF EXISTS idx_ingest_log_source_dataset_ts;
+ALTER TABLE ingest_log DROP COLUMN IF EXISTS detail;
+CREATE INDEX IF NOT EXISTS idx_ingest_log_source_dataset_ts ON ingest_log(source, dataset, timestamp);
+",
+    },
+    Migration {
+        version: "0011_corporate_action_details",
+        up: r"
+-- `date` is the ex-date; `value` is the cash amount per share or the
+-- split ratio.
+ALTER TABLE corporate_actions ADD COLUMN IF NOT EXISTS pay_date TIMESTAMP;
+ALTER TABLE corporate_actions ADD COLUMN IF NOT EXISTS value DOUBLE;
+ALTER TABLE corporate_actions ADD COLUMN IF NOT EXISTS currency TEXT;
+",
+        down: r"
+ALTER TABLE corporate_actions DROP COLUMN IF EXISTS currency;
+ALTER TABLE corporate_actions DROP COLUMN IF EXISTS value;
+ALTER TABLE corporate_actions DROP COLUMN IF EXISTS pay_date;
+",
+    },
+    Migration {
+        version: "0012_row_lineage",
+        up: r"
+CREATE TABLE IF NOT EXISTS ingest_batches (
+    batch_id TEXT PRIMARY KEY,
+    request_id TEXT NOT NULL,
+    source TEXT NOT NULL,
+    dataset TEXT NOT NULL,
+    binary_version TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+ALTER TABLE ingest_log ADD COLUMN IF NOT EXISTS batch_id TEXT;
+ALTER TABLE bars ADD COLUMN IF NOT EXISTS ingest_batch TEXT;
+ALTER TABLE quotes_latest ADD COLUMN IF NOT EXISTS ingest_batch TEXT;
+
+-- Values overwritten by a later batch, with the batch that overwrote them.
+CREATE TABLE IF NOT EXISTS bars_audit (
+    symbol TEXT NOT NULL,
+    interval TEXT NOT NULL,
+    ts TIMESTAMP NOT NULL,
+    open DOUBLE NOT NULL,
+    high DOUBLE NOT NULL,
+    low DOUBLE NOT NULL,
+    close DOUBLE NOT NULL,
+    volume BIGINT,
+    source TEXT,
+    ingest_batch TEXT,
+    updated_at TIMESTAMP NOT NULL,
+    superseded_by TEXT NOT NULL,
+    superseded_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS quotes_audit (
+    symbol TEXT NOT NULL,
+    price DOUBLE NOT NULL,
+    bid DOUBLE,
+    ask DOUBLE,
+    volume BIGINT,
+    as_of TIMESTAMP NOT NULL,
+    source TEXT,
+    ingest_batch TEXT,
+    updated_at TIMESTAMP NOT NULL,
+    superseded_by TEXT NOT NULL,
+    superseded_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_bars_audit_interval_symbol_ts ON bars_audit(interval, symbol, ts);
+CREATE INDEX IF NOT EXISTS idx_bars_audit_superseded_by ON bars_audit(superseded_by);
+",
+        down: r"
+DROP TABLE IF EXISTS quotes_audit;
+DROP TABLE IF EXISTS bars_audit;
+DROP INDEX IF EXISTS idx_quotes_latest_as_of;
+ALTER TABLE quotes_latest DROP COLUMN IF EXISTS ingest_batch;
+CREATE INDEX IF NOT EXISTS idx_quotes_latest_as_of ON quotes_latest(as_of);
+DROP INDEX IF EXISTS idx_bars_interval_symbol_ts;
+ALTER TABLE bars DROP COLUMN IF EXISTS ingest_batch;
+CREATE INDEX IF NOT EXISTS idx_bars_interval_symbol_ts ON bars(interval, symbol, ts);
+DROP INDEX IF EXISTS idx_ingest_log_source_dataset_ts;
+ALTER TABLE ingest_log DROP COLUMN IF EXISTS batch_id;
+CREATE INDEX IF NOT EXISTS idx_ingest_log_source_dataset_ts ON ingest_log(source, dataset, timestamp);
+DROP TABLE IF EXISTS ingest_batches;
+",
+    },
+    Migration {
+        version: "0013_universes",
+        up: r"
+CREATE TABLE IF NOT EXISTS universes (
+    name TEXT PRIMARY KEY,
+    description TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- One row per membership interval; `removed` is exclusive and NULL while
+-- the symbol is still a member.
+CREATE TABLE IF NOT EXISTS universe_members (
+    universe TEXT NOT NULL,
+    symbol TEXT NOT NULL,
+    added DATE NOT NULL,
+    removed DATE,
+    PRIMARY KEY(universe, symbol, added)
+);
+",
+        down: r"
+DROP TABLE IF EXISTS universe_members;
+DROP TABLE IF EXISTS universes;
+",
+    },
+    Migration {
+        version: "0014_empty_bar_sessions",
+        up: r"
+-- Weekdays a load fetched but got no bars for, such as exchange holidays,
+-- so incremental loads stop treating them as gaps.
+CREATE TABLE IF NOT EXISTS empty_bar_sessions (
+    symbol TEXT NOT NULL,
+    interval TEXT NOT NULL,
+    day DATE NOT NULL,
+    checked_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY(symbol, interval, day)
+);
+",
+        down: r"
+DROP TABLE IF EXISTS empty_bar_sessions;
+",
+    },
+    Migration {
+        version: "0015_ingest_batch_digest",
+        up: r"
+-- SHA-256 of the rows a batch ingested, to tell provider responses apart
+-- when request ids are reused.
+ALTER TABLE ingest_batches ADD COLUMN IF NOT EXISTS response_digest TEXT;
+",
+        down: r"
+ALTER TABLE ingest_batches DROP COLUMN IF EXISTS response_digest;
 ",
     },
 ];
 
-/// Apply database migrations in order.
-///
-/// Creates the `schema_migrations` table if it doesn't exist, then applies
-/// each migration in sequence, tracking which have been applied.
+/// Target version of a down migration that reverts every migration.
+pub const BASELINE_VERSION: &str = "0";
+
+/// Direction of a [`migrate`] run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationDirection {
+    Up,
+    Down,
+}
+
+impl MigrationDirection {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Up => "up",
+            Self::Down => "down",
+        }
+    }
+}
+
+/// A migration and whether the database has it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MigrationStatus {
+    pub version: String,
+    pub applied: bool,
+    pub applied_at: Option<String>,
+    /// False for a version applied by a newer build, which this one can
+    /// neither run nor revert.
+    pub known: bool,
+}
+
+/// Report from [`migrate`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MigrationReport {
+    pub direction: MigrationDirection,
+    pub dry_run: bool,
+    /// Schema version before the run, `None` for an empty database.
+    pub from: Option<String>,
+    /// Schema version after the run, or that the run would leave.
+    pub to: Option<String>,
+    /// Versions applied or reverted, in the order they run.
+    pub steps: Vec<String>,
+}
+
+/// Versions of every migration this build knows, in apply order.
+pub fn versions() -> impl Iterator<Item = &'static str> {
+    MIGRATIONS.iter().map(|migration| migration.version)
+}
+
+/// Latest migration this build knows.
+pub fn latest_version() -> &'static str {
+    MIGRATIONS[MIGRATIONS.len() - 1].version
+}
+
+/// Apply every pending migration in order.
 ///
 /// # Errors
-/// Returns an error if:
-/// - The `schema_migrations` table cannot be created
-/// - Any migration SQL fails to execute
-/// - The migration tracking insert fails
+/// Returns [`WarehouseError::SchemaTooNew`] for a database with migrations
+/// this build does not know, since a newer ferrotick applied them, and an
+/// error if a migration fails.
+pub fn apply_migrations(connection: &Connection) -> Result<(), WarehouseError> {
+    migrate(connection, MigrationDirection::Up, None, false).map(|_| ())
+}
+
+/// Status of every known migration, followed by any unknown version the
+/// database has.
+pub fn status(connection: &Connection) -> Result<Vec<MigrationStatus>, WarehouseError> {
+    let applied = applied_versions(connection)?;
+    let mut statuses = MIGRATIONS
+        .iter()
+        .map(|migration| MigrationStatus {
+            version: migration.version.to_string(),
+            applied: applied.contains_key(migration.version),
+            applied_at: applied.get(migration.version).cloned(),
+            known: true,
+        })
+        .collect::<Vec<_>>();
+    statuses.extend(
+        applied
+            .iter()
+            .filter(|(version, _)| !is_known(version))
+            .map(|(version, applied_at)| MigrationStatus {
+                version: version.clone(),
+                applied: true,
+                applied_at: Some(applied_at.clone()),
+                known: false,
+            }),
+    );
+    Ok(statuses)
+}
+
+/// Migrate up to `to`, the latest version by default, or down to `to`,
+/// which stays applied; [`BASELINE_VERSION`] reverts everything.
 ///
-/// # Security
-/// Migration version strings are hardcoded constants, not user input.
-/// The `escape_sql_string` function provides defense-in-depth against
-/// any accidental special characters in version strings.
-pub fn apply_migrations(connection: &Connection) -> Result<(), ::duckdb::Error> {
-    connection.execute_batch(
-        r"
+/// Down migrations first drop the views and macros, which
+/// [`crate::views::create_views`] recreates. With `dry_run` the steps are
+/// planned but nothing is changed.
+pub fn migrate(
+    connection: &Connection,
+    direction: MigrationDirection,
+    to: Option<&str>,
+    dry_run: bool,
+) -> Result<MigrationReport, WarehouseError> {
+    if !dry_run {
+        connection.execute_batch(
+            r"
 CREATE TABLE IF NOT EXISTS schema_migrations (
     version TEXT PRIMARY KEY,
     applied_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 ",
-    )?;
+        )?;
+    }
+    let applied = applied_versions(connection)?;
+    if let Some(unknown) = applied.keys().filter(|version| !is_known(version)).max() {
+        return Err(WarehouseError::SchemaTooNew {
+            version: unknown.clone(),
+            latest: latest_version().to_string(),
+        });
+    }
 
-    for migration in MIGRATIONS {
-        // Migration version is a hardcoded constant, safe to interpolate
-        let query = format!(
-            "SELECT COUNT(*) FROM schema_migrations WHERE version = '{}'",
-            escape_sql_string(migration.version)
-        );
-        let applied_count: i64 = connection.query_row(query.as_str(), [], |row| row.get(0))?;
-
-        if applied_count == 0 {
-            connection.execute_batch(migration.sql)?;
-            let insert = format!(
-                "INSERT INTO schema_migrations (version) VALUES ('{}')",
-                escape_sql_string(migration.version)
-            );
-            connection.execute_batch(insert.as_str())?;
+    // Number of migrations, in order, the target leaves in place.
+    let target = match (direction, to) {
+        (MigrationDirection::Up, None) => MIGRATIONS.len(),
+        (MigrationDirection::Down, None) => {
+            return Err(migration_error(
+                "down needs a target version; use 0 to revert every migration",
+            ));
+        }
+        (MigrationDirection::Down, Some(BASELINE_VERSION)) => 0,
+        (_, Some(version)) => {
+            let position = MIGRATIONS
+                .iter()
+                .position(|migration| migration.version == version)
+                .ok_or_else(|| migration_error(format!("unknown migration version '{version}'")))?;
+            if direction == MigrationDirection::Down && !applied.contains_key(version) {
+                return Err(migration_error(format!("{version} is not applied")));
+            }
+            position + 1
         }
+    };
+    let steps = match direction {
+        MigrationDirection::Up => MIGRATIONS[..target]
+            .iter()
+            .filter(|migration| !applied.contains_key(migration.version))
+            .collect::<Vec<_>>(),
+        MigrationDirection::Down => MIGRATIONS[target..]
+            .iter()
+            .rev()
+            .filter(|migration| applied.contains_key(migration.version))
+            .collect(),
+    };
+
+    let schema_version = |applied: &dyn Fn(&str) -> bool| {
+        MIGRATIONS
+            .iter()
+            .rev()
+            .find(|migration| applied(migration.version))
+            .map(|migration| migration.version.to_string())
+    };
+    let from = schema_version(&|version| applied.contains_key(version));
+    let to = schema_version(&|version| {
+        let stepped = steps.iter().any(|migration| migration.version == version);
+        applied.contains_key(version) != stepped
+    });
+    let report = MigrationReport {
+        direction,
+        dry_run,
+        from,
+        to,
+        steps: steps
+            .iter()
+            .map(|migration| migration.version.to_string())
+            .collect(),
+    };
+    if dry_run || steps.is_empty() {
+        return Ok(report);
     }
 
-    Ok(())
+    if direction == MigrationDirection::Down {
+        views::drop_views(connection)?;
+    }
+    for migration in steps {
+        run_step(connection, migration, direction)?;
+    }
+    Ok(report)
 }
 
-/// Escape a string for safe inclusion in SQL.
+/// Run one migration and record it in `schema_migrations`.
 ///
-/// Used for migration version strings which are hardcoded constants.
-fn escape_sql_string(value: &str) -> String {
-    value.replace('\'', "''")
+/// Statements commit one at a time, since DuckDB cannot build an index in
+/// a transaction that altered its table. Every schema change is guarded
+/// with `IF [NOT] EXISTS`, so a step that failed halfway can be run again.
+fn run_step(
+    connection: &Connection,
+    migration: &Migration,
+    direction: MigrationDirection,
+) -> Result<(), WarehouseError> {
+    let result = match direction {
+        MigrationDirection::Up => connection.execute_batch(migration.up).and_then(|()| {
+            connection.execute(
+                "INSERT INTO schema_migrations (version) VALUES (?)",
+                [migration.version],
+            )
+        }),
+        MigrationDirection::Down => connection.execute_batch(migration.down).and_then(|()| {
+            connection.execute(
+                "DELETE FROM schema_migrations WHERE version = ?",
+                [migration.version],
+            )
+        }),
+    };
+    result.map(|_| ()).map_err(|error| {
+        migration_error(format!(
+            "{} {} failed: {error}",
+            direction.as_str(),
+            migration.version
+        ))
+    })
+}
+
+/// `applied_at` of each version in `schema_migrations`, which may not
+/// exist yet.
+fn applied_versions(connection: &Connection) -> Result<BTreeMap<String, String>, WarehouseError> {
+    let exists: bool = connection.query_row(
+        "SELECT COUNT(*) > 0 FROM duckdb_tables() \
+         WHERE table_name = 'schema_migrations' AND database_name = current_database()",
+        [],
+        |row| row.get(0),
+    )?;
+    if !exists {
+        return Ok(BTreeMap::new());
+    }
+    let mut statement =
+        connection.prepare("SELECT version, CAST(applied_at AS VARCHAR) FROM schema_migrations")?;
+    let applied = statement
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<Result<BTreeMap<_, _>, _>>()?;
+    Ok(applied)
+}
+
+fn is_known(version: &str) -> bool {
+    MIGRATIONS
+        .iter()
+        .any(|migration| migration.version == version)
+}
+
+fn migration_error(message: impl Into<String>) -> WarehouseError {
+    WarehouseError::Migration(message.into())
+}
+
+impl Warehouse {
+    /// Migration status of the database at `config`, read without opening
+    /// the warehouse, which would migrate it.
+    pub fn migration_status(
+        config: &WarehouseConfig,
+    ) -> Result<Vec<MigrationStatus>, WarehouseError> {
+        if !config.db_path.exists() {
+            return status(&Connection::open_in_memory()?);
+        }
+        status(&Connection::open(&config.db_path)?)
+    }
+
+    /// Migrate the database at `config`, which must not be open; see
+    /// [`migrate`].
+    ///
+    /// Views and macros are recreated when the database ends at
+    /// [`latest_version`]. Below it they are left to the build that
+    /// matches the schema.
+    pub fn migrate(
+        config: &WarehouseConfig,
+        direction: MigrationDirection,
+        to: Option<&str>,
+        dry_run: bool,
+    ) -> Result<MigrationReport, WarehouseError> {
+        if let Some(parent) = config.db_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let connection = Connection::open(&config.db_path)?;
+        let report = migrate(&connection, direction, to, dry_run)?;
+        if !dry_run && report.to.as_deref() == Some(latest_version()) {
+            views::create_views(&connection)?;
+        }
+        connection.execute_batch("CHECKPOINT")?;
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Rows in the shape of the `0001_core_tables` schema, loaded before the
+    /// later migrations are replayed over them.
+    const FIXTURE: &str = r"
+INSERT INTO instruments (symbol, name, exchange, currency, asset_class, source)
+VALUES ('AAPL', 'Apple Inc.', 'XNAS', 'USD', 'equity', 'yahoo');
+INSERT INTO quotes_latest (symbol, price, bid, ask, volume, as_of, source)
+VALUES ('AAPL', 190.5, 190.4, 190.6, 1000, TIMESTAMP '2026-02-17 21:00:00', 'yahoo');
+INSERT INTO bars_1m (symbol, ts, open, high, low, close, volume, source)
+VALUES ('AAPL', TIMESTAMP '2026-02-17 14:30:00', 1, 2, 0.5, 1.5, 10, 'polygon');
+INSERT INTO bars_1d (symbol, ts, open, high, low, close, volume, source)
+VALUES ('AAPL', TIMESTAMP '2026-02-17 00:00:00', 1, 3, 0.5, 2, 100, 'yahoo');
+INSERT INTO fundamentals (symbol, metric, value, date, source)
+VALUES ('AAPL', 'pe_ratio', 29.1, TIMESTAMP '2026-01-30 00:00:00', 'yahoo');
+INSERT INTO corporate_actions (symbol, type, date, details, source)
+VALUES ('AAPL', 'dividend', TIMESTAMP '2026-02-09 00:00:00', '0.26', 'yahoo');
+INSERT INTO cache_manifest (source, dataset, symbol, partition_date, path, row_count, checksum)
+VALUES ('yahoo', 'bars_1d', 'AAPL', DATE '2026-02-17', '/cache/data.parquet', 1, 'abc');
+INSERT INTO ingest_log (request_id, symbol, source, dataset, status, latency_ms)
+VALUES ('req-1', 'AAPL', 'yahoo', 'bars_1d', 'ok', 12);
+";
+
+    fn count(connection: &Connection, sql: &str) -> i64 {
+        connection
+            .query_row(sql, [], |row| row.get(0))
+            .expect("count")
+    }
+
+    #[test]
+    fn every_migration_replays_up_and_down_over_fixture_db() {
+        let connection = Connection::open_in_memory().expect("connection");
+        let all = versions().collect::<Vec<_>>();
+        assert!(status(&connection)
+            .expect("status")
+            .iter()
+            .all(|migration| !migration.applied));
+
+        migrate(&connection, MigrationDirection::Up, Some(all[0]), false).expect("core tables");
+        connection.execute_batch(FIXTURE).expect("fixture rows");
+        for version in &all[1..] {
+            let report = migrate(&connection, MigrationDirection::Up, Some(version), false)
+                .unwrap_or_else(|error| panic!("up {version}: {error}"));
+            assert_eq!(report.steps, vec![version.to_string()]);
+            assert_eq!(report.to.as_deref(), Some(*version));
+        }
+        views::create_views(&connection).expect("views over the latest schema");
+        assert_eq!(count(&connection, "SELECT COUNT(*) FROM bars"), 2);
+        assert_eq!(
+            count(
+                &connection,
+                "SELECT COUNT(*) FROM ticker_history WHERE instrument_id = 'AAPL'"
+            ),
+            1
+        );
+
+        let plan =
+            migrate(&connection, MigrationDirection::Down, Some(all[0]), true).expect("dry run");
+        assert_eq!(plan.from.as_deref(), Some(latest_version()));
+        assert_eq!(plan.to.as_deref(), Some(all[0]));
+        assert_eq!(plan.steps.len(), all.len() - 1);
+        assert_eq!(plan.steps[0], latest_version());
+        assert!(status(&connection)
+            .expect("status")
+            .iter()
+            .all(|migration| migration.applied));
+
+        for index in (1..all.len()).rev() {
+            let report = migrate(
+                &connection,
+                MigrationDirection::Down,
+                Some(all[index - 1]),
+                false,
+            )
+            .unwrap_or_else(|error| panic!("down {}: {error}", all[index]));
+            assert_eq!(report.steps, vec![all[index].to_string()]);
+        }
+        // Reverting unified bars hands the legacy tables their rows back.
+        assert_eq!(count(&connection, "SELECT COUNT(*) FROM bars_1m"), 1);
+        assert_eq!(count(&connection, "SELECT COUNT(*) FROM bars_1d"), 1);
+        assert_eq!(
+            count(
+                &connection,
+                "SELECT COUNT(*) FROM information_schema.columns WHERE table_name = 'instruments'"
+            ),
+            8
+        );
+
+        migrate(
+            &connection,
+            MigrationDirection::Down,
+            Some(BASELINE_VERSION),
+            false,
+        )
+        .expect("down to baseline");
+        assert_eq!(
+            count(
+                &connection,
+                "SELECT COUNT(*) FROM duckdb_tables() WHERE table_name <> 'schema_migrations'"
+            ),
+            0
+        );
+
+        apply_migrations(&connection).expect("replay from scratch");
+        views::create_views(&connection).expect("views");
+        assert!(status(&connection)
+            .expect("status")
+            .iter()
+            .all(|migration| migration.applied && migration.known));
+    }
+
+    #[test]
+    fn databases_from_a_newer_build_are_refused() {
+        let connection = Connection::open_in_memory().expect("connection");
+        apply_migrations(&connection).expect("migrate");
+        connection
+            .execute_batch("INSERT INTO schema_migrations (version) VALUES ('9999_future')")
+            .expect("future migration");
+
+        let error = apply_migrations(&connection).expect_err("newer schema");
+        assert!(matches!(
+            &error,
+            WarehouseError::SchemaTooNew { version, .. } if version == "9999_future"
+        ));
+        assert!(migrate(
+            &connection,
+            MigrationDirection::Down,
+            Some(BASELINE_VERSION),
+            true
+        )
+        .is_err());
+        let unknown = status(&connection)
+            .expect("status")
+            .into_iter()
+            .filter(|migration| !migration.known)
+            .map(|migration| migration.version)
+            .collect::<Vec<_>>();
+        assert_eq!(unknown, vec![String::from("9999_future")]);
+
+        assert!(migrate(
+            &connection,
+            MigrationDirection::Up,
+            Some("0042_missing"),
+            true
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn unified_bars_migration_keeps_legacy_rows() {
+        let connection = Connection::open_in_memory().expect("connection");
+        connection
+            .execute_batch(
+                "CREATE TABLE schema_migrations (version TEXT PRIMARY KEY, applied_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP)",
+            )
+            .expect("migrations table");
+        let unified = MIGRATIONS
+            .iter()
+            .position(|migration| migration.version == "0009_unified_bars")
+            .expect("unified bars migration");
+        for migration in &MIGRATIONS[..unified] {
+            connection
+                .execute_batch(migration.up)
+                .expect("legacy migration");
+            connection
+                .execute(
+                    "INSERT INTO schema_migrations (version) VALUES (?)",
+                    [migration.version],
+                )
+                .expect("record migration");
+        }
+        connection
+            .execute_batch(
+                "INSERT INTO bars_1m (symbol, ts, open, high, low, close, volume, source) \
+                 VALUES ('AAPL', TIMESTAMP '2026-02-17 14:30:00', 1, 2, 0.5, 1.5, 10, 'polygon');
+                 INSERT INTO bars_1d (symbol, ts, open, high, low, close, volume, source) \
+                 VALUES ('AAPL', TIMESTAMP '2026-02-17 00:00:00', 1, 3, 0.5, 2, 100, 'yahoo');",
+            )
+            .expect("legacy rows");
+
+        apply_migrations(&connection).expect("apply unified migration");
+
+        let mut statement = connection
+            .prepare("SELECT interval, source FROM bars ORDER BY interval")
+            .expect("prepare");
+        let rows = statement
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })
+            .expect("query")
+            .collect::<Result<Vec<_>, _>>()
+            .expect("rows");
+        assert_eq!(
+            rows,
+            vec![
+                (String::from("1d"), String::from("yahoo")),
+                (String::from("1m"), String::from("polygon")),
+            ]
+        );
+    }
 }
@@ -0,0 +1,503 @@
+//! Typed query parameters and the named query library.
+//!
+//! Queries reference parameters as `$name` and receive their values through
+//! [`Warehouse::execute_query_with_params`], so user input is always bound,
+//! never spliced into SQL text.
+//!
+//! Named queries are `.sql` files in [`Warehouse::queries_dir`]. The file
+//! stem is the query name, and leading comment lines declare a description
+//! and the parameters with their types and optional defaults:
+//!
+//! ```sql
+//! -- @description Strongest symbols by trailing return
+//! -- @param window int = 63 trailing window in trading days
+//! -- @param top int = 10
+//! SELECT * FROM relative_strength_rank_v1(window_days := $window)
+//! WHERE rank <= $top
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use ::duckdb::types::{TimeUnit, ToSqlOutput, Value as DuckValue};
+use ::duckdb::{Statement, ToSql};
+use serde::Serialize;
+
+use crate::{Warehouse, WarehouseError};
+
+const MICROS_PER_DAY: i64 = 86_400_000_000;
+
+/// Named parameter values, keyed by name without the `$`.
+pub type QueryParams = BTreeMap<String, QueryParam>;
+
+/// A typed value bound to a `$name` query parameter.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum QueryParam {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+    /// `YYYY-MM-DD`, bound as a `TIMESTAMP` at midnight so it compares with
+    /// both `DATE` and `TIMESTAMP` columns.
+    Date(String),
+}
+
+impl From<&str> for QueryParam {
+    fn from(value: &str) -> Self {
+        Self::Text(value.to_string())
+    }
+}
+
+impl From<String> for QueryParam {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl From<i64> for QueryParam {
+    fn from(value: i64) -> Self {
+        Self::Int(value)
+    }
+}
+
+impl From<f64> for QueryParam {
+    fn from(value: f64) -> Self {
+        Self::Float(value)
+    }
+}
+
+impl From<bool> for QueryParam {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl ToSql for QueryParam {
+    fn to_sql(&self) -> ::duckdb::Result<ToSqlOutput<'_>> {
+        let value = match self {
+            Self::Null => DuckValue::Null,
+            Self::Bool(value) => DuckValue::Boolean(*value),
+            Self::Int(value) => DuckValue::BigInt(*value),
+            Self::Float(value) => DuckValue::Double(*value),
+            Self::Text(value) => DuckValue::Text(value.clone()),
+            Self::Date(value) => {
+                let days = epoch_days(value).ok_or_else(|| {
+                    ::duckdb::Error::ToSqlConversionFailure(
+                        format!("invalid date '{value}'").into(),
+                    )
+                })?;
+                DuckValue::Timestamp(TimeUnit::Microsecond, i64::from(days) * MICROS_PER_DAY)
+            }
+        };
+        Ok(ToSqlOutput::Owned(value))
+    }
+}
+
+/// Declared type of a query parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryParamType {
+    Text,
+    Int,
+    Float,
+    Bool,
+    Date,
+}
+
+impl QueryParamType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::Int => "int",
+            Self::Float => "float",
+            Self::Bool => "bool",
+            Self::Date => "date",
+        }
+    }
+
+    /// Convert the command-line text `raw` into a value of this type.
+    pub fn parse_value(self, raw: &str) -> Result<QueryParam, WarehouseError> {
+        let invalid = || {
+            WarehouseError::QueryRejected(format!(
+                "'{raw}' is not a valid {} parameter value",
+                self.as_str()
+            ))
+        };
+        match self {
+            Self::Text => Ok(QueryParam::Text(raw.to_string())),
+            Self::Int => raw
+                .trim()
+                .parse()
+                .map(QueryParam::Int)
+                .map_err(|_| invalid()),
+            Self::Float => raw
+                .trim()
+                .parse()
+                .map(QueryParam::Float)
+                .map_err(|_| invalid()),
+            Self::Bool => match raw.trim().to_ascii_lowercase().as_str() {
+                "true" => Ok(QueryParam::Bool(true)),
+                "false" => Ok(QueryParam::Bool(false)),
+                _ => Err(invalid()),
+            },
+            Self::Date => {
+                let raw = raw.trim();
+                epoch_days(raw)
+                    .map(|_| QueryParam::Date(raw.to_string()))
+                    .ok_or_else(invalid)
+            }
+        }
+    }
+}
+
+impl fmt::Display for QueryParamType {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+impl FromStr for QueryParamType {
+    type Err = WarehouseError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "text" | "string" | "varchar" => Ok(Self::Text),
+            "int" | "integer" | "bigint" => Ok(Self::Int),
+            "float" | "double" => Ok(Self::Float),
+            "bool" | "boolean" => Ok(Self::Bool),
+            "date" => Ok(Self::Date),
+            other => Err(WarehouseError::QueryRejected(format!(
+                "unknown parameter type '{other}', expected text, int, float, bool or date"
+            ))),
+        }
+    }
+}
+
+/// A parameter declared by a named query.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QueryParamSpec {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub param_type: QueryParamType,
+    /// Value used when the caller does not pass one; required otherwise.
+    pub default: Option<QueryParam>,
+    pub description: Option<String>,
+}
+
+/// A `.sql` file from the named query library.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NamedQuery {
+    pub name: String,
+    pub description: Option<String>,
+    pub params: Vec<QueryParamSpec>,
+    /// The statement, without the declaration header.
+    pub sql: String,
+}
+
+impl NamedQuery {
+    /// Parse a named query from its file contents.
+    pub fn parse(name: &str, contents: &str) -> Result<Self, WarehouseError> {
+        validate_query_name(name)?;
+        let invalid = |message: String| {
+            WarehouseError::QueryRejected(format!("named query '{name}': {message}"))
+        };
+
+        let mut description = None;
+        let mut params: Vec<QueryParamSpec> = Vec::new();
+        for line in contents.lines().map(str::trim) {
+            let Some(comment) = line.strip_prefix("--") else {
+                if line.is_empty() {
+                    continue;
+                }
+                break;
+            };
+            let comment = comment.trim();
+            if let Some(text) = comment.strip_prefix("@description") {
+                description = Some(text.trim().to_string()).filter(|text| !text.is_empty());
+            } else if let Some(declaration) = comment.strip_prefix("@param") {
+                let mut tokens = declaration.split_whitespace().peekable();
+                let (Some(param_name), Some(param_type)) = (tokens.next(), tokens.next()) else {
+                    return Err(invalid(format!(
+                        "'{line}' must be '@param <name> <type> [= <default>] [description]'"
+                    )));
+                };
+                let param_name = param_name.trim_start_matches('$');
+                if !is_identifier(param_name) {
+                    return Err(invalid(format!("invalid parameter name '{param_name}'")));
+                }
+                if params.iter().any(|param| param.name == param_name) {
+                    return Err(invalid(format!("parameter '{param_name}' declared twice")));
+                }
+                let param_type = QueryParamType::from_str(param_type)?;
+                let default = if tokens.next_if_eq(&"=").is_some() {
+                    let raw = tokens
+                        .next()
+                        .ok_or_else(|| invalid(format!("'{param_name}' has an empty default")))?;
+                    Some(param_type.parse_value(raw)?)
+                } else {
+                    None
+                };
+                let text = tokens.collect::<Vec<_>>().join(" ");
+                params.push(QueryParamSpec {
+                    name: param_name.to_string(),
+                    param_type,
+                    default,
+                    description: Some(text).filter(|text| !text.is_empty()),
+                });
+            }
+        }
+
+        // The statement starts after the header comments.
+        let sql = contents
+            .lines()
+            .skip_while(|line| {
+                let line = line.trim();
+                line.is_empty() || line.starts_with("--")
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+            .trim()
+            .to_string();
+        if sql.is_empty() {
+            return Err(invalid(String::from("file has no SQL statement")));
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            description,
+            params,
+            sql,
+        })
+    }
+
+    /// Read `<dir>/<name>.sql`.
+    pub fn load(dir: &Path, name: &str) -> Result<Self, WarehouseError> {
+        validate_query_name(name)?;
+        let path = dir.join(format!("{name}.sql"));
+        if !path.is_file() {
+            return Err(WarehouseError::QueryRejected(format!(
+                "named query '{name}' not found in {}",
+                dir.display()
+            )));
+        }
+        Self::parse(name, &fs::read_to_string(path)?)
+    }
+
+    /// Every `.sql` file in `dir`, sorted by name. A missing directory is an
+    /// empty library.
+    pub fn list(dir: &Path) -> Result<Vec<Self>, WarehouseError> {
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut paths = fs::read_dir(dir)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<PathBuf>, _>>()?;
+        paths.sort();
+
+        let mut queries = Vec::new();
+        for path in paths {
+            if path.extension().and_then(|extension| extension.to_str()) != Some("sql") {
+                continue;
+            }
+            let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            queries.push(Self::parse(name, &fs::read_to_string(&path)?)?);
+        }
+        Ok(queries)
+    }
+
+    /// Type the command-line `values` by the declared parameters and fill in
+    /// defaults. Undeclared and missing required parameters are rejected.
+    pub fn bind(&self, values: &BTreeMap<String, String>) -> Result<QueryParams, WarehouseError> {
+        if let Some(unknown) = values
+            .keys()
+            .find(|name| !self.params.iter().any(|param| &param.name == *name))
+        {
+            return Err(WarehouseError::QueryRejected(format!(
+                "named query '{}' has no parameter '{unknown}'",
+                self.name
+            )));
+        }
+
+        let mut params = QueryParams::new();
+        for spec in &self.params {
+            let value = match (values.get(&spec.name), &spec.default) {
+                (Some(raw), _) => spec.param_type.parse_value(raw)?,
+                (None, Some(default)) => default.clone(),
+                (None, None) => {
+                    return Err(WarehouseError::QueryRejected(format!(
+                        "named query '{}' requires parameter '{}' ({})",
+                        self.name, spec.name, spec.param_type
+                    )));
+                }
+            };
+            params.insert(spec.name.clone(), value);
+        }
+        Ok(params)
+    }
+}
+
+impl Warehouse {
+    /// Directory of the named query library (`<home>/queries`).
+    pub fn queries_dir(&self) -> PathBuf {
+        self.config.ferrotick_home.join("queries")
+    }
+
+    /// Named queries in [`Self::queries_dir`].
+    pub fn named_queries(&self) -> Result<Vec<NamedQuery>, WarehouseError> {
+        NamedQuery::list(self.queries_dir().as_path())
+    }
+
+    /// Load one named query from [`Self::queries_dir`].
+    pub fn named_query(&self, name: &str) -> Result<NamedQuery, WarehouseError> {
+        NamedQuery::load(self.queries_dir().as_path(), name)
+    }
+}
+
+/// Bind `params` to the `$name` placeholders of `statement`.
+///
+/// Every placeholder must have a value and every value a placeholder, so a
+/// typo fails loudly instead of silently binding `NULL`.
+pub(crate) fn bind_named_params(
+    statement: &mut Statement<'_>,
+    params: &QueryParams,
+) -> Result<(), WarehouseError> {
+    let mut placeholders = BTreeSet::new();
+    for index in 1..=statement.parameter_count() {
+        let name = statement.parameter_name(index)?;
+        let value = params.get(&name).ok_or_else(|| {
+            WarehouseError::QueryRejected(format!("missing value for parameter ${name}"))
+        })?;
+        statement.raw_bind_parameter(index, value)?;
+        placeholders.insert(name);
+    }
+
+    if let Some(unused) = params.keys().find(|name| !placeholders.contains(*name)) {
+        return Err(WarehouseError::QueryRejected(format!(
+            "query has no parameter ${unused}"
+        )));
+    }
+    Ok(())
+}
+
+fn validate_query_name(name: &str) -> Result<(), WarehouseError> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-')
+    {
+        return Err(WarehouseError::QueryRejected(format!(
+            "invalid query name '{name}', use letters, digits, '_' and '-'"
+        )));
+    }
+    Ok(())
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|ch| ch.is_ascii_alphabetic() || ch == '_')
+        && chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+}
+
+/// Days since 1970-01-01 of a `YYYY-MM-DD` date.
+fn epoch_days(date: &str) -> Option<i32> {
+    let mut parts = date.splitn(3, '-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: i64 = parts.next()?.parse().ok()?;
+    let day: i64 = parts.next()?.parse().ok()?;
+    let leap = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+    let month_days = [
+        31,
+        if leap { 29 } else { 28 },
+        31,
+        30,
+        31,
+        30,
+        31,
+        31,
+        30,
+        31,
+        30,
+        31,
+    ];
+    if date.len() != 10 || !(1..=12).contains(&month) {
+        return None;
+    }
+    if !(1..=month_days[usize::try_from(month - 1).ok()?]).contains(&day) {
+        return None;
+    }
+
+    // Days from civil, proleptic Gregorian calendar.
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    i32::try_from(era * 146_097 + day_of_era - 719_468).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn declarations_are_parsed_and_bound_by_type() {
+        let query = NamedQuery::parse(
+            "momentum_rank",
+            "-- @description Strongest symbols\n\
+             -- @param window int = 63 trailing window in trading days\n\
+             -- @param as_of date\n\
+             SELECT $window, $as_of",
+        )
+        .expect("parse");
+        assert_eq!(query.description.as_deref(), Some("Strongest symbols"));
+        assert_eq!(query.sql, "SELECT $window, $as_of");
+        assert_eq!(query.params[0].default, Some(QueryParam::Int(63)));
+        assert_eq!(
+            query.params[0].description.as_deref(),
+            Some("trailing window in trading days")
+        );
+
+        let values = BTreeMap::from([(String::from("as_of"), String::from("2024-02-29"))]);
+        let params = query.bind(&values).expect("bind");
+        assert_eq!(params["window"], QueryParam::Int(63));
+        assert_eq!(
+            params["as_of"],
+            QueryParam::Date(String::from("2024-02-29"))
+        );
+
+        assert!(query.bind(&BTreeMap::new()).is_err());
+        let typo = BTreeMap::from([
+            (String::from("as_of"), String::from("2024-02-29")),
+            (String::from("windw"), String::from("5")),
+        ]);
+        assert!(query.bind(&typo).is_err());
+        let bad_date = BTreeMap::from([(String::from("as_of"), String::from("2023-02-29"))]);
+        assert!(query.bind(&bad_date).is_err());
+    }
+
+    #[test]
+    fn epoch_days_match_known_dates() {
+        assert_eq!(epoch_days("1970-01-01"), Some(0));
+        assert_eq!(epoch_days("2000-03-01"), Some(11_017));
+        assert_eq!(epoch_days("1969-12-31"), Some(-1));
+        assert_eq!(epoch_days("2024-13-01"), None);
+        assert_eq!(epoch_days("2024-1-01"), None);
+    }
+
+    #[test]
+    fn invalid_names_and_declarations_are_rejected() {
+        assert!(NamedQuery::parse("../etc", "SELECT 1").is_err());
+        assert!(NamedQuery::parse("q", "-- @param n decimal\nSELECT $n").is_err());
+        assert!(NamedQuery::parse("q", "-- @param n int =\nSELECT $n").is_err());
+        assert!(NamedQuery::parse("q", "-- only a comment").is_err());
+    }
+}
@@ -0,0 +1,554 @@
+//! Retention, compaction and vacuum of the parquet cache and warehouse.
+//!
+//! A [`RetentionPolicy`] keeps each dataset for a number of days (forever if
+//! unset). [`Warehouse::compact_cache`] then:
+//!
+//! 1. deletes expired cache partitions and `bars` rows (with their
+//!    superseded versions in `bars_audit`), and prunes
+//!    `ingest_log` under the [`INGEST_LOG_DATASET`] key and `quotes_audit`
+//!    under the [`QUOTES_AUDIT_DATASET`] key;
+//! 2. merges the daily bars partitions of every closed month into a single
+//!    `month=YYYY-MM` file when they fit the monthly target size, updating
+//!    `cache_manifest`;
+//! 3. checkpoints DuckDB so deleted rows are returned to the file.
+//!
+//! With `dry_run` nothing is written: the report lists what would expire and
+//! which months would merge, but counts no bytes for the merges because the
+//! merged size is only known once the file exists.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ::duckdb::{Connection, ToSql};
+use serde::Serialize;
+
+use crate::duckdb::AccessMode;
+use crate::{
+    bars_interval, collect_parquet_files, escape_sql_string, finalize_transaction, log_partition,
+    parse_partition, path_to_sql, remove_partition_file, upsert_manifest_entry, write_checksum,
+    CachePartition, Warehouse, WarehouseError,
+};
+
+/// Dataset key of a [`RetentionPolicy`] that controls `ingest_log` pruning.
+pub const INGEST_LOG_DATASET: &str = "ingest_log";
+
+/// Dataset key of a [`RetentionPolicy`] that controls `quotes_audit` pruning.
+pub const QUOTES_AUDIT_DATASET: &str = "quotes_audit";
+
+/// How long each dataset is kept and how small partitions are compacted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    keep_days: BTreeMap<String, u32>,
+    monthly_target_bytes: u64,
+}
+
+impl Default for RetentionPolicy {
+    /// Minute bars for two years, `ingest_log` and `quotes_audit` for 90
+    /// days, everything else forever; months up to 128 MiB are compacted.
+    fn default() -> Self {
+        Self {
+            keep_days: BTreeMap::from([
+                (String::from("bars_1m"), 730),
+                (String::from(INGEST_LOG_DATASET), 90),
+                (String::from(QUOTES_AUDIT_DATASET), 90),
+            ]),
+            monthly_target_bytes: 128 * 1024 * 1024,
+        }
+    }
+}
+
+impl RetentionPolicy {
+    /// Keep `dataset` for `days` days.
+    pub fn with_keep_days(mut self, dataset: impl Into<String>, days: u32) -> Self {
+        self.keep_days.insert(dataset.into(), days);
+        self
+    }
+
+    /// Never expire `dataset`.
+    pub fn with_keep_forever(mut self, dataset: &str) -> Self {
+        self.keep_days.remove(dataset);
+        self
+    }
+
+    /// Largest monthly file compaction may produce.
+    pub fn with_monthly_target_bytes(mut self, bytes: u64) -> Self {
+        self.monthly_target_bytes = bytes;
+        self
+    }
+
+    /// Days `dataset` is kept, or `None` if it is kept forever.
+    pub fn keep_days(&self, dataset: &str) -> Option<u32> {
+        self.keep_days.get(dataset).copied()
+    }
+}
+
+/// Report from [`Warehouse::compact_cache`].
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CacheCompactionReport {
+    /// Whether the run only reported what it would do.
+    pub dry_run: bool,
+    /// Cache partitions removed because their data expired.
+    pub expired_partitions: usize,
+    /// Warehouse `bars` rows removed because they expired.
+    pub expired_rows: usize,
+    /// `ingest_log` rows pruned.
+    pub pruned_ingest_log_rows: usize,
+    /// Superseded quotes pruned from `quotes_audit`.
+    pub pruned_quotes_audit_rows: usize,
+    /// Daily partitions merged into monthly files.
+    pub compacted_partitions: usize,
+    /// Monthly files written (or rewritten) by compaction.
+    pub monthly_partitions: usize,
+    /// Cache bytes freed by expiry and compaction; a dry run counts expiry
+    /// only.
+    pub bytes_reclaimed: u64,
+    /// Bytes the warehouse file shrank by after the checkpoint; `None` on a
+    /// dry run.
+    pub warehouse_bytes_reclaimed: Option<u64>,
+}
+
+impl Warehouse {
+    /// Apply `policy` to the parquet cache, `bars` and `ingest_log`.
+    pub fn compact_cache(
+        &self,
+        policy: &RetentionPolicy,
+        dry_run: bool,
+    ) -> Result<CacheCompactionReport, WarehouseError> {
+        let mut report = CacheCompactionReport {
+            dry_run,
+            ..CacheCompactionReport::default()
+        };
+        let warehouse_bytes_before = fs::metadata(self.db_path()).map(|meta| meta.len());
+        let connection = self.manager.acquire(AccessMode::ReadWrite)?;
+        let today = days_since_epoch();
+        let current_month = format_epoch_day(&connection, today, "%Y-%m")?;
+        let mut cutoffs = HashMap::new();
+        for (dataset, days) in &policy.keep_days {
+            let cutoff = format_epoch_day(&connection, today - i64::from(*days), "%Y-%m-%d")?;
+            cutoffs.insert(dataset.as_str(), cutoff);
+        }
+
+        let cache_root = self.cache_root();
+        let mut files = Vec::new();
+        if cache_root.exists() {
+            collect_parquet_files(cache_root.as_path(), &mut files)?;
+        }
+        files.sort();
+
+        // Expire whole partitions, then bucket the surviving bars partitions
+        // of closed months for compaction.
+        let mut months: BTreeMap<(String, String, String, String), Vec<CachePartition>> =
+            BTreeMap::new();
+        for path in files {
+            let Some(partition) = parse_partition(path.as_path()) else {
+                continue;
+            };
+            let last_date = if partition.monthly {
+                last_day_of_month(&connection, &partition.partition_date)?
+            } else {
+                partition.partition_date.clone()
+            };
+            if cutoffs
+                .get(partition.dataset.as_str())
+                .is_some_and(|cutoff| last_date < *cutoff)
+            {
+                report.expired_partitions += 1;
+                report.bytes_reclaimed += file_size(&partition.path);
+                if !dry_run {
+                    remove_partition(&connection, &cache_root, &partition)?;
+                }
+                continue;
+            }
+
+            if bars_interval(&partition.dataset).is_some()
+                && partition.month() < current_month.as_str()
+            {
+                months
+                    .entry((
+                        partition.source.clone(),
+                        partition.dataset.clone(),
+                        partition.symbol.clone(),
+                        partition.month().to_string(),
+                    ))
+                    .or_default()
+                    .push(partition);
+            }
+        }
+
+        for (dataset, cutoff) in &cutoffs {
+            let params: [&dyn ToSql; 1] = [cutoff];
+            let (count_sql, delete_sql) = if *dataset == INGEST_LOG_DATASET {
+                (
+                    String::from(
+                        "SELECT COUNT(*) FROM ingest_log WHERE timestamp < CAST(? AS DATE)",
+                    ),
+                    String::from("DELETE FROM ingest_log WHERE timestamp < CAST(? AS DATE)"),
+                )
+            } else if *dataset == QUOTES_AUDIT_DATASET {
+                (
+                    String::from("SELECT COUNT(*) FROM quotes_audit WHERE as_of < CAST(? AS DATE)"),
+                    String::from("DELETE FROM quotes_audit WHERE as_of < CAST(? AS DATE)"),
+                )
+            } else if let Some(interval) = bars_interval(dataset) {
+                (
+                    format!(
+                        "SELECT COUNT(*) FROM bars \
+                         WHERE interval = '{interval}' AND ts < CAST(? AS DATE)"
+                    ),
+                    format!(
+                        "DELETE FROM bars WHERE interval = '{interval}' AND ts < CAST(? AS DATE)"
+                    ),
+                )
+            } else {
+                continue;
+            };
+            let rows = if dry_run {
+                let count: i64 =
+                    connection
+                        .query_row(count_sql.as_str(), params.as_slice(), |row| row.get(0))?;
+                usize::try_from(count).unwrap_or_default()
+            } else {
+                connection.execute(delete_sql.as_str(), params.as_slice())?
+            };
+            if let (false, Some(interval)) = (dry_run, bars_interval(dataset)) {
+                // Superseded versions expire with the bars they replaced.
+                let params: [&dyn ToSql; 2] = [&interval, cutoff];
+                connection.execute(
+                    "DELETE FROM bars_audit WHERE interval = ? AND ts < CAST(? AS DATE)",
+                    params.as_slice(),
+                )?;
+            }
+            if *dataset == INGEST_LOG_DATASET {
+                report.pruned_ingest_log_rows += rows;
+            } else if *dataset == QUOTES_AUDIT_DATASET {
+                report.pruned_quotes_audit_rows += rows;
+            } else {
+                report.expired_rows += rows;
+            }
+        }
+
+        for ((source, dataset, symbol, month), partitions) in months {
+            let daily = partitions
+                .iter()
+                .filter(|partition| !partition.monthly)
+                .count();
+            let input_bytes = partitions
+                .iter()
+                .map(|partition| file_size(&partition.path))
+                .sum::<u64>();
+            if daily == 0 || partitions.len() < 2 || input_bytes > policy.monthly_target_bytes {
+                continue;
+            }
+
+            let monthly = CachePartition::monthly(&cache_root, &source, &dataset, &symbol, &month)?;
+            let merged_bytes = if dry_run {
+                input_bytes
+            } else {
+                merge_partitions(&connection, &cache_root, &partitions, &monthly)?
+            };
+            report.compacted_partitions += daily;
+            report.monthly_partitions += 1;
+            report.bytes_reclaimed += input_bytes.saturating_sub(merged_bytes);
+        }
+
+        if !dry_run {
+            connection.execute_batch("CHECKPOINT")?;
+            report.warehouse_bytes_reclaimed = warehouse_bytes_before.ok().map(|before| {
+                let after = fs::metadata(self.db_path()).map_or(before, |meta| meta.len());
+                before.saturating_sub(after)
+            });
+        }
+
+        Ok(report)
+    }
+}
+
+/// Days since 1970-01-01 in UTC.
+///
+/// Taken from the system clock because `current_date` needs DuckDB's ICU
+/// extension, which may not be installed.
+fn days_since_epoch() -> i64 {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    i64::try_from(seconds / 86_400).unwrap_or_default()
+}
+
+/// Format the date `day` days after 1970-01-01 with `strftime` `format`.
+fn format_epoch_day(
+    connection: &Connection,
+    day: i64,
+    format: &str,
+) -> Result<String, WarehouseError> {
+    let params: [&dyn ToSql; 2] = [&day, &format];
+    Ok(connection.query_row(
+        "SELECT strftime(DATE '1970-01-01' + CAST(? AS INTEGER), ?)",
+        params.as_slice(),
+        |row| row.get(0),
+    )?)
+}
+
+/// Last `YYYY-MM-DD` date of the month starting on `first_day`.
+fn last_day_of_month(connection: &Connection, first_day: &str) -> Result<String, WarehouseError> {
+    let params: [&dyn ToSql; 1] = [&first_day];
+    Ok(connection.query_row(
+        "SELECT strftime(last_day(CAST(? AS DATE)), '%Y-%m-%d')",
+        params.as_slice(),
+        |row| row.get(0),
+    )?)
+}
+
+/// Merge `partitions` of one month into `monthly` and drop the inputs,
+/// returning the size of the merged file.
+fn merge_partitions(
+    connection: &Connection,
+    cache_root: &Path,
+    partitions: &[CachePartition],
+    monthly: &CachePartition,
+) -> Result<u64, WarehouseError> {
+    let directory = monthly
+        .path
+        .parent()
+        .expect("partition path has a month directory");
+    fs::create_dir_all(directory)?;
+
+    // Paths come from the cache scan and are escaped.
+    let inputs = partitions
+        .iter()
+        .map(|partition| {
+            format!(
+                "'{}'",
+                escape_sql_string(path_to_sql(partition.path.as_path()).as_str())
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    let staging = monthly.path.with_extension("parquet.tmp");
+    let copy_sql = format!(
+        "COPY (SELECT ts, open, high, low, close, volume \
+               FROM read_parquet([{inputs}], hive_partitioning = false, union_by_name = true) \
+               ORDER BY ts) \
+         TO '{path}' (FORMAT PARQUET)",
+        path = escape_sql_string(path_to_sql(staging.as_path()).as_str()),
+    );
+    connection.execute(copy_sql.as_str(), [])?;
+    let merged_bytes = file_size(&staging);
+    fs::rename(&staging, &monthly.path)?;
+    write_checksum(&monthly.path)?;
+    connection.execute_batch("BEGIN TRANSACTION")?;
+    let result = (|| -> Result<(), WarehouseError> {
+        for partition in partitions.iter().filter(|partition| !partition.monthly) {
+            remove_partition(connection, cache_root, partition)?;
+        }
+        upsert_manifest_entry(connection, monthly)?;
+        log_partition(connection, monthly, "cache-compact", "compacted", None)
+    })();
+    finalize_transaction(connection, result)?;
+    Ok(merged_bytes)
+}
+
+/// Delete a partition file with its checksum sidecar, its manifest row and
+/// any directories it leaves empty below the cache root.
+fn remove_partition(
+    connection: &Connection,
+    cache_root: &Path,
+    partition: &CachePartition,
+) -> Result<(), WarehouseError> {
+    remove_partition_file(&partition.path)?;
+    let path_str = path_to_sql(partition.path.as_path());
+    connection.execute("DELETE FROM cache_manifest WHERE path = ?", [&path_str])?;
+
+    let mut directory = partition.path.parent();
+    while let Some(current) = directory {
+        if current == cache_root || fs::remove_dir(current).is_err() {
+            break;
+        }
+        directory = current.parent();
+    }
+    Ok(())
+}
+
+fn file_size(path: &Path) -> u64 {
+    fs::metadata(path).map_or(0, |meta| meta.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::{BarRecord, QueryGuardrails, QuoteRecord, WarehouseConfig, PARTITION_FILE_NAME};
+
+    fn bar(ts: &str) -> BarRecord {
+        BarRecord {
+            symbol: String::from("AAPL"),
+            ts: ts.to_string(),
+            open: 100.0,
+            high: 110.0,
+            low: 90.0,
+            close: 105.0,
+            volume: Some(1_000),
+        }
+    }
+
+    // Read through the write connection: a read-only one opened earlier in
+    // the test would still see its original snapshot.
+    fn count(warehouse: &Warehouse, sql: &str) -> Value {
+        warehouse
+            .execute_query(sql, QueryGuardrails::default(), true)
+            .expect("query")
+            .rows[0][0]
+            .clone()
+    }
+
+    #[test]
+    fn compaction_expires_old_data_and_merges_closed_months() {
+        let temp = tempdir().expect("tempdir");
+        let ferrotick_home = temp.path().join("ferrotick-home");
+        let warehouse = Warehouse::open(WarehouseConfig {
+            ferrotick_home: ferrotick_home.clone(),
+            db_path: ferrotick_home.join("cache").join("warehouse.duckdb"),
+            max_pool_size: 2,
+        })
+        .expect("warehouse open");
+
+        warehouse
+            .ingest_bars(
+                "yahoo",
+                "bars_1d",
+                "req-daily",
+                &[
+                    bar("2021-03-01T00:00:00Z"),
+                    bar("2021-03-02T00:00:00Z"),
+                    bar("2021-03-03T00:00:00Z"),
+                ],
+                10,
+            )
+            .expect("daily ingest");
+        warehouse
+            .ingest_bars(
+                "yahoo",
+                "bars_1m",
+                "req-minute",
+                &[bar("2020-01-02T14:30:00Z")],
+                10,
+            )
+            .expect("minute ingest");
+        for (request_id, price) in [("req-q1", 190.0), ("req-q2", 191.0)] {
+            let quote = QuoteRecord {
+                symbol: String::from("AAPL"),
+                price,
+                bid: None,
+                ask: None,
+                volume: None,
+                currency: String::from("USD"),
+                as_of: String::from("2020-01-02T20:00:00Z"),
+            };
+            warehouse
+                .ingest_quotes("yahoo", request_id, &[quote], 10)
+                .expect("quote ingest");
+        }
+        warehouse
+            .execute_query(
+                "UPDATE ingest_log SET timestamp = TIMESTAMP '2020-01-02 00:00:00' \
+                 WHERE request_id = 'req-minute'",
+                QueryGuardrails::default(),
+                true,
+            )
+            .expect("age ingest log");
+
+        let symbol_dir = warehouse
+            .cache_root()
+            .join("source=yahoo")
+            .join("dataset=bars_1d")
+            .join("symbol=AAPL");
+        let monthly = symbol_dir.join("month=2021-03").join(PARTITION_FILE_NAME);
+        let policy = RetentionPolicy::default();
+
+        let preview = warehouse.compact_cache(&policy, true).expect("dry run");
+        assert!(preview.dry_run);
+        assert_eq!(preview.expired_partitions, 1);
+        assert_eq!(preview.expired_rows, 1);
+        assert_eq!(preview.pruned_ingest_log_rows, 1);
+        assert_eq!(preview.pruned_quotes_audit_rows, 1);
+        assert_eq!(preview.compacted_partitions, 3);
+        assert_eq!(preview.monthly_partitions, 1);
+        assert!(preview.bytes_reclaimed > 0);
+        assert_eq!(preview.warehouse_bytes_reclaimed, None);
+        assert!(!monthly.parent().expect("month dir").exists());
+        assert!(symbol_dir.join("date=2021-03-01").exists());
+        assert_eq!(
+            count(&warehouse, "SELECT COUNT(*) FROM bars_1m"),
+            Value::from(1)
+        );
+
+        let report = warehouse.compact_cache(&policy, false).expect("compact");
+        assert_eq!(report.expired_partitions, preview.expired_partitions);
+        assert_eq!(report.compacted_partitions, 3);
+        assert_eq!(report.pruned_quotes_audit_rows, 1);
+        assert!(report.bytes_reclaimed >= preview.bytes_reclaimed);
+        assert!(report.warehouse_bytes_reclaimed.is_some());
+        assert!(monthly.exists());
+        assert!(!symbol_dir.join("date=2021-03-01").exists());
+        assert!(!warehouse
+            .cache_root()
+            .join("source=yahoo")
+            .join("dataset=bars_1m")
+            .exists());
+        assert_eq!(
+            count(&warehouse, "SELECT COUNT(*) FROM bars_1m"),
+            Value::from(0)
+        );
+        assert_eq!(
+            count(
+                &warehouse,
+                "SELECT COUNT(*) FROM ingest_log WHERE request_id = 'req-minute'"
+            ),
+            Value::from(0)
+        );
+        assert_eq!(
+            count(&warehouse, "SELECT COUNT(*) FROM quotes_audit"),
+            Value::from(0)
+        );
+        assert_eq!(
+            count(&warehouse, "SELECT COUNT(*) FROM cache_manifest"),
+            Value::from(1)
+        );
+        assert_eq!(
+            count(&warehouse, "SELECT row_count FROM cache_manifest"),
+            Value::from(3)
+        );
+
+        // Later writes to a compacted month land in the monthly file, even
+        // when one batch touches several of its days.
+        warehouse
+            .ingest_bars(
+                "yahoo",
+                "bars_1d",
+                "req-late",
+                &[bar("2021-03-04T00:00:00Z"), bar("2021-03-05T00:00:00Z")],
+                10,
+            )
+            .expect("late ingest");
+        assert!(!symbol_dir.join("date=2021-03-04").exists());
+        assert_eq!(
+            count(&warehouse, "SELECT row_count FROM cache_manifest"),
+            Value::from(5)
+        );
+        let staged_copies = fs::read_dir(monthly.parent().expect("month dir"))
+            .expect("month dir")
+            .filter(|entry| {
+                entry
+                    .as_ref()
+                    .is_ok_and(|entry| entry.path().extension().is_some_and(|ext| ext == "tmp"))
+            })
+            .count();
+        assert_eq!(staged_copies, 0, "staged copies must not be left behind");
+        assert!(monthly.with_extension("parquet.sha256").is_file());
+        let rerun = warehouse.compact_cache(&policy, false).expect("recompact");
+        assert_eq!(rerun.compacted_partitions, 0);
+    }
+}
@@ -0,0 +1,1049 @@
+//! Sandboxed execution of untrusted SQL, such as queries sent by agents.
+//!
+//! Read-only mode only checks that a query starts with `SELECT`, which still
+//! lets it call `read_csv('/etc/passwd')` or pull in extensions.
+//! [`Warehouse::execute_sandboxed`] instead runs each query on its own
+//! `DuckDB` instance opened with external access, extension loading and
+//! configuration changes disabled, and with memory and thread limits. Before
+//! running, the query is checked against a table and a function allowlist.
+//!
+//! The allowlists are checked lexically: they cover relations named after
+//! `FROM` and `JOIN` and every `name(...)` call. The instance settings are
+//! the security boundary; the allowlists narrow which warehouse objects a
+//! query can touch. Every rejection names the [`SandboxRule`] that fired.
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::path::Path;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use ::duckdb::{Config, Connection};
+use ferrotick_telemetry::metrics;
+use serde::Serialize;
+
+use crate::{
+    execute_select_query, normalize_sql, QueryGuardrails, QueryParams, QueryResult, Warehouse,
+    WarehouseError,
+};
+
+/// Table functions allowed by default; they read nothing but their arguments.
+const SAFE_TABLE_FUNCTIONS: [&str; 3] = ["generate_series", "range", "unnest"];
+
+/// Function types that produce relations and can reach outside the query.
+const RELATION_FUNCTION_TYPES: [&str; 3] = ["table", "table_macro", "pragma"];
+
+/// Keywords that end a relation, so they are never read as its alias.
+const RELATION_STOP_WORDS: [&str; 31] = [
+    "anti",
+    "asof",
+    "cross",
+    "except",
+    "from",
+    "full",
+    "group",
+    "having",
+    "inner",
+    "intersect",
+    "join",
+    "lateral",
+    "left",
+    "limit",
+    "natural",
+    "offset",
+    "on",
+    "order",
+    "outer",
+    "pivot",
+    "positional",
+    "qualify",
+    "right",
+    "select",
+    "semi",
+    "tablesample",
+    "union",
+    "unpivot",
+    "using",
+    "where",
+    "window",
+];
+
+/// Keywords that end a `FROM` list at the same nesting level.
+const FROM_LIST_END_WORDS: [&str; 12] = [
+    "except",
+    "group",
+    "having",
+    "intersect",
+    "limit",
+    "offset",
+    "order",
+    "qualify",
+    "select",
+    "union",
+    "where",
+    "window",
+];
+
+/// The sandbox rule that rejected a query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SandboxRule {
+    /// Only a single `SELECT`, `WITH`, `FROM` or `EXPLAIN` statement may run.
+    Statement,
+    /// The query reads a relation outside the table allowlist.
+    Table,
+    /// The query calls a function outside the function allowlist.
+    Function,
+    /// The query reads or writes files, URLs or other databases.
+    ExternalAccess,
+    /// The query needs an extension that is not already loaded.
+    Extension,
+    /// The query needed more memory than the sandbox allows.
+    MemoryLimit,
+}
+
+impl SandboxRule {
+    /// Stable error code for the rule.
+    pub const fn code(self) -> &'static str {
+        match self {
+            Self::Statement => "sandbox.statement",
+            Self::Table => "sandbox.table_not_allowed",
+            Self::Function => "sandbox.function_not_allowed",
+            Self::ExternalAccess => "sandbox.external_access",
+            Self::Extension => "sandbox.extension",
+            Self::MemoryLimit => "sandbox.memory_limit",
+        }
+    }
+}
+
+impl fmt::Display for SandboxRule {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.code())
+    }
+}
+
+/// Limits and allowlists for [`Warehouse::execute_sandboxed`].
+///
+/// By default every table and view of the warehouse may be read, every
+/// scalar, aggregate and window function called, and the only table
+/// functions are `range`, `generate_series`, `unnest` and the warehouse's
+/// analytics macros. A query gets 256 MB of memory and one thread.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SqlSandbox {
+    tables: Option<BTreeSet<String>>,
+    functions: Option<BTreeSet<String>>,
+    memory_limit_mb: u64,
+    threads: u64,
+}
+
+impl Default for SqlSandbox {
+    fn default() -> Self {
+        Self {
+            tables: None,
+            functions: None,
+            memory_limit_mb: 256,
+            threads: 1,
+        }
+    }
+}
+
+impl SqlSandbox {
+    /// Sandbox with the default allowlists and limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only allow these tables and views.
+    pub fn with_allowed_tables<I, S>(mut self, tables: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.tables = Some(lowercase_set(tables));
+        self
+    }
+
+    /// Only allow calls to these functions, of any kind.
+    pub fn with_allowed_functions<I, S>(mut self, functions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.functions = Some(lowercase_set(functions));
+        self
+    }
+
+    /// Cap the memory a query may use.
+    pub fn with_memory_limit_mb(mut self, memory_limit_mb: u64) -> Self {
+        self.memory_limit_mb = memory_limit_mb;
+        self
+    }
+
+    /// Cap the threads a query may use.
+    pub fn with_threads(mut self, threads: u64) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    fn validate(&self) -> Result<(), WarehouseError> {
+        if self.memory_limit_mb == 0 {
+            return Err(WarehouseError::QueryRejected(String::from(
+                "sandbox memory limit must be greater than zero",
+            )));
+        }
+        if self.threads == 0 {
+            return Err(WarehouseError::QueryRejected(String::from(
+                "sandbox thread limit must be greater than zero",
+            )));
+        }
+        Ok(())
+    }
+
+    /// Open a locked-down, read-only instance of the database at `db_path`.
+    fn open(&self, db_path: &Path) -> Result<Connection, WarehouseError> {
+        let config = Config::default()
+            .access_mode(::duckdb::AccessMode::ReadOnly)?
+            .enable_external_access(false)?
+            .enable_autoload_extension(false)?
+            .max_memory(&format!("{}MB", self.memory_limit_mb))?
+            .threads(i64::try_from(self.threads).unwrap_or(i64::MAX))?
+            .with("allow_community_extensions", "false")?
+            // Spilling to disk would let a query work around the memory limit.
+            .with("max_temp_directory_size", "0B")?
+            .with("lock_configuration", "true")?;
+        Ok(Connection::open_with_flags(db_path, config)?)
+    }
+
+    fn check_tables(
+        &self,
+        connection: &Connection,
+        references: &References,
+    ) -> Result<(), WarehouseError> {
+        let database: String =
+            connection.query_row("SELECT current_database()", [], |row| row.get(0))?;
+        let relations = warehouse_relations(connection)?;
+        let allowed = self.tables.as_ref().unwrap_or(&relations);
+
+        for parts in &references.tables {
+            let permitted = match parts.as_slice() {
+                // CTE names are matched without scopes, so one may not
+                // shadow a table it could let through from another scope.
+                [name] => {
+                    allowed.contains(name)
+                        || (references.ctes.contains(name) && !relations.contains(name))
+                }
+                [schema, name] => schema == "main" && allowed.contains(name),
+                [catalog, schema, name] => {
+                    *catalog == database && schema == "main" && allowed.contains(name)
+                }
+                _ => false,
+            };
+            if !permitted {
+                return Err(WarehouseError::Sandbox {
+                    rule: SandboxRule::Table,
+                    message: format!("relation '{}' is not allowed", parts.join(".")),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn check_functions(
+        &self,
+        connection: &Connection,
+        references: &References,
+    ) -> Result<(), WarehouseError> {
+        let mut types = connection.prepare(
+            "SELECT DISTINCT function_type FROM duckdb_functions() \
+             WHERE lower(function_name) = ?",
+        )?;
+        let macros = warehouse_table_macros(connection)?;
+
+        for name in &references.functions {
+            let function_types = types
+                .query_map([name], |row| row.get::<_, String>(0))?
+                .collect::<Result<Vec<_>, _>>()?;
+            // Not a catalog function: a keyword such as `IN (` or a typo
+            // DuckDB reports itself.
+            if function_types.is_empty() {
+                continue;
+            }
+            let allowed = match &self.functions {
+                Some(functions) => functions.contains(name),
+                None => {
+                    SAFE_TABLE_FUNCTIONS.contains(&name.as_str())
+                        || macros.contains(name)
+                        || !function_types
+                            .iter()
+                            .any(|kind| RELATION_FUNCTION_TYPES.contains(&kind.as_str()))
+                }
+            };
+            if !allowed {
+                return Err(WarehouseError::Sandbox {
+                    rule: SandboxRule::Function,
+                    message: format!("function '{name}' is not allowed"),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Warehouse {
+    /// Execute one untrusted read-only query inside `sandbox`.
+    ///
+    /// The query runs on a dedicated instance without file, network or
+    /// extension access, within `sandbox`'s memory and thread limits, and is
+    /// interrupted once `guardrails.query_timeout_ms` elapses. Rejections are
+    /// [`WarehouseError::Sandbox`] with the rule that fired, or
+    /// [`WarehouseError::QueryTimeout`].
+    pub fn execute_sandboxed(
+        &self,
+        sql: &str,
+        params: &QueryParams,
+        guardrails: QueryGuardrails,
+        sandbox: &SqlSandbox,
+    ) -> Result<QueryResult, WarehouseError> {
+        guardrails.validate()?;
+        sandbox.validate()?;
+        let sql = normalize_sql(sql)?;
+        let tokens = tokenize(sql);
+        check_statement(&tokens)?;
+        let references = References::collect(&tokens)?;
+
+        let connection = sandbox.open(self.db_path())?;
+        let span = tracing::info_span!(
+            "warehouse_query",
+            read_only = true,
+            sandboxed = true,
+            rows = tracing::field::Empty
+        )
+        .entered();
+        let started = Instant::now();
+        let result = interrupt_after(&connection, guardrails.timeout(), || {
+            sandbox.check_tables(&connection, &references)?;
+            sandbox.check_functions(&connection, &references)?;
+            execute_select_query(&connection, sql, params, guardrails, started)
+        })
+        .map_err(|error| classify_error(error, guardrails.timeout()));
+        let outcome = match &result {
+            Ok(query) => {
+                span.record("rows", query.row_count);
+                "ok"
+            }
+            Err(_) => "error",
+        };
+        metrics::WAREHOUSE_QUERY_DURATION.observe(&[("outcome", outcome)], started.elapsed());
+        result
+    }
+}
+
+/// Run `query`, interrupting `connection` if it is still running after `timeout`.
+fn interrupt_after<T>(connection: &Connection, timeout: Duration, query: impl FnOnce() -> T) -> T {
+    let handle = connection.interrupt_handle();
+    let (done, finished) = mpsc::channel::<()>();
+    let watchdog = thread::spawn(move || {
+        if finished.recv_timeout(timeout) == Err(RecvTimeoutError::Timeout) {
+            handle.interrupt();
+        }
+    });
+    let result = query();
+    drop(done);
+    let _ = watchdog.join();
+    result
+}
+
+/// Map the `DuckDB` errors raised by the instance settings to their rules.
+fn classify_error(error: WarehouseError, timeout: Duration) -> WarehouseError {
+    let WarehouseError::DuckDb(inner) = &error else {
+        return error;
+    };
+    let message = inner.to_string();
+    let rule = if message.contains("Permission Error") {
+        SandboxRule::ExternalAccess
+    } else if message.contains("Out of Memory Error") {
+        SandboxRule::MemoryLimit
+    } else if message.contains("INTERRUPT Error") {
+        return WarehouseError::QueryTimeout {
+            timeout_ms: timeout.as_millis().min(u128::from(u64::MAX)) as u64,
+        };
+    } else if is_extension_error(&message) {
+        SandboxRule::Extension
+    } else {
+        return error;
+    };
+    WarehouseError::Sandbox { rule, message }
+}
+
+/// Whether DuckDB refused `message`'s query because it needs an extension
+/// that is not loaded. Matched on DuckDB's own wording, so a column or value
+/// that merely mentions "extension" is not mistaken for one.
+fn is_extension_error(message: &str) -> bool {
+    const PREFIXES: [&str; 4] = [
+        "Extension Autoloading Error",
+        "Missing Extension Error",
+        "IO Error: Extension",
+        "Invalid Input Error: Extension",
+    ];
+    (message.starts_with("Catalog Error:")
+        && message.contains("but it exists in the ")
+        && message.contains(" extension"))
+        || PREFIXES.iter().any(|prefix| message.starts_with(prefix))
+}
+
+/// Tables and views in the warehouse's `main` schema.
+fn warehouse_relations(connection: &Connection) -> Result<BTreeSet<String>, WarehouseError> {
+    let mut statement = connection.prepare(
+        "SELECT lower(table_name) FROM duckdb_tables() \
+         WHERE NOT internal AND schema_name = 'main' AND database_name = current_database() \
+         UNION \
+         SELECT lower(view_name) FROM duckdb_views() \
+         WHERE NOT internal AND schema_name = 'main' AND database_name = current_database()",
+    )?;
+    let names = statement
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<BTreeSet<String>, _>>()?;
+    Ok(names)
+}
+
+/// Table macros the warehouse defines, such as `rolling_beta_v1`.
+fn warehouse_table_macros(connection: &Connection) -> Result<BTreeSet<String>, WarehouseError> {
+    let mut statement = connection.prepare(
+        "SELECT lower(function_name) FROM duckdb_functions() \
+         WHERE function_type = 'table_macro' AND NOT internal \
+         AND schema_name = 'main' AND database_name = current_database()",
+    )?;
+    let names = statement
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<BTreeSet<String>, _>>()?;
+    Ok(names)
+}
+
+fn lowercase_set<I, S>(names: I) -> BTreeSet<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    names
+        .into_iter()
+        .map(|name| name.as_ref().trim().to_ascii_lowercase())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// A lexical SQL token; words are lowercased.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Word { text: String, quoted: bool },
+    Literal,
+    Param,
+    Punct(char),
+}
+
+impl Token {
+    /// An unquoted word equal to `keyword`.
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self, Self::Word { text, quoted: false } if text == keyword)
+    }
+
+    fn keyword(&self) -> Option<&str> {
+        match self {
+            Self::Word {
+                text,
+                quoted: false,
+            } => Some(text.as_str()),
+            _ => None,
+        }
+    }
+
+    fn word(&self) -> Option<&str> {
+        match self {
+            Self::Word { text, .. } => Some(text.as_str()),
+            _ => None,
+        }
+    }
+}
+
+/// Split `sql` into tokens, dropping comments and literal contents.
+fn tokenize(sql: &str) -> Vec<Token> {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut tokens = Vec::new();
+    let mut index = 0;
+    while index < chars.len() {
+        let ch = chars[index];
+        let next = chars.get(index + 1).copied();
+        if ch.is_whitespace() {
+            index += 1;
+        } else if ch == '-' && next == Some('-') {
+            while index < chars.len() && chars[index] != '\n' {
+                index += 1;
+            }
+        } else if ch == '/' && next == Some('*') {
+            index += 2;
+            while index < chars.len() && !(chars[index - 1] == '*' && chars[index] == '/') {
+                index += 1;
+            }
+            index += 1;
+        } else if ch == '\'' || ch == '"' {
+            let (text, end) = read_quoted(&chars, index, ch);
+            tokens.push(if ch == '"' {
+                Token::Word {
+                    text: text.to_ascii_lowercase(),
+                    quoted: true,
+                }
+            } else {
+                Token::Literal
+            });
+            index = end;
+        } else if ch == '$' {
+            let start = index + 1;
+            let mut end = start;
+            while end < chars.len() && is_word_char(chars[end]) {
+                end += 1;
+            }
+            if chars.get(end) == Some(&'$') {
+                // Dollar-quoted string: `$tag$ ... $tag$`.
+                let tag: Vec<char> = chars[index..=end].to_vec();
+                let mut close = end + 1;
+                while close < chars.len() && !chars[close..].starts_with(&tag) {
+                    close += 1;
+                }
+                tokens.push(Token::Literal);
+                index = (close + tag.len()).min(chars.len());
+            } else {
+                tokens.push(Token::Param);
+                index = end.max(start);
+            }
+        } else if ch.is_ascii_digit() {
+            while index < chars.len() && (is_word_char(chars[index]) || chars[index] == '.') {
+                index += 1;
+            }
+            tokens.push(Token::Literal);
+        } else if is_word_char(ch) {
+            let start = index;
+            while index < chars.len() && is_word_char(chars[index]) {
+                index += 1;
+            }
+            tokens.push(Token::Word {
+                text: chars[start..index]
+                    .iter()
+                    .collect::<String>()
+                    .to_ascii_lowercase(),
+                quoted: false,
+            });
+        } else {
+            tokens.push(Token::Punct(ch));
+            index += 1;
+        }
+    }
+    tokens
+}
+
+/// Read a `quote`-delimited token starting at `start`; doubled quotes escape.
+fn read_quoted(chars: &[char], start: usize, quote: char) -> (String, usize) {
+    let mut text = String::new();
+    let mut index = start + 1;
+    while index < chars.len() {
+        if chars[index] == quote {
+            if chars.get(index + 1) == Some(&quote) {
+                text.push(quote);
+                index += 2;
+                continue;
+            }
+            return (text, index + 1);
+        }
+        text.push(chars[index]);
+        index += 1;
+    }
+    (text, index)
+}
+
+fn is_word_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_'
+}
+
+/// Accept exactly one query statement.
+fn check_statement(tokens: &[Token]) -> Result<(), WarehouseError> {
+    if tokens.contains(&Token::Punct(';')) {
+        return Err(WarehouseError::Sandbox {
+            rule: SandboxRule::Statement,
+            message: String::from("multiple statements are not allowed"),
+        });
+    }
+
+    let mut words = tokens
+        .iter()
+        .skip_while(|token| **token == Token::Punct('('))
+        .map(Token::keyword);
+    let mut first = words.next().flatten();
+    if first == Some("explain") {
+        first = match words.next().flatten() {
+            Some("analyze") => words.next().flatten(),
+            other => other,
+        };
+    }
+    match first {
+        Some("select" | "with" | "from") => Ok(()),
+        other => Err(WarehouseError::Sandbox {
+            rule: SandboxRule::Statement,
+            message: format!(
+                "only SELECT, WITH, FROM and EXPLAIN queries are allowed, got '{}'",
+                other.unwrap_or_default().to_ascii_uppercase()
+            ),
+        }),
+    }
+}
+
+/// Relations, functions and common table expressions named by a query.
+#[derive(Debug, Default)]
+struct References {
+    /// Relations after `FROM` and `JOIN`, split on `.`.
+    tables: Vec<Vec<String>>,
+    /// Every name called like a function.
+    functions: BTreeSet<String>,
+    /// Names declared by `WITH`.
+    ctes: BTreeSet<String>,
+}
+
+impl References {
+    fn collect(tokens: &[Token]) -> Result<Self, WarehouseError> {
+        let mut references = Self::default();
+        let cte_declarations = references.collect_ctes(tokens);
+
+        // One entry per open parenthesis: whether it holds a query, and
+        // whether its `FROM` list is still open.
+        let mut levels = vec![(true, false)];
+        let mut expect_relation = false;
+        let mut index = 0;
+        while index < tokens.len() {
+            let token = &tokens[index];
+            let previous = index.checked_sub(1).map(|previous| &tokens[previous]);
+            let next = tokens.get(index + 1);
+
+            if let (Some(name), Some(Token::Punct('('))) = (token.word(), next) {
+                if !cte_declarations.contains(&index) {
+                    references.functions.insert(name.to_string());
+                }
+            }
+
+            match token {
+                Token::Punct('(') => {
+                    let holds_query = expect_relation
+                        || next.and_then(Token::keyword).is_some_and(is_query_start);
+                    levels.push((holds_query, false));
+                    index += 1;
+                    continue;
+                }
+                Token::Punct(')') if levels.len() > 1 => {
+                    levels.pop();
+                }
+                Token::Punct(',') if levels[levels.len() - 1].1 => {
+                    expect_relation = true;
+                    index += 1;
+                    continue;
+                }
+                _ => {}
+            }
+
+            let level = levels.len() - 1;
+            if expect_relation {
+                match token {
+                    Token::Literal => {
+                        return Err(WarehouseError::Sandbox {
+                            rule: SandboxRule::ExternalAccess,
+                            message: String::from(
+                                "reading files or URLs named by a string is not allowed",
+                            ),
+                        });
+                    }
+                    Token::Word { .. } if is_relation_prefix(token) => {
+                        index += 1;
+                        continue;
+                    }
+                    Token::Word { quoted, .. }
+                        if *quoted || token.keyword().is_some_and(|word| !is_query_start(word)) =>
+                    {
+                        let (parts, end) = qualified_name(tokens, index);
+                        if tokens.get(end) != Some(&Token::Punct('(')) {
+                            references.tables.push(parts);
+                            index = skip_alias(tokens, end);
+                            expect_relation = false;
+                            continue;
+                        }
+                    }
+                    _ => {}
+                }
+                expect_relation = false;
+            }
+
+            if let Some(keyword) = token.keyword() {
+                let (holds_query, _) = levels[level];
+                match keyword {
+                    "from"
+                        if holds_query
+                            && previous.is_none_or(|previous| !previous.is_keyword("distinct")) =>
+                    {
+                        levels[level].1 = true;
+                        expect_relation = true;
+                    }
+                    "join" if holds_query => expect_relation = true,
+                    "table" | "describe" | "summarize" | "show"
+                        if previous == Some(&Token::Punct('(')) =>
+                    {
+                        expect_relation = true;
+                    }
+                    word if FROM_LIST_END_WORDS.contains(&word) => levels[level].1 = false,
+                    _ => {}
+                }
+            }
+            index += 1;
+        }
+        Ok(references)
+    }
+
+    /// Record `WITH` names; returns the token indexes of their declarations.
+    fn collect_ctes(&mut self, tokens: &[Token]) -> BTreeSet<usize> {
+        let mut declarations = BTreeSet::new();
+        for (with, token) in tokens.iter().enumerate() {
+            if !token.is_keyword("with") {
+                continue;
+            }
+            let mut index = with + 1;
+            if tokens
+                .get(index)
+                .is_some_and(|token| token.is_keyword("recursive"))
+            {
+                index += 1;
+            }
+            while let Some(name) = tokens.get(index).and_then(Token::word) {
+                self.ctes.insert(name.to_string());
+                declarations.insert(index);
+                index += 1;
+                if tokens.get(index) == Some(&Token::Punct('(')) {
+                    index = matching_paren(tokens, index) + 1;
+                }
+                if !tokens
+                    .get(index)
+                    .is_some_and(|token| token.is_keyword("as"))
+                {
+                    break;
+                }
+                index += 1;
+                while tokens.get(index).is_some_and(|token| {
+                    token.is_keyword("not") || token.is_keyword("materialized")
+                }) {
+                    index += 1;
+                }
+                if tokens.get(index) != Some(&Token::Punct('(')) {
+                    break;
+                }
+                index = matching_paren(tokens, index) + 1;
+                if tokens.get(index) != Some(&Token::Punct(',')) {
+                    break;
+                }
+                index += 1;
+            }
+        }
+        declarations
+    }
+}
+
+/// Keywords that may precede a relation without being one.
+fn is_relation_prefix(token: &Token) -> bool {
+    token
+        .keyword()
+        .is_some_and(|keyword| matches!(keyword, "lateral" | "pivot" | "unpivot"))
+}
+
+fn is_query_start(keyword: &str) -> bool {
+    matches!(keyword, "select" | "with" | "from" | "values")
+}
+
+/// A dotted name starting at `start`, and the index just past it.
+fn qualified_name(tokens: &[Token], start: usize) -> (Vec<String>, usize) {
+    let mut parts = Vec::new();
+    let mut index = start;
+    while let Some(part) = tokens.get(index).and_then(Token::word) {
+        parts.push(part.to_string());
+        index += 1;
+        if tokens.get(index) != Some(&Token::Punct('.')) {
+            break;
+        }
+        index += 1;
+    }
+    (parts, index)
+}
+
+/// Skip an optional `[AS] alias [(columns)]` starting at `start`.
+fn skip_alias(tokens: &[Token], start: usize) -> usize {
+    let mut index = start;
+    let explicit = tokens
+        .get(index)
+        .is_some_and(|token| token.is_keyword("as"));
+    if explicit {
+        index += 1;
+    }
+    let is_alias = match tokens.get(index) {
+        Some(Token::Word { quoted: true, .. }) => true,
+        Some(token) => token
+            .keyword()
+            .is_some_and(|keyword| explicit || !RELATION_STOP_WORDS.contains(&keyword)),
+        None => false,
+    };
+    if is_alias {
+        index += 1;
+        if tokens.get(index) == Some(&Token::Punct('(')) {
+            index = matching_paren(tokens, index) + 1;
+        }
+    }
+    index
+}
+
+/// Index of the `)` closing the `(` at `open`, or the last token.
+fn matching_paren(tokens: &[Token], open: usize) -> usize {
+    let mut depth = 0usize;
+    for (index, token) in tokens.iter().enumerate().skip(open) {
+        match token {
+            Token::Punct('(') => depth += 1,
+            Token::Punct(')') => {
+                depth -= 1;
+                if depth == 0 {
+                    return index;
+                }
+            }
+            _ => {}
+        }
+    }
+    tokens.len().saturating_sub(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BarRecord, WarehouseConfig};
+    use tempfile::tempdir;
+
+    fn references(sql: &str) -> References {
+        References::collect(&tokenize(sql)).expect("references")
+    }
+
+    fn tables(sql: &str) -> Vec<String> {
+        references(sql)
+            .tables
+            .into_iter()
+            .map(|parts| parts.join("."))
+            .collect()
+    }
+
+    fn rule(result: Result<QueryResult, WarehouseError>) -> SandboxRule {
+        match result {
+            Err(WarehouseError::Sandbox { rule, .. }) => rule,
+            other => panic!("expected a sandbox rejection, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn relations_and_calls_are_found_in_every_position() {
+        assert_eq!(
+            tables(
+                "WITH recent AS (SELECT * FROM bars WHERE ts > $since) \
+                 SELECT r.symbol, extract(year FROM r.ts), q.price \
+                 FROM recent r JOIN main.quotes_latest AS q USING (symbol), \"News\" n \
+                 WHERE r.close IS DISTINCT FROM q.price \
+                 AND EXISTS (SELECT 1 FROM universe_members m WHERE m.symbol = r.symbol)"
+            ),
+            [
+                "bars",
+                "recent",
+                "main.quotes_latest",
+                "news",
+                "universe_members"
+            ]
+        );
+        assert_eq!(
+            tables("SELECT * FROM (a JOIN b ON a.x = b.x), range(3) t(i), c"),
+            ["a", "b", "c"]
+        );
+
+        let found =
+            references("SELECT upper(s), x IN (1, 2) FROM read_csv('x.csv') -- secret(\n /* y( */");
+        assert!(found.functions.contains("upper"));
+        assert!(found.functions.contains("read_csv"));
+        assert!(!found.functions.contains("secret"));
+        assert!(!found.functions.contains("y"));
+        assert!(found.ctes.is_empty());
+
+        let rejected = References::collect(&tokenize("SELECT * FROM '/etc/passwd'"));
+        assert!(matches!(
+            rejected,
+            Err(WarehouseError::Sandbox {
+                rule: SandboxRule::ExternalAccess,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn statements_other_than_one_query_are_rejected() {
+        for sql in ["SELECT 1", "(SELECT 1)", "FROM bars", "EXPLAIN SELECT 1"] {
+            assert!(check_statement(&tokenize(sql)).is_ok(), "{sql}");
+        }
+        for sql in [
+            "INSTALL httpfs",
+            "LOAD httpfs",
+            "ATTACH 'x.db'",
+            "COPY bars TO 'x.csv'",
+            "SET enable_external_access = true",
+            "PRAGMA version",
+            "SELECT 1; SELECT 2",
+            "EXPLAIN COPY bars TO 'x.csv'",
+        ] {
+            assert!(check_statement(&tokenize(sql)).is_err(), "{sql}");
+        }
+    }
+
+    #[test]
+    fn sandboxed_queries_stay_inside_the_warehouse() {
+        let temp = tempdir().expect("tempdir");
+        let ferrotick_home = temp.path().join("ferrotick-home");
+        let db_path = ferrotick_home.join("cache").join("warehouse.duckdb");
+        let warehouse = Warehouse::open(WarehouseConfig {
+            ferrotick_home,
+            db_path,
+            max_pool_size: 2,
+        })
+        .expect("warehouse open");
+        let bars = ["2024-05-01T00:00:00Z", "2024-05-02T00:00:00Z"].map(|ts| BarRecord {
+            symbol: String::from("AAPL"),
+            ts: ts.to_string(),
+            open: 100.0,
+            high: 110.0,
+            low: 90.0,
+            close: 105.0,
+            volume: Some(1_000),
+        });
+        warehouse
+            .ingest_bars("yahoo", "bars_1d", "req-1", &bars, 10)
+            .expect("ingest");
+        let secret = temp.path().join("secret.csv");
+        std::fs::write(&secret, "a\n1\n").expect("write secret");
+        let params = QueryParams::new();
+        let guardrails = QueryGuardrails::default();
+        let run = |sql: &str, sandbox: &SqlSandbox| {
+            warehouse.execute_sandboxed(sql, &params, guardrails, sandbox)
+        };
+
+        // The sandbox opens its own read-only instance, which must still see
+        // what the pool has written.
+        let result = run(
+            "SELECT count(*) AS n FROM bars b JOIN range(3) r ON true",
+            &SqlSandbox::new(),
+        )
+        .expect("allowed query");
+        assert_eq!(result.rows, vec![vec![serde_json::json!(6)]]);
+
+        let read_csv = format!("SELECT * FROM read_csv('{}')", secret.display());
+        assert_eq!(
+            rule(run(&read_csv, &SqlSandbox::new())),
+            SandboxRule::Function
+        );
+        // Allowlisting the function still cannot reach the file.
+        let sandbox = SqlSandbox::new().with_allowed_functions(["read_csv"]);
+        assert_eq!(rule(run(&read_csv, &sandbox)), SandboxRule::ExternalAccess);
+
+        assert_eq!(
+            rule(run("SELECT * FROM duckdb_settings()", &SqlSandbox::new())),
+            SandboxRule::Function
+        );
+        assert_eq!(
+            rule(run(
+                "SELECT * FROM information_schema.tables",
+                &SqlSandbox::new()
+            )),
+            SandboxRule::Table
+        );
+        assert_eq!(
+            rule(run("INSTALL httpfs", &SqlSandbox::new())),
+            SandboxRule::Statement
+        );
+
+        // Extensions are neither autoloaded nor installable.
+        assert_eq!(
+            rule(run("SELECT json_extract('{}', '$.a')", &SqlSandbox::new())),
+            SandboxRule::Extension
+        );
+        // Other errors that mention an extension are passed through.
+        assert!(matches!(
+            run("SELECT extension FROM bars", &SqlSandbox::new()),
+            Err(WarehouseError::DuckDb(_))
+        ));
+
+        let quotes_only = SqlSandbox::new().with_allowed_tables(["quotes_latest"]);
+        assert!(run("SELECT * FROM quotes_latest", &quotes_only).is_ok());
+        assert_eq!(
+            rule(run("SELECT * FROM bars_1d", &quotes_only)),
+            SandboxRule::Table
+        );
+        assert!(run(
+            "WITH recent AS (SELECT * FROM quotes_latest) SELECT * FROM recent",
+            &quotes_only
+        )
+        .is_ok());
+        assert_eq!(
+            rule(run(
+                "SELECT * FROM bars, (WITH bars AS (SELECT 1) SELECT * FROM bars)",
+                &quotes_only
+            )),
+            SandboxRule::Table
+        );
+
+        let no_upper = SqlSandbox::new().with_allowed_functions(["count"]);
+        assert_eq!(
+            rule(run("SELECT upper('a')", &no_upper)),
+            SandboxRule::Function
+        );
+
+        let tiny = SqlSandbox::new().with_memory_limit_mb(16);
+        assert_eq!(
+            rule(run(
+                "SELECT list(i ORDER BY i DESC) FROM range(50000000) t(i)",
+                &tiny
+            )),
+            SandboxRule::MemoryLimit
+        );
+    }
+
+    #[test]
+    fn long_queries_are_interrupted_at_the_timeout() {
+        let temp = tempdir().expect("tempdir");
+        let ferrotick_home = temp.path().join("ferrotick-home");
+        let db_path = ferrotick_home.join("cache").join("warehouse.duckdb");
+        let warehouse = Warehouse::open(WarehouseConfig {
+            ferrotick_home,
+            db_path,
+            max_pool_size: 2,
+        })
+        .expect("warehouse open");
+        let guardrails = QueryGuardrails {
+            max_rows: 10,
+            query_timeout_ms: 200,
+        };
+
+        let started = Instant::now();
+        let result = warehouse.execute_sandboxed(
+            "SELECT count(*) FROM range(1000000000000) a",
+            &QueryParams::new(),
+            guardrails,
+            &SqlSandbox::new(),
+        );
+        assert!(
+            matches!(
+                result,
+                Err(WarehouseError::QueryTimeout { timeout_ms: 200 })
+            ),
+            "{result:?}"
+        );
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+}
@@ -0,0 +1,704 @@
+//! Consistent snapshots of the warehouse and its parquet cache.
+//!
+//! [`Warehouse::snapshot`] copies the database inside one DuckDB
+//! transaction (`COPY FROM DATABASE`), so it is safe while other connections
+//! keep writing, then copies every partition listed in the snapshot's
+//! `cache_manifest` and checks it against the recorded checksum. The result
+//! is a directory, or a `.tar` / `.tar.gz` archive of one:
+//!
+//! ```text
+//! snapshot.json            # SnapshotManifest: schema version, files, SHA-256
+//! warehouse.duckdb
+//! cache/parquet/source=…/dataset=…/symbol=…/date=…/….parquet
+//! ```
+//!
+//! [`Warehouse::restore_snapshot`] verifies every file and the schema version
+//! against `schema_migrations` in a staging directory before moving the
+//! current database and cache aside to `pre-restore/` and swapping the
+//! snapshot in. Migrations newer than the snapshot are applied on open. A
+//! warehouse that is open in this or another process is never replaced.
+
+use std::collections::BTreeSet;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter};
+use std::path::{Component, Path, PathBuf};
+
+use ::duckdb::{Connection, ToSql};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+
+use crate::duckdb::{is_database_open, AccessMode};
+use crate::migrations;
+use crate::{
+    checksum_path, escape_sql_string, file_checksum, path_to_sql, Warehouse, WarehouseConfig,
+    WarehouseError,
+};
+
+/// Version of the snapshot layout written to [`SNAPSHOT_MANIFEST`].
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+/// Name of the manifest at the root of a snapshot.
+pub const SNAPSHOT_MANIFEST: &str = "snapshot.json";
+
+const SNAPSHOT_DATABASE: &str = "warehouse.duckdb";
+const SNAPSHOT_CACHE: &str = "cache/parquet";
+
+/// Contents of `snapshot.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    pub format_version: u32,
+    /// UTC time the database was copied, `YYYY-MM-DDTHH:MM:SSZ`.
+    pub created_at: String,
+    /// Latest migration applied to the snapshot database.
+    pub schema_version: String,
+    /// Every version in the snapshot's `schema_migrations`, sorted.
+    pub migrations: Vec<String>,
+    /// Cache root the snapshot was taken from; `cache_manifest` paths are
+    /// rewritten from it on restore.
+    pub cache_root: String,
+    pub files: Vec<SnapshotFile>,
+}
+
+/// A file in a snapshot, relative to its root.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotFile {
+    pub path: String,
+    pub bytes: u64,
+    pub sha256: String,
+}
+
+/// Report from [`Warehouse::snapshot`].
+#[derive(Debug, Clone, Serialize)]
+pub struct SnapshotReport {
+    pub output: PathBuf,
+    /// Whether `output` is a tar archive rather than a directory.
+    pub archive: bool,
+    pub schema_version: String,
+    pub files: usize,
+    pub bytes: u64,
+    pub cache_partitions: usize,
+    /// Partitions in `cache_manifest` whose file no longer exists.
+    pub missing_partitions: usize,
+}
+
+/// Report from [`Warehouse::restore_snapshot`].
+#[derive(Debug, Clone, Serialize)]
+pub struct RestoreReport {
+    pub snapshot: PathBuf,
+    pub created_at: String,
+    pub schema_version: String,
+    /// Migrations of this build the snapshot predates, applied after the swap.
+    pub migrations_applied: Vec<String>,
+    pub files_verified: usize,
+    pub cache_partitions: usize,
+    /// Where the replaced database and cache were moved.
+    pub previous: PathBuf,
+}
+
+/// On-disk form of a snapshot, from its file name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SnapshotArchive {
+    Directory,
+    Tar,
+    TarGz,
+}
+
+impl SnapshotArchive {
+    fn from_path(path: &Path) -> Self {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_ascii_lowercase())
+            .unwrap_or_default();
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Self::TarGz
+        } else if name.ends_with(".tar") {
+            Self::Tar
+        } else {
+            Self::Directory
+        }
+    }
+}
+
+impl Warehouse {
+    /// Write a consistent snapshot of the database and parquet cache to
+    /// `output`, a new directory or a `.tar` / `.tar.gz` / `.tgz` file.
+    pub fn snapshot(&self, output: &Path) -> Result<SnapshotReport, WarehouseError> {
+        let archive = SnapshotArchive::from_path(output);
+        let root = match archive {
+            SnapshotArchive::Directory => {
+                if output.exists() && fs::read_dir(output)?.next().is_some() {
+                    return Err(snapshot_error(format!(
+                        "{} already exists and is not empty",
+                        output.display()
+                    )));
+                }
+                output.to_path_buf()
+            }
+            SnapshotArchive::Tar | SnapshotArchive::TarGz => {
+                if output.exists() {
+                    return Err(snapshot_error(format!(
+                        "{} already exists",
+                        output.display()
+                    )));
+                }
+                staging_path(output)
+            }
+        };
+        if root != output && root.exists() {
+            fs::remove_dir_all(&root)?;
+        }
+        fs::create_dir_all(&root)?;
+
+        let result = self
+            .write_snapshot(&root)
+            .and_then(|(manifest, report)| match archive {
+                SnapshotArchive::Directory => Ok((manifest, report)),
+                SnapshotArchive::Tar | SnapshotArchive::TarGz => {
+                    write_archive(&root, output, archive == SnapshotArchive::TarGz)?;
+                    Ok((manifest, report))
+                }
+            });
+        // The staging directory of an archive always goes; a directory
+        // snapshot only when it failed half-written.
+        if root != output || result.is_err() {
+            let _ = fs::remove_dir_all(&root);
+        }
+        if result.is_err() && root != output {
+            let _ = fs::remove_file(output);
+        }
+
+        let (manifest, (cache_partitions, missing_partitions)) = result?;
+        Ok(SnapshotReport {
+            output: output.to_path_buf(),
+            archive: archive != SnapshotArchive::Directory,
+            schema_version: manifest.schema_version,
+            files: manifest.files.len(),
+            bytes: manifest.files.iter().map(|file| file.bytes).sum(),
+            cache_partitions,
+            missing_partitions,
+        })
+    }
+
+    /// Copy the database and cache into `root` and write its manifest,
+    /// returning it with the copied and missing partition counts.
+    fn write_snapshot(
+        &self,
+        root: &Path,
+    ) -> Result<(SnapshotManifest, (usize, usize)), WarehouseError> {
+        let database = root.join(SNAPSHOT_DATABASE);
+        {
+            let connection = self.manager.acquire(AccessMode::ReadWrite)?;
+            let current: String =
+                connection.query_row("SELECT current_database()", [], |row| row.get(0))?;
+            // One statement, one transaction: writers on other connections
+            // are either fully in the copy or not at all.
+            connection.execute_batch(&format!(
+                "ATTACH '{}' AS ferrotick_snapshot; \
+                 COPY FROM DATABASE \"{}\" TO ferrotick_snapshot; \
+                 DETACH ferrotick_snapshot;",
+                escape_sql_string(&path_to_sql(&database)),
+                current.replace('"', "\"\"")
+            ))?;
+        }
+
+        let snapshot = Connection::open(&database)?;
+        snapshot.execute_batch("CHECKPOINT")?;
+        let created_at: String = snapshot.query_row(
+            "SELECT strftime(CAST(now() AS TIMESTAMP), '%Y-%m-%dT%H:%M:%SZ')",
+            [],
+            |row| row.get(0),
+        )?;
+        let migrations = applied_migrations(&snapshot)?;
+        let mut statement =
+            snapshot.prepare("SELECT DISTINCT path, checksum FROM cache_manifest ORDER BY path")?;
+        let partitions = statement
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        drop(statement);
+        drop(snapshot);
+
+        let cache_root = self.cache_root();
+        let mut files = vec![snapshot_file(root, Path::new(SNAPSHOT_DATABASE))?];
+        let mut missing = 0;
+        for (path, checksum) in &partitions {
+            let source = PathBuf::from(path);
+            let relative = match source.strip_prefix(&cache_root) {
+                Ok(relative) if source.is_file() => Path::new(SNAPSHOT_CACHE).join(relative),
+                _ => {
+                    missing += 1;
+                    continue;
+                }
+            };
+            let target = root.join(&relative);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(&source, &target)?;
+            let file = snapshot_file(root, &relative)?;
+            if file.sha256 != *checksum {
+                return Err(snapshot_error(format!(
+                    "cache partition {path} does not match its cache_manifest checksum"
+                )));
+            }
+            files.push(file);
+        }
+
+        let manifest = SnapshotManifest {
+            format_version: SNAPSHOT_FORMAT_VERSION,
+            created_at,
+            schema_version: migrations.last().cloned().unwrap_or_default(),
+            migrations,
+            cache_root: path_to_sql(&cache_root),
+            files,
+        };
+        let writer = BufWriter::new(File::create(root.join(SNAPSHOT_MANIFEST))?);
+        serde_json::to_writer_pretty(writer, &manifest)
+            .map_err(|error| snapshot_error(error.to_string()))?;
+        let copied = manifest.files.len() - 1;
+        Ok((manifest, (copied, missing)))
+    }
+
+    /// Verify the snapshot at `snapshot` and swap it in for the warehouse of
+    /// `config`, which must not be open.
+    ///
+    /// The replaced database and cache are kept under `pre-restore/` next
+    /// to the database until the next restore.
+    pub fn restore_snapshot(
+        config: &WarehouseConfig,
+        snapshot: &Path,
+    ) -> Result<RestoreReport, WarehouseError> {
+        if !snapshot.exists() {
+            return Err(snapshot_error(format!(
+                "{} does not exist",
+                snapshot.display()
+            )));
+        }
+        ensure_closed(&config.db_path)?;
+        let db_dir = config
+            .db_path
+            .parent()
+            .map_or_else(|| PathBuf::from("."), Path::to_path_buf);
+        fs::create_dir_all(&db_dir)?;
+        let staging = db_dir.join(".restore-staging");
+        if staging.exists() {
+            fs::remove_dir_all(&staging)?;
+        }
+        fs::create_dir_all(&staging)?;
+
+        let result = stage_snapshot(snapshot, &staging).and_then(|manifest| {
+            let pending = verify_schema(&manifest, &staging.join(SNAPSHOT_DATABASE))?;
+            let previous = db_dir.join("pre-restore");
+            swap_in(config, &staging, &previous)?;
+            Ok((manifest, pending, previous))
+        });
+        let _ = fs::remove_dir_all(&staging);
+        let (manifest, pending, previous) = result?;
+
+        // Applies the pending migrations and recreates views.
+        let warehouse = Self::open(config.clone())?;
+        let cache_root = path_to_sql(&warehouse.cache_root());
+        let connection = warehouse.manager.acquire(AccessMode::ReadWrite)?;
+        let params: [&dyn ToSql; 3] = [&cache_root, &manifest.cache_root, &manifest.cache_root];
+        connection.execute(
+            "UPDATE cache_manifest SET path = ? || substr(path, length(?) + 1) \
+             WHERE starts_with(path, ?)",
+            params.as_slice(),
+        )?;
+
+        Ok(RestoreReport {
+            snapshot: snapshot.to_path_buf(),
+            created_at: manifest.created_at,
+            schema_version: manifest.schema_version,
+            migrations_applied: pending,
+            files_verified: manifest.files.len(),
+            cache_partitions: manifest.files.len() - 1,
+            previous,
+        })
+    }
+}
+
+/// Refuse to restore over a database another connection has open.
+///
+/// Pools in this process are tracked directly; another process shows up as
+/// DuckDB's file lock when the database is opened here.
+fn ensure_closed(db_path: &Path) -> Result<(), WarehouseError> {
+    let in_use = || {
+        snapshot_error(format!(
+            "{} is open; close the warehouse before restoring",
+            db_path.display()
+        ))
+    };
+    if is_database_open(db_path) {
+        return Err(in_use());
+    }
+    // A database that fails to open for any other reason is still replaced.
+    if let Some(Err(error)) = db_path.exists().then(|| Connection::open(db_path)) {
+        if error.to_string().contains("Could not set lock") {
+            return Err(in_use());
+        }
+    }
+    Ok(())
+}
+
+/// Unpack or copy the snapshot into `staging` and verify every file
+/// against the manifest.
+fn stage_snapshot(snapshot: &Path, staging: &Path) -> Result<SnapshotManifest, WarehouseError> {
+    let archive = SnapshotArchive::from_path(snapshot);
+    match archive {
+        SnapshotArchive::Directory => {
+            if !snapshot.join(SNAPSHOT_MANIFEST).is_file() {
+                return Err(snapshot_error(format!(
+                    "{} has no {SNAPSHOT_MANIFEST}",
+                    snapshot.display()
+                )));
+            }
+        }
+        SnapshotArchive::Tar => {
+            tar::Archive::new(BufReader::new(File::open(snapshot)?)).unpack(staging)?
+        }
+        SnapshotArchive::TarGz => {
+            tar::Archive::new(GzDecoder::new(BufReader::new(File::open(snapshot)?)))
+                .unpack(staging)?;
+        }
+    }
+
+    let root = match archive {
+        SnapshotArchive::Directory => snapshot,
+        SnapshotArchive::Tar | SnapshotArchive::TarGz => staging,
+    };
+    let manifest = File::open(root.join(SNAPSHOT_MANIFEST)).map_err(|_| {
+        snapshot_error(format!("{} has no {SNAPSHOT_MANIFEST}", snapshot.display()))
+    })?;
+    let manifest: SnapshotManifest = serde_json::from_reader(BufReader::new(manifest))
+        .map_err(|error| snapshot_error(format!("invalid {SNAPSHOT_MANIFEST}: {error}")))?;
+    if manifest.format_version != SNAPSHOT_FORMAT_VERSION {
+        return Err(snapshot_error(format!(
+            "unsupported snapshot format version {}",
+            manifest.format_version
+        )));
+    }
+    if !manifest
+        .files
+        .iter()
+        .any(|file| file.path == SNAPSHOT_DATABASE)
+    {
+        return Err(snapshot_error(format!(
+            "{SNAPSHOT_MANIFEST} does not list {SNAPSHOT_DATABASE}"
+        )));
+    }
+
+    for file in &manifest.files {
+        let relative = Path::new(&file.path);
+        if !relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(snapshot_error(format!("invalid file path '{}'", file.path)));
+        }
+        if root != staging {
+            let target = staging.join(relative);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(root.join(relative), &target)
+                .map_err(|error| snapshot_error(format!("cannot read {}: {error}", file.path)))?;
+        }
+        let staged = staging.join(relative);
+        if !staged.is_file() {
+            return Err(snapshot_error(format!("{} is missing", file.path)));
+        }
+        let actual = snapshot_file(staging, relative)?;
+        if actual.bytes != file.bytes || actual.sha256 != file.sha256 {
+            return Err(snapshot_error(format!(
+                "{} does not match its checksum",
+                file.path
+            )));
+        }
+        // Restored partitions carry the sidecars a rebuild verifies.
+        if file.path != SNAPSHOT_DATABASE {
+            fs::write(checksum_path(&staged), &file.sha256)?;
+        }
+    }
+    Ok(manifest)
+}
+
+/// Check the staged database's `schema_migrations` against the manifest
+/// and this build, returning the migrations still to apply.
+fn verify_schema(
+    manifest: &SnapshotManifest,
+    database: &Path,
+) -> Result<Vec<String>, WarehouseError> {
+    let connection = Connection::open(database)?;
+    let applied = applied_migrations(&connection)?;
+    drop(connection);
+    if applied != manifest.migrations
+        || applied.last().map(String::as_str) != Some(manifest.schema_version.as_str())
+    {
+        return Err(snapshot_error(format!(
+            "schema_migrations does not match schema version {} in {SNAPSHOT_MANIFEST}",
+            manifest.schema_version
+        )));
+    }
+
+    let known = migrations::versions().collect::<BTreeSet<_>>();
+    if let Some(unknown) = applied
+        .iter()
+        .find(|version| !known.contains(version.as_str()))
+    {
+        return Err(WarehouseError::SchemaTooNew {
+            version: unknown.clone(),
+            latest: migrations::latest_version().to_string(),
+        });
+    }
+    Ok(migrations::versions()
+        .filter(|version| !applied.iter().any(|applied| applied == version))
+        .map(str::to_string)
+        .collect())
+}
+
+/// Move the current database and cache to `previous`, then the staged
+/// snapshot into their place. Any failure puts the moved files back.
+fn swap_in(
+    config: &WarehouseConfig,
+    staging: &Path,
+    previous: &Path,
+) -> Result<(), WarehouseError> {
+    let cache_root = config.ferrotick_home.join("cache").join("parquet");
+    let wal = wal_path(&config.db_path);
+    if previous.exists() {
+        fs::remove_dir_all(previous)?;
+    }
+    fs::create_dir_all(previous)?;
+
+    let moves = [
+        (config.db_path.clone(), previous.join(SNAPSHOT_DATABASE)),
+        (wal, previous.join(format!("{SNAPSHOT_DATABASE}.wal"))),
+        (cache_root.clone(), previous.join("parquet")),
+    ];
+    let staged_cache = staging.join(SNAPSHOT_CACHE);
+    let mut moved = Vec::new();
+    let mut placed = Vec::new();
+    let result = (|| -> std::io::Result<()> {
+        for (from, to) in &moves {
+            if from.exists() {
+                fs::rename(from, to)?;
+                moved.push((from, to));
+            }
+        }
+        fs::rename(staging.join(SNAPSHOT_DATABASE), &config.db_path)?;
+        placed.push(&config.db_path);
+        if staged_cache.exists() {
+            if let Some(parent) = cache_root.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::rename(&staged_cache, &cache_root)?;
+            placed.push(&cache_root);
+        }
+        Ok(())
+    })();
+    if let Err(error) = result {
+        // Only remove what was swapped in, never a file that was not moved.
+        for path in placed {
+            let _ = if path.is_dir() {
+                fs::remove_dir_all(path)
+            } else {
+                fs::remove_file(path)
+            };
+        }
+        for (from, to) in moved.into_iter().rev() {
+            let _ = fs::rename(to, from);
+        }
+        return Err(error.into());
+    }
+    Ok(())
+}
+
+fn applied_migrations(connection: &Connection) -> Result<Vec<String>, WarehouseError> {
+    let mut statement =
+        connection.prepare("SELECT version FROM schema_migrations ORDER BY version")?;
+    let versions = statement
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(versions)
+}
+
+fn snapshot_file(root: &Path, relative: &Path) -> Result<SnapshotFile, WarehouseError> {
+    let path = root.join(relative);
+    Ok(SnapshotFile {
+        path: path_to_sql(relative),
+        bytes: fs::metadata(&path)?.len(),
+        sha256: file_checksum(&path)?,
+    })
+}
+
+fn write_archive(root: &Path, output: &Path, gzip: bool) -> Result<(), WarehouseError> {
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let file = BufWriter::new(File::create(output)?);
+    if gzip {
+        let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+        builder.append_dir_all(".", root)?;
+        builder.into_inner()?.finish()?;
+    } else {
+        let mut builder = tar::Builder::new(file);
+        builder.append_dir_all(".", root)?;
+        builder.into_inner()?;
+    }
+    Ok(())
+}
+
+/// Hidden sibling of `output` the snapshot is assembled in before archiving.
+fn staging_path(output: &Path) -> PathBuf {
+    let name = output
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    output.with_file_name(format!(".{name}.staging"))
+}
+
+fn wal_path(db_path: &Path) -> PathBuf {
+    let mut wal = db_path.as_os_str().to_owned();
+    wal.push(".wal");
+    PathBuf::from(wal)
+}
+
+fn snapshot_error(message: impl Into<String>) -> WarehouseError {
+    WarehouseError::Snapshot(message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BarRecord;
+    use tempfile::tempdir;
+
+    fn open(home: &Path) -> Warehouse {
+        Warehouse::open(WarehouseConfig {
+            db_path: home.join("cache").join("warehouse.duckdb"),
+            ferrotick_home: home.to_path_buf(),
+            max_pool_size: 2,
+        })
+        .expect("warehouse open")
+    }
+
+    fn bar(ts: &str, close: f64) -> BarRecord {
+        BarRecord {
+            symbol: String::from("AAPL"),
+            ts: ts.to_string(),
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume: Some(100),
+        }
+    }
+
+    fn count(warehouse: &Warehouse, sql: &str) -> i64 {
+        let connection = warehouse
+            .acquire_connection(AccessMode::ReadWrite)
+            .expect("connection");
+        connection
+            .query_row(sql, [], |row| row.get(0))
+            .expect("count")
+    }
+
+    #[test]
+    fn snapshots_restore_into_another_home_and_reject_tampering() {
+        let temp = tempdir().expect("tempdir");
+        let origin = open(&temp.path().join("origin"));
+        origin
+            .ingest_bars(
+                "test",
+                "bars_1d",
+                "req-1",
+                &[
+                    bar("2024-01-02T00:00:00Z", 1.0),
+                    bar("2024-01-03T00:00:00Z", 2.0),
+                ],
+                5,
+            )
+            .expect("ingest");
+
+        let archive = temp.path().join("frozen.tar.gz");
+        let report = origin.snapshot(&archive).expect("snapshot");
+        assert!(report.archive);
+        assert_eq!(report.cache_partitions, 2);
+        assert_eq!(report.missing_partitions, 0);
+        assert_eq!(
+            report.schema_version,
+            migrations::versions().last().expect("migrations")
+        );
+        assert!(origin.snapshot(&archive).is_err());
+
+        let directory = temp.path().join("frozen");
+        origin.snapshot(&directory).expect("directory snapshot");
+
+        // Restoring over a populated warehouse keeps the old one aside.
+        let home = temp.path().join("colleague");
+        let colleague = open(&home);
+        colleague
+            .ingest_bars(
+                "other",
+                "bars_1d",
+                "req-2",
+                &[bar("2023-06-01T00:00:00Z", 9.0)],
+                5,
+            )
+            .expect("ingest");
+        let config = colleague.config.clone();
+        drop(colleague);
+
+        let restored = Warehouse::restore_snapshot(&config, &archive).expect("restore");
+        assert!(restored.migrations_applied.is_empty());
+        assert_eq!(restored.files_verified, 3);
+        assert!(restored.previous.join(SNAPSHOT_DATABASE).is_file());
+
+        let warehouse = open(&home);
+        assert_eq!(count(&warehouse, "SELECT COUNT(*) FROM bars_1d"), 2);
+        let cache_root = path_to_sql(&warehouse.cache_root());
+        assert_eq!(
+            count(
+                &warehouse,
+                &format!(
+                    "SELECT COUNT(*) FROM cache_manifest WHERE starts_with(path, '{cache_root}')"
+                )
+            ),
+            2
+        );
+        drop(warehouse);
+
+        // A modified file or an unknown migration is refused before the swap.
+        let database = directory.join(SNAPSHOT_DATABASE);
+        let connection = Connection::open(&database).expect("snapshot db");
+        connection
+            .execute_batch("INSERT INTO schema_migrations (version) VALUES ('9999_future')")
+            .expect("future migration");
+        drop(connection);
+        let error = Warehouse::restore_snapshot(&config, &directory).expect_err("tampered");
+        assert!(error.to_string().contains("does not match its checksum"));
+
+        let manifest_path = directory.join(SNAPSHOT_MANIFEST);
+        let mut manifest: SnapshotManifest =
+            serde_json::from_slice(&fs::read(&manifest_path).expect("read")).expect("manifest");
+        let entry = snapshot_file(&directory, Path::new(SNAPSHOT_DATABASE)).expect("checksum");
+        manifest.files[0] = entry;
+        manifest.migrations.push(String::from("9999_future"));
+        manifest.schema_version = String::from("9999_future");
+        fs::write(&manifest_path, serde_json::to_vec(&manifest).expect("json")).expect("write");
+        let error = Warehouse::restore_snapshot(&config, &directory).expect_err("future");
+        assert!(error.to_string().contains("upgrade ferrotick"));
+
+        let warehouse = open(&home);
+        assert_eq!(count(&warehouse, "SELECT COUNT(*) FROM bars_1d"), 2);
+
+        // An open warehouse is never swapped out from under its connections.
+        let error = Warehouse::restore_snapshot(&config, &archive).expect_err("open");
+        assert!(error.to_string().contains("close the warehouse"));
+        assert_eq!(count(&warehouse, "SELECT COUNT(*) FROM bars_1d"), 2);
+    }
+}
@@ -0,0 +1,411 @@
+//! Named universes with point-in-time membership.
+//!
+//! A universe (`universes`) is a named list of symbols whose membership is
+//! kept as intervals in `universe_members`: a symbol joins on `added` and
+//! leaves on `removed` (exclusive), so a backtest can ask who was a member
+//! on each date instead of using today's list. A symbol may rejoin after it
+//! left; its intervals never overlap.
+
+use ::duckdb::{Connection, OptionalExt, ToSql};
+use serde::Serialize;
+
+use crate::duckdb::AccessMode;
+use crate::{finalize_transaction, Warehouse, WarehouseError};
+
+/// A universe and how many symbols are members today.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UniverseSummary {
+    pub name: String,
+    pub description: Option<String>,
+    pub current_members: usize,
+    pub created_at: String,
+}
+
+/// One membership interval of a symbol.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UniverseMember {
+    pub symbol: String,
+    /// First date of membership (YYYY-MM-DD).
+    pub added: String,
+    /// First date the symbol is no longer a member; `None` while it is.
+    pub removed: Option<String>,
+}
+
+impl UniverseMember {
+    /// Whether this interval covers `date` (YYYY-MM-DD, or an RFC 3339
+    /// timestamp whose UTC date is used).
+    pub fn is_member_on(&self, date: &str) -> bool {
+        let day = date.get(..10).unwrap_or(date);
+        self.added.as_str() <= day && self.removed.as_deref().is_none_or(|removed| day < removed)
+    }
+}
+
+/// Every membership interval of one universe, for resolving members date
+/// by date.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UniverseMembership {
+    pub universe: String,
+    pub intervals: Vec<UniverseMember>,
+}
+
+impl UniverseMembership {
+    pub fn new(universe: impl Into<String>, intervals: Vec<UniverseMember>) -> Self {
+        Self {
+            universe: universe.into(),
+            intervals,
+        }
+    }
+
+    /// Whether `symbol` was a member on `date`.
+    pub fn contains(&self, symbol: &str, date: &str) -> bool {
+        self.intervals
+            .iter()
+            .any(|member| member.symbol == symbol && member.is_member_on(date))
+    }
+
+    /// Symbols that were members on `date`, sorted.
+    pub fn members_on(&self, date: &str) -> Vec<&str> {
+        let mut members = self
+            .intervals
+            .iter()
+            .filter(|member| member.is_member_on(date))
+            .map(|member| member.symbol.as_str())
+            .collect::<Vec<_>>();
+        members.sort_unstable();
+        members.dedup();
+        members
+    }
+
+    /// Every symbol that was a member at some point, sorted.
+    pub fn symbols(&self) -> Vec<&str> {
+        let mut symbols = self
+            .intervals
+            .iter()
+            .map(|member| member.symbol.as_str())
+            .collect::<Vec<_>>();
+        symbols.sort_unstable();
+        symbols.dedup();
+        symbols
+    }
+}
+
+impl Warehouse {
+    /// Create an empty universe.
+    pub fn create_universe(
+        &self,
+        name: &str,
+        description: Option<&str>,
+    ) -> Result<(), WarehouseError> {
+        validate_name(name)?;
+        let connection = self.manager.acquire(AccessMode::ReadWrite)?;
+        if universe_exists(&connection, name)? {
+            return Err(WarehouseError::Universe(format!("'{name}' already exists")));
+        }
+        let params: [&dyn ToSql; 2] = [&name, &description];
+        connection.execute(
+            "INSERT INTO universes (name, description, created_at) \
+             VALUES (?, ?, CURRENT_TIMESTAMP)",
+            params.as_slice(),
+        )?;
+        Ok(())
+    }
+
+    /// Add `symbols` to `name` from `added` on, returning how many joined.
+    ///
+    /// Symbols that are already members are left as they are. A symbol that
+    /// left may rejoin, but not before the date it left.
+    pub fn add_universe_members(
+        &self,
+        name: &str,
+        symbols: &[String],
+        added: &str,
+    ) -> Result<usize, WarehouseError> {
+        let connection = self.manager.acquire(AccessMode::ReadWrite)?;
+        require_universe(&connection, name)?;
+        let added = normalize_date(&connection, added)?;
+
+        connection.execute_batch("BEGIN TRANSACTION")?;
+        let result = (|| -> Result<usize, WarehouseError> {
+            let mut joined = 0;
+            for symbol in symbols {
+                let params: [&dyn ToSql; 2] = [&name, symbol];
+                let (open, last_removed): (i64, Option<String>) = connection.query_row(
+                    "SELECT COUNT(*) FILTER (WHERE removed IS NULL), \
+                            CAST(MAX(removed) AS VARCHAR) \
+                     FROM universe_members WHERE universe = ? AND symbol = ?",
+                    params.as_slice(),
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )?;
+                if open > 0 {
+                    continue;
+                }
+                if let Some(removed) = last_removed.filter(|removed| *removed > added) {
+                    return Err(WarehouseError::Universe(format!(
+                        "'{name}': {symbol} was a member until {removed} and cannot rejoin \
+                         on {added}"
+                    )));
+                }
+
+                let params: [&dyn ToSql; 3] = [&name, symbol, &added];
+                connection.execute(
+                    "INSERT INTO universe_members (universe, symbol, added, removed) \
+                     VALUES (?, ?, CAST(? AS DATE), NULL)",
+                    params.as_slice(),
+                )?;
+                joined += 1;
+            }
+            Ok(joined)
+        })();
+
+        finalize_transaction(&connection, result)
+    }
+
+    /// End the membership of `symbols` in `name` on `removed`, returning how
+    /// many left.
+    pub fn remove_universe_members(
+        &self,
+        name: &str,
+        symbols: &[String],
+        removed: &str,
+    ) -> Result<usize, WarehouseError> {
+        let connection = self.manager.acquire(AccessMode::ReadWrite)?;
+        require_universe(&connection, name)?;
+        let removed = normalize_date(&connection, removed)?;
+
+        connection.execute_batch("BEGIN TRANSACTION")?;
+        let result = (|| -> Result<usize, WarehouseError> {
+            for symbol in symbols {
+                let params: [&dyn ToSql; 2] = [&name, symbol];
+                let added: Option<String> = connection
+                    .query_row(
+                        "SELECT CAST(added AS VARCHAR) FROM universe_members \
+                         WHERE universe = ? AND symbol = ? AND removed IS NULL",
+                        params.as_slice(),
+                        |row| row.get(0),
+                    )
+                    .optional()?;
+                let Some(added) = added else {
+                    return Err(WarehouseError::Universe(format!(
+                        "'{name}': {symbol} is not a member"
+                    )));
+                };
+                if removed <= added {
+                    return Err(WarehouseError::Universe(format!(
+                        "'{name}': {symbol} joined on {added} and must be removed after that"
+                    )));
+                }
+
+                let params: [&dyn ToSql; 3] = [&removed, &name, symbol];
+                connection.execute(
+                    "UPDATE universe_members SET removed = CAST(? AS DATE) \
+                     WHERE universe = ? AND symbol = ? AND removed IS NULL",
+                    params.as_slice(),
+                )?;
+            }
+            Ok(symbols.len())
+        })();
+
+        finalize_transaction(&connection, result)
+    }
+
+    /// Every universe, by name.
+    pub fn universes(&self) -> Result<Vec<UniverseSummary>, WarehouseError> {
+        let connection = self.manager.acquire(AccessMode::ReadOnly)?;
+        let mut statement = connection.prepare(
+            "SELECT u.name, u.description, \
+                    COUNT(DISTINCT m.symbol) FILTER ( \
+                        WHERE m.added <= t.today \
+                          AND (m.removed IS NULL OR m.removed > t.today)), \
+                    strftime(u.created_at, '%Y-%m-%dT%H:%M:%SZ') \
+             FROM universes u \
+             LEFT JOIN universe_members m ON m.universe = u.name \
+             CROSS JOIN (SELECT CAST(CAST(now() AS TIMESTAMP) AS DATE) AS today) t \
+             GROUP BY u.name, u.description, u.created_at \
+             ORDER BY u.name",
+        )?;
+        let rows = statement.query_map([], |row| {
+            Ok(UniverseSummary {
+                name: row.get(0)?,
+                description: row.get(1)?,
+                current_members: usize::try_from(row.get::<_, i64>(2)?).unwrap_or_default(),
+                created_at: row.get(3)?,
+            })
+        })?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(WarehouseError::from)
+    }
+
+    /// Symbols that were members of `name` on `as_of` (YYYY-MM-DD), sorted.
+    pub fn universe_members(&self, name: &str, as_of: &str) -> Result<Vec<String>, WarehouseError> {
+        let connection = self.manager.acquire(AccessMode::ReadOnly)?;
+        require_universe(&connection, name)?;
+        let as_of = normalize_date(&connection, as_of)?;
+        let mut statement = connection.prepare(
+            "SELECT DISTINCT symbol FROM universe_members \
+             WHERE universe = ? AND added <= CAST(? AS DATE) \
+               AND (removed IS NULL OR removed > CAST(? AS DATE)) \
+             ORDER BY symbol",
+        )?;
+        let params: [&dyn ToSql; 3] = [&name, &as_of, &as_of];
+        let rows = statement.query_map(params.as_slice(), |row| row.get(0))?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(WarehouseError::from)
+    }
+
+    /// Every membership interval of `name`.
+    pub fn universe_membership(&self, name: &str) -> Result<UniverseMembership, WarehouseError> {
+        let connection = self.manager.acquire(AccessMode::ReadOnly)?;
+        require_universe(&connection, name)?;
+        let mut statement = connection.prepare(
+            "SELECT symbol, CAST(added AS VARCHAR), CAST(removed AS VARCHAR) \
+             FROM universe_members WHERE universe = ? \
+             ORDER BY symbol, added",
+        )?;
+        let rows = statement.query_map([name], |row| {
+            Ok(UniverseMember {
+                symbol: row.get(0)?,
+                added: row.get(1)?,
+                removed: row.get(2)?,
+            })
+        })?;
+        let intervals = rows.collect::<Result<Vec<_>, _>>()?;
+        Ok(UniverseMembership::new(name, intervals))
+    }
+}
+
+fn validate_name(name: &str) -> Result<(), WarehouseError> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '_' | '-' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(WarehouseError::Universe(format!(
+            "name '{name}' may only contain letters, digits, '_', '-' and '.'"
+        )))
+    }
+}
+
+fn universe_exists(connection: &Connection, name: &str) -> Result<bool, WarehouseError> {
+    let count: i64 = connection.query_row(
+        "SELECT COUNT(*) FROM universes WHERE name = ?",
+        [name],
+        |row| row.get(0),
+    )?;
+    Ok(count > 0)
+}
+
+fn require_universe(connection: &Connection, name: &str) -> Result<(), WarehouseError> {
+    if universe_exists(connection, name)? {
+        Ok(())
+    } else {
+        Err(WarehouseError::Universe(format!(
+            "'{name}' does not exist; create it with `ferrotick universe create {name}`"
+        )))
+    }
+}
+
+/// `raw` as YYYY-MM-DD, so dates compare as strings.
+fn normalize_date(connection: &Connection, raw: &str) -> Result<String, WarehouseError> {
+    connection
+        .query_row(
+            "SELECT CAST(TRY_CAST(? AS DATE) AS VARCHAR)",
+            [raw],
+            |row| row.get::<_, Option<String>>(0),
+        )?
+        .ok_or_else(|| WarehouseError::Universe(format!("invalid date '{raw}'")))
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::WarehouseConfig;
+
+    fn symbols(list: &[&str]) -> Vec<String> {
+        list.iter().map(|symbol| symbol.to_string()).collect()
+    }
+
+    #[test]
+    fn membership_resolves_as_of_each_date() {
+        let temp = tempdir().expect("tempdir");
+        let ferrotick_home = temp.path().join("ferrotick-home");
+        let warehouse = Warehouse::open(WarehouseConfig {
+            ferrotick_home: ferrotick_home.clone(),
+            db_path: ferrotick_home.join("cache").join("warehouse.duckdb"),
+            max_pool_size: 2,
+        })
+        .expect("warehouse open");
+
+        warehouse
+            .create_universe("sp500", Some("S&P 500"))
+            .expect("create");
+        assert!(matches!(
+            warehouse.create_universe("sp500", None),
+            Err(WarehouseError::Universe(_))
+        ));
+        assert!(warehouse.create_universe("bad name", None).is_err());
+        assert!(warehouse
+            .add_universe_members("nope", &symbols(&["AAPL"]), "2020-01-01")
+            .is_err());
+
+        let joined = warehouse
+            .add_universe_members("sp500", &symbols(&["AAPL", "ENRN", "MSFT"]), "2000-01-03")
+            .expect("add");
+        assert_eq!(joined, 3);
+        // Already a member: nothing changes.
+        assert_eq!(
+            warehouse
+                .add_universe_members("sp500", &symbols(&["AAPL"]), "2010-01-04")
+                .expect("re-add"),
+            0
+        );
+        warehouse
+            .remove_universe_members("sp500", &symbols(&["ENRN"]), "2001-12-03")
+            .expect("remove");
+        warehouse
+            .remove_universe_members("sp500", &symbols(&["MSFT"]), "2005-06-01")
+            .expect("remove");
+        assert!(warehouse
+            .add_universe_members("sp500", &symbols(&["MSFT"]), "2005-01-01")
+            .is_err());
+        warehouse
+            .add_universe_members("sp500", &symbols(&["MSFT"]), "2006-01-03")
+            .expect("rejoin");
+        assert!(warehouse
+            .remove_universe_members("sp500", &symbols(&["ENRN"]), "2002-01-01")
+            .is_err());
+        assert!(warehouse
+            .remove_universe_members("sp500", &symbols(&["AAPL"]), "1999-01-01")
+            .is_err());
+        assert!(warehouse.universe_members("sp500", "not-a-date").is_err());
+
+        assert_eq!(
+            warehouse
+                .universe_members("sp500", "2001-06-01")
+                .expect("members"),
+            symbols(&["AAPL", "ENRN", "MSFT"])
+        );
+        assert_eq!(
+            warehouse
+                .universe_members("sp500", "2005-09-01")
+                .expect("members"),
+            symbols(&["AAPL"])
+        );
+
+        let membership = warehouse.universe_membership("sp500").expect("membership");
+        assert_eq!(membership.intervals.len(), 4);
+        assert_eq!(membership.symbols(), vec!["AAPL", "ENRN", "MSFT"]);
+        assert!(membership.contains("ENRN", "2001-12-02T20:00:00Z"));
+        assert!(!membership.contains("ENRN", "2001-12-03"));
+        assert_eq!(membership.members_on("2006-01-03"), vec!["AAPL", "MSFT"]);
+        assert!(membership.members_on("1999-12-31").is_empty());
+
+        let listed = warehouse.universes().expect("list");
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].description.as_deref(), Some("S&P 500"));
+        assert_eq!(listed[0].current_members, 2);
+    }
+}
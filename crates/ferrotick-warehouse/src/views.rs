@@ -1,18 +1,354 @@
 //! Database views for analytical queries.
+//!
+//! Cross-sectional analytics live in versioned views and table macros
+//! (`*_v1`) listed in [`ANALYTICS_CATALOG`]. A change to the output of one
+//! ships as a new `_v2` next to it, so saved queries keep their meaning.
+//! Rolling statistics are `NULL` until their window is full.
 
 use ::duckdb::Connection;
+use serde::Serialize;
+
+/// Whether a catalog entry is queried as a view or as a table macro.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnalyticsKind {
+    View,
+    TableMacro,
+}
+
+/// A named table macro parameter with its default.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct AnalyticsParameter {
+    pub name: &'static str,
+    pub default: &'static str,
+    pub description: &'static str,
+}
+
+/// A documented analytics view or table macro.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct AnalyticsObject {
+    pub name: &'static str,
+    pub kind: AnalyticsKind,
+    pub version: u32,
+    pub description: &'static str,
+    pub parameters: &'static [AnalyticsParameter],
+    pub columns: &'static [&'static str],
+    pub example: &'static str,
+}
+
+const WINDOW_DAYS: &str = "trailing window length in trading days";
+
+/// Analytics views and table macros created by [`create_views`].
+pub const ANALYTICS_CATALOG: &[AnalyticsObject] = &[
+    AnalyticsObject {
+        name: "rolling_beta_v1",
+        kind: AnalyticsKind::TableMacro,
+        version: 1,
+        description: "Rolling beta of daily returns against a benchmark symbol",
+        parameters: &[
+            AnalyticsParameter {
+                name: "benchmark",
+                default: "'SPY'",
+                description: "benchmark symbol in bars_1d",
+            },
+            AnalyticsParameter {
+                name: "window_days",
+                default: "60",
+                description: WINDOW_DAYS,
+            },
+        ],
+        columns: &["symbol", "date", "beta", "observations"],
+        example: "SELECT * FROM rolling_beta_v1(benchmark := 'QQQ', window_days := 120)",
+    },
+    AnalyticsObject {
+        name: "rolling_correlation_v1",
+        kind: AnalyticsKind::TableMacro,
+        version: 1,
+        description: "Rolling correlation of daily returns with a benchmark symbol",
+        parameters: &[
+            AnalyticsParameter {
+                name: "benchmark",
+                default: "'SPY'",
+                description: "benchmark symbol in bars_1d",
+            },
+            AnalyticsParameter {
+                name: "window_days",
+                default: "60",
+                description: WINDOW_DAYS,
+            },
+        ],
+        columns: &["symbol", "date", "correlation", "observations"],
+        example: "SELECT * FROM rolling_correlation_v1(window_days := 20)",
+    },
+    AnalyticsObject {
+        name: "vw_drawdown_v1",
+        kind: AnalyticsKind::View,
+        version: 1,
+        description: "Daily close against its running peak; drawdown is <= 0",
+        parameters: &[],
+        columns: &["symbol", "date", "close", "peak_close", "drawdown"],
+        example: "SELECT symbol, MIN(drawdown) FROM vw_drawdown_v1 GROUP BY symbol",
+    },
+    AnalyticsObject {
+        name: "atr_v1",
+        kind: AnalyticsKind::TableMacro,
+        version: 1,
+        description: "True range and its simple moving average (average true range)",
+        parameters: &[AnalyticsParameter {
+            name: "window_days",
+            default: "14",
+            description: WINDOW_DAYS,
+        }],
+        columns: &["symbol", "date", "true_range", "atr"],
+        example: "SELECT * FROM atr_v1(window_days := 20) WHERE symbol = 'AAPL'",
+    },
+    AnalyticsObject {
+        name: "high_low_distance_v1",
+        kind: AnalyticsKind::TableMacro,
+        version: 1,
+        description: "Distance of the close from the trailing high and low (52 weeks by default)",
+        parameters: &[AnalyticsParameter {
+            name: "window_days",
+            default: "252",
+            description: WINDOW_DAYS,
+        }],
+        columns: &[
+            "symbol",
+            "date",
+            "close",
+            "window_high",
+            "window_low",
+            "pct_from_high",
+            "pct_from_low",
+        ],
+        example: "SELECT * FROM high_low_distance_v1() WHERE pct_from_high > -0.05",
+    },
+    AnalyticsObject {
+        name: "relative_strength_rank_v1",
+        kind: AnalyticsKind::TableMacro,
+        version: 1,
+        description: "Trailing return ranked across all symbols on each date; rank 1 is strongest",
+        parameters: &[AnalyticsParameter {
+            name: "window_days",
+            default: "63",
+            description: WINDOW_DAYS,
+        }],
+        columns: &[
+            "symbol",
+            "date",
+            "trailing_return",
+            "rank",
+            "percentile",
+            "universe_size",
+        ],
+        example: "SELECT * FROM relative_strength_rank_v1(window_days := 126) WHERE rank <= 10",
+    },
+    AnalyticsObject {
+        name: "volume_zscore_v1",
+        kind: AnalyticsKind::TableMacro,
+        version: 1,
+        description: "Volume z-score against the preceding window, excluding the current day",
+        parameters: &[AnalyticsParameter {
+            name: "window_days",
+            default: "20",
+            description: WINDOW_DAYS,
+        }],
+        columns: &["symbol", "date", "volume", "volume_zscore"],
+        example: "SELECT * FROM volume_zscore_v1() WHERE volume_zscore > 3",
+    },
+];
+
+/// Definitions of the [`ANALYTICS_CATALOG`] entries.
+///
+/// Each runs as its own batch: DuckDB rejects a `WINDOW` name that an earlier
+/// statement of the same batch already defined.
+const ANALYTICS_SQL: [&str; 7] = [
+    r"
+CREATE OR REPLACE MACRO rolling_beta_v1(benchmark := 'SPY', window_days := 60) AS TABLE
+WITH paired AS (
+    SELECT r.symbol, r.date, r.return_pct, b.return_pct AS benchmark_return_pct
+    FROM vw_returns_daily r
+    JOIN vw_returns_daily b ON b.symbol = benchmark AND b.date = r.date
+    WHERE r.return_pct IS NOT NULL AND b.return_pct IS NOT NULL
+)
+SELECT
+    symbol,
+    date,
+    CASE
+        WHEN COUNT(*) OVER w = window_days
+        THEN REGR_SLOPE(return_pct, benchmark_return_pct) OVER w
+    END AS beta,
+    COUNT(*) OVER w AS observations
+FROM paired
+WINDOW w AS (
+    PARTITION BY symbol ORDER BY date
+    ROWS BETWEEN window_days - 1 PRECEDING AND CURRENT ROW
+);
+",
+    r"
+CREATE OR REPLACE MACRO rolling_correlation_v1(benchmark := 'SPY', window_days := 60) AS TABLE
+WITH paired AS (
+    SELECT r.symbol, r.date, r.return_pct, b.return_pct AS benchmark_return_pct
+    FROM vw_returns_daily r
+    JOIN vw_returns_daily b ON b.symbol = benchmark AND b.date = r.date
+    WHERE r.return_pct IS NOT NULL AND b.return_pct IS NOT NULL
+)
+SELECT
+    symbol,
+    date,
+    CASE
+        WHEN COUNT(*) OVER w = window_days
+        THEN CORR(return_pct, benchmark_return_pct) OVER w
+    END AS correlation,
+    COUNT(*) OVER w AS observations
+FROM paired
+WINDOW w AS (
+    PARTITION BY symbol ORDER BY date
+    ROWS BETWEEN window_days - 1 PRECEDING AND CURRENT ROW
+);
+",
+    r"
+CREATE OR REPLACE VIEW vw_drawdown_v1 AS
+SELECT
+    symbol,
+    CAST(ts AS DATE) AS date,
+    close,
+    MAX(close) OVER w AS peak_close,
+    close / NULLIF(MAX(close) OVER w, 0) - 1.0 AS drawdown
+FROM bars_1d
+WINDOW w AS (
+    PARTITION BY symbol ORDER BY ts
+    ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW
+);
+",
+    r"
+CREATE OR REPLACE MACRO atr_v1(window_days := 14) AS TABLE
+WITH ranges AS (
+    SELECT
+        symbol,
+        ts,
+        GREATEST(
+            high - low,
+            ABS(high - LAG(close) OVER p),
+            ABS(low - LAG(close) OVER p)
+        ) AS true_range
+    FROM bars_1d
+    WINDOW p AS (PARTITION BY symbol ORDER BY ts)
+)
+SELECT
+    symbol,
+    CAST(ts AS DATE) AS date,
+    true_range,
+    CASE WHEN COUNT(*) OVER w = window_days THEN AVG(true_range) OVER w END AS atr
+FROM ranges
+WINDOW w AS (
+    PARTITION BY symbol ORDER BY ts
+    ROWS BETWEEN window_days - 1 PRECEDING AND CURRENT ROW
+);
+",
+    r"
+CREATE OR REPLACE MACRO high_low_distance_v1(window_days := 252) AS TABLE
+WITH ranges AS (
+    SELECT
+        symbol,
+        CAST(ts AS DATE) AS date,
+        close,
+        CASE WHEN COUNT(*) OVER w = window_days THEN MAX(high) OVER w END AS window_high,
+        CASE WHEN COUNT(*) OVER w = window_days THEN MIN(low) OVER w END AS window_low
+    FROM bars_1d
+    WINDOW w AS (
+        PARTITION BY symbol ORDER BY ts
+        ROWS BETWEEN window_days - 1 PRECEDING AND CURRENT ROW
+    )
+)
+SELECT
+    symbol,
+    date,
+    close,
+    window_high,
+    window_low,
+    close / NULLIF(window_high, 0) - 1.0 AS pct_from_high,
+    close / NULLIF(window_low, 0) - 1.0 AS pct_from_low
+FROM ranges;
+",
+    r"
+CREATE OR REPLACE MACRO relative_strength_rank_v1(window_days := 63) AS TABLE
+WITH momentum AS (
+    SELECT
+        symbol,
+        CAST(ts AS DATE) AS date,
+        close / NULLIF(LAG(close, window_days) OVER (PARTITION BY symbol ORDER BY ts), 0)
+            - 1.0 AS trailing_return
+    FROM bars_1d
+)
+SELECT
+    symbol,
+    date,
+    trailing_return,
+    RANK() OVER (PARTITION BY date ORDER BY trailing_return DESC) AS rank,
+    PERCENT_RANK() OVER (PARTITION BY date ORDER BY trailing_return) AS percentile,
+    COUNT(*) OVER (PARTITION BY date) AS universe_size
+FROM momentum
+WHERE trailing_return IS NOT NULL;
+",
+    r"
+CREATE OR REPLACE MACRO volume_zscore_v1(window_days := 20) AS TABLE
+SELECT
+    symbol,
+    CAST(ts AS DATE) AS date,
+    volume,
+    CASE
+        WHEN COUNT(volume) OVER w = window_days
+        THEN (volume - AVG(volume) OVER w) / NULLIF(STDDEV_SAMP(volume) OVER w, 0)
+    END AS volume_zscore
+FROM bars_1d
+WINDOW w AS (
+    PARTITION BY symbol ORDER BY ts
+    ROWS BETWEEN window_days PRECEDING AND 1 PRECEDING
+);
+",
+];
 
 /// Create database views for common analytical queries.
 ///
 /// Creates the following views:
+/// - `bars_1m`, `bars_5m`, `bars_15m`, `bars_1h`, `bars_1d`: one interval of
+///   the unified `bars` table each, with the pre-0009 table columns
 /// - `vw_returns_daily`: Daily return percentages per symbol
 /// - `vw_volatility_20d`: 20-day rolling volatility
 /// - `vw_gaps_open`: Gap percentages between close and open
 /// - `vw_source_latency`: Average latency by source and dataset
+/// - `vw_financials_periodic`: Statement line items pivoted to one row per period
+/// - `vw_financials_ttm`: Trailing-twelve-month figures from quarterly statements
+/// - `vw_financial_ratios`: Margins, returns, leverage and growth per period
+/// - `vw_fundamentals_pit`: Fundamentals and statement metrics with `period_end`
+///   and `available_at`; statements without a known report date are assumed
+///   public 45 days (quarterly) or 90 days (annual) after period end
+/// - `vw_symbol_map`: Every known symbol, past ticker and provider alias mapped
+///   to its stable `instrument_id` and current symbol
+/// - `vw_news_sentiment_daily`: Article count and average sentiment per symbol
+///   and UTC day, keyed for joining onto daily bars or features
+///
+/// And the table macro `fundamentals_asof(symbol, ts)`, which returns the
+/// latest value of each metric that was public at `ts`, plus the analytics
+/// in [`ANALYTICS_CATALOG`].
 ///
 /// # Errors
 /// Returns an error if the view creation SQL fails to execute.
 pub fn create_views(connection: &Connection) -> Result<(), ::duckdb::Error> {
+    // Interval views come first; the analytical views below read `bars_1d`.
+    for interval in crate::BAR_INTERVALS {
+        // Intervals are hardcoded constants, safe to interpolate
+        connection.execute_batch(
+            format!(
+                "CREATE OR REPLACE VIEW bars_{interval} AS \
+                 SELECT symbol, ts, open, high, low, close, volume, source, updated_at \
+                 FROM bars WHERE interval = '{interval}'"
+            )
+            .as_str(),
+        )?;
+    }
+
     connection.execute_batch(
         r"
 CREATE OR REPLACE VIEW vw_returns_daily AS
@@ -58,8 +394,222 @@ SELECT
 FROM ingest_log
 WHERE latency_ms IS NOT NULL
 GROUP BY source, dataset;
+
+CREATE OR REPLACE VIEW vw_financials_periodic AS
+SELECT
+    symbol,
+    period AS basis,
+    end_date AS period_end,
+    MAX(fiscal_year) AS fiscal_year,
+    MAX(fiscal_quarter) AS fiscal_quarter,
+    MAX(CASE WHEN metric = 'revenue' THEN value END) AS revenue,
+    MAX(CASE WHEN metric = 'gross_profit' THEN value END) AS gross_profit,
+    MAX(CASE WHEN metric = 'operating_income' THEN value END) AS operating_income,
+    MAX(CASE WHEN metric = 'net_income' THEN value END) AS net_income,
+    MAX(CASE WHEN metric = 'eps' THEN value END) AS eps,
+    MAX(CASE WHEN metric = 'operating_cash_flow' THEN value END) AS operating_cash_flow,
+    MAX(CASE WHEN metric = 'capital_expenditures' THEN value END) AS capital_expenditures,
+    MAX(CASE WHEN metric = 'total_assets' THEN value END) AS total_assets,
+    MAX(CASE WHEN metric = 'total_liabilities' THEN value END) AS total_liabilities,
+    MAX(CASE WHEN metric = 'total_equity' THEN value END) AS total_equity,
+    MAX(CASE WHEN metric = 'current_assets' THEN value END) AS current_assets,
+    MAX(CASE WHEN metric = 'current_liabilities' THEN value END) AS current_liabilities,
+    MAX(CASE WHEN metric = 'total_debt' THEN value END) AS total_debt
+FROM financial_statements
+WHERE metric IS NOT NULL
+GROUP BY symbol, period, end_date;
+
+CREATE OR REPLACE VIEW vw_financials_ttm AS
+SELECT
+    symbol,
+    'ttm' AS basis,
+    period_end,
+    fiscal_year,
+    fiscal_quarter,
+    CASE WHEN COUNT(revenue) OVER w = 4 THEN SUM(revenue) OVER w END AS revenue,
+    CASE WHEN COUNT(gross_profit) OVER w = 4 THEN SUM(gross_profit) OVER w END AS gross_profit,
+    CASE WHEN COUNT(operating_income) OVER w = 4 THEN SUM(operating_income) OVER w END AS operating_income,
+    CASE WHEN COUNT(net_income) OVER w = 4 THEN SUM(net_income) OVER w END AS net_income,
+    CASE WHEN COUNT(eps) OVER w = 4 THEN SUM(eps) OVER w END AS eps,
+    CASE WHEN COUNT(operating_cash_flow) OVER w = 4 THEN SUM(operating_cash_flow) OVER w END AS operating_cash_flow,
+    CASE WHEN COUNT(capital_expenditures) OVER w = 4 THEN SUM(capital_expenditures) OVER w END AS capital_expenditures,
+    total_assets,
+    total_liabilities,
+    total_equity,
+    current_assets,
+    current_liabilities,
+    total_debt
+FROM vw_financials_periodic
+WHERE basis = 'quarterly'
+WINDOW w AS (PARTITION BY symbol ORDER BY period_end ROWS BETWEEN 3 PRECEDING AND CURRENT ROW)
+QUALIFY COUNT(*) OVER w = 4
+    AND date_diff('day', MIN(period_end) OVER w, period_end) BETWEEN 250 AND 300;
+
+CREATE OR REPLACE VIEW vw_financial_ratios AS
+WITH periods AS (
+    SELECT *, operating_cash_flow - ABS(capital_expenditures) AS free_cash_flow
+    FROM (
+        SELECT * FROM vw_financials_periodic
+        UNION ALL
+        SELECT * FROM vw_financials_ttm
+    )
+),
+market_caps AS (
+    SELECT symbol, arg_max(value, date) AS market_cap
+    FROM fundamentals
+    WHERE metric = 'market_cap'
+    GROUP BY symbol
+)
+SELECT
+    cur.symbol,
+    cur.basis,
+    cur.period_end,
+    cur.fiscal_year,
+    cur.fiscal_quarter,
+    cur.revenue,
+    cur.net_income,
+    cur.free_cash_flow,
+    cur.gross_profit / NULLIF(cur.revenue, 0) AS gross_margin,
+    cur.operating_income / NULLIF(cur.revenue, 0) AS operating_margin,
+    cur.net_income / NULLIF(cur.revenue, 0) AS net_margin,
+    cur.net_income / NULLIF(cur.total_equity, 0) AS return_on_equity,
+    cur.net_income / NULLIF(cur.total_assets, 0) AS return_on_assets,
+    cur.current_assets / NULLIF(cur.current_liabilities, 0) AS current_ratio,
+    COALESCE(cur.total_debt, cur.total_liabilities) / NULLIF(cur.total_equity, 0) AS debt_to_equity,
+    cur.free_cash_flow / NULLIF(mc.market_cap, 0) AS fcf_yield,
+    (cur.revenue - py.revenue) / NULLIF(ABS(py.revenue), 0) AS revenue_growth_yoy,
+    (cur.revenue - pq.revenue) / NULLIF(ABS(pq.revenue), 0) AS revenue_growth_qoq,
+    (cur.net_income - py.net_income) / NULLIF(ABS(py.net_income), 0) AS net_income_growth_yoy,
+    (cur.net_income - pq.net_income) / NULLIF(ABS(pq.net_income), 0) AS net_income_growth_qoq,
+    (cur.eps - py.eps) / NULLIF(ABS(py.eps), 0) AS eps_growth_yoy,
+    (cur.eps - pq.eps) / NULLIF(ABS(pq.eps), 0) AS eps_growth_qoq
+FROM periods cur
+LEFT JOIN periods py
+    ON py.symbol = cur.symbol
+    AND py.basis = cur.basis
+    AND date_diff('day', py.period_end, cur.period_end) BETWEEN 330 AND 400
+LEFT JOIN periods pq
+    ON pq.symbol = cur.symbol
+    AND pq.basis = cur.basis
+    AND cur.basis <> 'annual'
+    AND date_diff('day', pq.period_end, cur.period_end) BETWEEN 75 AND 105
+LEFT JOIN market_caps mc ON mc.symbol = cur.symbol;
+
+CREATE OR REPLACE VIEW vw_fundamentals_pit AS
+SELECT
+    symbol,
+    'snapshot' AS basis,
+    'fundamentals' AS dataset,
+    metric,
+    value,
+    period_end,
+    COALESCE(available_at, date) AS available_at,
+    source
+FROM fundamentals
+UNION ALL
+SELECT
+    symbol,
+    period AS basis,
+    statement_type AS dataset,
+    metric,
+    value,
+    end_date AS period_end,
+    COALESCE(
+        available_at,
+        end_date + to_days(CASE WHEN period = 'annual' THEN 90 ELSE 45 END)
+    ) AS available_at,
+    source
+FROM financial_statements
+WHERE metric IS NOT NULL;
+
+CREATE OR REPLACE MACRO fundamentals_asof(target_symbol, as_of_ts) AS TABLE
+SELECT symbol, basis, dataset, metric, value, period_end, available_at, source
+FROM vw_fundamentals_pit
+WHERE symbol = target_symbol
+    AND available_at <= CAST(as_of_ts AS TIMESTAMP)
+QUALIFY ROW_NUMBER() OVER (
+    PARTITION BY basis, metric
+    ORDER BY available_at DESC, period_end DESC NULLS LAST, dataset
+) = 1;
+
+CREATE OR REPLACE VIEW vw_symbol_map AS
+WITH current_symbols AS (
+    SELECT instrument_id, symbol AS current_symbol
+    FROM ticker_history
+    WHERE valid_to IS NULL
+    QUALIFY ROW_NUMBER() OVER (PARTITION BY instrument_id ORDER BY valid_from DESC) = 1
+)
+SELECT
+    th.symbol,
+    CAST(NULL AS TEXT) AS provider,
+    th.instrument_id,
+    cs.current_symbol,
+    th.valid_from,
+    th.valid_to
+FROM ticker_history th
+LEFT JOIN current_symbols cs ON cs.instrument_id = th.instrument_id
+UNION ALL
+SELECT
+    ia.alias AS symbol,
+    ia.provider,
+    ia.instrument_id,
+    cs.current_symbol,
+    CAST(NULL AS TIMESTAMP) AS valid_from,
+    CAST(NULL AS TIMESTAMP) AS valid_to
+FROM instrument_aliases ia
+LEFT JOIN current_symbols cs ON cs.instrument_id = ia.instrument_id;
+
+CREATE OR REPLACE VIEW vw_news_sentiment_daily AS
+SELECT
+    nt.symbol,
+    CAST(n.published_at AS DATE) AS date,
+    COUNT(*) AS article_count,
+    AVG(COALESCE(nt.sentiment, n.sentiment)) AS avg_sentiment,
+    CASE
+        WHEN SUM(nt.relevance) FILTER (WHERE nt.sentiment IS NOT NULL) > 0 THEN
+            SUM(nt.sentiment * nt.relevance) FILTER (WHERE nt.sentiment IS NOT NULL)
+            / SUM(nt.relevance) FILTER (WHERE nt.sentiment IS NOT NULL)
+        ELSE NULL
+    END AS weighted_sentiment
+FROM news_tickers nt
+JOIN news n ON n.url = nt.url
+GROUP BY nt.symbol, CAST(n.published_at AS DATE);
 ",
     )?;
 
+    for sql in ANALYTICS_SQL {
+        connection.execute_batch(sql)?;
+    }
+
+    Ok(())
+}
+
+/// Drop every view and macro, so down migrations can change the tables
+/// beneath them. [`create_views`] puts them back.
+///
+/// # Errors
+/// Returns an error if the catalog cannot be read or a drop fails.
+pub fn drop_views(connection: &Connection) -> Result<(), ::duckdb::Error> {
+    let mut statement = connection.prepare(
+        "SELECT 'VIEW', view_name FROM duckdb_views() \
+         WHERE NOT internal AND database_name = current_database() \
+         UNION ALL \
+         SELECT DISTINCT CASE function_type WHEN 'table_macro' THEN 'MACRO TABLE' ELSE 'MACRO' END, \
+         function_name FROM duckdb_functions() \
+         WHERE NOT internal AND function_type IN ('macro', 'table_macro') \
+         AND database_name = current_database()",
+    )?;
+    let objects = statement
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    for (kind, name) in objects {
+        // Names come from the catalog; quote them as identifiers.
+        connection.execute_batch(&format!(
+            "DROP {kind} IF EXISTS \"{}\"",
+            name.replace('"', "\"\"")
+        ))?;
+    }
     Ok(())
 }
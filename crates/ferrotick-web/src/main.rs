@@ -11,9 +11,25 @@ mod models;
 mod routes;
 
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), ferrotick_telemetry::TelemetryError> {
+    let telemetry = ferrotick_telemetry::Telemetry::install(
+        ferrotick_telemetry::TelemetryConfig::new("ferrotick-web").with_env_exporter(),
+    )?;
+    if telemetry.exports() {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(10));
+            loop {
+                interval.tick().await;
+                if let Err(error) = telemetry.flush().await {
+                    eprintln!("warning: {error}");
+                }
+            }
+        });
+    }
+
     let app = Router::new()
         .route("/health", get(routes::health::health_check))
+        .route("/metrics", get(routes::metrics::metrics))
         .route("/api/backtest/run", post(routes::backtest::run_backtest))
         .route("/api/strategies", get(routes::strategies::list_strategies))
         .layer(CorsLayer::permissive());
@@ -26,4 +42,5 @@ async fn main() {
         .expect("failed to bind TCP listener");
 
     axum::serve(listener, app).await.expect("axum server error");
+    Ok(())
 }
@@ -0,0 +1,9 @@
+use axum::http::header;
+
+/// Prometheus scrape endpoint for the process-wide metrics registry.
+pub async fn metrics() -> ([(header::HeaderName, &'static str); 1], String) {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        ferrotick_telemetry::render_prometheus(),
+    )
+}
@@ -1,3 +1,4 @@
 pub mod backtest;
 pub mod health;
+pub mod metrics;
 pub mod strategies;
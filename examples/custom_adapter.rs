@@ -18,6 +18,8 @@
 //! - `bars()` - Fetch OHLCV bars
 //! - `fundamentals()` - Fetch fundamentals
 //! - `search()` - Search instruments
+//! - `financials()`, `earnings()`, `ticks()`, `economic()`, `news()` -
+//!   Remaining endpoints, which may simply return `unsupported_endpoint`
 //! - `health()` - Health check
 
 use std::future::Future;
@@ -25,12 +27,20 @@ use std::pin::Pin;
 use std::sync::Arc;
 
 use ferrotick_core::{
-    BarsRequest, BarSeries, CapabilitySet, DataSource, Endpoint, FundamentalsBatch,
-    FundamentalsRequest, HealthState, HealthStatus, ProviderId, Quote, QuoteBatch,
-    QuoteRequest, SearchBatch, SearchRequest, SourceError, Symbol, UtcDateTime,
-    Interval, Bar,
+    Bar, BarSeries, BarsRequest, CapabilitySet, DataSource, EarningsBatch, EarningsRequest,
+    EconomicRequest, EconomicSeries, Endpoint, FinancialsBatch, FinancialsRequest,
+    FundamentalsBatch, FundamentalsRequest, HealthStatus, Interval, NewsBatch, NewsRequest,
+    ProviderId, Quote, QuoteBatch, QuoteRequest, SearchBatch, SearchRequest, SourceError,
+    SourceRouterBuilder, SourceStrategy, Symbol, TickBatch, TicksRequest, UtcDateTime,
 };
 
+type SourceFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, SourceError>> + Send + 'a>>;
+
+/// Shared answer for endpoints this adapter does not serve.
+fn unsupported<'a, T: Send + 'a>(endpoint: Endpoint) -> SourceFuture<'a, T> {
+    Box::pin(async move { Err(SourceError::unsupported_endpoint(endpoint)) })
+}
+
 /// A mock adapter for demonstration purposes.
 ///
 /// This adapter returns static data and is useful for testing
@@ -45,7 +55,7 @@ impl MockAdapter {
     /// Create a new mock adapter with the specified score.
     pub fn new(score: u16) -> Self {
         Self {
-            id: ProviderId::new("mock"),
+            id: ProviderId::new("mock").expect("valid provider name"),
             capabilities: CapabilitySet::full(),
             score,
         }
@@ -54,7 +64,7 @@ impl MockAdapter {
     /// Create a mock adapter with limited capabilities.
     pub fn with_capabilities(capabilities: CapabilitySet) -> Self {
         Self {
-            id: ProviderId::new("mock"),
+            id: ProviderId::new("mock").expect("valid provider name"),
             capabilities,
             score: 50,
         }
@@ -63,17 +73,14 @@ impl MockAdapter {
 
 impl DataSource for MockAdapter {
     fn id(&self) -> ProviderId {
-        self.id.clone()
+        self.id
     }
 
     fn capabilities(&self) -> CapabilitySet {
         self.capabilities
     }
 
-    fn quote<'a>(
-        &'a self,
-        req: QuoteRequest,
-    ) -> Pin<Box<dyn Future<Output = Result<QuoteBatch, SourceError>> + Send + 'a>> {
+    fn quote<'a>(&'a self, req: QuoteRequest) -> SourceFuture<'a, QuoteBatch> {
         Box::pin(async move {
             // Check capability
             if !self.capabilities.supports(Endpoint::Quote) {
@@ -81,79 +88,91 @@ impl DataSource for MockAdapter {
             }
 
             // Generate mock quotes
-            let quotes: Vec<Quote> = req
+            let quotes = req
                 .symbols
                 .into_iter()
-                .map(|symbol| Quote {
-                    symbol,
-                    price: 100.0,
-                    bid: Some(99.50),
-                    ask: Some(100.50),
-                    volume: Some(1_000_000),
-                    currency: "USD".to_string(),
-                    as_of: UtcDateTime::now(),
+                .map(|symbol| {
+                    Quote::new(
+                        symbol,
+                        100.0,
+                        Some(99.50),
+                        Some(100.50),
+                        Some(1_000_000),
+                        "USD",
+                        UtcDateTime::now(),
+                    )
                 })
-                .collect();
+                .collect::<Result<Vec<Quote>, _>>()
+                .map_err(|error| SourceError::internal(error.to_string()))?;
 
             Ok(QuoteBatch { quotes })
         })
     }
 
-    fn bars<'a>(
-        &'a self,
-        req: BarsRequest,
-    ) -> Pin<Box<dyn Future<Output = Result<BarSeries, SourceError>> + Send + 'a>> {
+    fn bars<'a>(&'a self, req: BarsRequest) -> SourceFuture<'a, BarSeries> {
         Box::pin(async move {
             // Check capability
             if !self.capabilities.supports(Endpoint::Bars) {
                 return Err(SourceError::unsupported_endpoint(Endpoint::Bars));
             }
 
-            // Generate mock bars
-            let bars: Vec<Bar> = (0..req.limit.min(100))
+            // Generate one mock bar per day, validated like real provider data
+            let bars = (0..req.limit.min(100))
                 .map(|i| {
                     let base = 100.0 + (i as f64 * 0.5);
-                    Bar {
-                        ts: UtcDateTime::now(), // In real code, this would be actual timestamps
-                        open: base,
-                        high: base + 1.0,
-                        low: base - 1.0,
-                        close: base + 0.5,
-                        volume: Some(500_000),
-                        vwap: Some(base + 0.25),
-                    }
+                    let ts = UtcDateTime::from_unix_timestamp(1_700_000_000 + i as i64 * 86_400)?;
+                    Bar::new(
+                        ts,
+                        base,
+                        base + 1.0,
+                        base - 1.0,
+                        base + 0.5,
+                        Some(500_000),
+                        Some(base + 0.25),
+                    )
                 })
-                .collect();
+                .collect::<Result<Vec<Bar>, _>>()
+                .map_err(|error| SourceError::internal(error.to_string()))?;
 
-            // Note: In real implementation, you'd create Bars properly with validation
-            Ok(BarSeries::new(req.symbol, Interval::Daily, bars))
+            Ok(BarSeries::new(req.symbol, req.interval, bars))
         })
     }
 
+    // For brevity, the remaining endpoints are unsupported in this example
+
     fn fundamentals<'a>(
         &'a self,
         _req: FundamentalsRequest,
-    ) -> Pin<Box<dyn Future<Output = Result<FundamentalsBatch, SourceError>> + Send + 'a>> {
-        Box::pin(async move {
-            // For brevity, return unsupported for this example
-            Err(SourceError::unsupported_endpoint(Endpoint::Fundamentals))
-        })
+    ) -> SourceFuture<'a, FundamentalsBatch> {
+        unsupported(Endpoint::Fundamentals)
     }
 
-    fn search<'a>(
-        &'a self,
-        _req: SearchRequest,
-    ) -> Pin<Box<dyn Future<Output = Result<SearchBatch, SourceError>> + Send + 'a>> {
-        Box::pin(async move {
-            // For brevity, return unsupported for this example
-            Err(SourceError::unsupported_endpoint(Endpoint::Search))
-        })
+    fn search<'a>(&'a self, _req: SearchRequest) -> SourceFuture<'a, SearchBatch> {
+        unsupported(Endpoint::Search)
+    }
+
+    fn financials<'a>(&'a self, _req: FinancialsRequest) -> SourceFuture<'a, FinancialsBatch> {
+        unsupported(Endpoint::Financials)
+    }
+
+    fn earnings<'a>(&'a self, _req: EarningsRequest) -> SourceFuture<'a, EarningsBatch> {
+        unsupported(Endpoint::Earnings)
+    }
+
+    fn ticks<'a>(&'a self, _req: TicksRequest) -> SourceFuture<'a, TickBatch> {
+        unsupported(Endpoint::Ticks)
+    }
+
+    fn economic<'a>(&'a self, _req: EconomicRequest) -> SourceFuture<'a, EconomicSeries> {
+        unsupported(Endpoint::Economic)
+    }
+
+    fn news<'a>(&'a self, _req: NewsRequest) -> SourceFuture<'a, NewsBatch> {
+        unsupported(Endpoint::News)
     }
 
     fn health<'a>(&'a self) -> Pin<Box<dyn Future<Output = HealthStatus> + Send + 'a>> {
-        Box::pin(async move {
-            HealthStatus::healthy(self.score)
-        })
+        Box::pin(async move { HealthStatus::healthy(self.score) })
     }
 }
 
@@ -180,7 +199,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Fetch a quote
     println!("\n📈 Fetching quote...");
-    let request = QuoteRequest::new(vec![Symbol::new("AAPL"), Symbol::new("MSFT")])?;
+    let request = QuoteRequest::new(vec![Symbol::parse("AAPL")?, Symbol::parse("MSFT")?])?;
     let response = adapter.quote(request).await?;
 
     println!("   Received {} quotes:", response.quotes.len());
@@ -196,19 +215,37 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Test with limited capabilities
     println!("\n🔧 Testing limited capabilities...");
-    let limited = MockAdapter::with_capabilities(
-        CapabilitySet::new(true, false, false, false)
+    let limited = MockAdapter::with_capabilities(CapabilitySet::new(
+        true, false, false, false, false, false, false, false, false,
+    ));
+
+    println!(
+        "   Capabilities: {:?}",
+        limited.capabilities().supported_endpoints()
     );
-    
-    println!("   Capabilities: {:?}", limited.capabilities().supported_endpoints());
-    
+
     // Try bars (should fail)
-    let bars_request = BarsRequest::new(Symbol::new("AAPL"), Interval::Daily, 10)?;
+    let bars_request = BarsRequest::new(Symbol::parse("AAPL")?, Interval::OneDay, 10)?;
     match limited.bars(bars_request).await {
         Ok(_) => println!("   ❌ Unexpected success!"),
         Err(e) => println!("   ✅ Expected error: {}", e.message()),
     }
 
+    // Register the adapter with the router and select it by name
+    println!("\n🧭 Routing through SourceRouter...");
+    let router = SourceRouterBuilder::new()
+        .with_yahoo_enabled(false)
+        .with_provider(Arc::new(MockAdapter::new(75)))
+        .build();
+    let request = QuoteRequest::new(vec![Symbol::parse("AAPL")?])?;
+    match router
+        .route_quote(&request, SourceStrategy::Strict("mock".parse()?))
+        .await
+    {
+        Ok(success) => println!("   ✅ Routed via {:?}", success.source_chain),
+        Err(failure) => println!("   ❌ Routing failed: {:?}", failure.errors),
+    }
+
     println!("\n✅ Custom adapter example complete!");
     Ok(())
 }
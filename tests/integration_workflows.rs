@@ -1,10 +1,10 @@
 use std::collections::HashSet;
 
 use ferrotick_backtest::{
-    BacktestConfig, BacktestEngine, BarEvent, Order, Portfolio,
+    BacktestConfig, BacktestEngine, BarEvent, Order, OrderSide, OrderType, Portfolio,
     SignalAction as BacktestSignalAction, SignalEvent, Strategy as BacktestStrategy,
 };
-use ferrotick_core::{Bar, Symbol, UtcDateTime};
+use ferrotick_core::{Bar, Symbol, UniverseMember, UniverseMembership, UtcDateTime};
 use ferrotick_ml::{FeatureConfig, FeatureEngineer, IndicatorSelection, Model, SVMClassifier};
 use ferrotick_optimization::GridSearchOptimizer;
 use ferrotick_strategies::SignalAction as StrategySignalAction;
@@ -186,6 +186,38 @@ impl BacktestStrategy for MultiAssetTrackingStrategy {
     }
 }
 
+/// Buys one share of every symbol on every bar.
+struct BuyEveryBarStrategy;
+
+impl BacktestStrategy for BuyEveryBarStrategy {
+    fn on_bar(&mut self, bar: &BarEvent, _portfolio: &Portfolio) -> Option<SignalEvent> {
+        Some(SignalEvent {
+            symbol: bar.symbol.clone(),
+            ts: bar.bar.ts,
+            action: BacktestSignalAction::Buy,
+            strength: 1.0,
+            reason: String::from("always_buy"),
+        })
+    }
+
+    fn create_order(
+        &self,
+        signal: &SignalEvent,
+        _portfolio: &Portfolio,
+        _config: &BacktestConfig,
+    ) -> Option<Order> {
+        Some(Order::new(
+            signal.symbol.clone(),
+            OrderSide::Buy,
+            OrderType::Market,
+            1.0,
+            None,
+            None,
+            signal.ts,
+        ))
+    }
+}
+
 #[test]
 fn test_full_data_to_signal_pipeline() {
     let bars = make_wave_bars(260);
@@ -362,3 +394,50 @@ async fn test_multi_asset_portfolio_backtest() {
     assert!(strategy.seen_symbols.contains("AAPLC150DEC24"));
     assert!(strategy.seen_symbols.contains("ESM26"));
 }
+
+#[tokio::test]
+async fn test_universe_backtest_only_buys_members_on_each_date() {
+    let mut events = Vec::new();
+    for day in 0..10 {
+        let ts = UtcDateTime::from_unix_timestamp(day * 86_400).expect("valid ts");
+        for symbol in ["AAPL", "ENRN"] {
+            let bar =
+                Bar::new(ts, 100.0, 101.0, 99.0, 100.0, Some(1_000), None).expect("valid bar");
+            events.push(BarEvent::new(
+                Symbol::parse(symbol).expect("valid symbol"),
+                bar,
+            ));
+        }
+    }
+    // ENRN leaves the universe on day 3.
+    let membership = UniverseMembership::new(
+        "test",
+        vec![
+            UniverseMember {
+                symbol: String::from("AAPL"),
+                added: String::from("1970-01-01"),
+                removed: None,
+            },
+            UniverseMember {
+                symbol: String::from("ENRN"),
+                added: String::from("1970-01-01"),
+                removed: Some(String::from("1970-01-04")),
+            },
+        ],
+    );
+
+    let mut engine = BacktestEngine::new(BacktestConfig::default()).with_universe(membership);
+    engine
+        .run(&mut BuyEveryBarStrategy, &events)
+        .await
+        .expect("universe backtest");
+
+    // Orders fill on the next bar, so the last day's order stays pending.
+    let position = |symbol: &str| {
+        engine
+            .portfolio()
+            .position(&Symbol::parse(symbol).expect("valid symbol"))
+    };
+    assert_eq!(position("AAPL"), 9.0);
+    assert_eq!(position("ENRN"), 3.0);
+}